@@ -2,12 +2,15 @@
 
 from __future__ import annotations
 
-from collections.abc import Mapping, Sequence
+import os
+from collections import OrderedDict, defaultdict, deque
+from collections.abc import Iterable, Mapping, Sequence
 from typing import (
     TYPE_CHECKING,
     Any,
     Callable,
     Generic,
+    Literal,
     TypeVar,
     final,
 )
@@ -23,7 +26,13 @@ if TYPE_CHECKING:
     import numpy as np
     from numpy.typing import NDArray
 
-    from ..pyany_serde import InitStrategy, NumpySerdeConfig, PyAnySerdeType
+    from ..pyany_serde import (
+        ExtraPolicy,
+        InitStrategy,
+        NumpySerdeConfig,
+        PyAnySerdeType,
+        SchemaPolicy,
+    )
 
     DType = TypeVar(
         "DType",
@@ -47,10 +56,17 @@ else:
         pass
 
 __all__ = [
+    "ArrowIpcExporter",
+    "ChecksumError",
+    "ExtraPolicy",
     "InitStrategy",
     "NumpySerdeConfig",
     "PyAnySerdeType",
     "PythonSerde",
+    "RecordReader",
+    "RecordWriter",
+    "RingBuffer",
+    "SchemaPolicy",
 ]
 
 T = TypeVar("T")
@@ -58,6 +74,55 @@ TInner = TypeVar("TInner")
 KeysT = TypeVar("KeysT")
 ValuesT = TypeVar("ValuesT")
 
+class ChecksumError(ValueError):
+    """
+    Raised when serialized data doesn't match its checksum, e.g. because it was only partially written. Raised by CHECKSUMMED serdes and by RecordReader.
+    """
+
+class ExtraPolicy:
+    """
+    Defines what the TYPEDDICT and DATACLASS serdes do with keys (or instance attributes) which are not part of the serde type.
+    """
+
+    @override
+    def __reduce__(self) -> tuple[ExtraPolicy, tuple[Any, ...]]: ...
+
+    @final
+    class IGNORE(ExtraPolicy):
+        __match_args__ = ()
+
+        def __new__(cls) -> ExtraPolicy.IGNORE:
+            """
+            Extra keys are silently dropped
+            """
+            ...
+
+    @final
+    class FORBID(ExtraPolicy):
+        __match_args__ = ()
+
+        def __new__(cls) -> ExtraPolicy.FORBID:
+            """
+            Extra keys raise a ValueError during serialization
+            """
+            ...
+
+    @final
+    class PASSTHROUGH(ExtraPolicy):
+        __match_args__ = ("fallback_serde_type",)
+
+        @property
+        def fallback_serde_type(self) -> PyAnySerdeType[Any]: ...
+        def __new__(
+            cls, fallback_serde_type: PyAnySerdeType[Any]
+        ) -> ExtraPolicy.PASSTHROUGH:
+            """
+            fallback_serde_type: the serde type used for the values of extra keys. Extra keys must be strings.
+            """
+            ...
+
+    ...
+
 class InitStrategy:
     @override
     def __reduce__(self) -> tuple[InitStrategy, tuple[Any, ...]]: ...
@@ -86,6 +151,36 @@ class InitStrategy:
 
         def __new__(cls) -> InitStrategy.NONE: ...
 
+    @final
+    class AUTO(InitStrategy):
+        __match_args__ = ()
+
+        def __new__(cls) -> InitStrategy.AUTO:
+            """
+            Uses dataclasses.fields (or the attributes of an attrs class) to determine which fields to pass to the constructor of the class (those with init=True), using the init alias of private attrs attributes. The remaining fields are set after construction.
+            """
+            ...
+
+    @final
+    class BYPASS(InitStrategy):
+        __match_args__ = ()
+
+        def __new__(cls) -> InitStrategy.BYPASS:
+            """
+            Creates the instance using object.__new__ and sets all fields using object.__setattr__, so neither __init__ nor __post_init__ is called. This works with frozen dataclasses and classes with __slots__.
+            """
+            ...
+
+    @final
+    class MODELCONSTRUCT(InitStrategy):
+        __match_args__ = ()
+
+        def __new__(cls) -> InitStrategy.MODELCONSTRUCT:
+            """
+            Creates the instance using the model_construct classmethod of a pydantic model, which skips validation.
+            """
+            ...
+
     ...
 
 class NumpySerdeConfig:
@@ -151,6 +246,16 @@ class PyAnySerdeType(Generic[T]):
     def __get_pydantic_core_schema__(
         cls, _source_type: Any, handler: GetCoreSchemaHandler
     ) -> CoreSchema: ...
+    @staticmethod
+    def from_type_hint(tp: Any, strict: bool = False) -> PyAnySerdeType[Any]:
+        """
+        Infers a PyAnySerdeType from a type hint. Supported hints are bool, int, float, complex, str, bytes, Any (as DYNAMIC), Optional, Union, Literal, Annotated, Enum subclasses, TypedDict classes, dataclasses, numpy.typing.NDArray with a supported dtype, and list, set, frozenset, deque, tuple, dict, OrderedDict, and defaultdict (with or without type arguments). Dataclass fields with init=False are set after construction. Classes which refer to themselves (directly or through other classes) are inferred as definitions of a DEFINITIONS wrapping the result, and are referred to with REF by the name of the class.
+        tp: the type hint
+        strict: if True, raise a ValueError for type hints which cannot be inferred instead of using PICKLE for them
+
+        Note that UNION types created by this method use a generated option_choice_fn, which cannot be pickled.
+        """
+        ...
 
     @final
     class BOOL(PyAnySerdeType[bool]):
@@ -164,18 +269,89 @@ class PyAnySerdeType(Generic[T]):
 
         def __new__(cls) -> PyAnySerdeType.BYTES: ...
 
+    @final
+    class CHECKSUMMED(PyAnySerdeType[TInner], Generic[TInner]):
+        __match_args__ = ("value_serde_type",)
+
+        @property
+        def value_serde_type(self) -> PyAnySerdeType[TInner]: ...
+        def __new__(
+            cls, value_serde_type: PyAnySerdeType[TInner]
+        ) -> PyAnySerdeType.CHECKSUMMED[TInner]:
+            """
+            value_serde_type: the serde to be used with the value. Its serialized data is followed by a CRC32C checksum, which is verified before the value is retrieved; a mismatch (e.g. from a writer which crashed midway through writing shared memory) raises ChecksumError.
+            """
+            ...
+
+    @final
+    class COLUMNARLIST(PyAnySerdeType[TInner], Generic[TInner]):
+        __match_args__ = (
+            "items_serde_type",
+            "as_columns",
+        )
+
+        @property
+        def items_serde_type(self) -> PyAnySerdeType[Any]: ...
+        @property
+        def as_columns(self) -> bool: ...
+        def __new__(
+            cls,
+            items_serde_type: PyAnySerdeType[Any],
+            as_columns: bool = False,
+        ) -> PyAnySerdeType.COLUMNARLIST[TInner]:
+            """
+            items_serde_type: a DATACLASS or TYPEDDICT (with ExtraPolicy.IGNORE, SchemaPolicy.FIXED, and no optional keys). A list of items is serialized column by column: the values of each field are written together, so BOOL, FLOAT, and INT fields are written as packed arrays.
+            as_columns: if True, the data is a dict mapping each field to the sequence of its values (e.g. a numpy array) instead of a list of items. When retrieving, BOOL, FLOAT, INT, and NUMPY fields become numpy arrays (stacked along a new first axis for NUMPY fields, which must then all have the same shape) and other fields become lists.
+            """
+            ...
+
     @final
     class COMPLEX(PyAnySerdeType[complex]):
         __match_args__ = ()
 
         def __new__(cls) -> PyAnySerdeType.COMPLEX: ...
 
+    @final
+    class COMPRESSED(PyAnySerdeType[TInner], Generic[TInner]):
+        __match_args__ = (
+            "value_serde_type",
+            "codec",
+            "level",
+            "min_size",
+        )
+
+        @property
+        def value_serde_type(self) -> PyAnySerdeType[TInner]: ...
+        @property
+        def codec(self) -> Literal["lz4", "zstd"]: ...
+        @property
+        def level(self) -> int | None: ...
+        @property
+        def min_size(self) -> int: ...
+        def __new__(
+            cls,
+            value_serde_type: PyAnySerdeType[TInner],
+            codec: Literal["lz4", "zstd"] = "lz4",
+            level: int | None = None,
+            min_size: int = 256,
+        ) -> PyAnySerdeType.COMPRESSED[TInner]:
+            """
+            value_serde_type: the serde to be used with the value. Its serialized data is compressed as a single block.
+            codec: the compression codec to use. lz4 is the fastest; zstd compresses better but is slower.
+            level: the compression level for zstd (at most 22, where negative levels are faster and None uses zstd's default). lz4 does not support levels.
+            min_size: values whose serialized data is smaller than this many bytes are stored uncompressed, as is any data which doesn't get smaller when compressed.
+            """
+            ...
+
     @final
     class DATACLASS(PyAnySerdeType[TInner], Generic[TInner]):
         __match_args__ = (
             "clazz",
             "init_strategy",
             "field_serde_type_dict",
+            "extra",
+            "alphabetical_field_order",
+            "schema",
         )
 
         @property
@@ -186,16 +362,92 @@ class PyAnySerdeType(Generic[T]):
         def field_serde_type_dict(
             self,
         ) -> dict[str, PyAnySerdeType[Any]]: ...
+        @property
+        def extra(self) -> ExtraPolicy: ...
+        @property
+        def alphabetical_field_order(self) -> bool: ...
+        @property
+        def schema(self) -> SchemaPolicy: ...
         def __new__(
             cls,
             clazz: type[TInner],
             init_strategy: InitStrategy,
             field_serde_type_dict: Mapping[str, PyAnySerdeType[Any]],
+            extra: ExtraPolicy = ExtraPolicy.IGNORE(),
+            alphabetical_field_order: bool = False,
+            schema: SchemaPolicy = SchemaPolicy.FIXED(),
         ) -> PyAnySerdeType.DATACLASS[TInner]:
             """
             clazz: the dataclass to be serialized
             init_strategy: defines the initialization strategy
             field_serde_type_dict: dict to define the serde to be used with each field in the dataclass
+            extra: defines what to do with instance attributes (from the instance __dict__) which are not in field_serde_type_dict
+            alphabetical_field_order: fields are serialized in the order of field_serde_type_dict by default. Set this to serialize them in alphabetical order instead, which was the behavior of older versions.
+            schema: defines whether the serialized fields must exactly match field_serde_type_dict, or whether fields can be added and removed over time
+            """
+            ...
+
+    @final
+    class DEFAULTDICT(
+        PyAnySerdeType[defaultdict[KeysT, ValuesT]], Generic[KeysT, ValuesT]
+    ):
+        __match_args__ = (
+            "keys_serde_type",
+            "values_serde_type",
+        )
+
+        @property
+        def keys_serde_type(self) -> PyAnySerdeType[KeysT]: ...
+        @property
+        def values_serde_type(self) -> PyAnySerdeType[ValuesT]: ...
+        def __new__(
+            cls,
+            keys_serde_type: PyAnySerdeType[KeysT],
+            values_serde_type: PyAnySerdeType[ValuesT],
+        ) -> PyAnySerdeType.DEFAULTDICT[KeysT, ValuesT]:
+            """
+            The default_factory of each defaultdict is serialized by reference (its module and qualname), so it must be importable, such as a builtin, class, or module-level function. Lambdas and nested functions raise a ValueError.
+            """
+            ...
+
+    @final
+    class DEFINITIONS(PyAnySerdeType[TInner], Generic[TInner]):
+        __match_args__ = (
+            "definitions",
+            "root_serde_type",
+            "max_depth",
+        )
+
+        @property
+        def definitions(self) -> dict[str, PyAnySerdeType[Any]]: ...
+        @property
+        def root_serde_type(self) -> PyAnySerdeType[TInner]: ...
+        @property
+        def max_depth(self) -> int: ...
+        def __new__(
+            cls,
+            definitions: Mapping[str, PyAnySerdeType[Any]],
+            root_serde_type: PyAnySerdeType[TInner],
+            max_depth: int = 256,
+        ) -> PyAnySerdeType.DEFINITIONS[TInner]:
+            """
+            definitions: named serde types which can be referred to using REF from within root_serde_type or the definitions themselves (including recursively), as well as from nested DEFINITIONS
+            root_serde_type: the serde type used for the data. The serialized format is the same as that of root_serde_type.
+            max_depth: the maximum number of nested REFs, e.g. the maximum depth of a tree. Exceeding this raises a RecursionError.
+            """
+            ...
+
+    @final
+    class DEQUE(PyAnySerdeType[deque[TInner]], Generic[TInner]):
+        __match_args__ = ("items_serde_type",)
+
+        @property
+        def items_serde_type(self) -> PyAnySerdeType[TInner]: ...
+        def __new__(
+            cls, items_serde_type: PyAnySerdeType[TInner]
+        ) -> PyAnySerdeType.DEQUE[TInner]:
+            """
+            The maxlen of each deque is serialized along with its items.
             """
             ...
 
@@ -222,12 +474,46 @@ class PyAnySerdeType(Generic[T]):
 
         def __new__(cls) -> PyAnySerdeType.DYNAMIC: ...
 
+    @final
+    class ENUM(PyAnySerdeType[TInner], Generic[TInner]):
+        __match_args__ = ("values",)
+
+        @property
+        def values(self) -> list[TInner]: ...
+        def __new__(
+            cls, values: type[TInner] | Iterable[TInner]
+        ) -> PyAnySerdeType.ENUM[TInner]:
+            """
+            values: an Enum class (all of its members are used) or the list of allowed values (e.g. the arguments of a Literal). Each value is serialized as its index in this list and must be hashable.
+            """
+            ...
+
     @final
     class FLOAT(PyAnySerdeType[float]):
         __match_args__ = ()
 
         def __new__(cls) -> PyAnySerdeType.FLOAT: ...
 
+    @final
+    class FROZENSET(PyAnySerdeType[frozenset[TInner]], Generic[TInner]):
+        __match_args__ = ("items_serde_type",)
+
+        @property
+        def items_serde_type(self) -> PyAnySerdeType[TInner]: ...
+        def __new__(
+            cls, items_serde_type: PyAnySerdeType[TInner]
+        ) -> PyAnySerdeType.FROZENSET[TInner]: ...
+
+    @final
+    class HOMOGENEOUSTUPLE(PyAnySerdeType[tuple[TInner, ...]], Generic[TInner]):
+        __match_args__ = ("items_serde_type",)
+
+        @property
+        def items_serde_type(self) -> PyAnySerdeType[TInner]: ...
+        def __new__(
+            cls, items_serde_type: PyAnySerdeType[TInner]
+        ) -> PyAnySerdeType.HOMOGENEOUSTUPLE[TInner]: ...
+
     @final
     class INT(PyAnySerdeType[int]):
         __match_args__ = ()
@@ -244,6 +530,42 @@ class PyAnySerdeType(Generic[T]):
             cls, items_serde_type: PyAnySerdeType[TInner]
         ) -> PyAnySerdeType.LIST[TInner]: ...
 
+    @final
+    class MEMO(PyAnySerdeType[TInner], Generic[TInner]):
+        __match_args__ = ("value_serde_type",)
+
+        @property
+        def value_serde_type(self) -> PyAnySerdeType[TInner]: ...
+        def __new__(
+            cls, value_serde_type: PyAnySerdeType[TInner]
+        ) -> PyAnySerdeType.MEMO[TInner]:
+            """
+            value_serde_type: the serde to be used with the value. If the same object (by identity) was already serialized by a MEMO in the same append call, only a reference to it is written, and retrieving the data returns the same object in both places. The memo is shared by all MEMOs nested inside the outermost MEMO, so to deduplicate e.g. the items of a list, wrap both the list and its items: MEMO(LIST(MEMO(...))). Cyclic references are not supported.
+            """
+            ...
+
+    @final
+    class NAMEDTUPLE(PyAnySerdeType[TInner], Generic[TInner]):
+        __match_args__ = (
+            "clazz",
+            "item_serde_types",
+        )
+
+        @property
+        def clazz(self) -> TInner: ...
+        @property
+        def item_serde_types(self) -> list[PyAnySerdeType[Any]]: ...
+        def __new__(
+            cls,
+            clazz: type[TInner],
+            item_serde_types: Sequence[PyAnySerdeType[Any]],
+        ) -> PyAnySerdeType.NAMEDTUPLE[TInner]:
+            """
+            clazz: the NamedTuple class to be serialized
+            item_serde_types: the serde to be used with each field of the NamedTuple, in field order. The serialized format is the same as TUPLE with these item serde types.
+            """
+            ...
+
     @final
     class NUMPY(PyAnySerdeType[NDArray[DType]], Generic[DType]):
         __match_args__ = (
@@ -269,12 +591,58 @@ class PyAnySerdeType(Generic[T]):
             cls, value_serde_type: PyAnySerdeType[TInner]
         ) -> PyAnySerdeType.OPTION[TInner]: ...
 
+    @final
+    class ORDEREDDICT(
+        PyAnySerdeType[OrderedDict[KeysT, ValuesT]], Generic[KeysT, ValuesT]
+    ):
+        __match_args__ = (
+            "keys_serde_type",
+            "values_serde_type",
+        )
+
+        @property
+        def keys_serde_type(self) -> PyAnySerdeType[KeysT]: ...
+        @property
+        def values_serde_type(self) -> PyAnySerdeType[ValuesT]: ...
+        def __new__(
+            cls,
+            keys_serde_type: PyAnySerdeType[KeysT],
+            values_serde_type: PyAnySerdeType[ValuesT],
+        ) -> PyAnySerdeType.ORDEREDDICT[KeysT, ValuesT]: ...
+
     @final
     class PICKLE(PyAnySerdeType[Any]):
         __match_args__ = ()
 
         def __new__(cls) -> PyAnySerdeType.PICKLE: ...
 
+    @final
+    class PRIMITIVELIST(PyAnySerdeType[TInner], Generic[TInner]):
+        __match_args__ = (
+            "items_serde_type",
+            "length",
+            "as_tuple",
+        )
+
+        @property
+        def items_serde_type(self) -> PyAnySerdeType[Any]: ...
+        @property
+        def length(self) -> int | None: ...
+        @property
+        def as_tuple(self) -> bool: ...
+        def __new__(
+            cls,
+            items_serde_type: PyAnySerdeType[Any],
+            length: int | None = None,
+            as_tuple: bool = False,
+        ) -> PyAnySerdeType.PRIMITIVELIST[TInner]:
+            """
+            items_serde_type: one of BOOL, FLOAT, or INT. The items are written back to back as packed scalars.
+            length: if provided, every sequence must have exactly this many items and no length prefix is written
+            as_tuple: if True, sequences are retrieved as tuples rather than lists (either is accepted when serializing)
+            """
+            ...
+
     @final
     class PYTHONSERDE(PyAnySerdeType[TInner], Generic[TInner]):
         __match_args__ = ("python_serde",)
@@ -285,6 +653,18 @@ class PyAnySerdeType(Generic[T]):
             cls, python_serde: PythonSerde[TInner]
         ) -> PyAnySerdeType.PYTHONSERDE[TInner]: ...
 
+    @final
+    class REF(PyAnySerdeType[Any]):
+        __match_args__ = ("name",)
+
+        @property
+        def name(self) -> str: ...
+        def __new__(cls, name: str) -> PyAnySerdeType.REF:
+            """
+            name: the name of a definition in a DEFINITIONS containing this serde type
+            """
+            ...
+
     @final
     class SET(PyAnySerdeType[set[TInner]], Generic[TInner]):
         __match_args__ = ("items_serde_type",)
@@ -313,16 +693,41 @@ class PyAnySerdeType(Generic[T]):
 
     @final
     class TYPEDDICT(PyAnySerdeType[TInner], Generic[TInner]):
-        __match_args__ = ("key_serde_type_dict",)
+        __match_args__ = (
+            "key_serde_type_dict",
+            "optional_keys",
+            "extra",
+            "alphabetical_field_order",
+            "schema",
+        )
 
         @property
         def key_serde_type_dict(
             self,
         ) -> dict[str, PyAnySerdeType[Any]]: ...
+        @property
+        def optional_keys(self) -> list[str]: ...
+        @property
+        def extra(self) -> ExtraPolicy: ...
+        @property
+        def alphabetical_field_order(self) -> bool: ...
+        @property
+        def schema(self) -> SchemaPolicy: ...
         def __new__(
             cls,
             key_serde_type_dict: Mapping[str, PyAnySerdeType[Any]],
-        ) -> PyAnySerdeType.TYPEDDICT[TInner]: ...
+            optional_keys: Sequence[str] = (),
+            extra: ExtraPolicy = ExtraPolicy.IGNORE(),
+            alphabetical_field_order: bool = False,
+            schema: SchemaPolicy = SchemaPolicy.FIXED(),
+        ) -> PyAnySerdeType.TYPEDDICT[TInner]:
+            """
+            optional_keys: keys of key_serde_type_dict which may be absent (e.g. NotRequired keys, or keys of a TypedDict with total=False). Absent keys are omitted from the retrieved dict.
+            extra: defines what to do with keys which are not in key_serde_type_dict
+            alphabetical_field_order: keys are serialized in the order of key_serde_type_dict by default. Set this to serialize them in alphabetical order instead, which was the behavior of older versions.
+            schema: defines whether the serialized keys must exactly match key_serde_type_dict, or whether keys can be added and removed over time
+            """
+            ...
 
     @final
     class UNION(PyAnySerdeType[Any]):
@@ -340,3 +745,173 @@ class PyAnySerdeType(Generic[T]):
             option_serde_types: Sequence[PyAnySerdeType[Any]],
             option_choice_fn: Callable[[Any], int],
         ) -> PyAnySerdeType.UNION: ...
+
+@final
+class ArrowIpcExporter(Generic[T]):
+    """
+    Converts batches of objects of one PyAnySerdeType (such as retrieved objects) into the record batches of an Arrow IPC stream, which can be read by pyarrow (e.g. with pyarrow.ipc.open_stream) but doesn't need it. The fields of a DATACLASS, TYPEDDICT, TUPLE, or NAMEDTUPLE are the columns of the stream, and other values are in a single column named "value".
+
+    TYPEDDICT and DATACLASS map to structs, TUPLE to a struct with fields named by index and NAMEDTUPLE to a struct with the fields of the named tuple, ENUM to a dictionary of its values (or the values of its enum members, which must all have the same bool, int, float, str, or bytes type), sequence and set types to lists (or fixed-size lists for PRIMITIVELIST with a length), dict types to maps, NUMPY with a STATIC config to nested fixed-size lists of its shape, and OPTION to a nullable type. CHECKSUMMED, COMPRESSED, and MEMO map to the type of their values. Serde types whose values have no fixed Arrow type (such as PICKLE, PYTHONSERDE, DYNAMIC, and UNION) raise a ValueError.
+    """
+
+    def __new__(cls, serde_type: PyAnySerdeType[T]) -> ArrowIpcExporter[T]: ...
+    def write_batch(self, objs: Iterable[T]) -> None:
+        """
+        Writes the objects as one record batch of the stream
+        """
+        ...
+
+    def finish(self) -> bytes:
+        """
+        Ends the stream and returns its bytes. The exporter can't be written to afterwards.
+        """
+        ...
+
+@final
+class RecordWriter(Generic[T]):
+    """
+    Writes a sequence of objects serialized by one PyAnySerdeType to a record file. The file starts with the schema of the serde type and its fingerprint, followed by the length-prefixed records. Can be used as a context manager, which closes the writer on exit.
+    """
+
+    def __new__(
+        cls,
+        path: str | os.PathLike[str],
+        serde_type: PyAnySerdeType[T],
+        checksums: bool = True,
+        index: bool = True,
+    ) -> RecordWriter[T]:
+        """
+        path: the path of the record file, which is created (or truncated)
+        serde_type: the serde used to serialize the records
+        checksums: whether to write a CRC32C checksum of each record, which is verified when the record is read
+        index: whether to write an index of the records when the writer is closed, which allows RecordReader to seek without scanning the file
+        """
+        ...
+
+    @property
+    def fingerprint(self) -> int: ...
+    def write(self, obj: T) -> None: ...
+    def close(self) -> None:
+        """
+        Writes the index (if enabled) and flushes the file. This also happens when the writer is garbage collected.
+        """
+        ...
+
+    def __enter__(self) -> RecordWriter[T]: ...
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> None: ...
+
+@final
+class RecordReader(Generic[T]):
+    """
+    Reads the records of a record file written by RecordWriter, by iteration or by index. Files without an index (including files whose writer was not closed) are scanned as needed.
+    """
+
+    def __new__(
+        cls,
+        path: str | os.PathLike[str],
+        serde_type: PyAnySerdeType[T] | None = None,
+    ) -> RecordReader[T]:
+        """
+        path: the path of the record file
+        serde_type: the serde used to deserialize the records, which must match the fingerprint of the file. If None, the serde type is reconstructed from the schema in the file, which unpickles the classes and values it contains, so only do this with trusted files.
+        """
+        ...
+
+    @property
+    def serde_type(self) -> PyAnySerdeType[T]: ...
+    @property
+    def fingerprint(self) -> int: ...
+    def seek(self, record_idx: int) -> None:
+        """
+        Moves the reader to the record with index record_idx, so that it is the next record returned by iteration
+        """
+        ...
+
+    def __len__(self) -> int: ...
+    def __getitem__(self, record_idx: int) -> T: ...
+    def __iter__(self) -> RecordReader[T]: ...
+    def __next__(self) -> T: ...
+
+@final
+class RingBuffer(Generic[T]):
+    """
+    A bounded channel over a named shared memory segment, whose slots objects are serialized into using the serde of the channel. Any number of writers (in any number of processes) may send to the channel, but only one reader may receive from it at a time. A RingBuffer can only be used by the thread which created or opened it.
+    """
+
+    @staticmethod
+    def create(
+        serde_type: PyAnySerdeType[T],
+        n_slots: int,
+        slot_size: int,
+        name: str | None = None,
+    ) -> RingBuffer[T]:
+        """
+        Creates the shared memory segment of a new channel, which is removed when the returned RingBuffer is dropped.
+        serde_type: the serde used to serialize objects sent to the channel
+        n_slots: the number of objects which may be in the channel at a time
+        slot_size: the maximum size in bytes of a serialized object
+        name: the name of the shared memory segment, or None to generate a unique name
+        """
+        ...
+
+    @staticmethod
+    def open(serde_type: PyAnySerdeType[T], name: str) -> RingBuffer[T]:
+        """
+        Opens an existing channel by the name of its shared memory segment.
+        serde_type: the serde used to serialize objects sent to the channel, which must be equivalent to the serde used by the other ends of the channel
+        name: the name of the channel
+        """
+        ...
+
+    @property
+    def name(self) -> str: ...
+    @property
+    def n_slots(self) -> int: ...
+    @property
+    def slot_size(self) -> int: ...
+    def send(self, obj: T, timeout: float | None = None) -> None:
+        """
+        Serializes obj into the next free slot, waiting for one if the channel is full. Raises TimeoutError if no slot is freed within timeout seconds, and ValueError if the serialized object is larger than the slot size.
+        """
+        ...
+
+    def recv(self, timeout: float | None = None) -> T:
+        """
+        Retrieves the next object from the channel, waiting for one if the channel is empty. Raises TimeoutError if no object is sent within timeout seconds.
+        """
+        ...
+
+class SchemaPolicy:
+    """
+    Defines how the TYPEDDICT and DATACLASS serdes handle changes to their fields between the writer and the reader of serialized data.
+    """
+
+    @override
+    def __reduce__(self) -> tuple[SchemaPolicy, tuple[Any, ...]]: ...
+
+    @final
+    class FIXED(SchemaPolicy):
+        __match_args__ = ()
+
+        def __new__(cls) -> SchemaPolicy.FIXED:
+            """
+            Fields are written back to back with no metadata, so data can only be read with exactly the same fields it was written with
+            """
+            ...
+
+    @final
+    class VERSIONED(SchemaPolicy):
+        __match_args__ = ("field_defaults",)
+
+        @property
+        def field_defaults(self) -> dict[str, Any]: ...
+        def __new__(
+            cls, field_defaults: Mapping[str, Any] = {}
+        ) -> SchemaPolicy.VERSIONED:
+            """
+            Each field is written with its name and length, so data written with other fields can still be read. Fields which are not known to the reader are skipped, and fields which are missing from the data are filled with (a deep copy of) their value in field_defaults. Missing fields without a default raise an error, except for optional keys of a TYPEDDICT.
+            field_defaults: the default values of fields which may be missing from serialized data
+            """
+            ...
+
+    ...
@@ -0,0 +1,84 @@
+from collections import OrderedDict, defaultdict, deque
+
+from pyany_serde import PyAnySerdeType
+from pyany_serde.containers_tests import (  # pyright:  ignore [reportMissingImports]
+    deserialize,  # pyright: ignore [reportUnknownVariableType]
+    serialize,  # pyright: ignore [reportUnknownVariableType]
+)
+
+DEQUE_SERDE_TYPE: PyAnySerdeType[deque[int]] = PyAnySerdeType.DEQUE(
+    PyAnySerdeType.INT()
+)
+DEFAULTDICT_SERDE_TYPE: PyAnySerdeType[defaultdict[str, list[int]]] = (
+    PyAnySerdeType.DEFAULTDICT(
+        PyAnySerdeType.STRING(), PyAnySerdeType.LIST(PyAnySerdeType.INT())
+    )
+)
+
+
+def make_default_list() -> list[int]:
+    return [-1]
+
+
+def roundtrip(serde_type, obj):
+    return deserialize(serde_type, serialize(serde_type, obj))
+
+
+def test_deque():
+    obj = deque([1, 2, 3])
+    actual = roundtrip(DEQUE_SERDE_TYPE, obj)
+    assert type(actual) is deque
+    assert actual == obj
+    assert actual.maxlen is None
+
+
+def test_deque_maxlen():
+    obj = deque([1, 2, 3, 4], maxlen=3)
+    actual = roundtrip(DEQUE_SERDE_TYPE, obj)
+    assert type(actual) is deque
+    assert actual == deque([2, 3, 4])
+    assert actual.maxlen == 3
+    actual.append(5)
+    assert actual == deque([3, 4, 5])
+
+
+def test_deque_wrong_type():
+    try:
+        serialize(DEQUE_SERDE_TYPE, [1, 2, 3])
+        raise AssertionError("Expected a list to be rejected by DEQUE")
+    except TypeError:
+        pass
+
+
+def test_defaultdict():
+    obj = defaultdict(list, {"a": [1, 2], "b": []})
+    actual = roundtrip(DEFAULTDICT_SERDE_TYPE, obj)
+    assert type(actual) is defaultdict
+    assert actual == obj
+    assert actual.default_factory is list
+    assert actual["c"] == []
+
+
+def test_defaultdict_default_factories():
+    for default_factory in [None, make_default_list, OrderedDict]:
+        obj = defaultdict(default_factory, {"a": [1]})
+        actual = roundtrip(DEFAULTDICT_SERDE_TYPE, obj)
+        assert actual == obj
+        assert actual.default_factory is default_factory
+
+
+def test_defaultdict_default_factory_not_importable():
+    obj = defaultdict(lambda: [0], {"a": [1]})
+    try:
+        serialize(DEFAULTDICT_SERDE_TYPE, obj)
+        raise AssertionError("Expected a lambda default_factory to be rejected")
+    except ValueError:
+        pass
+
+
+def test_defaultdict_wrong_type():
+    try:
+        serialize(DEFAULTDICT_SERDE_TYPE, {"a": [1]})
+        raise AssertionError("Expected a dict to be rejected by DEFAULTDICT")
+    except TypeError:
+        pass
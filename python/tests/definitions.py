@@ -0,0 +1,59 @@
+from dataclasses import dataclass
+
+from pyany_serde import InitStrategy, PyAnySerdeType
+from pyany_serde.definitions_tests import (  # pyright:  ignore [reportMissingImports]
+    deserialize,  # pyright: ignore [reportUnknownVariableType]
+    serialize,  # pyright: ignore [reportUnknownVariableType]
+)
+
+
+@dataclass
+class Node:
+    value: int
+    children: list["Node"]
+
+
+def node_serde_type(max_depth: int) -> PyAnySerdeType[Node]:
+    return PyAnySerdeType.DEFINITIONS(
+        {
+            "Node": PyAnySerdeType.DATACLASS(
+                Node,
+                InitStrategy.ALL(),
+                {
+                    "value": PyAnySerdeType.INT(),
+                    "children": PyAnySerdeType.LIST(PyAnySerdeType.REF("Node")),
+                },
+            )
+        },
+        PyAnySerdeType.REF("Node"),
+        max_depth=max_depth,
+    )
+
+
+def make_chain(depth: int) -> Node:
+    node = Node(depth, [])
+    for value in reversed(range(depth)):
+        node = Node(value, [node])
+    return node
+
+
+def test_tree():
+    tree = Node(0, [Node(1, [Node(3, [])]), Node(2, [])])
+    assert deserialize(node_serde_type(8), serialize(node_serde_type(8), tree)) == tree
+
+
+def test_max_depth():
+    # make_chain(3) has 4 nested nodes, each of which is a REF
+    chain = make_chain(3)
+    data = serialize(node_serde_type(4), chain)
+    assert deserialize(node_serde_type(4), data) == chain
+    try:
+        deserialize(node_serde_type(3), data)
+        raise AssertionError("Expected retrieving too many nested REFs to fail")
+    except RecursionError:
+        pass
+    try:
+        serialize(node_serde_type(3), chain)
+        raise AssertionError("Expected appending too many nested REFs to fail")
+    except RecursionError:
+        pass
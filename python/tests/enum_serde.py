@@ -0,0 +1,77 @@
+from enum import Enum, IntEnum
+
+from pyany_serde import PyAnySerdeType
+from pyany_serde.enum_serde_tests import (  # pyright:  ignore [reportMissingImports]
+    deserialize,  # pyright: ignore [reportUnknownVariableType]
+    serialize,  # pyright: ignore [reportUnknownVariableType]
+)
+
+
+class Color(Enum):
+    RED = "red"
+    GREEN = "green"
+    BLUE = "blue"
+
+
+class Level(IntEnum):
+    LOW = 1
+    HIGH = 2
+
+
+def roundtrip(serde_type, obj):
+    return deserialize(serde_type, serialize(serde_type, obj))
+
+
+def test_enum_class():
+    serde_type = PyAnySerdeType.ENUM(Color)
+    for member in Color:
+        data = serialize(serde_type, member)
+        # Members are serialized as their index
+        assert len(data) == 1
+        assert deserialize(serde_type, data) is member
+    try:
+        serialize(serde_type, "red")
+        raise AssertionError("Expected the value of a member to be rejected")
+    except ValueError:
+        pass
+
+
+def test_literal_values():
+    serde_type = PyAnySerdeType.ENUM(["a", "b", 3, None])
+    for value in ["a", "b", 3, None]:
+        assert roundtrip(serde_type, value) == value
+    try:
+        serialize(serde_type, "c")
+        raise AssertionError("Expected a value which isn't in the ENUM to be rejected")
+    except ValueError:
+        pass
+
+
+def test_equal_values_of_different_types():
+    serde_type = PyAnySerdeType.ENUM([1, True, 1.0, 0, False])
+    for value in [1, True, 1.0, 0, False]:
+        actual = roundtrip(serde_type, value)
+        assert type(actual) is type(value)
+        assert actual == value
+    int_serde_type = PyAnySerdeType.ENUM([0, 1])
+    for value in [True, 1.0, Level.LOW]:
+        try:
+            serialize(int_serde_type, value)
+            raise AssertionError(f"Expected {value!r} to be rejected by ENUM([0, 1])")
+        except ValueError:
+            pass
+    level_serde_type = PyAnySerdeType.ENUM(Level)
+    assert roundtrip(level_serde_type, Level.HIGH) is Level.HIGH
+    try:
+        serialize(level_serde_type, 2)
+        raise AssertionError("Expected an int to be rejected by ENUM(Level)")
+    except ValueError:
+        pass
+
+
+def test_duplicate_values():
+    try:
+        serialize(PyAnySerdeType.ENUM(["a", "b", "a"]), "a")
+        raise AssertionError("Expected duplicate values to be rejected")
+    except ValueError:
+        pass
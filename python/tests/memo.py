@@ -0,0 +1,44 @@
+from pyany_serde import PyAnySerdeType
+from pyany_serde.memo_tests import (  # pyright:  ignore [reportMissingImports]
+    deserialize,  # pyright: ignore [reportUnknownVariableType]
+    serialize,  # pyright: ignore [reportUnknownVariableType]
+)
+
+MEMO_LIST_SERDE_TYPE: PyAnySerdeType[list[dict[str, int]]] = PyAnySerdeType.MEMO(
+    PyAnySerdeType.LIST(
+        PyAnySerdeType.MEMO(
+            PyAnySerdeType.DICT(PyAnySerdeType.STRING(), PyAnySerdeType.INT())
+        )
+    )
+)
+LIST_SERDE_TYPE: PyAnySerdeType[list[dict[str, int]]] = PyAnySerdeType.LIST(
+    PyAnySerdeType.DICT(PyAnySerdeType.STRING(), PyAnySerdeType.INT())
+)
+
+
+def test_shared_identity():
+    shared = {"x": 1, "y": 2}
+    obj = [shared, {"x": 1, "y": 2}, shared]
+    data = serialize(MEMO_LIST_SERDE_TYPE, obj)
+    actual = deserialize(MEMO_LIST_SERDE_TYPE, data)
+    assert actual == obj
+    assert actual[0] is actual[2]
+    # Equal but distinct objects are not deduplicated
+    assert actual[0] is not actual[1]
+    # Only a reference is written for the repeated object
+    assert len(data) < len(serialize(LIST_SERDE_TYPE, obj))
+
+
+def test_memo_scoped_to_one_call():
+    shared = {"x": 1}
+    data = serialize(MEMO_LIST_SERDE_TYPE, [shared])
+    first = deserialize(MEMO_LIST_SERDE_TYPE, data)
+    second = deserialize(MEMO_LIST_SERDE_TYPE, data)
+    assert first == second == [shared]
+    assert first[0] is not second[0]
+
+
+def test_without_memo():
+    shared = {"x": 1}
+    actual = deserialize(LIST_SERDE_TYPE, serialize(LIST_SERDE_TYPE, [shared, shared]))
+    assert actual[0] is not actual[1]
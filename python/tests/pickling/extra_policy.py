@@ -0,0 +1,24 @@
+import pickle
+
+from pyany_serde import ExtraPolicy, PyAnySerdeType
+from pyany_serde.pickling_extra_policy_tests import (  # pyright:  ignore [reportMissingImports]
+    validate_eq,  # pyright: ignore [reportUnknownVariableType]
+)
+
+
+def test_ignore():
+    expected = ExtraPolicy.IGNORE()
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_forbid():
+    expected = ExtraPolicy.FORBID()
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_passthrough():
+    expected = ExtraPolicy.PASSTHROUGH(fallback_serde_type=PyAnySerdeType.DYNAMIC())
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
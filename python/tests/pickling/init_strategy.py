@@ -22,3 +22,21 @@ def test_none():
     expected = InitStrategy.NONE()
     actual = pickle.loads(pickle.dumps(expected))
     validate_eq(expected, actual, "$")
+
+
+def test_auto():
+    expected = InitStrategy.AUTO()
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_bypass():
+    expected = InitStrategy.BYPASS()
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_modelconstruct():
+    expected = InitStrategy.MODELCONSTRUCT()
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
@@ -1,18 +1,26 @@
 import pickle
 import struct
+from collections import OrderedDict, defaultdict, deque
 from dataclasses import dataclass
-from typing import Any, Literal, TypedDict
+from enum import Enum
+from typing import Any, Literal, NamedTuple, TypedDict
 
 import numpy as np
 from numpy.typing import NDArray
-from pyany_serde import InitStrategy, NumpySerdeConfig, PyAnySerdeType
+from pyany_serde import (
+    ExtraPolicy,
+    InitStrategy,
+    NumpySerdeConfig,
+    PyAnySerdeType,
+    SchemaPolicy,
+)
 from pyany_serde.pickling_pyany_serde_type_tests import (  # pyright:  ignore [reportMissingImports]
     validate_eq,  # pyright: ignore [reportUnknownVariableType]
 )
 from pyany_serde.python_serde import (  # pyright: ignore [reportImplicitRelativeImport]
     PythonSerde,
 )
-from typing_extensions import override
+from typing_extensions import NotRequired, override
 
 
 class MyClass:
@@ -30,11 +38,32 @@ class MyDataclass:
     b: str
 
 
+@dataclass
+class MyNode:
+    value: int
+    children: list["MyNode"]
+
+
+class MyNamedTuple(NamedTuple):
+    a: int
+    b: str
+
+
+class MyEnum(Enum):
+    A = 1
+    B = "b"
+
+
 class MyTypedDict(TypedDict):
     a: int
     b: str
 
 
+class MyPartialTypedDict(TypedDict):
+    a: int
+    b: NotRequired[str]
+
+
 class MySerde(PythonSerde[MyClass]):
     fmt: str = "=ll"
 
@@ -89,12 +118,41 @@ def test_bytes():
     validate_eq(expected, actual, "$")
 
 
+def test_checksummed():
+    expected: PyAnySerdeType[list[int]] = PyAnySerdeType.CHECKSUMMED(
+        PyAnySerdeType.LIST(PyAnySerdeType.INT())
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_columnarlist():
+    expected: PyAnySerdeType[list[MyDataclass]] = PyAnySerdeType.COLUMNARLIST(
+        PyAnySerdeType.DATACLASS(
+            MyDataclass,
+            InitStrategy.ALL(),
+            {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.STRING()},
+        ),
+        as_columns=True,
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
 def test_complex():
     expected: PyAnySerdeType[complex] = PyAnySerdeType.COMPLEX()
     actual = pickle.loads(pickle.dumps(expected))
     validate_eq(expected, actual, "$")
 
 
+def test_compressed():
+    expected: PyAnySerdeType[list[int]] = PyAnySerdeType.COMPRESSED(
+        PyAnySerdeType.LIST(PyAnySerdeType.INT()), codec="zstd", level=6, min_size=64
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
 def test_dataclass():
     expected: PyAnySerdeType[MyDataclass] = PyAnySerdeType.DATACLASS(
         MyDataclass,
@@ -105,6 +163,75 @@ def test_dataclass():
     validate_eq(expected, actual, "$")
 
 
+def test_dataclass_extra_forbid():
+    expected: PyAnySerdeType[MyDataclass] = PyAnySerdeType.DATACLASS(
+        MyDataclass,
+        init_strategy=InitStrategy.ALL(),
+        field_serde_type_dict={"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.STRING()},
+        extra=ExtraPolicy.FORBID(),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_dataclass_alphabetical_field_order():
+    expected: PyAnySerdeType[MyDataclass] = PyAnySerdeType.DATACLASS(
+        MyDataclass,
+        init_strategy=InitStrategy.ALL(),
+        field_serde_type_dict={"b": PyAnySerdeType.STRING(), "a": PyAnySerdeType.INT()},
+        alphabetical_field_order=True,
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_dataclass_schema_versioned():
+    expected: PyAnySerdeType[MyDataclass] = PyAnySerdeType.DATACLASS(
+        MyDataclass,
+        init_strategy=InitStrategy.ALL(),
+        field_serde_type_dict={"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.STRING()},
+        schema=SchemaPolicy.VERSIONED(field_defaults={"b": ""}),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_defaultdict():
+    expected: PyAnySerdeType[defaultdict[str, list[int]]] = PyAnySerdeType.DEFAULTDICT(
+        keys_serde_type=PyAnySerdeType.STRING(),
+        values_serde_type=PyAnySerdeType.LIST(PyAnySerdeType.INT()),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_definitions():
+    expected: PyAnySerdeType[MyNode] = PyAnySerdeType.DEFINITIONS(
+        definitions={
+            "MyNode": PyAnySerdeType.DATACLASS(
+                MyNode,
+                init_strategy=InitStrategy.ALL(),
+                field_serde_type_dict={
+                    "value": PyAnySerdeType.INT(),
+                    "children": PyAnySerdeType.LIST(PyAnySerdeType.REF("MyNode")),
+                },
+            )
+        },
+        root_serde_type=PyAnySerdeType.REF("MyNode"),
+        max_depth=10,
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_deque():
+    expected: PyAnySerdeType[deque[int]] = PyAnySerdeType.DEQUE(
+        items_serde_type=PyAnySerdeType.INT(),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
 def test_dict():
     expected: PyAnySerdeType[dict[str, int]] = PyAnySerdeType.DICT(
         keys_serde_type=PyAnySerdeType.STRING(),
@@ -120,12 +247,40 @@ def test_dynamic():
     validate_eq(expected, actual, "$")
 
 
+def test_enum():
+    expected: PyAnySerdeType[MyEnum] = PyAnySerdeType.ENUM(MyEnum)
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_enum_literal():
+    expected: PyAnySerdeType[Literal["a", "b", 3]] = PyAnySerdeType.ENUM(["a", "b", 3])
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
 def test_float():
     expected: PyAnySerdeType[float] = PyAnySerdeType.FLOAT()
     actual = pickle.loads(pickle.dumps(expected))
     validate_eq(expected, actual, "$")
 
 
+def test_frozenset():
+    expected: PyAnySerdeType[frozenset[int]] = PyAnySerdeType.FROZENSET(
+        items_serde_type=PyAnySerdeType.INT(),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_homogeneoustuple():
+    expected: PyAnySerdeType[tuple[str, ...]] = PyAnySerdeType.HOMOGENEOUSTUPLE(
+        items_serde_type=PyAnySerdeType.STRING(),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
 def test_int():
     expected: PyAnySerdeType[int] = PyAnySerdeType.INT()
     actual = pickle.loads(pickle.dumps(expected))
@@ -140,6 +295,26 @@ def test_list():
     validate_eq(expected, actual, "$")
 
 
+def test_memo():
+    expected: PyAnySerdeType[list[int]] = PyAnySerdeType.MEMO(
+        value_serde_type=PyAnySerdeType.LIST(PyAnySerdeType.MEMO(PyAnySerdeType.INT())),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_namedtuple():
+    expected: PyAnySerdeType[MyNamedTuple] = PyAnySerdeType.NAMEDTUPLE(
+        MyNamedTuple,
+        item_serde_types=[
+            PyAnySerdeType.INT(),
+            PyAnySerdeType.STRING(),
+        ],
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
 def test_numpy_dynamic():
     expected: PyAnySerdeType[NDArray[np.int64]] = PyAnySerdeType.NUMPY(
         dtype=np.int64,
@@ -178,12 +353,39 @@ def test_option():
     validate_eq(expected, actual, "$")
 
 
+def test_ordereddict():
+    expected: PyAnySerdeType[OrderedDict[str, int]] = PyAnySerdeType.ORDEREDDICT(
+        keys_serde_type=PyAnySerdeType.STRING(),
+        values_serde_type=PyAnySerdeType.INT(),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
 def test_pickle():
     expected: PyAnySerdeType[Any] = PyAnySerdeType.PICKLE()
     actual = pickle.loads(pickle.dumps(expected))
     validate_eq(expected, actual, "$")
 
 
+def test_primitivelist():
+    expected: PyAnySerdeType[list[float]] = PyAnySerdeType.PRIMITIVELIST(
+        items_serde_type=PyAnySerdeType.FLOAT(),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_primitivelist_fixed_length():
+    expected: PyAnySerdeType[tuple[int, int, int]] = PyAnySerdeType.PRIMITIVELIST(
+        items_serde_type=PyAnySerdeType.INT(),
+        length=3,
+        as_tuple=True,
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
 def test_pythonserde():
     expected: PyAnySerdeType[MyClass] = PyAnySerdeType.PYTHONSERDE(
         python_serde=MySerde(),
@@ -225,6 +427,55 @@ def test_typeddict():
     validate_eq(expected, actual, "$")
 
 
+def test_typeddict_optional_keys():
+    expected: PyAnySerdeType[MyPartialTypedDict] = PyAnySerdeType.TYPEDDICT[
+        MyPartialTypedDict
+    ](
+        {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.STRING()},
+        optional_keys=["b"],
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_typeddict_extra_passthrough():
+    expected: PyAnySerdeType[MyTypedDict] = PyAnySerdeType.TYPEDDICT[MyTypedDict](
+        {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.STRING()},
+        extra=ExtraPolicy.PASSTHROUGH(PyAnySerdeType.DYNAMIC()),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_typeddict_declaration_order():
+    expected: PyAnySerdeType[MyTypedDict] = PyAnySerdeType.TYPEDDICT[MyTypedDict](
+        {"b": PyAnySerdeType.STRING(), "a": PyAnySerdeType.INT()}
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_typeddict_alphabetical_field_order():
+    expected: PyAnySerdeType[MyTypedDict] = PyAnySerdeType.TYPEDDICT[MyTypedDict](
+        {"b": PyAnySerdeType.STRING(), "a": PyAnySerdeType.INT()},
+        alphabetical_field_order=True,
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_typeddict_schema_versioned():
+    expected: PyAnySerdeType[MyPartialTypedDict] = PyAnySerdeType.TYPEDDICT[
+        MyPartialTypedDict
+    ](
+        {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.STRING()},
+        optional_keys=["b"],
+        schema=SchemaPolicy.VERSIONED(field_defaults={"a": 0}),
+    )
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
 def test_union():
     expected: PyAnySerdeType[int | str] = PyAnySerdeType.UNION(
         [PyAnySerdeType.INT(), PyAnySerdeType.STRING()],
@@ -0,0 +1,24 @@
+import pickle
+
+from pyany_serde import SchemaPolicy
+from pyany_serde.pickling_schema_policy_tests import (  # pyright:  ignore [reportMissingImports]
+    validate_eq,  # pyright: ignore [reportUnknownVariableType]
+)
+
+
+def test_fixed():
+    expected = SchemaPolicy.FIXED()
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_versioned():
+    expected = SchemaPolicy.VERSIONED()
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
+
+
+def test_versioned_field_defaults():
+    expected = SchemaPolicy.VERSIONED(field_defaults={"a": 0, "b": [1, 2]})
+    actual = pickle.loads(pickle.dumps(expected))
+    validate_eq(expected, actual, "$")
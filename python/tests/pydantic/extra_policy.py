@@ -0,0 +1,78 @@
+from typing import Any
+
+from pyany_serde import ExtraPolicy, PyAnySerdeType
+from pyany_serde.pydantic_extra_policy_tests import (  # pyright:  ignore [reportMissingImports]
+    validate_eq,  # pyright: ignore [reportUnknownVariableType]
+)
+
+from pydantic import BaseModel
+
+
+class MyModel(BaseModel):
+    my_field: ExtraPolicy
+
+
+def validate_model_construction_flows(
+    expected: ExtraPolicy, my_field_dict: dict[str, Any], model_json: str
+):
+    # constructor with instance
+    actual = MyModel(my_field=expected).my_field
+    validate_eq(expected, actual, "$")
+
+    # constructor with dict
+    actual = MyModel(my_field=my_field_dict).my_field  # pyright: ignore [reportArgumentType]
+    validate_eq(expected, actual, "$")
+
+    # validate json
+    actual = MyModel.model_validate_json(model_json).my_field
+    validate_eq(expected, actual, "$")
+
+    # validate instance
+    actual = MyModel.model_validate(MyModel(my_field=expected)).my_field
+    validate_eq(expected, actual, "$")
+
+    # validate dict
+    actual = MyModel.model_validate({"my_field": my_field_dict}).my_field
+    validate_eq(expected, actual, "$")
+
+
+def test_ignore():
+    expected = ExtraPolicy.IGNORE()
+    my_field_dict = {"type": "ignore"}
+    model_json = """
+{
+    "my_field": {
+        "type": "ignore"
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_forbid():
+    expected = ExtraPolicy.FORBID()
+    my_field_dict = {"type": "forbid"}
+    model_json = """
+{
+    "my_field": {
+        "type": "forbid"
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_passthrough():
+    expected = ExtraPolicy.PASSTHROUGH(PyAnySerdeType.DYNAMIC())
+    my_field_dict = {"type": "passthrough", "fallback_serde_type": {"type": "dynamic"}}
+    model_json = """
+{
+    "my_field": {
+        "type": "passthrough",
+        "fallback_serde_type": {
+            "type": "dynamic"
+        }
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
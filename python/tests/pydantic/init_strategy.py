@@ -74,3 +74,42 @@ def test_none():
 }
 """
     validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_auto():
+    expected = InitStrategy.AUTO()
+    my_field_dict = {"type": "auto"}
+    model_json = """
+{
+    "my_field": {
+        "type": "auto"
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_bypass():
+    expected = InitStrategy.BYPASS()
+    my_field_dict = {"type": "bypass"}
+    model_json = """
+{
+    "my_field": {
+        "type": "bypass"
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_modelconstruct():
+    expected = InitStrategy.MODELCONSTRUCT()
+    my_field_dict = {"type": "modelconstruct"}
+    model_json = """
+{
+    "my_field": {
+        "type": "modelconstruct"
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
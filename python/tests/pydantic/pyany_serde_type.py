@@ -1,18 +1,26 @@
 import pickle
 import struct
+from collections import OrderedDict, defaultdict, deque
 from dataclasses import dataclass
-from typing import Any, Literal, TypedDict
+from enum import Enum
+from typing import Any, Literal, NamedTuple, TypedDict
 
 import numpy as np
 from numpy.typing import NDArray
-from pyany_serde import InitStrategy, NumpySerdeConfig, PyAnySerdeType
+from pyany_serde import (
+    ExtraPolicy,
+    InitStrategy,
+    NumpySerdeConfig,
+    PyAnySerdeType,
+    SchemaPolicy,
+)
 from pyany_serde.pydantic_pyany_serde_type_tests import (  # pyright:  ignore [reportMissingImports]
     validate_eq,  # pyright: ignore [reportUnknownVariableType]
 )
 from pyany_serde.python_serde import (  # pyright: ignore [reportImplicitRelativeImport]
     PythonSerde,
 )
-from typing_extensions import override
+from typing_extensions import NotRequired, override
 
 from pydantic import BaseModel
 
@@ -36,11 +44,32 @@ class MyDataclass:
     b: str
 
 
+@dataclass
+class MyNode:
+    value: int
+    children: list["MyNode"]
+
+
+class MyNamedTuple(NamedTuple):
+    a: int
+    b: str
+
+
+class MyEnum(Enum):
+    A = 1
+    B = "b"
+
+
 class MyTypedDict(TypedDict):
     a: int
     b: str
 
 
+class MyPartialTypedDict(TypedDict):
+    a: int
+    b: NotRequired[str]
+
+
 class MySerde(PythonSerde[MyClass]):
     fmt: str = "=ll"
 
@@ -133,6 +162,62 @@ def test_bytes():
     validate_model_construction_flows(expected, my_field_dict, model_json)
 
 
+def test_checksummed():
+    expected: PyAnySerdeType[int] = PyAnySerdeType.CHECKSUMMED(PyAnySerdeType.INT())
+    my_field_dict = {
+        "type": "checksummed",
+        "value_serde_type": {"type": "int"},
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "checksummed",
+        "value_serde_type": {
+            "type": "int"
+        }
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_columnarlist():
+    expected: PyAnySerdeType[dict[str, Any]] = PyAnySerdeType.COLUMNARLIST(
+        PyAnySerdeType.TYPEDDICT(
+            {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.FLOAT()}
+        ),
+        as_columns=True,
+    )
+    my_field_dict = {
+        "type": "columnarlist",
+        "items_serde_type": {
+            "type": "typeddict",
+            "key_serde_type_dict": {"a": {"type": "int"}, "b": {"type": "float"}},
+        },
+        "as_columns": True,
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "columnarlist",
+        "items_serde_type": {
+            "type": "typeddict",
+            "key_serde_type_dict": {
+                "a": {
+                    "type": "int"
+                },
+                "b": {
+                    "type": "float"
+                }
+            }
+        },
+        "as_columns": true
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
 def test_complex():
     expected: PyAnySerdeType[complex] = PyAnySerdeType.COMPLEX()
     my_field_dict = {"type": "complex"}
@@ -146,6 +231,37 @@ def test_complex():
     validate_model_construction_flows(expected, my_field_dict, model_json)
 
 
+def test_compressed():
+    expected: PyAnySerdeType[list[int]] = PyAnySerdeType.COMPRESSED(
+        PyAnySerdeType.LIST(PyAnySerdeType.INT()), codec="zstd", level=6
+    )
+    my_field_dict = {
+        "type": "compressed",
+        "value_serde_type": {
+            "type": "list",
+            "items_serde_type": {"type": "int"},
+        },
+        "codec": "zstd",
+        "level": 6,
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "compressed",
+        "value_serde_type": {
+            "type": "list",
+            "items_serde_type": {
+                "type": "int"
+            }
+        },
+        "codec": "zstd",
+        "level": 6
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
 def test_dataclass():
     expected: PyAnySerdeType[MyDataclass] = PyAnySerdeType.DATACLASS(
         MyDataclass,
@@ -181,6 +297,121 @@ def test_dataclass():
     validate_model_construction_flows(expected, my_field_dict, model_json)
 
 
+def test_defaultdict():
+    expected: PyAnySerdeType[defaultdict[str, list[int]]] = PyAnySerdeType.DEFAULTDICT(
+        PyAnySerdeType.STRING(),
+        PyAnySerdeType.LIST(PyAnySerdeType.INT()),
+    )
+    my_field_dict = {
+        "type": "defaultdict",
+        "keys_serde_type": {"type": "string"},
+        "values_serde_type": {"type": "list", "items_serde_type": {"type": "int"}},
+    }
+    model_json = f"""
+{{
+    "my_field": {{
+        "type": "defaultdict",
+        "keys_serde_type": {{
+            "type": "string"
+        }},
+        "values_serde_type": {{
+            "type": "list",
+            "items_serde_type": {{
+                "type": "int"
+            }}
+        }}
+    }}
+}}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_definitions():
+    expected: PyAnySerdeType[MyNode] = PyAnySerdeType.DEFINITIONS(
+        {
+            "MyNode": PyAnySerdeType.DATACLASS(
+                MyNode,
+                InitStrategy.ALL(),
+                {
+                    "value": PyAnySerdeType.INT(),
+                    "children": PyAnySerdeType.LIST(PyAnySerdeType.REF("MyNode")),
+                },
+            )
+        },
+        PyAnySerdeType.REF("MyNode"),
+    )
+    my_field_dict = {
+        "type": "definitions",
+        "definitions": {
+            "MyNode": {
+                "type": "dataclass",
+                "dataclass_pkl": pickle.dumps(MyNode).hex(),
+                "init_strategy": {"type": "all"},
+                "field_serde_type_dict": {
+                    "value": {"type": "int"},
+                    "children": {
+                        "type": "list",
+                        "items_serde_type": {"type": "ref", "name": "MyNode"},
+                    },
+                },
+            }
+        },
+        "root_serde_type": {"type": "ref", "name": "MyNode"},
+    }
+    model_json = f"""
+{{
+    "my_field": {{
+        "type": "definitions",
+        "definitions": {{
+            "MyNode": {{
+                "type": "dataclass",
+                "dataclass_pkl": "{pickle.dumps(MyNode).hex()}",
+                "init_strategy": {{
+                    "type": "all"
+                }},
+                "field_serde_type_dict": {{
+                    "value": {{
+                        "type": "int"
+                    }},
+                    "children": {{
+                        "type": "list",
+                        "items_serde_type": {{
+                            "type": "ref",
+                            "name": "MyNode"
+                        }}
+                    }}
+                }}
+            }}
+        }},
+        "root_serde_type": {{
+            "type": "ref",
+            "name": "MyNode"
+        }}
+    }}
+}}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_deque():
+    expected: PyAnySerdeType[deque[int]] = PyAnySerdeType.DEQUE(PyAnySerdeType.INT())
+    my_field_dict = {
+        "type": "deque",
+        "items_serde_type": {"type": "int"},
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "deque",
+        "items_serde_type": {
+            "type": "int"
+        }
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
 def test_dict():
     expected: PyAnySerdeType[dict[str, int]] = PyAnySerdeType.DICT(
         PyAnySerdeType.STRING(),
@@ -220,6 +451,23 @@ def test_dynamic():
     validate_model_construction_flows(expected, my_field_dict, model_json)
 
 
+def test_enum():
+    expected: PyAnySerdeType[MyEnum] = PyAnySerdeType.ENUM(MyEnum)
+    my_field_dict = {
+        "type": "enum",
+        "values_pkl": pickle.dumps(list(MyEnum)).hex(),
+    }
+    model_json = f"""
+{{
+    "my_field": {{
+        "type": "enum",
+        "values_pkl": "{pickle.dumps(list(MyEnum)).hex()}"
+    }}
+}}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
 def test_float():
     expected: PyAnySerdeType[float] = PyAnySerdeType.FLOAT()
     my_field_dict = {"type": "float"}
@@ -233,6 +481,46 @@ def test_float():
     validate_model_construction_flows(expected, my_field_dict, model_json)
 
 
+def test_frozenset():
+    expected: PyAnySerdeType[frozenset[int]] = PyAnySerdeType.FROZENSET(PyAnySerdeType.INT())
+    my_field_dict = {
+        "type": "frozenset",
+        "items_serde_type": {"type": "int"},
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "frozenset",
+        "items_serde_type": {
+            "type": "int"
+        }
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_homogeneoustuple():
+    expected: PyAnySerdeType[tuple[str, ...]] = PyAnySerdeType.HOMOGENEOUSTUPLE(
+        PyAnySerdeType.STRING()
+    )
+    my_field_dict = {
+        "type": "homogeneoustuple",
+        "items_serde_type": {"type": "string"},
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "homogeneoustuple",
+        "items_serde_type": {
+            "type": "string"
+        }
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
 def test_int():
     expected: PyAnySerdeType[int] = PyAnySerdeType.INT()
     my_field_dict = {"type": "int"}
@@ -265,6 +553,57 @@ def test_list():
     validate_model_construction_flows(expected, my_field_dict, model_json)
 
 
+def test_memo():
+    expected: PyAnySerdeType[int] = PyAnySerdeType.MEMO(PyAnySerdeType.INT())
+    my_field_dict = {
+        "type": "memo",
+        "value_serde_type": {"type": "int"},
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "memo",
+        "value_serde_type": {
+            "type": "int"
+        }
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_namedtuple():
+    expected: PyAnySerdeType[MyNamedTuple] = PyAnySerdeType.NAMEDTUPLE(
+        MyNamedTuple,
+        [PyAnySerdeType.INT(), PyAnySerdeType.STRING()],
+    )
+    my_field_dict = {
+        "type": "namedtuple",
+        "namedtuple_pkl": pickle.dumps(MyNamedTuple).hex(),
+        "item_serde_types": [
+            {"type": "int"},
+            {"type": "string"},
+        ],
+    }
+    model_json = f"""
+{{
+    "my_field": {{
+        "type": "namedtuple",
+        "namedtuple_pkl": "{pickle.dumps(MyNamedTuple).hex()}",
+        "item_serde_types": [
+            {{
+                "type": "int"
+            }},
+            {{
+                "type": "string"
+            }}
+        ]
+    }}
+}}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
 def test_numpy_dynamic():
     expected: PyAnySerdeType[NDArray[np.int64]] = PyAnySerdeType.NUMPY(
         dtype=np.int64,
@@ -364,6 +703,32 @@ def test_option():
     validate_model_construction_flows(expected, my_field_dict, model_json)
 
 
+def test_ordereddict():
+    expected: PyAnySerdeType[OrderedDict[str, int]] = PyAnySerdeType.ORDEREDDICT(
+        PyAnySerdeType.STRING(),
+        PyAnySerdeType.INT(),
+    )
+    my_field_dict = {
+        "type": "ordereddict",
+        "keys_serde_type": {"type": "string"},
+        "values_serde_type": {"type": "int"},
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "ordereddict",
+        "keys_serde_type": {
+            "type": "string"
+        },
+        "values_serde_type": {
+            "type": "int"
+        }
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
 def test_pickle():
     expected: PyAnySerdeType[Any] = PyAnySerdeType.PICKLE()
     my_field_dict = {"type": "pickle"}
@@ -377,6 +742,31 @@ def test_pickle():
     validate_model_construction_flows(expected, my_field_dict, model_json)
 
 
+def test_primitivelist():
+    expected: PyAnySerdeType[tuple[int, int, int]] = PyAnySerdeType.PRIMITIVELIST(
+        PyAnySerdeType.INT(), length=3, as_tuple=True
+    )
+    my_field_dict = {
+        "type": "primitivelist",
+        "items_serde_type": {"type": "int"},
+        "length": 3,
+        "as_tuple": True,
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "primitivelist",
+        "items_serde_type": {
+            "type": "int"
+        },
+        "length": 3,
+        "as_tuple": true
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
 def test_pythonserde():
     expected: PyAnySerdeType[MyClass] = PyAnySerdeType.PYTHONSERDE(MySerde())
     my_field_dict = {
@@ -481,6 +871,136 @@ def test_typeddict():
     validate_model_construction_flows(expected, my_field_dict, model_json)
 
 
+def test_typeddict_optional_keys():
+    expected: PyAnySerdeType[MyPartialTypedDict] = PyAnySerdeType.TYPEDDICT(
+        {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.STRING()},
+        optional_keys=["b"],
+    )
+    my_field_dict = {
+        "type": "typeddict",
+        "key_serde_type_dict": {"a": {"type": "int"}, "b": {"type": "string"}},
+        "optional_keys": ["b"],
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "typeddict",
+        "key_serde_type_dict": {
+            "a": {
+                "type": "int"
+            },
+            "b": {
+                "type": "string"
+            }
+        },
+        "optional_keys": [
+            "b"
+        ]
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_typeddict_extra_passthrough():
+    expected: PyAnySerdeType[MyTypedDict] = PyAnySerdeType.TYPEDDICT(
+        {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.STRING()},
+        extra=ExtraPolicy.PASSTHROUGH(PyAnySerdeType.DYNAMIC()),
+    )
+    my_field_dict = {
+        "type": "typeddict",
+        "key_serde_type_dict": {"a": {"type": "int"}, "b": {"type": "string"}},
+        "extra": {"type": "passthrough", "fallback_serde_type": {"type": "dynamic"}},
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "typeddict",
+        "key_serde_type_dict": {
+            "a": {
+                "type": "int"
+            },
+            "b": {
+                "type": "string"
+            }
+        },
+        "extra": {
+            "type": "passthrough",
+            "fallback_serde_type": {
+                "type": "dynamic"
+            }
+        }
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_typeddict_alphabetical_field_order():
+    expected: PyAnySerdeType[MyTypedDict] = PyAnySerdeType.TYPEDDICT(
+        {"b": PyAnySerdeType.STRING(), "a": PyAnySerdeType.INT()},
+        alphabetical_field_order=True,
+    )
+    my_field_dict = {
+        "type": "typeddict",
+        "key_serde_type_dict": {"b": {"type": "string"}, "a": {"type": "int"}},
+        "alphabetical_field_order": True,
+    }
+    model_json = """
+{
+    "my_field": {
+        "type": "typeddict",
+        "key_serde_type_dict": {
+            "b": {
+                "type": "string"
+            },
+            "a": {
+                "type": "int"
+            }
+        },
+        "alphabetical_field_order": true
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_typeddict_schema_versioned():
+    field_defaults = {"a": 0}
+    expected: PyAnySerdeType[MyTypedDict] = PyAnySerdeType.TYPEDDICT(
+        {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.STRING()},
+        schema=SchemaPolicy.VERSIONED(field_defaults),
+    )
+    my_field_dict = {
+        "type": "typeddict",
+        "key_serde_type_dict": {"a": {"type": "int"}, "b": {"type": "string"}},
+        "schema": {
+            "type": "versioned",
+            "field_defaults_pkl": pickle.dumps(field_defaults).hex(),
+        },
+    }
+    model_json = f"""
+{{
+    "my_field": {{
+        "type": "typeddict",
+        "key_serde_type_dict": {{
+            "a": {{
+                "type": "int"
+            }},
+            "b": {{
+                "type": "string"
+            }}
+        }},
+        "schema": {{
+            "type": "versioned",
+            "field_defaults_pkl": "{pickle.dumps(field_defaults).hex()}"
+        }}
+    }}
+}}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
 def test_union():
     expected: PyAnySerdeType[int | str] = PyAnySerdeType.UNION(
         [PyAnySerdeType.INT(), PyAnySerdeType.STRING()],
@@ -0,0 +1,68 @@
+import pickle
+from typing import Any
+
+from pyany_serde import SchemaPolicy
+from pyany_serde.pydantic_schema_policy_tests import (  # pyright:  ignore [reportMissingImports]
+    validate_eq,  # pyright: ignore [reportUnknownVariableType]
+)
+
+from pydantic import BaseModel
+
+
+class MyModel(BaseModel):
+    my_field: SchemaPolicy
+
+
+def validate_model_construction_flows(
+    expected: SchemaPolicy, my_field_dict: dict[str, Any], model_json: str
+):
+    # constructor with instance
+    actual = MyModel(my_field=expected).my_field
+    validate_eq(expected, actual, "$")
+
+    # constructor with dict
+    actual = MyModel(my_field=my_field_dict).my_field  # pyright: ignore [reportArgumentType]
+    validate_eq(expected, actual, "$")
+
+    # validate json
+    actual = MyModel.model_validate_json(model_json).my_field
+    validate_eq(expected, actual, "$")
+
+    # validate instance
+    actual = MyModel.model_validate(MyModel(my_field=expected)).my_field
+    validate_eq(expected, actual, "$")
+
+    # validate dict
+    actual = MyModel.model_validate({"my_field": my_field_dict}).my_field
+    validate_eq(expected, actual, "$")
+
+
+def test_fixed():
+    expected = SchemaPolicy.FIXED()
+    my_field_dict = {"type": "fixed"}
+    model_json = """
+{
+    "my_field": {
+        "type": "fixed"
+    }
+}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
+
+
+def test_versioned():
+    field_defaults = {"a": 0, "b": [1, 2]}
+    expected = SchemaPolicy.VERSIONED(field_defaults)
+    my_field_dict = {
+        "type": "versioned",
+        "field_defaults_pkl": pickle.dumps(field_defaults).hex(),
+    }
+    model_json = f"""
+{{
+    "my_field": {{
+        "type": "versioned",
+        "field_defaults_pkl": "{pickle.dumps(field_defaults).hex()}"
+    }}
+}}
+"""
+    validate_model_construction_flows(expected, my_field_dict, model_json)
@@ -0,0 +1,171 @@
+import os
+import tempfile
+from dataclasses import dataclass
+
+from pyany_serde import (
+    ChecksumError,
+    InitStrategy,
+    PyAnySerdeType,
+    RecordReader,
+    RecordWriter,
+)
+
+
+@dataclass
+class MyDataclass:
+    idx: int
+    name: str
+    values: list[float]
+
+
+MY_DATACLASS_SERDE_TYPE: PyAnySerdeType[MyDataclass] = PyAnySerdeType.DATACLASS(
+    MyDataclass,
+    InitStrategy.ALL(),
+    {
+        "idx": PyAnySerdeType.INT(),
+        "name": PyAnySerdeType.STRING(),
+        "values": PyAnySerdeType.LIST(PyAnySerdeType.FLOAT()),
+    },
+)
+
+OBJS = [MyDataclass(idx, f"obj{idx}", [float(idx)] * (idx % 5)) for idx in range(20)]
+
+
+def write_records(path: str, checksums: bool = True, index: bool = True):
+    with RecordWriter(path, MY_DATACLASS_SERDE_TYPE, checksums=checksums, index=index) as writer:
+        for obj in OBJS:
+            writer.write(obj)
+
+
+def test_roundtrip():
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        for checksums in [True, False]:
+            for index in [True, False]:
+                path = os.path.join(tmp_dir, f"records_{checksums}_{index}.bin")
+                write_records(path, checksums, index)
+                reader = RecordReader(path, MY_DATACLASS_SERDE_TYPE)
+                assert list(reader) == OBJS
+                assert len(reader) == len(OBJS)
+                assert reader[7] == OBJS[7]
+                assert reader[-1] == OBJS[-1]
+                assert reader[0] == OBJS[0]
+                # Iteration continues from the last record read
+                assert next(reader) == OBJS[1]
+                reader.seek(18)
+                assert list(reader) == OBJS[18:]
+                reader.seek(len(OBJS))
+                assert list(reader) == []
+                try:
+                    reader[len(OBJS)]
+                    raise AssertionError("Expected IndexError")
+                except IndexError:
+                    pass
+
+
+def test_seek_without_reading_first():
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        path = os.path.join(tmp_dir, "records.bin")
+        write_records(path, index=False)
+        reader = RecordReader(path, MY_DATACLASS_SERDE_TYPE)
+        reader.seek(12)
+        assert next(reader) == OBJS[12]
+        assert reader[3] == OBJS[3]
+        assert list(reader) == OBJS[4:]
+
+
+def test_schema_from_file():
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        path = os.path.join(tmp_dir, "records.bin")
+        write_records(path)
+        reader = RecordReader(path)
+        assert list(reader) == OBJS
+        assert RecordReader(path, reader.serde_type).fingerprint == reader.fingerprint
+
+
+def test_fingerprint_mismatch():
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        path = os.path.join(tmp_dir, "records.bin")
+        write_records(path)
+        try:
+            RecordReader(path, PyAnySerdeType.INT())
+            raise AssertionError("Expected ValueError")
+        except ValueError:
+            pass
+
+
+def test_checksum_failure():
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        path = os.path.join(tmp_dir, "records.bin")
+        write_records(path)
+        with open(path, "rb") as f:
+            data = bytearray(f.read())
+        # Corrupt the name of record 5
+        data[data.index(b"obj5")] ^= 0xFF
+        with open(path, "wb") as f:
+            f.write(data)
+        reader = RecordReader(path, MY_DATACLASS_SERDE_TYPE)
+        assert reader[4] == OBJS[4]
+        try:
+            reader[5]
+            raise AssertionError("Expected ChecksumError")
+        except ChecksumError:
+            pass
+        assert reader[6] == OBJS[6]
+        # Iteration skips past the corrupted record
+        reader.seek(4)
+        assert next(reader) == OBJS[4]
+        try:
+            next(reader)
+            raise AssertionError("Expected ChecksumError")
+        except ChecksumError:
+            pass
+        assert next(reader) == OBJS[6]
+        assert list(reader) == OBJS[7:]
+        assert len(reader) == len(OBJS)
+
+
+def test_index_error_message():
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        path = os.path.join(tmp_dir, "records.bin")
+        write_records(path, index=False)
+        reader = RecordReader(path, MY_DATACLASS_SERDE_TYPE)
+        try:
+            reader[len(OBJS)]
+            raise AssertionError("Expected IndexError")
+        except IndexError as e:
+            assert f"with {len(OBJS)} records" in str(e), str(e)
+
+
+def test_unclosed_writer():
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        path = os.path.join(tmp_dir, "records.bin")
+        write_records(path)
+        # Without the index (as if the writer was never closed) the records are scanned instead
+        with open(path, "rb") as f:
+            data = f.read()
+        with open(path, "wb") as f:
+            f.write(data[: -(8 * len(OBJS) + 24)])
+        reader = RecordReader(path, MY_DATACLASS_SERDE_TYPE)
+        assert len(reader) == len(OBJS)
+        assert reader[-2] == OBJS[-2]
+
+
+def test_truncated_record():
+    with tempfile.TemporaryDirectory() as tmp_dir:
+        path = os.path.join(tmp_dir, "records.bin")
+        write_records(path, index=False)
+        with open(path, "rb") as f:
+            data = f.read()
+        # The last record is cut short, or replaced by the largest possible record length
+        for truncated in [data[:-4], data + b"\xff" * 8]:
+            with open(path, "wb") as f:
+                f.write(truncated)
+            reader = RecordReader(path, MY_DATACLASS_SERDE_TYPE)
+            assert reader[len(OBJS) - 2] == OBJS[-2]
+            try:
+                len(reader)
+                raise AssertionError("Expected EOFError")
+            except EOFError:
+                pass
+            reader.seek(0)
+            assert [next(reader) for _ in range(len(OBJS) - 1)] == OBJS[:-1]
@@ -0,0 +1,118 @@
+import os
+import subprocess
+import threading
+from dataclasses import dataclass
+
+from pyany_serde import InitStrategy, PyAnySerdeType, RingBuffer
+from pyany_serde.ring_buffer_tests import (  # pyright:  ignore [reportMissingImports]
+    get_producer_command,  # pyright: ignore [reportUnknownVariableType]
+)
+
+
+@dataclass
+class MyDataclass:
+    idx: int
+    name: str
+    values: list[float]
+
+
+MY_DATACLASS_SERDE_TYPE: PyAnySerdeType[MyDataclass] = PyAnySerdeType.DATACLASS(
+    MyDataclass,
+    InitStrategy.ALL(),
+    {
+        "idx": PyAnySerdeType.INT(),
+        "name": PyAnySerdeType.STRING(),
+        "values": PyAnySerdeType.LIST(PyAnySerdeType.FLOAT()),
+    },
+)
+
+
+def make_obj(idx: int) -> MyDataclass:
+    return MyDataclass(idx, f"obj{idx}", [float(idx)] * (idx % 5))
+
+
+def test_send_recv():
+    ring_buffer = RingBuffer.create(MY_DATACLASS_SERDE_TYPE, n_slots=4, slot_size=256)
+    assert ring_buffer.n_slots == 4
+    assert ring_buffer.slot_size == 256
+    for idx in range(3):
+        ring_buffer.send(make_obj(idx))
+    for idx in range(3):
+        assert ring_buffer.recv() == make_obj(idx)
+
+
+def test_timeouts():
+    ring_buffer = RingBuffer.create(PyAnySerdeType.INT(), n_slots=2, slot_size=16)
+    try:
+        ring_buffer.recv(timeout=0.01)
+        raise AssertionError("Expected recv on an empty RingBuffer to time out")
+    except TimeoutError:
+        pass
+    ring_buffer.send(1)
+    ring_buffer.send(2)
+    try:
+        ring_buffer.send(3, timeout=0.01)
+        raise AssertionError("Expected send on a full RingBuffer to time out")
+    except TimeoutError:
+        pass
+    assert ring_buffer.recv() == 1
+    ring_buffer.send(3)
+    assert ring_buffer.recv() == 2
+    assert ring_buffer.recv() == 3
+
+
+def test_object_too_large():
+    ring_buffer = RingBuffer.create(PyAnySerdeType.STRING(), n_slots=2, slot_size=32)
+    try:
+        ring_buffer.send("a" * 100)
+        raise AssertionError("Expected send of an object larger than the slot size to fail")
+    except ValueError:
+        pass
+    # The failed send doesn't use up a slot
+    ring_buffer.send("small", timeout=0.01)
+    ring_buffer.send("small2", timeout=0.01)
+    assert ring_buffer.recv(timeout=1) == "small"
+    assert ring_buffer.recv(timeout=1) == "small2"
+
+
+def test_threads():
+    ring_buffer = RingBuffer.create(MY_DATACLASS_SERDE_TYPE, n_slots=4, slot_size=256)
+    name = ring_buffer.name
+
+    # A RingBuffer can only be used by the thread which created it, so each writer opens its own
+    def write(offset: int):
+        writer = RingBuffer.open(MY_DATACLASS_SERDE_TYPE, name)
+        for idx in range(offset, 100, 2):
+            writer.send(make_obj(idx), timeout=10)
+
+    writers = [threading.Thread(target=write, args=(offset,)) for offset in range(2)]
+    for writer in writers:
+        writer.start()
+    received = [ring_buffer.recv(timeout=10) for _ in range(100)]
+    for writer in writers:
+        writer.join()
+    assert sorted(received, key=lambda obj: obj.idx) == [make_obj(idx) for idx in range(100)]
+    # The objects of each writer are received in the order they were sent
+    for offset in range(2):
+        idxs = [obj.idx for obj in received if obj.idx % 2 == offset]
+        assert idxs == sorted(idxs)
+
+
+def produce(name: str):
+    writer = RingBuffer.open(MY_DATACLASS_SERDE_TYPE, name)
+    for idx in range(100):
+        writer.send(make_obj(idx), timeout=10)
+
+
+def test_processes():
+    ring_buffer = RingBuffer.create(MY_DATACLASS_SERDE_TYPE, n_slots=4, slot_size=256)
+    # The producer is a new process running produce, since forking this (multithreaded) process
+    # could deadlock
+    process = subprocess.Popen(
+        get_producer_command(),
+        env={**os.environ, "RING_BUFFER_PRODUCER_NAME": ring_buffer.name},
+        stdout=subprocess.DEVNULL,
+    )
+    received = [ring_buffer.recv(timeout=10) for _ in range(100)]
+    assert process.wait(timeout=10) == 0
+    assert received == [make_obj(idx) for idx in range(100)]
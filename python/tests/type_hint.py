@@ -0,0 +1,278 @@
+from collections import OrderedDict, defaultdict, deque
+from dataclasses import dataclass, field
+from enum import Enum
+from typing import Annotated, Any, Literal, NamedTuple, Optional, TypedDict, Union
+
+from pyany_serde import InitStrategy, NumpySerdeConfig, PyAnySerdeType
+from pyany_serde.type_hint_tests import (  # pyright:  ignore [reportMissingImports]
+    validate_eq,  # pyright: ignore [reportUnknownVariableType]
+)
+from typing_extensions import NotRequired
+
+
+class MyClass:
+    pass
+
+
+class MyEnum(Enum):
+    A = 1
+    B = "b"
+
+
+class MyTypedDict(TypedDict):
+    b: str
+    a: NotRequired[int]
+
+
+@dataclass
+class MyDataclass:
+    b: int
+    a: list[str]
+
+
+@dataclass
+class MyPartialInitDataclass:
+    a: int
+    b: int = field(default=0, init=False)
+
+
+class MyNamedTuple(NamedTuple):
+    a: int
+    b: str
+
+
+@dataclass
+class MyRecursiveDataclass:
+    value: int
+    next: Optional["MyRecursiveDataclass"]
+
+
+@dataclass
+class MyTree:
+    children: list["MyTree"]
+
+
+@dataclass
+class MyTreePair:
+    first: MyTree
+    second: MyTree
+
+
+def test_basic_types():
+    validate_eq(PyAnySerdeType.BOOL(), PyAnySerdeType.from_type_hint(bool), "$")
+    validate_eq(PyAnySerdeType.BYTES(), PyAnySerdeType.from_type_hint(bytes), "$")
+    validate_eq(PyAnySerdeType.COMPLEX(), PyAnySerdeType.from_type_hint(complex), "$")
+    validate_eq(PyAnySerdeType.DYNAMIC(), PyAnySerdeType.from_type_hint(Any), "$")
+    validate_eq(PyAnySerdeType.FLOAT(), PyAnySerdeType.from_type_hint(float), "$")
+    validate_eq(PyAnySerdeType.INT(), PyAnySerdeType.from_type_hint(int), "$")
+    validate_eq(PyAnySerdeType.STRING(), PyAnySerdeType.from_type_hint(str), "$")
+
+
+def test_containers():
+    validate_eq(
+        PyAnySerdeType.LIST(PyAnySerdeType.INT()),
+        PyAnySerdeType.from_type_hint(list[int]),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.LIST(PyAnySerdeType.DYNAMIC()),
+        PyAnySerdeType.from_type_hint(list),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.SET(PyAnySerdeType.STRING()),
+        PyAnySerdeType.from_type_hint(set[str]),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.FROZENSET(PyAnySerdeType.STRING()),
+        PyAnySerdeType.from_type_hint(frozenset[str]),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.DEQUE(PyAnySerdeType.FLOAT()),
+        PyAnySerdeType.from_type_hint(deque[float]),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.TUPLE([PyAnySerdeType.INT(), PyAnySerdeType.STRING()]),
+        PyAnySerdeType.from_type_hint(tuple[int, str]),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.HOMOGENEOUSTUPLE(PyAnySerdeType.INT()),
+        PyAnySerdeType.from_type_hint(tuple[int, ...]),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.DICT(PyAnySerdeType.STRING(), PyAnySerdeType.INT()),
+        PyAnySerdeType.from_type_hint(dict[str, int]),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.ORDEREDDICT(PyAnySerdeType.STRING(), PyAnySerdeType.INT()),
+        PyAnySerdeType.from_type_hint(OrderedDict[str, int]),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.DEFAULTDICT(PyAnySerdeType.STRING(), PyAnySerdeType.INT()),
+        PyAnySerdeType.from_type_hint(defaultdict[str, int]),
+        "$",
+    )
+
+
+def test_annotated():
+    validate_eq(
+        PyAnySerdeType.INT(),
+        PyAnySerdeType.from_type_hint(Annotated[int, "metadata"]),
+        "$",
+    )
+
+
+def test_optional():
+    validate_eq(
+        PyAnySerdeType.OPTION(PyAnySerdeType.INT()),
+        PyAnySerdeType.from_type_hint(Optional[int]),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.OPTION(PyAnySerdeType.STRING()),
+        PyAnySerdeType.from_type_hint(str | None),
+        "$",
+    )
+
+
+def test_union():
+    for tp in [Union[int, str, bool], int | str | bool]:
+        actual = PyAnySerdeType.from_type_hint(tp)
+        assert isinstance(actual, PyAnySerdeType.UNION)
+        assert len(actual.option_serde_types) == 3
+        validate_eq(PyAnySerdeType.INT(), actual.option_serde_types[0], "$[0]")
+        validate_eq(PyAnySerdeType.STRING(), actual.option_serde_types[1], "$[1]")
+        validate_eq(PyAnySerdeType.BOOL(), actual.option_serde_types[2], "$[2]")
+        assert actual.option_choice_fn(1) == 0
+        assert actual.option_choice_fn("a") == 1
+        assert actual.option_choice_fn(True) == 2
+
+
+def test_enum_literal():
+    validate_eq(
+        PyAnySerdeType.ENUM(list(MyEnum)),
+        PyAnySerdeType.from_type_hint(MyEnum),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.ENUM(["a", 1]),
+        PyAnySerdeType.from_type_hint(Literal["a", 1]),
+        "$",
+    )
+
+
+def test_typeddict():
+    validate_eq(
+        PyAnySerdeType.TYPEDDICT(
+            {"b": PyAnySerdeType.STRING(), "a": PyAnySerdeType.INT()},
+            optional_keys=["a"],
+        ),
+        PyAnySerdeType.from_type_hint(MyTypedDict),
+        "$",
+    )
+
+
+def test_dataclass():
+    validate_eq(
+        PyAnySerdeType.DATACLASS(
+            MyDataclass,
+            InitStrategy.ALL(),
+            {"b": PyAnySerdeType.INT(), "a": PyAnySerdeType.LIST(PyAnySerdeType.STRING())},
+        ),
+        PyAnySerdeType.from_type_hint(MyDataclass),
+        "$",
+    )
+    validate_eq(
+        PyAnySerdeType.DATACLASS(
+            MyPartialInitDataclass,
+            InitStrategy.SOME(["a"]),
+            {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.INT()},
+        ),
+        PyAnySerdeType.from_type_hint(MyPartialInitDataclass),
+        "$",
+    )
+
+
+def test_namedtuple():
+    validate_eq(
+        PyAnySerdeType.NAMEDTUPLE(
+            MyNamedTuple, [PyAnySerdeType.INT(), PyAnySerdeType.STRING()]
+        ),
+        PyAnySerdeType.from_type_hint(MyNamedTuple),
+        "$",
+    )
+
+
+def test_recursive():
+    validate_eq(
+        PyAnySerdeType.DEFINITIONS(
+            {
+                "MyRecursiveDataclass": PyAnySerdeType.DATACLASS(
+                    MyRecursiveDataclass,
+                    InitStrategy.ALL(),
+                    {
+                        "value": PyAnySerdeType.INT(),
+                        "next": PyAnySerdeType.OPTION(
+                            PyAnySerdeType.REF("MyRecursiveDataclass")
+                        ),
+                    },
+                )
+            },
+            PyAnySerdeType.REF("MyRecursiveDataclass"),
+        ),
+        PyAnySerdeType.from_type_hint(MyRecursiveDataclass, strict=True),
+        "$",
+    )
+    # A recursive class used more than once is only defined once
+    validate_eq(
+        PyAnySerdeType.DEFINITIONS(
+            {
+                "MyTree": PyAnySerdeType.DATACLASS(
+                    MyTree,
+                    InitStrategy.ALL(),
+                    {"children": PyAnySerdeType.LIST(PyAnySerdeType.REF("MyTree"))},
+                )
+            },
+            PyAnySerdeType.DATACLASS(
+                MyTreePair,
+                InitStrategy.ALL(),
+                {
+                    "first": PyAnySerdeType.REF("MyTree"),
+                    "second": PyAnySerdeType.REF("MyTree"),
+                },
+            ),
+        ),
+        PyAnySerdeType.from_type_hint(MyTreePair),
+        "$",
+    )
+
+
+def test_fallback():
+    validate_eq(PyAnySerdeType.PICKLE(), PyAnySerdeType.from_type_hint(MyClass), "$")
+
+
+def test_strict():
+    for tp in [MyClass, list[MyClass]]:
+        try:
+            PyAnySerdeType.from_type_hint(tp, strict=True)
+        except ValueError:
+            continue
+        raise AssertionError(f"Expected strict inference of {tp} to fail")
+
+
+def test_numpy():
+    import numpy as np
+    from numpy.typing import NDArray
+
+    validate_eq(
+        PyAnySerdeType.NUMPY(np.float32, config=NumpySerdeConfig.DYNAMIC()),
+        PyAnySerdeType.from_type_hint(NDArray[np.float32]),
+        "$",
+    )
@@ -0,0 +1,69 @@
+from pyany_serde import PyAnySerdeType, SchemaPolicy
+from pyany_serde.typed_dict_tests import (  # pyright:  ignore [reportMissingImports]
+    deserialize,  # pyright: ignore [reportUnknownVariableType]
+    serialize,  # pyright: ignore [reportUnknownVariableType]
+)
+
+OPTIONAL_KEYS = [f"k{idx}" for idx in range(10)]
+BOOLS_SERDE_TYPE = PyAnySerdeType.TYPEDDICT(
+    {key: PyAnySerdeType.BOOL() for key in OPTIONAL_KEYS},
+    optional_keys=OPTIONAL_KEYS,
+)
+MIXED_SERDE_TYPE = PyAnySerdeType.TYPEDDICT(
+    {
+        "required": PyAnySerdeType.INT(),
+        "name": PyAnySerdeType.STRING(),
+        "values": PyAnySerdeType.LIST(PyAnySerdeType.FLOAT()),
+    },
+    optional_keys=["name", "values"],
+)
+
+
+def roundtrip(serde_type, obj):
+    return deserialize(serde_type, serialize(serde_type, obj))
+
+
+def test_presence_bitmap():
+    obj = {"k0": True, "k3": False, "k9": True}
+    data = serialize(BOOLS_SERDE_TYPE, obj)
+    # One bit per optional key, followed by only the present values
+    assert data == bytes([0b00001001, 0b00000010, 1, 0, 1])
+    actual = deserialize(BOOLS_SERDE_TYPE, data)
+    assert actual == obj
+    assert "k1" not in actual
+    assert roundtrip(BOOLS_SERDE_TYPE, {}) == {}
+    full = {key: True for key in OPTIONAL_KEYS}
+    assert roundtrip(BOOLS_SERDE_TYPE, full) == full
+
+
+def test_absent_optional_keys():
+    for obj in [
+        {"required": 1},
+        {"required": 2, "name": "a"},
+        {"required": 3, "values": [1.5]},
+        {"required": 4, "name": "b", "values": []},
+    ]:
+        actual = roundtrip(MIXED_SERDE_TYPE, obj)
+        assert actual == obj
+        assert actual.keys() == obj.keys()
+
+
+def test_absent_optional_keys_versioned():
+    serde_type = PyAnySerdeType.TYPEDDICT(
+        {"required": PyAnySerdeType.INT(), "name": PyAnySerdeType.STRING()},
+        optional_keys=["name"],
+        schema=SchemaPolicy.VERSIONED(),
+    )
+    assert roundtrip(serde_type, {"required": 1}) == {"required": 1}
+    assert roundtrip(serde_type, {"required": 1, "name": "a"}) == {
+        "required": 1,
+        "name": "a",
+    }
+
+
+def test_missing_required_key():
+    try:
+        serialize(MIXED_SERDE_TYPE, {"name": "a"})
+        raise AssertionError("Expected a missing required key to be rejected")
+    except KeyError:
+        pass
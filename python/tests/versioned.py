@@ -0,0 +1,90 @@
+from asyncio import InvalidStateError
+from dataclasses import dataclass
+
+from pyany_serde import InitStrategy, PyAnySerdeType, SchemaPolicy
+from pyany_serde.versioned_tests import (  # pyright:  ignore [reportMissingImports]
+    deserialize,  # pyright: ignore [reportUnknownVariableType]
+    serialize,  # pyright: ignore [reportUnknownVariableType]
+)
+
+
+@dataclass
+class OldPoint:
+    x: int
+    label: str
+
+
+@dataclass
+class NewPoint:
+    x: int
+    y: float
+    tags: list[str]
+
+
+OLD_DATACLASS_SERDE_TYPE = PyAnySerdeType.DATACLASS(
+    OldPoint,
+    InitStrategy.ALL(),
+    {"x": PyAnySerdeType.INT(), "label": PyAnySerdeType.STRING()},
+    schema=SchemaPolicy.VERSIONED(),
+)
+NEW_DATACLASS_SERDE_TYPE = PyAnySerdeType.DATACLASS(
+    NewPoint,
+    InitStrategy.ALL(),
+    {
+        "x": PyAnySerdeType.INT(),
+        "y": PyAnySerdeType.FLOAT(),
+        "tags": PyAnySerdeType.LIST(PyAnySerdeType.STRING()),
+    },
+    schema=SchemaPolicy.VERSIONED({"y": 0.5, "tags": ["default"]}),
+)
+OLD_TYPEDDICT_SERDE_TYPE = PyAnySerdeType.TYPEDDICT(
+    {"a": PyAnySerdeType.INT(), "b": PyAnySerdeType.LIST(PyAnySerdeType.INT())},
+    schema=SchemaPolicy.VERSIONED(),
+)
+NEW_TYPEDDICT_SERDE_TYPE = PyAnySerdeType.TYPEDDICT(
+    {"c": PyAnySerdeType.STRING(), "a": PyAnySerdeType.INT()},
+    schema=SchemaPolicy.VERSIONED({"c": "default"}),
+)
+
+
+def test_dataclass_read_old_payload():
+    data = serialize(OLD_DATACLASS_SERDE_TYPE, OldPoint(1, "skipped"))
+    first = deserialize(NEW_DATACLASS_SERDE_TYPE, data)
+    assert first == NewPoint(1, 0.5, ["default"])
+    second = deserialize(NEW_DATACLASS_SERDE_TYPE, data)
+    assert second == first
+    # Defaults are copied, so mutating one retrieved object doesn't affect the others
+    assert second.tags is not first.tags
+
+
+def test_dataclass_read_new_payload():
+    data = serialize(NEW_DATACLASS_SERDE_TYPE, NewPoint(2, 1.5, ["a", "b"]))
+    assert deserialize(NEW_DATACLASS_SERDE_TYPE, data) == NewPoint(2, 1.5, ["a", "b"])
+    try:
+        deserialize(OLD_DATACLASS_SERDE_TYPE, data)
+        raise AssertionError("Expected a field without a default to be required")
+    except InvalidStateError:
+        pass
+
+
+def test_typeddict_read_old_and_new_payloads():
+    old_data = serialize(OLD_TYPEDDICT_SERDE_TYPE, {"a": 1, "b": [1, 2, 3]})
+    assert deserialize(NEW_TYPEDDICT_SERDE_TYPE, old_data) == {"c": "default", "a": 1}
+    new_data = serialize(NEW_TYPEDDICT_SERDE_TYPE, {"c": "x", "a": 2})
+    assert deserialize(NEW_TYPEDDICT_SERDE_TYPE, new_data) == {"c": "x", "a": 2}
+    try:
+        deserialize(OLD_TYPEDDICT_SERDE_TYPE, new_data)
+        raise AssertionError("Expected a missing key without a default to be rejected")
+    except InvalidStateError:
+        pass
+
+
+def test_typeddict_skip_unknown_fields():
+    reader_serde_type = PyAnySerdeType.TYPEDDICT(
+        {"a": PyAnySerdeType.INT()}, schema=SchemaPolicy.VERSIONED()
+    )
+    for data in [
+        serialize(OLD_TYPEDDICT_SERDE_TYPE, {"a": 3, "b": [4, 5]}),
+        serialize(NEW_TYPEDDICT_SERDE_TYPE, {"c": "unknown", "a": 3}),
+    ]:
+        assert deserialize(reader_serde_type, data) == {"a": 3}
@@ -0,0 +1,1229 @@
+use std::sync::Arc;
+
+use arrow_array::types::{
+    ArrowPrimitiveType, Float32Type, Float64Type, Int8Type, Int16Type, Int32Type, Int64Type,
+    UInt8Type, UInt16Type, UInt32Type, UInt64Type,
+};
+use arrow_array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, DictionaryArray, FixedSizeListArray, Float64Array,
+    Int64Array, ListArray, MapArray, PrimitiveArray, RecordBatch, StringArray, StructArray,
+    UInt32Array,
+};
+use arrow_buffer::{NullBuffer, OffsetBuffer, ScalarBuffer};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{ArrowError, DataType, Field, Fields, Schema, SchemaRef};
+use numpy::{Element, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyString};
+
+use crate::common::NumpyDtype;
+use crate::pyany_serde_impl::NumpySerdeConfig;
+use crate::{PyAnySerdeType, PyAnySerdeTypeKind};
+
+// The name of the column holding the objects when they aren't dataclasses or typed dicts (whose
+// fields are the columns instead)
+const VALUE_COLUMN_NAME: &str = "value";
+
+fn arrow_err(err: ArrowError) -> PyErr {
+    PyValueError::new_err(format!("Arrow error: {err}"))
+}
+
+fn unsupported_err(serde_type: &PyAnySerdeType, reason: &str) -> PyErr {
+    PyValueError::new_err(format!(
+        "PyAnySerdeType.{} cannot be converted to an Arrow type: {reason}",
+        PyAnySerdeTypeKind::from(serde_type)
+    ))
+}
+
+#[derive(Clone)]
+struct StructField {
+    name: String,
+    column: ArrowColumn,
+    // Whether the field may be missing from the typed dict
+    optional: bool,
+}
+
+// The Arrow representation of a PyAnySerdeType, which knows how to build an Arrow array from
+// objects of that serde type
+#[derive(Clone)]
+enum ArrowColumn {
+    Boolean,
+    Int64,
+    Float64,
+    Complex,
+    Utf8,
+    Binary,
+    // Nested fixed-size lists of the dtype, one level per dimension of the shape
+    Numpy {
+        dtype: NumpyDtype,
+        shape: Vec<usize>,
+    },
+    List(Box<ArrowColumn>),
+    FixedSizeList {
+        items: Box<ArrowColumn>,
+        length: usize,
+    },
+    Map {
+        keys: Box<ArrowColumn>,
+        values: Box<ArrowColumn>,
+    },
+    Nullable(Box<ArrowColumn>),
+    Struct {
+        fields: Vec<StructField>,
+        source: StructSource,
+    },
+    // The index of each value in values, with the plain values (the values of enum members) as
+    // the dictionary of the array
+    Dictionary {
+        values: Arc<Vec<Py<PyAny>>>,
+        plain_values: Arc<Vec<Py<PyAny>>>,
+        value_column: Box<ArrowColumn>,
+    },
+}
+
+// Where the values of the fields of a struct come from
+#[derive(Clone, Copy)]
+enum StructSource {
+    // The attributes of a dataclass
+    Attrs,
+    // The keys of a typed dict
+    Keys,
+    // The items of a tuple or named tuple, in field order
+    Items,
+}
+
+fn get_struct_fields(
+    py: Python<'_>,
+    entries: Vec<(&String, &PyAnySerdeType)>,
+    optional_keys: &[String],
+) -> PyResult<Vec<StructField>> {
+    entries
+        .into_iter()
+        .map(|(name, serde_type)| {
+            Ok(StructField {
+                name: name.clone(),
+                column: ArrowColumn::new(py, serde_type)?,
+                optional: optional_keys.contains(name),
+            })
+        })
+        .collect()
+}
+
+// The index of obj in the values of an ENUM. Values only match objects of the same type, so that
+// e.g. True doesn't match 1.
+fn get_enum_value_idx(values: &[Py<PyAny>], obj: &Bound<'_, PyAny>) -> PyResult<u32> {
+    let obj_type = obj.get_type();
+    for (idx, value) in values.iter().enumerate() {
+        let value = value.bind(obj.py());
+        if value.is(obj) || (value.get_type().is(&obj_type) && value.eq(obj)?) {
+            return Ok(idx as u32);
+        }
+    }
+    Err(PyValueError::new_err(format!(
+        "{} is not one of the values of the ENUM",
+        obj.repr()?
+    )))
+}
+
+fn get_nulls(values: &[Option<Bound<'_, PyAny>>]) -> Option<NullBuffer> {
+    values
+        .iter()
+        .any(Option::is_none)
+        .then(|| NullBuffer::from_iter(values.iter().map(Option::is_some)))
+}
+
+fn extract_values<'py, T: FromPyObjectOwned<'py>>(
+    values: &[Option<Bound<'py, PyAny>>],
+) -> PyResult<Vec<Option<T>>> {
+    values
+        .iter()
+        .map(|value| value.as_ref().map(|obj| obj.extract::<T>()).transpose())
+        .map(|result| result.map_err(Into::into))
+        .collect()
+}
+
+fn get_tuple_fields(
+    py: Python<'_>,
+    names: Vec<String>,
+    item_serde_types: &[PyAnySerdeType],
+) -> PyResult<Vec<StructField>> {
+    names
+        .into_iter()
+        .zip(item_serde_types.iter())
+        .map(|(name, serde_type)| {
+            Ok(StructField {
+                name,
+                column: ArrowColumn::new(py, serde_type)?,
+                optional: false,
+            })
+        })
+        .collect()
+}
+
+// The column of the plain values of an ENUM, which must all have the same Python type
+fn get_enum_value_column(
+    serde_type: &PyAnySerdeType,
+    plain_values: &[Bound<'_, PyAny>],
+) -> PyResult<ArrowColumn> {
+    let Some(first_value) = plain_values.first() else {
+        Err(unsupported_err(serde_type, "it has no values"))?
+    };
+    let value_type = first_value.get_type();
+    if plain_values
+        .iter()
+        .any(|value| !value.get_type().is(&value_type))
+    {
+        Err(unsupported_err(
+            serde_type,
+            "its values must all have the same type",
+        ))?
+    }
+    Ok(if first_value.is_instance_of::<PyBool>() {
+        ArrowColumn::Boolean
+    } else if first_value.is_instance_of::<PyInt>() {
+        ArrowColumn::Int64
+    } else if first_value.is_instance_of::<PyFloat>() {
+        ArrowColumn::Float64
+    } else if first_value.is_instance_of::<PyString>() {
+        ArrowColumn::Utf8
+    } else if first_value.is_instance_of::<PyBytes>() {
+        ArrowColumn::Binary
+    } else {
+        Err(unsupported_err(
+            serde_type,
+            "its values must be bools, ints, floats, strs, or bytes",
+        ))?
+    })
+}
+
+fn get_list_field(column: &ArrowColumn) -> Arc<Field> {
+    Arc::new(Field::new_list_field(
+        column.data_type(),
+        column.is_nullable(),
+    ))
+}
+
+fn build_numpy_array<'py, A: ArrowPrimitiveType>(
+    values: &[Option<Bound<'py, PyAny>>],
+    shape: &[usize],
+) -> PyResult<ArrayRef>
+where
+    A::Native: Element,
+{
+    let n_elements = shape.iter().product::<usize>();
+    let mut data = Vec::with_capacity(values.len() * n_elements);
+    for value in values.iter() {
+        match value {
+            Some(obj) => {
+                let array = obj.cast::<PyArrayDyn<A::Native>>()?.readonly();
+                if array.shape() != shape {
+                    Err(PyValueError::new_err(format!(
+                        "NUMPY array has shape {:?} but expected {shape:?}",
+                        array.shape()
+                    )))?
+                }
+                data.extend(array.as_array().iter().copied());
+            }
+            None => data.extend(std::iter::repeat_n(A::Native::default(), n_elements)),
+        }
+    }
+    let nulls = get_nulls(values);
+    let Some((&outer_dim, inner_dims)) = shape.split_first() else {
+        return Ok(Arc::new(PrimitiveArray::<A>::new(
+            ScalarBuffer::from(data),
+            nulls,
+        )));
+    };
+    let mut array: ArrayRef = Arc::new(PrimitiveArray::<A>::new(ScalarBuffer::from(data), None));
+    for &dim in inner_dims.iter().rev() {
+        array = Arc::new(
+            FixedSizeListArray::try_new(
+                Arc::new(Field::new_list_field(array.data_type().clone(), false)),
+                dim as i32,
+                array,
+                None,
+            )
+            .map_err(arrow_err)?,
+        );
+    }
+    Ok(Arc::new(
+        FixedSizeListArray::try_new(
+            Arc::new(Field::new_list_field(array.data_type().clone(), false)),
+            outer_dim as i32,
+            array,
+            nulls,
+        )
+        .map_err(arrow_err)?,
+    ))
+}
+
+impl ArrowColumn {
+    fn new(py: Python<'_>, serde_type: &PyAnySerdeType) -> PyResult<Self> {
+        Ok(match serde_type {
+            PyAnySerdeType::BOOL {} => ArrowColumn::Boolean,
+            PyAnySerdeType::BYTES {} => ArrowColumn::Binary,
+            PyAnySerdeType::CHECKSUMMED { value_serde_type }
+            | PyAnySerdeType::COMPRESSED {
+                value_serde_type, ..
+            }
+            | PyAnySerdeType::MEMO { value_serde_type } => {
+                ArrowColumn::new(py, &value_serde_type.extract(py)?)?
+            }
+            PyAnySerdeType::COLUMNARLIST {
+                items_serde_type,
+                as_columns,
+            } => {
+                if *as_columns {
+                    Err(unsupported_err(
+                        serde_type,
+                        "as_columns must be false, so that the value is a list of items",
+                    ))?
+                }
+                ArrowColumn::List(Box::new(ArrowColumn::new(
+                    py,
+                    &items_serde_type.extract(py)?,
+                )?))
+            }
+            PyAnySerdeType::COMPLEX {} => ArrowColumn::Complex,
+            PyAnySerdeType::DATACLASS {
+                field_serde_type_dict,
+                ..
+            } => ArrowColumn::Struct {
+                fields: get_struct_fields(py, field_serde_type_dict.iter().collect(), &[])?,
+                source: StructSource::Attrs,
+            },
+            PyAnySerdeType::DEFAULTDICT {
+                keys_serde_type,
+                values_serde_type,
+                ..
+            }
+            | PyAnySerdeType::DICT {
+                keys_serde_type,
+                values_serde_type,
+            }
+            | PyAnySerdeType::ORDEREDDICT {
+                keys_serde_type,
+                values_serde_type,
+            } => {
+                let keys = ArrowColumn::new(py, &keys_serde_type.extract(py)?)?;
+                if keys.is_nullable() {
+                    Err(unsupported_err(serde_type, "Arrow map keys cannot be null"))?
+                }
+                ArrowColumn::Map {
+                    keys: Box::new(keys),
+                    values: Box::new(ArrowColumn::new(py, &values_serde_type.extract(py)?)?),
+                }
+            }
+            PyAnySerdeType::DEQUE { items_serde_type }
+            | PyAnySerdeType::FROZENSET { items_serde_type }
+            | PyAnySerdeType::HOMOGENEOUSTUPLE { items_serde_type }
+            | PyAnySerdeType::LIST { items_serde_type }
+            | PyAnySerdeType::SET { items_serde_type } => ArrowColumn::List(Box::new(
+                ArrowColumn::new(py, &items_serde_type.extract(py)?)?,
+            )),
+            PyAnySerdeType::ENUM { values } => {
+                let enum_type = py.import("enum")?.getattr("Enum")?;
+                let plain_values = values
+                    .iter()
+                    .map(|value| {
+                        let value = value.bind(py);
+                        if value.is_instance(&enum_type)? {
+                            value.getattr("value")
+                        } else {
+                            Ok(value.clone())
+                        }
+                    })
+                    .collect::<PyResult<Vec<_>>>()?;
+                ArrowColumn::Dictionary {
+                    value_column: Box::new(get_enum_value_column(serde_type, &plain_values)?),
+                    values: Arc::new(values.iter().map(|value| value.clone_ref(py)).collect()),
+                    plain_values: Arc::new(
+                        plain_values
+                            .into_iter()
+                            .map(|value| value.unbind())
+                            .collect(),
+                    ),
+                }
+            }
+            PyAnySerdeType::FLOAT {} => ArrowColumn::Float64,
+            PyAnySerdeType::INT {} => ArrowColumn::Int64,
+            PyAnySerdeType::NAMEDTUPLE {
+                clazz,
+                item_serde_types,
+            } => {
+                let names = clazz
+                    .bind(py)
+                    .getattr("_fields")?
+                    .extract::<Vec<String>>()?;
+                if names.len() != item_serde_types.len() {
+                    Err(unsupported_err(
+                        serde_type,
+                        "the number of item serde types must match the fields of the named tuple",
+                    ))?
+                }
+                ArrowColumn::Struct {
+                    fields: get_tuple_fields(py, names, item_serde_types)?,
+                    source: StructSource::Items,
+                }
+            }
+            PyAnySerdeType::NUMPY { dtype, config } => match config {
+                NumpySerdeConfig::STATIC {
+                    shape,
+                    postprocessor_fn: None,
+                    ..
+                } => ArrowColumn::Numpy {
+                    dtype: *dtype,
+                    shape: shape.clone(),
+                },
+                NumpySerdeConfig::STATIC { .. } => Err(unsupported_err(
+                    serde_type,
+                    "the retrieved objects of a config with a postprocessor_fn aren't known to be arrays",
+                ))?,
+                NumpySerdeConfig::DYNAMIC { .. } => Err(unsupported_err(
+                    serde_type,
+                    "only STATIC configs have a fixed shape",
+                ))?,
+            },
+            PyAnySerdeType::OPTION { value_serde_type } => ArrowColumn::Nullable(Box::new(
+                ArrowColumn::new(py, &value_serde_type.extract(py)?)?,
+            )),
+            PyAnySerdeType::PRIMITIVELIST {
+                items_serde_type,
+                length,
+                ..
+            } => {
+                let items = Box::new(ArrowColumn::new(py, &items_serde_type.extract(py)?)?);
+                match length {
+                    Some(length) => ArrowColumn::FixedSizeList {
+                        items,
+                        length: *length,
+                    },
+                    None => ArrowColumn::List(items),
+                }
+            }
+            PyAnySerdeType::STRING {} => ArrowColumn::Utf8,
+            PyAnySerdeType::TUPLE { item_serde_types } => ArrowColumn::Struct {
+                fields: get_tuple_fields(
+                    py,
+                    (0..item_serde_types.len())
+                        .map(|idx| idx.to_string())
+                        .collect(),
+                    item_serde_types,
+                )?,
+                source: StructSource::Items,
+            },
+            PyAnySerdeType::TYPEDDICT {
+                key_serde_type_dict,
+                optional_keys,
+                ..
+            } => ArrowColumn::Struct {
+                fields: get_struct_fields(py, key_serde_type_dict.iter().collect(), optional_keys)?,
+                source: StructSource::Keys,
+            },
+            PyAnySerdeType::DEFINITIONS { .. } | PyAnySerdeType::REF { .. } => Err(
+                unsupported_err(serde_type, "recursive types have no Arrow equivalent"),
+            )?,
+            PyAnySerdeType::DYNAMIC {}
+            | PyAnySerdeType::PICKLE {}
+            | PyAnySerdeType::PYTHONSERDE { .. }
+            | PyAnySerdeType::UNION { .. } => Err(unsupported_err(
+                serde_type,
+                "the type of its values isn't known from the serde type",
+            ))?,
+        })
+    }
+
+    fn is_nullable(&self) -> bool {
+        matches!(self, ArrowColumn::Nullable(_))
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            ArrowColumn::Boolean => DataType::Boolean,
+            ArrowColumn::Int64 => DataType::Int64,
+            ArrowColumn::Float64 => DataType::Float64,
+            ArrowColumn::Complex => DataType::Struct(Fields::from(vec![
+                Field::new("real", DataType::Float64, false),
+                Field::new("imag", DataType::Float64, false),
+            ])),
+            ArrowColumn::Utf8 => DataType::Utf8,
+            ArrowColumn::Binary => DataType::Binary,
+            ArrowColumn::Numpy { dtype, shape } => {
+                let primitive_type = match dtype {
+                    NumpyDtype::INT8 => DataType::Int8,
+                    NumpyDtype::INT16 => DataType::Int16,
+                    NumpyDtype::INT32 => DataType::Int32,
+                    NumpyDtype::INT64 => DataType::Int64,
+                    NumpyDtype::UINT8 => DataType::UInt8,
+                    NumpyDtype::UINT16 => DataType::UInt16,
+                    NumpyDtype::UINT32 => DataType::UInt32,
+                    NumpyDtype::UINT64 => DataType::UInt64,
+                    NumpyDtype::FLOAT32 => DataType::Float32,
+                    NumpyDtype::FLOAT64 => DataType::Float64,
+                };
+                shape.iter().rev().fold(primitive_type, |data_type, &dim| {
+                    DataType::new_fixed_size_list(data_type, dim as i32, false)
+                })
+            }
+            ArrowColumn::List(items) => DataType::List(get_list_field(items)),
+            ArrowColumn::FixedSizeList { items, length } => {
+                DataType::FixedSizeList(get_list_field(items), *length as i32)
+            }
+            ArrowColumn::Map { keys, values } => DataType::Map(
+                Arc::new(Field::new(
+                    "entries",
+                    ArrowColumn::get_map_entries_data_type(keys, values),
+                    false,
+                )),
+                false,
+            ),
+            ArrowColumn::Nullable(value) => value.data_type(),
+            ArrowColumn::Struct { fields, .. } => {
+                DataType::Struct(ArrowColumn::get_struct_arrow_fields(fields))
+            }
+            ArrowColumn::Dictionary { value_column, .. } => DataType::Dictionary(
+                Box::new(DataType::UInt32),
+                Box::new(value_column.data_type()),
+            ),
+        }
+    }
+
+    fn get_map_entries_data_type(keys: &ArrowColumn, values: &ArrowColumn) -> DataType {
+        DataType::Struct(Fields::from(vec![
+            Field::new("keys", keys.data_type(), false),
+            Field::new("values", values.data_type(), values.is_nullable()),
+        ]))
+    }
+
+    fn get_struct_arrow_fields(fields: &[StructField]) -> Fields {
+        fields
+            .iter()
+            .map(|field| {
+                Field::new(
+                    &field.name,
+                    field.column.data_type(),
+                    field.optional || field.column.is_nullable(),
+                )
+            })
+            .collect()
+    }
+
+    // Builds an array with an element for each value, where None values are null elements
+    fn build_array<'py>(
+        &self,
+        py: Python<'py>,
+        values: &[Option<Bound<'py, PyAny>>],
+    ) -> PyResult<ArrayRef> {
+        Ok(match self {
+            ArrowColumn::Boolean => Arc::new(BooleanArray::from(extract_values::<bool>(values)?)),
+            ArrowColumn::Int64 => Arc::new(Int64Array::from(extract_values::<i64>(values)?)),
+            ArrowColumn::Float64 => Arc::new(Float64Array::from(extract_values::<f64>(values)?)),
+            ArrowColumn::Complex => {
+                let (real, imag) = values
+                    .iter()
+                    .map(|value| match value {
+                        Some(obj) => Ok((
+                            obj.getattr("real")?.extract::<f64>()?,
+                            obj.getattr("imag")?.extract::<f64>()?,
+                        )),
+                        None => Ok((0.0, 0.0)),
+                    })
+                    .collect::<PyResult<(Vec<_>, Vec<_>)>>()?;
+                let DataType::Struct(fields) = self.data_type() else {
+                    unreachable!()
+                };
+                Arc::new(
+                    StructArray::try_new(
+                        fields,
+                        vec![
+                            Arc::new(Float64Array::from(real)),
+                            Arc::new(Float64Array::from(imag)),
+                        ],
+                        get_nulls(values),
+                    )
+                    .map_err(arrow_err)?,
+                )
+            }
+            ArrowColumn::Utf8 => Arc::new(
+                extract_values::<String>(values)?
+                    .into_iter()
+                    .collect::<StringArray>(),
+            ),
+            ArrowColumn::Binary => {
+                let bytes_values = values
+                    .iter()
+                    .map(|value| value.as_ref().map(|obj| obj.cast::<PyBytes>()).transpose())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Arc::new(
+                    bytes_values
+                        .iter()
+                        .map(|bytes_option| bytes_option.map(|bytes| bytes.as_bytes()))
+                        .collect::<BinaryArray>(),
+                )
+            }
+            ArrowColumn::Numpy { dtype, shape } => match dtype {
+                NumpyDtype::INT8 => build_numpy_array::<Int8Type>(values, shape)?,
+                NumpyDtype::INT16 => build_numpy_array::<Int16Type>(values, shape)?,
+                NumpyDtype::INT32 => build_numpy_array::<Int32Type>(values, shape)?,
+                NumpyDtype::INT64 => build_numpy_array::<Int64Type>(values, shape)?,
+                NumpyDtype::UINT8 => build_numpy_array::<UInt8Type>(values, shape)?,
+                NumpyDtype::UINT16 => build_numpy_array::<UInt16Type>(values, shape)?,
+                NumpyDtype::UINT32 => build_numpy_array::<UInt32Type>(values, shape)?,
+                NumpyDtype::UINT64 => build_numpy_array::<UInt64Type>(values, shape)?,
+                NumpyDtype::FLOAT32 => build_numpy_array::<Float32Type>(values, shape)?,
+                NumpyDtype::FLOAT64 => build_numpy_array::<Float64Type>(values, shape)?,
+            },
+            ArrowColumn::List(items) => {
+                let mut lengths = Vec::with_capacity(values.len());
+                let mut item_values = Vec::new();
+                for value in values.iter() {
+                    let n_items_before = item_values.len();
+                    if let Some(obj) = value {
+                        for item in obj.try_iter()? {
+                            item_values.push(Some(item?));
+                        }
+                    }
+                    lengths.push(item_values.len() - n_items_before);
+                }
+                Arc::new(
+                    ListArray::try_new(
+                        get_list_field(items),
+                        OffsetBuffer::from_lengths(lengths),
+                        items.build_array(py, &item_values)?,
+                        get_nulls(values),
+                    )
+                    .map_err(arrow_err)?,
+                )
+            }
+            ArrowColumn::FixedSizeList { items, length } => {
+                let mut item_values = Vec::with_capacity(values.len() * length);
+                for value in values.iter() {
+                    match value {
+                        Some(obj) => {
+                            let n_items_before = item_values.len();
+                            for item in obj.try_iter()? {
+                                item_values.push(Some(item?));
+                            }
+                            let n_items = item_values.len() - n_items_before;
+                            if n_items != *length {
+                                Err(PyValueError::new_err(format!(
+                                    "PRIMITIVELIST has {n_items} items but expected {length}"
+                                )))?
+                            }
+                        }
+                        None => item_values.extend(std::iter::repeat_n(None, *length)),
+                    }
+                }
+                Arc::new(
+                    FixedSizeListArray::try_new(
+                        get_list_field(items),
+                        *length as i32,
+                        items.build_array(py, &item_values)?,
+                        get_nulls(values),
+                    )
+                    .map_err(arrow_err)?,
+                )
+            }
+            ArrowColumn::Map {
+                keys,
+                values: map_values,
+            } => {
+                let mut lengths = Vec::with_capacity(values.len());
+                let mut key_values = Vec::new();
+                let mut value_values = Vec::new();
+                for value in values.iter() {
+                    let n_entries_before = key_values.len();
+                    if let Some(obj) = value {
+                        for (key, value) in obj.cast::<PyDict>()?.iter() {
+                            key_values.push(Some(key));
+                            value_values.push(Some(value));
+                        }
+                    }
+                    lengths.push(key_values.len() - n_entries_before);
+                }
+                let DataType::Struct(entry_fields) =
+                    ArrowColumn::get_map_entries_data_type(keys, map_values)
+                else {
+                    unreachable!()
+                };
+                let entries = StructArray::try_new(
+                    entry_fields,
+                    vec![
+                        keys.build_array(py, &key_values)?,
+                        map_values.build_array(py, &value_values)?,
+                    ],
+                    None,
+                )
+                .map_err(arrow_err)?;
+                let DataType::Map(entries_field, _) = self.data_type() else {
+                    unreachable!()
+                };
+                Arc::new(
+                    MapArray::try_new(
+                        entries_field,
+                        OffsetBuffer::from_lengths(lengths),
+                        entries,
+                        get_nulls(values),
+                        false,
+                    )
+                    .map_err(arrow_err)?,
+                )
+            }
+            ArrowColumn::Nullable(value) => {
+                let value_values = values
+                    .iter()
+                    .map(|value| value.as_ref().filter(|obj| !obj.is_none()).cloned())
+                    .collect::<Vec<_>>();
+                value.build_array(py, &value_values)?
+            }
+            ArrowColumn::Struct { fields, source } => {
+                Arc::new(self.build_struct_array(py, fields, *source, values)?)
+            }
+            ArrowColumn::Dictionary {
+                values: enum_values,
+                plain_values,
+                value_column,
+            } => {
+                let keys = values
+                    .iter()
+                    .map(|value| {
+                        value
+                            .as_ref()
+                            .map(|obj| get_enum_value_idx(enum_values, obj))
+                            .transpose()
+                    })
+                    .collect::<PyResult<UInt32Array>>()?;
+                let dictionary = value_column.build_array(
+                    py,
+                    &plain_values
+                        .iter()
+                        .map(|value| Some(value.bind(py).clone()))
+                        .collect::<Vec<_>>(),
+                )?;
+                Arc::new(
+                    DictionaryArray::<UInt32Type>::try_new(keys, dictionary).map_err(arrow_err)?,
+                )
+            }
+        })
+    }
+
+    fn build_struct_array<'py>(
+        &self,
+        py: Python<'py>,
+        fields: &[StructField],
+        source: StructSource,
+        values: &[Option<Bound<'py, PyAny>>],
+    ) -> PyResult<StructArray> {
+        let nulls = get_nulls(values);
+        if fields.is_empty() {
+            return Ok(StructArray::new_empty_fields(values.len(), nulls));
+        }
+        let children = fields
+            .iter()
+            .enumerate()
+            .map(|(field_idx, field)| {
+                let name = PyString::new(py, &field.name);
+                let field_values = values
+                    .iter()
+                    .map(|value| {
+                        let Some(obj) = value else {
+                            return Ok(None);
+                        };
+                        match source {
+                            StructSource::Attrs => return Ok(Some(obj.getattr(&name)?)),
+                            StructSource::Items => return Ok(Some(obj.get_item(field_idx)?)),
+                            StructSource::Keys => (),
+                        }
+                        match obj.cast::<PyDict>()?.get_item(&name)? {
+                            Some(field_value) => Ok(Some(field_value)),
+                            None if field.optional => Ok(None),
+                            None => Err(PyKeyError::new_err(format!(
+                                "TYPEDDICT is missing required key {name}"
+                            ))),
+                        }
+                    })
+                    .collect::<PyResult<Vec<_>>>()?;
+                field.column.build_array(py, &field_values)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        StructArray::try_new(
+            ArrowColumn::get_struct_arrow_fields(fields),
+            children,
+            nulls,
+        )
+        .map_err(arrow_err)
+    }
+}
+
+// The Arrow data type of the values of serde_type
+pub fn get_arrow_data_type(serde_type: &PyAnySerdeType) -> PyResult<DataType> {
+    Python::attach(|py| Ok(ArrowColumn::new(py, serde_type)?.data_type()))
+}
+
+fn get_schema(column: &ArrowColumn) -> Schema {
+    match column {
+        ArrowColumn::Struct { fields, .. } => {
+            Schema::new(ArrowColumn::get_struct_arrow_fields(fields))
+        }
+        _ => Schema::new(vec![Field::new(
+            VALUE_COLUMN_NAME,
+            column.data_type(),
+            column.is_nullable(),
+        )]),
+    }
+}
+
+// The Arrow schema of record batches of objects of serde_type. The fields of dataclasses and
+// typed dicts are the columns of the schema, and other values are in a single "value" column.
+pub fn get_arrow_schema(serde_type: &PyAnySerdeType) -> PyResult<Schema> {
+    Python::attach(|py| Ok(get_schema(&ArrowColumn::new(py, serde_type)?)))
+}
+
+// Writes batches of objects of one PyAnySerdeType (e.g. retrieved objects) as record batches of
+// an Arrow IPC stream, which is returned when the exporter is finished.
+#[pyclass(unsendable)]
+pub struct ArrowIpcExporter {
+    column: ArrowColumn,
+    schema: SchemaRef,
+    writer_option: Option<StreamWriter<Vec<u8>>>,
+}
+
+impl ArrowIpcExporter {
+    pub fn new(py: Python<'_>, serde_type: &PyAnySerdeType) -> PyResult<Self> {
+        let column = ArrowColumn::new(py, serde_type)?;
+        let schema = Arc::new(get_schema(&column));
+        let writer = StreamWriter::try_new(Vec::new(), &schema).map_err(arrow_err)?;
+        Ok(ArrowIpcExporter {
+            column,
+            schema,
+            writer_option: Some(writer),
+        })
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn write_batch<'py>(&mut self, objs: &Bound<'py, PyAny>) -> PyResult<()> {
+        let py = objs.py();
+        let writer = self
+            .writer_option
+            .as_mut()
+            .ok_or_else(|| PyValueError::new_err("ArrowIpcExporter is finished"))?;
+        let values = objs
+            .try_iter()?
+            .map(|obj| obj.map(Some))
+            .collect::<PyResult<Vec<_>>>()?;
+        let batch = match &self.column {
+            ArrowColumn::Struct { fields, source } => RecordBatch::from(
+                self.column
+                    .build_struct_array(py, fields, *source, &values)?,
+            ),
+            column => {
+                RecordBatch::try_new(self.schema.clone(), vec![column.build_array(py, &values)?])
+                    .map_err(arrow_err)?
+            }
+        };
+        writer.write(&batch).map_err(arrow_err)
+    }
+
+    // Ends the stream and returns its bytes
+    pub fn finish(&mut self) -> PyResult<Vec<u8>> {
+        let mut writer = self
+            .writer_option
+            .take()
+            .ok_or_else(|| PyValueError::new_err("ArrowIpcExporter is finished"))?;
+        writer.finish().map_err(arrow_err)?;
+        writer.into_inner().map_err(arrow_err)
+    }
+}
+
+#[pymethods]
+impl ArrowIpcExporter {
+    #[new]
+    fn new_py(py: Python<'_>, serde_type: PyAnySerdeType) -> PyResult<Self> {
+        ArrowIpcExporter::new(py, &serde_type)
+    }
+
+    #[pyo3(name = "write_batch")]
+    fn write_batch_py<'py>(&mut self, objs: &Bound<'py, PyAny>) -> PyResult<()> {
+        self.write_batch(objs)
+    }
+
+    #[pyo3(name = "finish")]
+    fn finish_py<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new(py, &self.finish()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int64Type;
+    use arrow_ipc::reader::StreamReader;
+    use pyo3::ffi::c_str;
+
+    use crate::pyany_serde_impl::{ExtraPolicy, SchemaPolicy};
+
+    use super::*;
+
+    fn typed_dict(
+        entries: Vec<(&str, PyAnySerdeType)>,
+        optional_keys: Vec<&str>,
+    ) -> PyAnySerdeType {
+        PyAnySerdeType::TYPEDDICT {
+            key_serde_type_dict: entries
+                .into_iter()
+                .map(|(key, serde_type)| (key.to_string(), serde_type))
+                .collect(),
+            optional_keys: optional_keys.into_iter().map(str::to_string).collect(),
+            extra: ExtraPolicy::IGNORE {},
+            alphabetical_field_order: false,
+            schema: SchemaPolicy::FIXED {},
+        }
+    }
+
+    fn get_test_serde_type(py: Python<'_>) -> PyResult<PyAnySerdeType> {
+        Ok(typed_dict(
+            vec![
+                ("idx", PyAnySerdeType::INT {}),
+                (
+                    "name",
+                    PyAnySerdeType::OPTION {
+                        value_serde_type: Py::new(py, PyAnySerdeType::STRING {})?,
+                    },
+                ),
+                (
+                    "values",
+                    PyAnySerdeType::LIST {
+                        items_serde_type: Py::new(py, PyAnySerdeType::FLOAT {})?,
+                    },
+                ),
+                (
+                    "counts",
+                    PyAnySerdeType::DICT {
+                        keys_serde_type: Py::new(py, PyAnySerdeType::STRING {})?,
+                        values_serde_type: Py::new(py, PyAnySerdeType::INT {})?,
+                    },
+                ),
+                (
+                    "flags",
+                    PyAnySerdeType::PRIMITIVELIST {
+                        items_serde_type: Py::new(py, PyAnySerdeType::BOOL {})?,
+                        length: Some(2),
+                        as_tuple: true,
+                    },
+                ),
+                ("extra", PyAnySerdeType::BYTES {}),
+            ],
+            vec!["extra"],
+        ))
+    }
+
+    #[test]
+    fn test_arrow_schema() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let schema = get_arrow_schema(&get_test_serde_type(py)?)?;
+            let fields = schema
+                .fields()
+                .iter()
+                .map(|field| {
+                    (
+                        field.name().as_str(),
+                        field.data_type().clone(),
+                        field.is_nullable(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                fields,
+                vec![
+                    ("idx", DataType::Int64, false),
+                    ("name", DataType::Utf8, true),
+                    (
+                        "values",
+                        DataType::new_list(DataType::Float64, false),
+                        false
+                    ),
+                    (
+                        "counts",
+                        DataType::Map(
+                            Arc::new(Field::new(
+                                "entries",
+                                DataType::Struct(Fields::from(vec![
+                                    Field::new("keys", DataType::Utf8, false),
+                                    Field::new("values", DataType::Int64, false),
+                                ])),
+                                false
+                            )),
+                            false
+                        ),
+                        false
+                    ),
+                    (
+                        "flags",
+                        DataType::new_fixed_size_list(DataType::Boolean, 2, false),
+                        false
+                    ),
+                    ("extra", DataType::Binary, true),
+                ]
+            );
+
+            let numpy_serde_type = PyAnySerdeType::NUMPY {
+                dtype: NumpyDtype::FLOAT32,
+                config: NumpySerdeConfig::STATIC {
+                    shape: vec![2, 3],
+                    preprocessor_fn: None,
+                    postprocessor_fn: None,
+                    allocation_pool_min_size: 0,
+                    allocation_pool_max_size: None,
+                    allocation_pool_warning_size: None,
+                },
+            };
+            let schema = get_arrow_schema(&PyAnySerdeType::OPTION {
+                value_serde_type: Py::new(py, numpy_serde_type)?,
+            })?;
+            assert_eq!(schema.fields().len(), 1);
+            assert_eq!(schema.field(0).name(), VALUE_COLUMN_NAME);
+            assert!(schema.field(0).is_nullable());
+            assert_eq!(
+                schema.field(0).data_type(),
+                &DataType::new_fixed_size_list(
+                    DataType::new_fixed_size_list(DataType::Float32, 3, false),
+                    2,
+                    false
+                )
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_arrow_ipc_export() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let mut exporter = ArrowIpcExporter::new(py, &get_test_serde_type(py)?)?;
+            let batches = [
+                py.eval(
+                    c_str!(
+                        "[{'idx': 0, 'name': 'a', 'values': [1.0, 2.5], 'counts': {'x': 1}, 'flags': (True, False), 'extra': b'ab'}, {'idx': 1, 'name': None, 'values': [], 'counts': {}, 'flags': (False, False)}]"
+                    ),
+                    None,
+                    None,
+                )?,
+                py.eval(
+                    c_str!(
+                        "[{'idx': 2, 'name': 'c', 'values': [3.0], 'counts': {'y': 2, 'z': 3}, 'flags': (True, True)}]"
+                    ),
+                    None,
+                    None,
+                )?,
+            ];
+            for batch in batches.iter() {
+                exporter.write_batch(batch)?;
+            }
+            let stream = exporter.finish()?;
+            assert!(exporter.write_batch(&batches[0]).is_err());
+
+            let reader = StreamReader::try_new(stream.as_slice(), None).unwrap();
+            assert_eq!(reader.schema().as_ref(), exporter.schema());
+            let record_batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(record_batches.len(), 2);
+            let first = &record_batches[0];
+            assert_eq!(first.num_rows(), 2);
+            assert_eq!(
+                first.column(0).as_primitive::<Int64Type>().values(),
+                &[0, 1]
+            );
+            let names = first.column(1).as_string::<i32>();
+            assert_eq!(names.value(0), "a");
+            assert!(names.is_null(1));
+            let values = first.column(2).as_list::<i32>();
+            assert_eq!(values.value_offsets(), &[0, 2, 2]);
+            let counts = first.column(3).as_map();
+            assert_eq!(counts.value_offsets(), &[0, 1, 1]);
+            assert_eq!(counts.keys().as_string::<i32>().value(0), "x");
+            let flags = first.column(4).as_fixed_size_list();
+            assert_eq!(flags.value_length(), 2);
+            assert!(flags.values().as_boolean().value(0));
+            let extra = first.column(5).as_binary::<i32>();
+            assert_eq!(extra.value(0), b"ab");
+            assert!(extra.is_null(1));
+            let second = &record_batches[1];
+            assert_eq!(second.num_rows(), 1);
+            assert_eq!(second.column(3).as_map().entries().len(), 2);
+
+            let mut exporter = ArrowIpcExporter::new(
+                py,
+                &PyAnySerdeType::LIST {
+                    items_serde_type: Py::new(py, PyAnySerdeType::INT {})?,
+                },
+            )?;
+            exporter.write_batch(&py.eval(c_str!("[[1, 2], [3]]"), None, None)?)?;
+            let stream = exporter.finish()?;
+            let record_batches = StreamReader::try_new(stream.as_slice(), None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let lists = record_batches[0].column(0).as_list::<i32>();
+            assert_eq!(
+                lists.values().as_primitive::<Int64Type>().values(),
+                &[1, 2, 3]
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_arrow_tuple_and_enum_export() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let point = py.eval(
+                c_str!("__import__('collections').namedtuple('Point', ['x', 'y'])"),
+                None,
+                None,
+            )?;
+            let serde_type = PyAnySerdeType::TUPLE {
+                item_serde_types: vec![
+                    PyAnySerdeType::ENUM {
+                        values: py.eval(c_str!("[1, True, 'a']"), None, None)?.extract()?,
+                    },
+                    PyAnySerdeType::ENUM {
+                        values: py.eval(c_str!("[1, 2, 3]"), None, None)?.extract()?,
+                    },
+                    PyAnySerdeType::NAMEDTUPLE {
+                        clazz: point.clone().unbind(),
+                        item_serde_types: vec![PyAnySerdeType::INT {}, PyAnySerdeType::FLOAT {}],
+                    },
+                ],
+            };
+            assert!(get_arrow_schema(&serde_type).is_err());
+
+            let PyAnySerdeType::TUPLE {
+                mut item_serde_types,
+            } = serde_type
+            else {
+                unreachable!()
+            };
+            item_serde_types.remove(0);
+            let serde_type = PyAnySerdeType::TUPLE { item_serde_types };
+            let schema = get_arrow_schema(&serde_type)?;
+            let fields = schema
+                .fields()
+                .iter()
+                .map(|field| (field.name().as_str(), field.data_type().clone()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                fields,
+                vec![
+                    (
+                        "0",
+                        DataType::Dictionary(Box::new(DataType::UInt32), Box::new(DataType::Int64))
+                    ),
+                    (
+                        "1",
+                        DataType::Struct(Fields::from(vec![
+                            Field::new("x", DataType::Int64, false),
+                            Field::new("y", DataType::Float64, false),
+                        ]))
+                    ),
+                ]
+            );
+
+            let mut exporter = ArrowIpcExporter::new(py, &serde_type)?;
+            let locals = PyDict::new(py);
+            locals.set_item("Point", point)?;
+            exporter.write_batch(&py.eval(
+                c_str!("[(3, Point(1, 0.5)), (1, Point(2, 1.5))]"),
+                None,
+                Some(&locals),
+            )?)?;
+            assert!(
+                exporter
+                    .write_batch(&py.eval(
+                        c_str!("[(True, Point(1, 0.5))]"),
+                        None,
+                        Some(&locals)
+                    )?)
+                    .is_err()
+            );
+            let stream = exporter.finish()?;
+            let record_batches = StreamReader::try_new(stream.as_slice(), None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(record_batches.len(), 1);
+            let first = &record_batches[0];
+            let values = first.column(0).as_dictionary::<UInt32Type>();
+            assert_eq!(values.keys().values(), &[2, 0]);
+            assert_eq!(
+                values.values().as_primitive::<Int64Type>().values(),
+                &[1, 2, 3]
+            );
+            let points = first.column(1).as_struct();
+            assert_eq!(
+                points.column(0).as_primitive::<Int64Type>().values(),
+                &[1, 2]
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_arrow_unsupported() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let cases = [
+                (PyAnySerdeType::PICKLE {}, "PICKLE"),
+                (PyAnySerdeType::DYNAMIC {}, "DYNAMIC"),
+                (
+                    PyAnySerdeType::NUMPY {
+                        dtype: NumpyDtype::INT32,
+                        config: NumpySerdeConfig::DYNAMIC {
+                            preprocessor_fn: None,
+                            postprocessor_fn: None,
+                        },
+                    },
+                    "NUMPY",
+                ),
+                (
+                    typed_dict(
+                        vec![(
+                            "value",
+                            PyAnySerdeType::LIST {
+                                items_serde_type: Py::new(py, PyAnySerdeType::PICKLE {})?,
+                            },
+                        )],
+                        vec![],
+                    ),
+                    "PICKLE",
+                ),
+                (
+                    PyAnySerdeType::DICT {
+                        keys_serde_type: Py::new(
+                            py,
+                            PyAnySerdeType::OPTION {
+                                value_serde_type: Py::new(py, PyAnySerdeType::INT {})?,
+                            },
+                        )?,
+                        values_serde_type: Py::new(py, PyAnySerdeType::INT {})?,
+                    },
+                    "DICT",
+                ),
+            ];
+            for (serde_type, kind) in cases {
+                let err = get_arrow_schema(&serde_type).unwrap_err();
+                assert!(err.is_instance_of::<PyValueError>(py));
+                assert!(
+                    err.to_string().contains(&format!(
+                        "PyAnySerdeType.{kind} cannot be converted to an Arrow type"
+                    )),
+                    "{err}"
+                );
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,80 @@
+// Prints an annotated hexdump of a serialized value, for debugging a producer and a consumer which
+// disagree about the serialized format.
+//
+// usage: pyany-serde-inspect <schema file> <data file> [--offset N] [--max-bytes N]
+//
+// The schema file contains the JSON of the pydantic serialization of the PyAnySerdeType (which is
+// also the schema stored in record files), and the data file contains the serialized bytes.
+use std::fs;
+use std::process::ExitCode;
+
+use pyany_serde::debug_decoder::hexdump;
+use pyany_serde::record_file::get_serde_type_from_schema;
+use pyo3::prelude::*;
+
+const USAGE: &str =
+    "usage: pyany-serde-inspect <schema file> <data file> [--offset N] [--max-bytes N]";
+
+struct Args {
+    schema_path: String,
+    data_path: String,
+    offset: usize,
+    max_bytes: Option<usize>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut paths = Vec::new();
+    let mut offset = 0;
+    let mut max_bytes = Some(64);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--offset" | "--max-bytes" => {
+                let val = args
+                    .next()
+                    .ok_or_else(|| format!("{arg} requires a value"))?
+                    .parse::<usize>()
+                    .map_err(|err| format!("invalid value for {arg}: {err}"))?;
+                if arg == "--offset" {
+                    offset = val;
+                } else {
+                    // 0 means no limit
+                    max_bytes = Some(val).filter(|&val| val > 0);
+                }
+            }
+            "-h" | "--help" => Err(USAGE.to_owned())?,
+            _ => paths.push(arg),
+        }
+    }
+    let [schema_path, data_path] = <[String; 2]>::try_from(paths).map_err(|_| USAGE.to_owned())?;
+    Ok(Args {
+        schema_path,
+        data_path,
+        offset,
+        max_bytes,
+    })
+}
+
+fn run(args: Args) -> Result<String, String> {
+    let schema = fs::read_to_string(&args.schema_path)
+        .map_err(|err| format!("failed to read {}: {err}", args.schema_path))?;
+    let buf = fs::read(&args.data_path)
+        .map_err(|err| format!("failed to read {}: {err}", args.data_path))?;
+    Python::initialize();
+    let serde_type = Python::attach(|py| get_serde_type_from_schema(py, &schema))
+        .map_err(|err| format!("invalid schema: {err}"))?;
+    hexdump(&serde_type, &buf, args.offset, args.max_bytes).map_err(|err| err.to_string())
+}
+
+fn main() -> ExitCode {
+    match parse_args().and_then(run) {
+        Ok(out) => {
+            print!("{out}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
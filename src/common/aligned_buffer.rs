@@ -0,0 +1,111 @@
+use std::io::{ErrorKind, Read};
+use std::mem::size_of;
+
+// Buffers are grown by at most this many bytes at a time while reading, so that a corrupted
+// length can't cause a large allocation without the data to fill it
+const READ_CHUNK_SIZE: usize = 1 << 20;
+
+// A buffer of bytes backed by u64s, so that it starts at an 8 byte aligned address, which is the
+// largest alignment needed by numpy data. Serialized data in an aligned buffer can be retrieved
+// with numpy alignment padding computed relative to the start of the buffer.
+pub struct AlignedBuffer {
+    data: Vec<u64>,
+    len: usize,
+}
+
+impl AlignedBuffer {
+    pub fn new(len: usize) -> Self {
+        AlignedBuffer {
+            data: vec![0; len.div_ceil(size_of::<u64>())],
+            len,
+        }
+    }
+
+    // Reads exactly len bytes from reader
+    pub fn read_from(reader: &mut dyn Read, len: usize) -> std::io::Result<Self> {
+        Self::read_from_offset(reader, 0, len)
+    }
+
+    // Reads exactly len bytes from reader into the buffer after offset zeroed bytes
+    pub fn read_from_offset(
+        reader: &mut dyn Read,
+        offset: usize,
+        len: usize,
+    ) -> std::io::Result<Self> {
+        let mut buffer = AlignedBuffer::new(offset);
+        let len = offset.saturating_add(len);
+        while buffer.len < len {
+            let start = buffer.len;
+            let end = len.min(start + READ_CHUNK_SIZE);
+            buffer.data.resize(end.div_ceil(size_of::<u64>()), 0);
+            buffer.len = end;
+            reader.read_exact(&mut buffer.as_mut_slice()[start..])?;
+        }
+        Ok(buffer)
+    }
+
+    // Reads the rest of reader into the buffer after offset zeroed bytes
+    pub fn read_to_end_from_offset(reader: &mut dyn Read, offset: usize) -> std::io::Result<Self> {
+        let mut buffer = AlignedBuffer::new(offset);
+        loop {
+            let start = buffer.len;
+            buffer
+                .data
+                .resize((start + READ_CHUNK_SIZE).div_ceil(size_of::<u64>()), 0);
+            buffer.len = start + READ_CHUNK_SIZE;
+            let mut n_read = 0;
+            while n_read < READ_CHUNK_SIZE {
+                match reader.read(&mut buffer.as_mut_slice()[start + n_read..]) {
+                    Ok(0) => break,
+                    Ok(n) => n_read += n,
+                    Err(err) if err.kind() == ErrorKind::Interrupted => (),
+                    Err(err) => return Err(err),
+                }
+            }
+            buffer.len = start + n_read;
+            buffer.data.truncate(buffer.len.div_ceil(size_of::<u64>()));
+            if n_read < READ_CHUNK_SIZE {
+                return Ok(buffer);
+            }
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &bytemuck::cast_slice(&self.data)[..self.len]
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut bytemuck::cast_slice_mut(&mut self.data)[..self.len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aligned_buffer_read_from() {
+        let data = (0..100u8).collect::<Vec<_>>();
+        let buffer = AlignedBuffer::read_from(&mut &data[..], 100).unwrap();
+        assert_eq!(buffer.as_slice(), &data[..]);
+        assert_eq!(buffer.as_slice().as_ptr() as usize % size_of::<u64>(), 0);
+        // A length far beyond the data fails when the data runs out, rather than allocating it
+        let err = AlignedBuffer::read_from(&mut &data[..], usize::MAX)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_aligned_buffer_read_to_end() {
+        let data = (0..=255u8)
+            .cycle()
+            .take(READ_CHUNK_SIZE + 100)
+            .collect::<Vec<_>>();
+        let buffer = AlignedBuffer::read_to_end_from_offset(&mut &data[..], 3).unwrap();
+        assert_eq!(&buffer.as_slice()[..3], &[0, 0, 0]);
+        assert_eq!(&buffer.as_slice()[3..], &data[..]);
+        let buffer = AlignedBuffer::read_to_end_from_offset(&mut &data[..0], 0).unwrap();
+        assert!(buffer.as_slice().is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+use std::str::FromStr;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+use strum_macros::{Display, EnumIter, EnumString};
+
+#[derive(Debug, PartialEq, Clone, Copy, Display, EnumString, EnumIter)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum CompressionCodec {
+    // The LZ4 block format, using the lz4_flex crate
+    LZ4,
+    // The zstd format, using the zstd crate
+    ZSTD,
+}
+
+impl<'py> IntoPyObject<'py> for CompressionCodec {
+    type Target = PyString;
+
+    type Output = Bound<'py, PyString>;
+
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(PyString::new(py, &self.to_string()))
+    }
+}
+
+impl<'py> FromPyObject<'_, 'py> for CompressionCodec {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'_, 'py, PyAny>) -> PyResult<Self> {
+        let codec = ob.extract::<String>()?;
+        CompressionCodec::from_str(&codec).map_err(|_| {
+            PyValueError::new_err(format!(
+                "Invalid compression codec: {codec} (expected \"lz4\" or \"zstd\")"
+            ))
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+
+// CRC-32C (Castagnoli), as used by e.g. iSCSI, ext4, and many record formats
+const POLY: u32 = 0x82F6_3B78;
+
+const TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut idx = 0;
+    while idx < 256 {
+        let mut crc = idx as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+};
+
+create_exception!(
+    pyany_serde,
+    ChecksumError,
+    PyValueError,
+    "Raised when serialized data doesn't match its checksum, e.g. because it was only partially written."
+);
+
+// Continues the checksum crc (which is 0 for empty data) with the bytes of data
+pub fn crc32c_update(crc: u32, data: &[u8]) -> u32 {
+    !data.iter().fold(!crc, |crc, &byte| {
+        TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+pub fn crc32c(data: &[u8]) -> u32 {
+    crc32c_update(0, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(crc32c(b""), 0);
+        assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+        assert_eq!(crc32c(&[0; 32]), 0x8A91_36AA);
+        assert_eq!(
+            crc32c_update(crc32c(b"1234"), b"56789"),
+            crc32c(b"123456789")
+        );
+    }
+}
@@ -1,7 +1,18 @@
 mod align;
+mod aligned_buffer;
+mod compression_codec;
+mod crc32c;
 mod numpy_dtype_enum;
+mod packed_primitive;
 mod python_type;
 
 pub use align::get_bytes_to_alignment;
+pub use aligned_buffer::AlignedBuffer;
+pub use compression_codec::CompressionCodec;
+pub use crc32c::{ChecksumError, crc32c, crc32c_update};
 pub use numpy_dtype_enum::NumpyDtype;
+pub use packed_primitive::{
+    PackedPrimitive, append_packed_many, append_packed_many_vec, retrieve_packed_many,
+    retrieve_packed_many_stacked,
+};
 pub use python_type::{detect_python_type, get_python_type_byte, retrieve_python_type, PythonType};
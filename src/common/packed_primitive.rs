@@ -0,0 +1,158 @@
+use std::mem::size_of;
+
+use bytemuck::{NoUninit, cast_slice};
+use numpy::{Element, IntoPyArray};
+use pyo3::exceptions::asyncio::InvalidStateError;
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::communication::insert_bytes;
+
+// The packed representation of each item is identical to what the matching scalar serde writes, so a
+// packed list without a fixed length is wire compatible with a LIST of that scalar.
+pub trait PackedPrimitive: NoUninit + Clone {
+    fn extract_item<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Self>;
+    fn retrieve_packed(bytes: &[u8]) -> PyResult<Vec<Self>>;
+    fn into_item<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>>;
+}
+
+macro_rules! impl_packed_primitive {
+    ($type:ty) => {
+        impl PackedPrimitive for $type {
+            fn extract_item<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+                obj.extract::<$type>()
+            }
+
+            fn retrieve_packed(bytes: &[u8]) -> PyResult<Vec<Self>> {
+                Ok(bytes
+                    .chunks_exact(size_of::<$type>())
+                    .map(|chunk| <$type>::from_ne_bytes(chunk.try_into().unwrap()))
+                    .collect())
+            }
+
+            fn into_item<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+                Ok(self.into_pyobject(py)?.into_any())
+            }
+        }
+    };
+}
+
+impl_packed_primitive!(i64);
+impl_packed_primitive!(f64);
+
+impl PackedPrimitive for bool {
+    fn extract_item<'py>(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        obj.extract::<bool>()
+    }
+
+    fn retrieve_packed(bytes: &[u8]) -> PyResult<Vec<Self>> {
+        bytes
+            .iter()
+            .map(|&v| match v {
+                0 => Ok(false),
+                1 => Ok(true),
+                v => Err(InvalidStateError::new_err(format!(
+                    "tried to retrieve bool from shared_memory but got value {v}"
+                ))),
+            })
+            .collect()
+    }
+
+    fn into_item<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(self.into_pyobject(py)?.to_owned().into_any())
+    }
+}
+
+// Since the packed representation of each item is what the matching scalar serde writes, the scalar
+// serdes use these to append and retrieve many items at once.
+pub fn append_packed_many<'py, T: PackedPrimitive>(
+    buf: &mut [u8],
+    offset: usize,
+    objs: &Bound<'py, PyAny>,
+) -> PyResult<usize> {
+    let items = objs
+        .try_iter()?
+        .map(|obj| T::extract_item(&obj?))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(insert_bytes(buf, offset, cast_slice::<T, u8>(&items)))
+}
+
+pub fn append_packed_many_vec<'py, T: PackedPrimitive>(
+    v: &mut Vec<u8>,
+    objs: &Bound<'py, PyAny>,
+) -> PyResult<()> {
+    for obj in objs.try_iter()? {
+        v.extend_from_slice(cast_slice::<T, u8>(&[T::extract_item(&obj?)?]));
+    }
+    Ok(())
+}
+
+pub fn retrieve_packed_many<'py, T: PackedPrimitive>(
+    py: Python<'py>,
+    buf: &[u8],
+    offset: usize,
+    n: usize,
+) -> PyResult<(Bound<'py, PyList>, usize)> {
+    let end = offset + n * size_of::<T>();
+    let items = T::retrieve_packed(&buf[offset..end])?
+        .into_iter()
+        .map(|item| item.into_item(py))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok((PyList::new(py, items)?, end))
+}
+
+pub fn retrieve_packed_many_stacked<'py, T: PackedPrimitive + Element>(
+    py: Python<'py>,
+    buf: &[u8],
+    offset: usize,
+    n: usize,
+) -> PyResult<(Bound<'py, PyAny>, usize)> {
+    let end = offset + n * size_of::<T>();
+    let items = T::retrieve_packed(&buf[offset..end])?;
+    Ok((items.into_pyarray(py).into_any(), end))
+}
+
+// Implements append_many, append_many_vec, retrieve_many, and retrieve_many_stacked of PyAnySerde
+// for a scalar serde whose data is the packed representation of the given PackedPrimitive
+#[macro_export]
+macro_rules! impl_packed_many {
+    ($type:ty) => {
+        fn append_many<'py>(
+            &mut self,
+            buf: &mut [u8],
+            offset: usize,
+            objs: &pyo3::Bound<'py, pyo3::PyAny>,
+        ) -> pyo3::PyResult<usize> {
+            $crate::common::append_packed_many::<$type>(buf, offset, objs)
+        }
+
+        fn append_many_vec<'py>(
+            &mut self,
+            v: &mut Vec<u8>,
+            _start_addr: Option<usize>,
+            objs: &pyo3::Bound<'py, pyo3::PyAny>,
+        ) -> pyo3::PyResult<()> {
+            $crate::common::append_packed_many_vec::<$type>(v, objs)
+        }
+
+        fn retrieve_many<'py>(
+            &mut self,
+            py: pyo3::Python<'py>,
+            buf: &[u8],
+            offset: usize,
+            n: usize,
+        ) -> pyo3::PyResult<(pyo3::Bound<'py, pyo3::types::PyList>, usize)> {
+            $crate::common::retrieve_packed_many::<$type>(py, buf, offset, n)
+        }
+
+        fn retrieve_many_stacked<'py>(
+            &mut self,
+            py: pyo3::Python<'py>,
+            buf: &[u8],
+            offset: usize,
+            n: usize,
+        ) -> pyo3::PyResult<(pyo3::Bound<'py, pyo3::PyAny>, usize)> {
+            $crate::common::retrieve_packed_many_stacked::<$type>(py, buf, offset, n)
+        }
+    };
+}
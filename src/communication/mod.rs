@@ -0,0 +1,398 @@
+use std::io::{ErrorKind, Read};
+use std::mem::size_of;
+use std::os::raw::c_double;
+
+use pyo3::exceptions::asyncio::InvalidStateError;
+use pyo3::prelude::*;
+
+use paste::paste;
+
+use crate::PyAnySerde;
+use crate::common::get_bytes_to_alignment;
+
+mod ring_buffer;
+mod stream;
+
+pub use ring_buffer::RingBuffer;
+pub use stream::{StreamReader, StreamWriter};
+
+macro_rules! define_primitive_communication {
+    ($type:ty) => {
+        paste! {
+            pub fn [<append_ $type>](buf: &mut [u8], offset: usize, val: $type) -> usize {
+                let end = offset + size_of::<$type>();
+                buf[offset..end].copy_from_slice(&val.to_ne_bytes());
+                end
+            }
+
+            pub fn [<append_ $type _vec>](v: &mut Vec<u8>, val: $type) {
+                v.extend_from_slice(&val.to_ne_bytes());
+            }
+
+            pub fn [<retrieve_ $type>](buf: &[u8], offset: usize) -> PyResult<($type, usize)> {
+                let end = offset + size_of::<$type>();
+                Ok(($type::from_ne_bytes(buf[offset..end].try_into()?), end))
+            }
+
+            pub fn [<append_ $type _stream>](writer: &mut StreamWriter, val: $type) -> PyResult<()> {
+                writer.write_all(&val.to_ne_bytes())
+            }
+
+            pub fn [<retrieve_ $type _stream>](reader: &mut StreamReader) -> PyResult<$type> {
+                let mut bytes = [0; size_of::<$type>()];
+                reader.read_into(&mut bytes)?;
+                Ok($type::from_ne_bytes(bytes))
+            }
+        }
+    };
+}
+
+define_primitive_communication!(u8);
+define_primitive_communication!(u32);
+define_primitive_communication!(usize);
+define_primitive_communication!(c_double);
+define_primitive_communication!(i64);
+define_primitive_communication!(u64);
+define_primitive_communication!(f32);
+define_primitive_communication!(f64);
+
+pub fn append_bool(buf: &mut [u8], offset: usize, val: bool) -> usize {
+    let end = offset + size_of::<u8>();
+    buf[offset..end].copy_from_slice(&(val as u8).to_ne_bytes());
+    end
+}
+
+pub fn append_bool_vec(v: &mut Vec<u8>, val: bool) {
+    v.extend_from_slice(&(val as u8).to_ne_bytes());
+}
+
+pub fn retrieve_bool(buf: &[u8], offset: usize) -> PyResult<(bool, usize)> {
+    let end = offset + size_of::<bool>();
+    let val = match u8::from_ne_bytes(buf[offset..end].try_into()?) {
+        0 => Ok(false),
+        1 => Ok(true),
+        v => Err(InvalidStateError::new_err(format!(
+            "tried to retrieve bool from shared_memory but got value {v}"
+        ))),
+    }?;
+    Ok((val, end))
+}
+
+pub fn append_bool_stream(writer: &mut StreamWriter, val: bool) -> PyResult<()> {
+    append_u8_stream(writer, val as u8)
+}
+
+pub fn retrieve_bool_stream(reader: &mut StreamReader) -> PyResult<bool> {
+    match retrieve_u8_stream(reader)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        v => Err(InvalidStateError::new_err(format!(
+            "tried to retrieve bool from stream but got value {v}"
+        ))),
+    }
+}
+
+pub fn append_varint(buf: &mut [u8], mut offset: usize, mut val: usize) -> usize {
+    while val >= 0x80 {
+        buf[offset] = (val as u8) | 0x80;
+        offset += 1;
+        val >>= 7;
+    }
+    buf[offset] = val as u8;
+    offset + 1
+}
+
+pub fn append_varint_vec(v: &mut Vec<u8>, mut val: usize) {
+    while val >= 0x80 {
+        v.push((val as u8) | 0x80);
+        val >>= 7;
+    }
+    v.push(val as u8);
+}
+
+pub fn retrieve_varint(buf: &[u8], mut offset: usize) -> PyResult<(usize, usize)> {
+    let mut val = 0usize;
+    let mut shift = 0;
+    loop {
+        let Some(&byte) = buf.get(offset) else {
+            Err(InvalidStateError::new_err(
+                "tried to retrieve varint from shared_memory but it was truncated",
+            ))?
+        };
+        offset += 1;
+        if shift >= usize::BITS || (byte & 0x7f) as usize > (usize::MAX >> shift) {
+            Err(InvalidStateError::new_err(
+                "tried to retrieve varint from shared_memory but it overflowed usize",
+            ))?
+        }
+        val |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((val, offset));
+        }
+        shift += 7;
+    }
+}
+
+pub fn append_varint_stream(writer: &mut StreamWriter, val: usize) -> PyResult<()> {
+    let mut v = Vec::new();
+    append_varint_vec(&mut v, val);
+    writer.write_all(&v)
+}
+
+pub fn retrieve_varint_stream(reader: &mut StreamReader) -> PyResult<usize> {
+    let mut val = 0usize;
+    let mut shift = 0;
+    loop {
+        let byte = retrieve_u8_stream(reader)?;
+        if shift >= usize::BITS || (byte & 0x7f) as usize > (usize::MAX >> shift) {
+            Err(InvalidStateError::new_err(
+                "tried to retrieve varint from stream but it overflowed usize",
+            ))?
+        }
+        val |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(val);
+        }
+        shift += 7;
+    }
+}
+
+// Discriminants (e.g. the chosen option of a union) are written as a single byte when there are at most 256 options, and as a varint otherwise.
+pub fn append_discriminant(buf: &mut [u8], offset: usize, idx: usize, n_options: usize) -> usize {
+    if n_options <= 256 {
+        append_u8(buf, offset, idx as u8)
+    } else {
+        append_varint(buf, offset, idx)
+    }
+}
+
+pub fn append_discriminant_vec(v: &mut Vec<u8>, idx: usize, n_options: usize) {
+    if n_options <= 256 {
+        append_u8_vec(v, idx as u8)
+    } else {
+        append_varint_vec(v, idx)
+    }
+}
+
+pub fn retrieve_discriminant(
+    buf: &[u8],
+    offset: usize,
+    n_options: usize,
+) -> PyResult<(usize, usize)> {
+    if n_options <= 256 {
+        let (idx, offset) = retrieve_u8(buf, offset)?;
+        Ok((idx as usize, offset))
+    } else {
+        retrieve_varint(buf, offset)
+    }
+}
+
+pub fn append_discriminant_stream(
+    writer: &mut StreamWriter,
+    idx: usize,
+    n_options: usize,
+) -> PyResult<()> {
+    if n_options <= 256 {
+        append_u8_stream(writer, idx as u8)
+    } else {
+        append_varint_stream(writer, idx)
+    }
+}
+
+pub fn retrieve_discriminant_stream(
+    reader: &mut StreamReader,
+    n_options: usize,
+) -> PyResult<usize> {
+    if n_options <= 256 {
+        Ok(retrieve_u8_stream(reader)? as usize)
+    } else {
+        retrieve_varint_stream(reader)
+    }
+}
+
+pub fn append_bytes_vec(v: &mut Vec<u8>, bytes: &[u8]) {
+    append_usize_vec(v, bytes.len());
+    v.extend_from_slice(bytes);
+}
+
+pub fn append_string_vec(v: &mut Vec<u8>, s: &String) {
+    append_bytes_vec(v, s.as_bytes());
+}
+
+pub fn retrieve_string(buf: &[u8], offset: usize) -> PyResult<(String, usize)> {
+    let (string_bytes, offset) = retrieve_bytes(buf, offset)?;
+    Ok((String::from_utf8(string_bytes.to_vec())?, offset))
+}
+
+pub fn insert_bytes(buf: &mut [u8], offset: usize, bytes: &[u8]) -> usize {
+    let end = offset + bytes.len();
+    buf[offset..end].copy_from_slice(bytes);
+    end
+}
+
+pub fn append_bytes(buf: &mut [u8], offset: usize, bytes: &[u8]) -> usize {
+    let bytes_len = bytes.len();
+    let start = append_usize(buf, offset, bytes_len);
+    let end = start + bytes.len();
+    buf[start..end].copy_from_slice(bytes);
+    end
+}
+
+pub fn retrieve_bytes(buf: &[u8], offset: usize) -> PyResult<(&[u8], usize)> {
+    let (len, start) = retrieve_usize(buf, offset)?;
+    let end = start + len;
+    Ok((&buf[start..end], end))
+}
+
+pub fn append_bytes_stream(writer: &mut StreamWriter, bytes: &[u8]) -> PyResult<()> {
+    append_usize_stream(writer, bytes.len())?;
+    writer.write_all(bytes)
+}
+
+pub fn retrieve_bytes_stream(reader: &mut StreamReader) -> PyResult<Vec<u8>> {
+    let len = retrieve_usize_stream(reader)?;
+    reader.read_vec(len)
+}
+
+pub fn retrieve_string_stream(reader: &mut StreamReader) -> PyResult<String> {
+    Ok(String::from_utf8(retrieve_bytes_stream(reader)?)?)
+}
+
+// Numpy alignment padding in a stream is relative to the position of the stream
+pub fn append_padding_stream<T>(writer: &mut StreamWriter) -> PyResult<()> {
+    let n_bytes = get_bytes_to_alignment::<T>(writer.position());
+    writer.write_all(&vec![0; n_bytes])
+}
+
+pub fn retrieve_padding_stream<T>(reader: &mut StreamReader) -> PyResult<()> {
+    let n_bytes = get_bytes_to_alignment::<T>(reader.position());
+    reader.skip(n_bytes)
+}
+
+// Fills buf from reader, returning false if the reader was already at the end of the stream. A
+// stream which ends partway through buf is an UnexpectedEof error, like with read_exact.
+pub fn read_exact_or_eof(reader: &mut dyn Read, buf: &mut [u8]) -> std::io::Result<bool> {
+    let mut n_read = 0;
+    while n_read < buf.len() {
+        match reader.read(&mut buf[n_read..]) {
+            Ok(0) if n_read == 0 => return Ok(false),
+            Ok(0) => Err(ErrorKind::UnexpectedEof)?,
+            Ok(n) => n_read += n,
+            Err(err) if err.kind() == ErrorKind::Interrupted => (),
+            Err(err) => Err(err)?,
+        }
+    }
+    Ok(true)
+}
+
+pub fn append_python_option_bound<'py, F>(
+    buf: &mut [u8],
+    mut offset: usize,
+    obj_option: &Option<Bound<'py, PyAny>>,
+    serde_option: &mut Option<Box<dyn PyAnySerde>>,
+    err: F,
+) -> PyResult<usize>
+where
+    F: FnOnce() -> PyErr,
+{
+    if let Some(obj) = obj_option {
+        offset = append_bool(buf, offset, true);
+        offset = serde_option
+            .as_deref_mut()
+            .ok_or_else(err)?
+            .append(buf, offset, obj)?;
+    } else {
+        offset = append_bool(buf, offset, false);
+    }
+    Ok(offset)
+}
+
+pub fn append_python_option<'py, F>(
+    py: Python<'py>,
+    buf: &mut [u8],
+    mut offset: usize,
+    obj_option: &Option<Py<PyAny>>,
+    serde_option: &mut Option<Box<dyn PyAnySerde>>,
+    err: F,
+) -> PyResult<usize>
+where
+    F: FnOnce() -> PyErr,
+{
+    if let Some(obj) = obj_option {
+        offset = append_bool(buf, offset, true);
+        offset = serde_option
+            .as_deref_mut()
+            .ok_or_else(err)?
+            .append(buf, offset, obj.bind(py))?;
+    } else {
+        offset = append_bool(buf, offset, false);
+    }
+    Ok(offset)
+}
+
+pub fn retrieve_python_option<'py, F>(
+    py: Python<'py>,
+    buf: &mut [u8],
+    mut offset: usize,
+    serde_option: &mut Option<Box<dyn PyAnySerde>>,
+    err: F,
+) -> PyResult<(Option<Bound<'py, PyAny>>, usize)>
+where
+    F: FnOnce() -> PyErr,
+{
+    let is_some;
+    (is_some, offset) = retrieve_bool(buf, offset)?;
+    let obj_option = if is_some {
+        let obj;
+        (obj, offset) = serde_option
+            .as_deref_mut()
+            .ok_or_else(err)?
+            .retrieve(py, buf, offset)?;
+        Some(obj)
+    } else {
+        None
+    };
+    Ok((obj_option, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_roundtrip() -> PyResult<()> {
+        for val in [0, 1, 127, 128, 255, 256, 16383, 16384, usize::MAX] {
+            let mut v = Vec::new();
+            append_varint_vec(&mut v, val);
+            let mut buf = vec![0; v.len()];
+            assert_eq!(append_varint(&mut buf, 0, val), v.len());
+            assert_eq!(buf, v);
+            assert_eq!(retrieve_varint(&v, 0)?, (val, v.len()));
+            assert!(retrieve_varint(&v[..v.len() - 1], 0).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_discriminant_width() -> PyResult<()> {
+        let mut v = Vec::new();
+        append_discriminant_vec(&mut v, 255, 256);
+        assert_eq!(v, vec![255]);
+        assert_eq!(retrieve_discriminant(&v, 0, 256)?, (255, 1));
+        let mut v = Vec::new();
+        append_discriminant_vec(&mut v, 300, 301);
+        assert_eq!(v.len(), 2);
+        assert_eq!(retrieve_discriminant(&v, 0, 301)?, (300, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_exact_or_eof() {
+        let mut buf = [0; 4];
+        assert!(read_exact_or_eof(&mut &[1u8, 2, 3, 4][..], &mut buf).unwrap());
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert!(!read_exact_or_eof(&mut &[][..], &mut buf).unwrap());
+        let err = read_exact_or_eof(&mut &[1u8, 2][..], &mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
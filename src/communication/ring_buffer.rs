@@ -0,0 +1,471 @@
+use std::cell::Cell;
+use std::mem::size_of;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+use std::sync::Once;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use pyo3::exceptions::{PyTimeoutError, PyValueError};
+use pyo3::prelude::*;
+use raw_sync::Timeout;
+use raw_sync::events::{Event, EventImpl, EventInit, EventState};
+use shared_memory::{Shmem, ShmemConf};
+
+use crate::{PyAnySerde, PyAnySerdeType};
+
+const MAGIC: u64 = u64::from_ne_bytes(*b"PYARING1");
+const ALIGNMENT: usize = 64;
+const SLOT_HEADER_SIZE: usize = 2 * size_of::<u64>();
+// Waits on the events are split into slices of this length, so a missed wakeup (e.g. when several
+// slots are freed before a waiting writer gets to run) only delays the waiter by this much.
+const WAIT_SLICE: Duration = Duration::from_millis(10);
+
+const SLOT_EMPTY: u64 = 0;
+const SLOT_READY: u64 = 1;
+// A slot which was claimed by a writer which was dropped before it could send an object, which is
+// passed over by the reader
+const SLOT_SKIPPED: u64 = 2;
+
+// The layout of the shared memory segment is this header, the not_empty and not_full events, and
+// then n_slots slots. Each slot is its state, the length of the serialized data, and slot_size
+// bytes for the serialized data itself. Everything is aligned to ALIGNMENT bytes, so the data of
+// every slot has the same alignment in every process.
+#[repr(C)]
+struct RingHeader {
+    magic: AtomicU64,
+    n_slots: u64,
+    slot_size: u64,
+    // The number of slots claimed by writers and released by the reader so far
+    write_idx: AtomicU64,
+    read_idx: AtomicU64,
+}
+
+fn round_up(n: usize) -> usize {
+    n.div_ceil(ALIGNMENT) * ALIGNMENT
+}
+
+fn events_offset() -> usize {
+    round_up(size_of::<RingHeader>())
+}
+
+fn event_region_size() -> usize {
+    // The event aligns part of itself to a pointer boundary, hence the extra usize
+    round_up(Event::size_of(None) + size_of::<usize>())
+}
+
+fn slots_offset() -> usize {
+    events_offset() + 2 * event_region_size()
+}
+
+fn slot_stride(slot_size: usize) -> usize {
+    round_up(SLOT_HEADER_SIZE + slot_size)
+}
+
+fn shmem_size(n_slots: usize, slot_size: usize) -> usize {
+    slots_offset() + n_slots * slot_stride(slot_size)
+}
+
+thread_local! {
+    static IN_SLOT_APPEND: Cell<bool> = const { Cell::new(false) };
+}
+static INSTALL_SLOT_APPEND_PANIC_HOOK: Once = Once::new();
+
+// Appends obj to the start of slot, returning None if it doesn't fit. Appending past the end of a
+// buffer panics, so the panic is caught, and a panic hook keeps it from being reported.
+fn append_to_slot(
+    serde: &mut Box<dyn PyAnySerde>,
+    slot: &mut [u8],
+    obj: &Bound<'_, PyAny>,
+) -> Option<PyResult<usize>> {
+    INSTALL_SLOT_APPEND_PANIC_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IN_SLOT_APPEND.get() {
+                default_hook(info);
+            }
+        }));
+    });
+    IN_SLOT_APPEND.set(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| serde.append(slot, 0, obj)));
+    IN_SLOT_APPEND.set(false);
+    result.ok()
+}
+
+fn sync_err(context: &str, err: Box<dyn std::error::Error>) -> PyErr {
+    PyValueError::new_err(format!("{context}: {err}"))
+}
+
+struct RingShared {
+    shmem: Shmem,
+    not_empty: Box<dyn EventImpl>,
+    not_full: Box<dyn EventImpl>,
+    n_slots: usize,
+    slot_size: usize,
+}
+
+// The mapping and the process-shared pthread primitives behind the events may be used from any
+// thread; they are only !Send and !Sync because they are accessed through raw pointers. This is
+// what allows waiting on the events with the GIL released.
+unsafe impl Send for RingShared {}
+unsafe impl Sync for RingShared {}
+
+impl RingShared {
+    fn header(&self) -> &RingHeader {
+        unsafe { &*(self.shmem.as_ptr() as *const RingHeader) }
+    }
+
+    fn slot_ptr(&self, slot_idx: usize) -> *mut u8 {
+        unsafe {
+            self.shmem
+                .as_ptr()
+                .add(slots_offset() + (slot_idx % self.n_slots) * slot_stride(self.slot_size))
+        }
+    }
+
+    fn slot_state(&self, slot_idx: usize) -> &AtomicU64 {
+        unsafe { &*(self.slot_ptr(slot_idx) as *const AtomicU64) }
+    }
+
+    fn slot_len_ptr(&self, slot_idx: usize) -> *mut u64 {
+        unsafe { self.slot_ptr(slot_idx).add(size_of::<u64>()) as *mut u64 }
+    }
+
+    fn slot_data_ptr(&self, slot_idx: usize) -> *mut u8 {
+        unsafe { self.slot_ptr(slot_idx).add(SLOT_HEADER_SIZE) }
+    }
+
+    // Claims the next slot for a writer, or returns None if all the slots are in use
+    fn try_claim(&self) -> Option<usize> {
+        let header = self.header();
+        let mut write_idx = header.write_idx.load(Ordering::Acquire);
+        loop {
+            let read_idx = header.read_idx.load(Ordering::Acquire);
+            if write_idx - read_idx >= self.n_slots as u64 {
+                return None;
+            }
+            match header.write_idx.compare_exchange_weak(
+                write_idx,
+                write_idx + 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Some(write_idx as usize),
+                Err(cur_write_idx) => write_idx = cur_write_idx,
+            }
+        }
+    }
+
+    // Repeatedly calls f until it returns a value, waiting on event in between. Returns None if
+    // the deadline passes first.
+    fn wait_until<T>(
+        &self,
+        event: &dyn EventImpl,
+        deadline: Option<Instant>,
+        mut f: impl FnMut() -> Option<T>,
+    ) -> Option<T> {
+        loop {
+            if let Some(v) = f() {
+                return Some(v);
+            }
+            let wait = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return None;
+                    }
+                    (deadline - now).min(WAIT_SLICE)
+                }
+                None => WAIT_SLICE,
+            };
+            // An error (including timing out) just means the condition needs to be checked again
+            let _ = event.wait(Timeout::Val(wait));
+        }
+    }
+}
+
+// A bounded channel over a named shared memory segment, with fixed-size slots which objects are
+// serialized into by the serde of the channel. Any number of writers (in any number of
+// processes) may send to the channel, but only one reader may receive from it at a time. The
+// process which creates the channel owns the shared memory segment, which is removed when its
+// RingBuffer is dropped. Like its serde, a RingBuffer can only be used by the thread that made it;
+// other threads (and processes) open their own RingBuffer using the name of the channel.
+#[pyclass(unsendable)]
+pub struct RingBuffer {
+    shared: RingShared,
+    serde: Box<dyn PyAnySerde>,
+    scratch: Vec<u8>,
+    // A slot claimed by a send which failed, which is used by the next send instead of claiming
+    // another one
+    claimed_slot_idx: Option<usize>,
+}
+
+impl RingBuffer {
+    pub fn create(
+        serde: Box<dyn PyAnySerde>,
+        n_slots: usize,
+        slot_size: usize,
+        name: Option<&str>,
+    ) -> PyResult<Self> {
+        if n_slots == 0 || slot_size == 0 {
+            Err(PyValueError::new_err(
+                "n_slots and slot_size of a RingBuffer must be positive",
+            ))?
+        }
+        let mut conf = ShmemConf::new().size(shmem_size(n_slots, slot_size));
+        if let Some(name) = name {
+            conf = conf.os_id(name);
+        }
+        let shmem = conf.create().map_err(|err| {
+            PyValueError::new_err(format!(
+                "failed to create shared memory for RingBuffer: {err}"
+            ))
+        })?;
+        let ptr = shmem.as_ptr();
+        // A newly created segment is zeroed, so all the slots start out empty
+        unsafe {
+            (ptr as *mut RingHeader).write(RingHeader {
+                magic: AtomicU64::new(0),
+                n_slots: n_slots as u64,
+                slot_size: slot_size as u64,
+                write_idx: AtomicU64::new(0),
+                read_idx: AtomicU64::new(0),
+            })
+        };
+        let (not_empty, _) = unsafe { Event::new(ptr.add(events_offset()), true) }
+            .map_err(|err| sync_err("failed to create RingBuffer event", err))?;
+        let (not_full, _) =
+            unsafe { Event::new(ptr.add(events_offset() + event_region_size()), true) }
+                .map_err(|err| sync_err("failed to create RingBuffer event", err))?;
+        let shared = RingShared {
+            shmem,
+            not_empty,
+            not_full,
+            n_slots,
+            slot_size,
+        };
+        shared.header().magic.store(MAGIC, Ordering::Release);
+        Ok(RingBuffer {
+            shared,
+            serde,
+            scratch: Vec::new(),
+            claimed_slot_idx: None,
+        })
+    }
+
+    pub fn open(serde: Box<dyn PyAnySerde>, name: &str) -> PyResult<Self> {
+        let shmem = ShmemConf::new().os_id(name).open().map_err(|err| {
+            PyValueError::new_err(format!(
+                "failed to open shared memory {name} for RingBuffer: {err}"
+            ))
+        })?;
+        let ptr = shmem.as_ptr();
+        let header = unsafe { &*(ptr as *const RingHeader) };
+        if shmem.len() < slots_offset() || header.magic.load(Ordering::Acquire) != MAGIC {
+            Err(PyValueError::new_err(format!(
+                "shared memory {name} does not contain a RingBuffer"
+            )))?
+        }
+        let n_slots = header.n_slots as usize;
+        let slot_size = header.slot_size as usize;
+        if shmem.len() < shmem_size(n_slots, slot_size) {
+            Err(PyValueError::new_err(format!(
+                "shared memory {name} is too small for a RingBuffer with {n_slots} slots of {slot_size} bytes"
+            )))?
+        }
+        let (not_empty, _) = unsafe { Event::from_existing(ptr.add(events_offset())) }
+            .map_err(|err| sync_err("failed to open RingBuffer event", err))?;
+        let (not_full, _) =
+            unsafe { Event::from_existing(ptr.add(events_offset() + event_region_size())) }
+                .map_err(|err| sync_err("failed to open RingBuffer event", err))?;
+        Ok(RingBuffer {
+            shared: RingShared {
+                shmem,
+                not_empty,
+                not_full,
+                n_slots,
+                slot_size,
+            },
+            serde,
+            scratch: Vec::new(),
+            claimed_slot_idx: None,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.shared.shmem.get_os_id()
+    }
+
+    pub fn n_slots(&self) -> usize {
+        self.shared.n_slots
+    }
+
+    pub fn slot_size(&self) -> usize {
+        self.shared.slot_size
+    }
+
+    // Serializes obj directly into the next slot, waiting (with the GIL released) for a slot to
+    // become free if the channel is full. If obj can't be serialized or doesn't fit in a slot, the
+    // slot stays claimed by this RingBuffer and is used by its next send, so the reader waits for
+    // that send (or for this RingBuffer to be dropped) before receiving from later slots.
+    pub fn send<'py>(
+        &mut self,
+        obj: &Bound<'py, PyAny>,
+        timeout: Option<Duration>,
+    ) -> PyResult<()> {
+        let py = obj.py();
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let shared = &self.shared;
+        let slot_idx = match self.claimed_slot_idx.take() {
+            Some(slot_idx) => slot_idx,
+            None => py
+                .detach(|| shared.wait_until(&*shared.not_full, deadline, || shared.try_claim()))
+                .ok_or_else(|| {
+                    PyTimeoutError::new_err("timed out waiting for a free slot in the RingBuffer")
+                })?,
+        };
+        let slot =
+            unsafe { slice::from_raw_parts_mut(shared.slot_data_ptr(slot_idx), shared.slot_size) };
+        // The data of every slot is aligned to ALIGNMENT bytes, so numpy alignment padding
+        // relative to the start of the slot is the same as relative to its address
+        let len = match append_to_slot(&mut self.serde, slot, obj) {
+            Some(Ok(len)) => len,
+            Some(Err(err)) => {
+                self.claimed_slot_idx = Some(slot_idx);
+                Err(err)?
+            }
+            None => {
+                // The object is serialized again just to report its size
+                self.claimed_slot_idx = Some(slot_idx);
+                self.scratch.clear();
+                self.serde.append_vec(&mut self.scratch, Some(0), obj)?;
+                Err(PyValueError::new_err(format!(
+                    "object of {} bytes does not fit in the RingBuffer slot size of {} bytes",
+                    self.scratch.len(),
+                    shared.slot_size
+                )))?
+            }
+        };
+        unsafe { shared.slot_len_ptr(slot_idx).write(len as u64) };
+        shared
+            .slot_state(slot_idx)
+            .store(SLOT_READY, Ordering::Release);
+        let _ = shared.not_empty.set(EventState::Signaled);
+        Ok(())
+    }
+
+    // Retrieves the object in the next slot, waiting (with the GIL released) for one to be sent if
+    // the channel is empty
+    pub fn recv<'py>(
+        &mut self,
+        py: Python<'py>,
+        timeout: Option<Duration>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let shared = &self.shared;
+        let header = shared.header();
+        loop {
+            let slot_idx = header.read_idx.load(Ordering::Acquire) as usize;
+            let state = py
+                .detach(|| {
+                    shared.wait_until(&*shared.not_empty, deadline, || {
+                        let state = shared.slot_state(slot_idx).load(Ordering::Acquire);
+                        (state != SLOT_EMPTY).then_some(state)
+                    })
+                })
+                .ok_or_else(|| {
+                    PyTimeoutError::new_err("timed out waiting for an object in the RingBuffer")
+                })?;
+            let result = (state == SLOT_READY).then(|| {
+                let len = unsafe { shared.slot_len_ptr(slot_idx).read() } as usize;
+                let buf = unsafe { slice::from_raw_parts(shared.slot_data_ptr(slot_idx), len) };
+                self.serde.retrieve(py, buf, 0)
+            });
+            shared
+                .slot_state(slot_idx)
+                .store(SLOT_EMPTY, Ordering::Relaxed);
+            header
+                .read_idx
+                .store(slot_idx as u64 + 1, Ordering::Release);
+            let _ = shared.not_full.set(EventState::Signaled);
+            if let Some(result) = result {
+                return result.map(|(obj, _)| obj);
+            }
+        }
+    }
+}
+
+impl Drop for RingBuffer {
+    fn drop(&mut self) {
+        if let Some(slot_idx) = self.claimed_slot_idx.take() {
+            let shared = &self.shared;
+            shared
+                .slot_state(slot_idx)
+                .store(SLOT_SKIPPED, Ordering::Release);
+            let _ = shared.not_empty.set(EventState::Signaled);
+        }
+    }
+}
+
+fn get_timeout(timeout: Option<f64>) -> PyResult<Option<Duration>> {
+    timeout
+        .map(|timeout| {
+            Duration::try_from_secs_f64(timeout)
+                .map_err(|err| PyValueError::new_err(format!("invalid timeout {timeout}: {err}")))
+        })
+        .transpose()
+}
+
+#[pymethods]
+impl RingBuffer {
+    #[staticmethod]
+    #[pyo3(name = "create", signature = (serde_type, n_slots, slot_size, name = None))]
+    fn create_py(
+        serde_type: PyAnySerdeType,
+        n_slots: usize,
+        slot_size: usize,
+        name: Option<String>,
+    ) -> PyResult<Self> {
+        RingBuffer::create(
+            (&serde_type).try_into()?,
+            n_slots,
+            slot_size,
+            name.as_deref(),
+        )
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "open")]
+    fn open_py(serde_type: PyAnySerdeType, name: String) -> PyResult<Self> {
+        RingBuffer::open((&serde_type).try_into()?, &name)
+    }
+
+    #[getter]
+    fn get_name(&self) -> String {
+        self.name().to_owned()
+    }
+
+    #[getter]
+    fn get_n_slots(&self) -> usize {
+        self.n_slots()
+    }
+
+    #[getter]
+    fn get_slot_size(&self) -> usize {
+        self.slot_size()
+    }
+
+    #[pyo3(name = "send", signature = (obj, timeout = None))]
+    fn send_py<'py>(&mut self, obj: &Bound<'py, PyAny>, timeout: Option<f64>) -> PyResult<()> {
+        self.send(obj, get_timeout(timeout)?)
+    }
+
+    #[pyo3(name = "recv", signature = (timeout = None))]
+    fn recv_py<'py>(
+        &mut self,
+        py: Python<'py>,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.recv(py, get_timeout(timeout)?)
+    }
+}
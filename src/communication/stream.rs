@@ -0,0 +1,166 @@
+use std::io::{ErrorKind, Read, Write};
+use std::mem::size_of;
+
+use pyo3::exceptions::PyEOFError;
+use pyo3::prelude::*;
+
+use crate::common::AlignedBuffer;
+
+use super::read_exact_or_eof;
+
+// Writes serialized data to a std::io::Write, keeping track of how many bytes have been written so
+// that numpy alignment padding can be computed relative to where the writer started. Data written
+// through a StreamWriter is identical to data appended with append_vec using a start_addr of 0.
+pub struct StreamWriter<'a> {
+    writer: &'a mut dyn Write,
+    position: usize,
+}
+
+impl<'a> StreamWriter<'a> {
+    pub fn new(writer: &'a mut dyn Write) -> Self {
+        StreamWriter {
+            writer,
+            position: 0,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn write_all(&mut self, bytes: &[u8]) -> PyResult<()> {
+        self.writer.write_all(bytes)?;
+        self.position += bytes.len();
+        Ok(())
+    }
+}
+
+// Reads serialized data from a std::io::Read, keeping track of how many bytes have been read so
+// that numpy alignment padding can be computed relative to where the reader started.
+pub struct StreamReader<'a> {
+    reader: &'a mut dyn Read,
+    position: usize,
+    // Whether the stream ended before anything was read from it
+    at_end: bool,
+    // The rest of the stream and the offset of the current position in it, once it has been read
+    // by retrieve_from_rest
+    rest: Option<(AlignedBuffer, usize)>,
+}
+
+impl<'a> StreamReader<'a> {
+    pub fn new(reader: &'a mut dyn Read) -> Self {
+        StreamReader {
+            reader,
+            position: 0,
+            at_end: false,
+            rest: None,
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.at_end
+    }
+
+    fn map_err(err: std::io::Error) -> PyErr {
+        if err.kind() == ErrorKind::UnexpectedEof {
+            PyEOFError::new_err("stream ended in the middle of a serialized object")
+        } else {
+            err.into()
+        }
+    }
+
+    // Fills buf from the stream
+    pub fn read_into(&mut self, buf: &mut [u8]) -> PyResult<()> {
+        let at_start = self.position == 0;
+        if !read_exact_or_eof(self, buf).map_err(Self::map_err)? {
+            self.at_end = at_start;
+            Err(Self::map_err(ErrorKind::UnexpectedEof.into()))?
+        }
+        Ok(())
+    }
+
+    // Reads len bytes from the stream. The bytes are read in chunks, so a corrupted length can't
+    // cause a large allocation without the data to fill it.
+    pub fn read_vec(&mut self, len: usize) -> PyResult<Vec<u8>> {
+        let mut v = Vec::new();
+        self.by_ref()
+            .take(len as u64)
+            .read_to_end(&mut v)
+            .map_err(Self::map_err)?;
+        if v.len() != len {
+            Err(Self::map_err(ErrorKind::UnexpectedEof.into()))?
+        }
+        Ok(v)
+    }
+
+    // Reads len bytes from the stream into an aligned buffer, returning the buffer and the offset
+    // of the bytes in it. The offset is chosen so that the address of each byte in the buffer has
+    // the same alignment as its position in the stream, which means serialized data can be
+    // retrieved from the buffer with the same numpy alignment padding it was written with.
+    pub fn read_aligned(&mut self, len: usize) -> PyResult<(AlignedBuffer, usize)> {
+        let offset = self.position % size_of::<u64>();
+        let buffer = AlignedBuffer::read_from_offset(self, offset, len).map_err(Self::map_err)?;
+        Ok((buffer, offset))
+    }
+
+    // Reads the rest of the stream into an aligned buffer (if it hasn't been read already) and
+    // retrieves an object from it with retrieve_fn, which is given the buffer and the offset of the
+    // current position in it, and returns the object and the offset after it. This is for data
+    // whose length isn't known until it has been retrieved.
+    pub fn retrieve_from_rest<T>(
+        &mut self,
+        retrieve_fn: impl FnOnce(&[u8], usize) -> PyResult<(T, usize)>,
+    ) -> PyResult<T> {
+        if self.rest.is_none() {
+            let offset = self.position % size_of::<u64>();
+            let buffer = AlignedBuffer::read_to_end_from_offset(self.reader, offset)
+                .map_err(Self::map_err)?;
+            self.rest = Some((buffer, offset));
+        }
+        let (buffer, offset) = self.rest.as_mut().unwrap();
+        if *offset == buffer.as_slice().len() {
+            self.at_end = self.position == 0;
+            Err(Self::map_err(ErrorKind::UnexpectedEof.into()))?
+        }
+        let (obj, end) = retrieve_fn(buffer.as_slice(), *offset)?;
+        self.position += end - *offset;
+        *offset = end;
+        Ok(obj)
+    }
+
+    // Whether retrieve_from_rest read data from the stream which hasn't been used yet
+    pub fn has_unused_rest(&self) -> bool {
+        self.rest
+            .as_ref()
+            .is_some_and(|(buffer, offset)| *offset < buffer.as_slice().len())
+    }
+
+    // Reads and discards len bytes from the stream
+    pub fn skip(&mut self, len: usize) -> PyResult<()> {
+        let n_skipped = std::io::copy(&mut self.by_ref().take(len as u64), &mut std::io::sink())
+            .map_err(Self::map_err)?;
+        if n_skipped != len as u64 {
+            Err(Self::map_err(ErrorKind::UnexpectedEof.into()))?
+        }
+        Ok(())
+    }
+}
+
+impl Read for StreamReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n_read = match &mut self.rest {
+            Some((buffer, offset)) => {
+                let n_read = (&buffer.as_slice()[*offset..]).read(buf)?;
+                *offset += n_read;
+                n_read
+            }
+            None => self.reader.read(buf)?,
+        };
+        self.position += n_read;
+        Ok(n_read)
+    }
+}
@@ -0,0 +1,2922 @@
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::rc::Rc;
+
+use pyo3::exceptions::asyncio::InvalidStateError;
+use pyo3::exceptions::{PyRecursionError, PyValueError};
+use pyo3::prelude::*;
+use serde_json::{Value, json};
+
+use crate::common::{
+    CompressionCodec, NumpyDtype, PythonType, crc32c, get_bytes_to_alignment, get_python_type_byte,
+    retrieve_python_type,
+};
+use crate::communication::{
+    append_bool_vec, append_bytes_vec, append_discriminant_vec, append_f64_vec, append_i64_vec,
+    append_u32_vec, append_usize_vec, append_varint_vec,
+};
+use crate::pyany_serde_impl::{ExtraPolicy, NumpySerdeConfig, SchemaPolicy};
+use crate::{FieldSerdeTypeMap, PyAnySerdeType, PyAnySerdeTypeKind};
+
+// A value decoded from a serialized buffer, along with the range of the buffer it was decoded
+// from. The kind is None for the fields of a VERSIONED field table which are not fields of the
+// serde type (whose values are left as bytes).
+#[derive(Debug, Clone)]
+pub struct DecodedNode {
+    pub kind: Option<PyAnySerdeTypeKind>,
+    pub start: usize,
+    pub end: usize,
+    pub value: DecodedValue,
+}
+
+#[derive(Debug, Clone)]
+pub enum DecodedValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Complex {
+        real: f64,
+        imag: f64,
+    },
+    String(String),
+    // The data of BYTES and PICKLE, and the values of unknown fields
+    Bytes(Vec<u8>),
+    Option(Option<Box<DecodedNode>>),
+    Items(Vec<DecodedNode>),
+    Deque {
+        maxlen: Option<usize>,
+        items: Vec<DecodedNode>,
+    },
+    Entries(Vec<(DecodedNode, DecodedNode)>),
+    DefaultDict {
+        // The module and qualname of the default_factory
+        default_factory: Option<(String, String)>,
+        entries: Vec<(DecodedNode, DecodedNode)>,
+    },
+    Fields {
+        fields: Vec<(String, DecodedNode)>,
+        extras: Vec<(String, DecodedNode)>,
+    },
+    Columns {
+        n_items: usize,
+        columns: Vec<(String, Vec<DecodedNode>)>,
+    },
+    Union {
+        discriminant: usize,
+        value: Box<DecodedNode>,
+    },
+    Enum {
+        discriminant: usize,
+    },
+    Memo {
+        memo_idx: usize,
+        value: Box<DecodedNode>,
+    },
+    MemoRef {
+        memo_idx: usize,
+    },
+    Checksummed {
+        checksum: u32,
+        valid: bool,
+        value: Box<DecodedNode>,
+    },
+    // The range of the value is relative to the start of data, which is the decompressed data
+    Compressed {
+        compressed: bool,
+        data: Vec<u8>,
+        value: Box<DecodedNode>,
+    },
+    Numpy {
+        dtype: NumpyDtype,
+        shape: Vec<usize>,
+        // The alignment padding written before the data
+        padding: usize,
+        data: Vec<Value>,
+    },
+    // The value of the Python type given by the type byte
+    Dynamic(Box<DecodedNode>),
+}
+
+// The elements of numpy arrays, which are rendered as JSON numbers (or strings for non-finite
+// floats, like FLOAT values)
+trait NumpyItem: Sized {
+    fn from_item_bytes(bytes: &[u8]) -> Self;
+    fn to_json(self) -> Value;
+    fn from_json(value: &Value) -> Option<Self>;
+    fn append_item_vec(self, v: &mut Vec<u8>);
+}
+
+macro_rules! impl_numpy_int_item {
+    ($type:ty) => {
+        impl NumpyItem for $type {
+            fn from_item_bytes(bytes: &[u8]) -> Self {
+                <$type>::from_ne_bytes(bytes.try_into().unwrap())
+            }
+
+            fn to_json(self) -> Value {
+                Value::from(self)
+            }
+
+            fn from_json(value: &Value) -> Option<Self> {
+                value
+                    .as_i64()
+                    .and_then(|v| <$type>::try_from(v).ok())
+                    .or_else(|| value.as_u64().and_then(|v| <$type>::try_from(v).ok()))
+            }
+
+            fn append_item_vec(self, v: &mut Vec<u8>) {
+                v.extend_from_slice(&self.to_ne_bytes());
+            }
+        }
+    };
+}
+
+macro_rules! impl_numpy_float_item {
+    ($type:ty) => {
+        impl NumpyItem for $type {
+            fn from_item_bytes(bytes: &[u8]) -> Self {
+                <$type>::from_ne_bytes(bytes.try_into().unwrap())
+            }
+
+            fn to_json(self) -> Value {
+                float_to_json(self as f64)
+            }
+
+            fn from_json(value: &Value) -> Option<Self> {
+                float_from_json(value).map(|v| v as $type)
+            }
+
+            fn append_item_vec(self, v: &mut Vec<u8>) {
+                v.extend_from_slice(&self.to_ne_bytes());
+            }
+        }
+    };
+}
+
+impl_numpy_int_item!(i8);
+impl_numpy_int_item!(i16);
+impl_numpy_int_item!(i32);
+impl_numpy_int_item!(i64);
+impl_numpy_int_item!(u8);
+impl_numpy_int_item!(u16);
+impl_numpy_int_item!(u32);
+impl_numpy_int_item!(u64);
+impl_numpy_float_item!(f32);
+impl_numpy_float_item!(f64);
+
+// Evaluates body with item as the element type of dtype
+macro_rules! with_numpy_item {
+    ($dtype:expr, $item:ident => $body:expr) => {
+        match $dtype {
+            NumpyDtype::INT8 => {
+                type $item = i8;
+                $body
+            }
+            NumpyDtype::INT16 => {
+                type $item = i16;
+                $body
+            }
+            NumpyDtype::INT32 => {
+                type $item = i32;
+                $body
+            }
+            NumpyDtype::INT64 => {
+                type $item = i64;
+                $body
+            }
+            NumpyDtype::UINT8 => {
+                type $item = u8;
+                $body
+            }
+            NumpyDtype::UINT16 => {
+                type $item = u16;
+                $body
+            }
+            NumpyDtype::UINT32 => {
+                type $item = u32;
+                $body
+            }
+            NumpyDtype::UINT64 => {
+                type $item = u64;
+                $body
+            }
+            NumpyDtype::FLOAT32 => {
+                type $item = f32;
+                $body
+            }
+            NumpyDtype::FLOAT64 => {
+                type $item = f64;
+                $body
+            }
+        }
+    };
+}
+
+// JSON has no non-finite numbers, so those are written as the strings "nan", "inf", and "-inf"
+fn float_to_json(val: f64) -> Value {
+    if val.is_finite() {
+        Value::from(val)
+    } else {
+        Value::from(val.to_string().to_lowercase())
+    }
+}
+
+fn float_from_json(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        _ => value.as_f64(),
+    }
+}
+
+// The scope of REF serde types, following the lexical scoping of ref_serde. The depth is the
+// number of REFs which have been resolved to get to the scope.
+#[derive(Clone, Default)]
+struct Scope {
+    definitions_option: Option<Rc<DefinitionsScope>>,
+    depth: usize,
+}
+
+struct DefinitionsScope {
+    definitions: HashMap<String, PyAnySerdeType>,
+    max_depth: usize,
+    parent: Option<Rc<DefinitionsScope>>,
+}
+
+impl Scope {
+    fn enter_definitions(&self, definitions: &FieldSerdeTypeMap, max_depth: usize) -> Scope {
+        Scope {
+            definitions_option: Some(Rc::new(DefinitionsScope {
+                definitions: definitions
+                    .iter()
+                    .map(|(name, serde_type)| (name.clone(), serde_type.clone()))
+                    .collect(),
+                max_depth,
+                parent: self.definitions_option.clone(),
+            })),
+            depth: self.depth,
+        }
+    }
+
+    // Returns the definition name refers to, and the scope to use inside of it
+    fn resolve(&self, name: &str) -> PyResult<(PyAnySerdeType, Scope)> {
+        let mut definitions_option = self.definitions_option.as_ref();
+        while let Some(definitions_scope) = definitions_option {
+            if let Some(definition) = definitions_scope.definitions.get(name) {
+                if self.depth >= definitions_scope.max_depth {
+                    Err(PyRecursionError::new_err(format!(
+                        "REF {name} exceeded the max_depth of {} of its DEFINITIONS",
+                        definitions_scope.max_depth
+                    )))?
+                }
+                return Ok((
+                    definition.clone(),
+                    Scope {
+                        definitions_option: Some(definitions_scope.clone()),
+                        depth: self.depth + 1,
+                    },
+                ));
+            }
+            definitions_option = definitions_scope.parent.as_ref();
+        }
+        Err(PyValueError::new_err(format!(
+            "REF {name} does not refer to the name of a definition in any DEFINITIONS containing it"
+        )))
+    }
+}
+
+fn python_serde_err() -> PyErr {
+    PyValueError::new_err(
+        "PYTHONSERDE values can only be read by their python serde, so they cannot be decoded or encoded",
+    )
+}
+
+fn get_numpy_padding(dtype: NumpyDtype, addr: usize) -> usize {
+    with_numpy_item!(dtype, T => get_bytes_to_alignment::<T>(addr))
+}
+
+fn get_field_entries(
+    items_serde_type: &PyAnySerdeType,
+) -> PyResult<Vec<(&String, &PyAnySerdeType)>> {
+    match items_serde_type {
+        PyAnySerdeType::DATACLASS {
+            field_serde_type_dict,
+            alphabetical_field_order,
+            ..
+        } => Ok(field_serde_type_dict.serialization_order(*alphabetical_field_order)),
+        PyAnySerdeType::TYPEDDICT {
+            key_serde_type_dict,
+            alphabetical_field_order,
+            ..
+        } => Ok(key_serde_type_dict.serialization_order(*alphabetical_field_order)),
+        _ => Err(PyValueError::new_err(format!(
+            "COLUMNARLIST items_serde_type must be DATACLASS or TYPEDDICT, but was {items_serde_type}"
+        ))),
+    }
+}
+
+// Unlike the retrieve functions of the communication module, these fail instead of panicking when
+// the buffer is too short, since the buffers being debugged may well be truncated or corrupt.
+fn read<'a>(buf: &'a [u8], offset: usize, len: usize, what: &str) -> PyResult<(&'a [u8], usize)> {
+    offset
+        .checked_add(len)
+        .filter(|&end| end <= buf.len())
+        .map(|end| (&buf[offset..end], end))
+        .ok_or_else(|| {
+            InvalidStateError::new_err(format!(
+                "tried to decode {what} ({len} bytes) at offset {offset}, but the buffer is only {} bytes",
+                buf.len()
+            ))
+        })
+}
+
+macro_rules! define_read_primitive {
+    ($type:ty) => {
+        paste::paste! {
+            fn [<read_ $type>](buf: &[u8], offset: usize, what: &str) -> PyResult<($type, usize)> {
+                let (bytes, end) = read(buf, offset, size_of::<$type>(), what)?;
+                Ok((<$type>::from_ne_bytes(bytes.try_into()?), end))
+            }
+        }
+    };
+}
+
+define_read_primitive!(u8);
+define_read_primitive!(u32);
+define_read_primitive!(usize);
+define_read_primitive!(i64);
+define_read_primitive!(f64);
+
+fn read_bool(buf: &[u8], offset: usize, what: &str) -> PyResult<(bool, usize)> {
+    match read_u8(buf, offset, what)? {
+        (0, end) => Ok((false, end)),
+        (1, end) => Ok((true, end)),
+        (v, _) => Err(InvalidStateError::new_err(format!(
+            "tried to decode {what} as a bool at offset {offset}, but got value {v}"
+        ))),
+    }
+}
+
+fn read_varint(buf: &[u8], mut offset: usize, what: &str) -> PyResult<(usize, usize)> {
+    let mut val = 0usize;
+    let mut shift = 0;
+    loop {
+        let byte;
+        (byte, offset) = read_u8(buf, offset, what)?;
+        if shift >= usize::BITS || (byte & 0x7f) as usize > (usize::MAX >> shift) {
+            Err(InvalidStateError::new_err(format!(
+                "tried to decode {what} as a varint, but it overflowed usize"
+            )))?
+        }
+        val |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok((val, offset));
+        }
+        shift += 7;
+    }
+}
+
+fn read_discriminant(
+    buf: &[u8],
+    offset: usize,
+    n_options: usize,
+    what: &str,
+) -> PyResult<(usize, usize)> {
+    if n_options <= 256 {
+        let (idx, offset) = read_u8(buf, offset, what)?;
+        Ok((idx as usize, offset))
+    } else {
+        read_varint(buf, offset, what)
+    }
+}
+
+fn read_bytes<'a>(buf: &'a [u8], offset: usize, what: &str) -> PyResult<(&'a [u8], usize)> {
+    let (len, start) = read_usize(buf, offset, what)?;
+    read(buf, start, len, what)
+}
+
+fn read_string(buf: &[u8], offset: usize, what: &str) -> PyResult<(String, usize)> {
+    let (bytes, end) = read_bytes(buf, offset, what)?;
+    Ok((String::from_utf8(bytes.to_vec())?, end))
+}
+
+// Counts the values written by MEMO, to give each one its memo index. Like in memo_serde, the
+// outermost MEMO starts a new memo table which is shared by the MEMOs nested inside it.
+#[derive(Default)]
+struct MemoTable {
+    depth: usize,
+    n_values: usize,
+}
+
+impl MemoTable {
+    fn enter(&mut self) {
+        if self.depth == 0 {
+            self.n_values = 0;
+        }
+        self.depth += 1;
+    }
+
+    fn exit(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn push(&mut self) -> usize {
+        self.n_values += 1;
+        self.n_values - 1
+    }
+}
+
+struct Decoder<'py> {
+    py: Python<'py>,
+    memo_table: MemoTable,
+}
+
+impl<'py> Decoder<'py> {
+    fn decode(
+        &mut self,
+        serde_type: &PyAnySerdeType,
+        scope: &Scope,
+        buf: &[u8],
+        start: usize,
+    ) -> PyResult<DecodedNode> {
+        let py = self.py;
+        let kind = PyAnySerdeTypeKind::from(serde_type);
+        let (value, end) = match serde_type {
+            // DEFINITIONS and REF add nothing to the serialized format, so they have no nodes
+            PyAnySerdeType::DEFINITIONS {
+                definitions,
+                root_serde_type,
+                max_depth,
+            } => {
+                return self.decode(
+                    &root_serde_type.extract(py)?,
+                    &scope.enter_definitions(definitions, *max_depth),
+                    buf,
+                    start,
+                );
+            }
+            PyAnySerdeType::REF { name } => {
+                let (definition, definition_scope) = scope.resolve(name)?;
+                return self.decode(&definition, &definition_scope, buf, start);
+            }
+            PyAnySerdeType::BOOL {} => {
+                let (val, end) = read_bool(buf, start, "BOOL")?;
+                (DecodedValue::Bool(val), end)
+            }
+            PyAnySerdeType::INT {} => {
+                let (val, end) = read_i64(buf, start, "INT")?;
+                (DecodedValue::Int(val), end)
+            }
+            PyAnySerdeType::FLOAT {} => {
+                let (val, end) = read_f64(buf, start, "FLOAT")?;
+                (DecodedValue::Float(val), end)
+            }
+            PyAnySerdeType::COMPLEX {} => {
+                let (real, offset) = read_f64(buf, start, "the real part of a COMPLEX")?;
+                let (imag, end) = read_f64(buf, offset, "the imaginary part of a COMPLEX")?;
+                (DecodedValue::Complex { real, imag }, end)
+            }
+            PyAnySerdeType::STRING {} => {
+                let (val, end) = read_string(buf, start, "STRING")?;
+                (DecodedValue::String(val), end)
+            }
+            PyAnySerdeType::BYTES {} | PyAnySerdeType::PICKLE {} => {
+                let (val, end) = read_bytes(buf, start, &kind.to_string())?;
+                (DecodedValue::Bytes(val.to_vec()), end)
+            }
+            PyAnySerdeType::PYTHONSERDE { .. } => Err(python_serde_err())?,
+            PyAnySerdeType::OPTION { value_serde_type } => {
+                let (is_some, offset) = read_bool(buf, start, "the flag of an OPTION")?;
+                if is_some {
+                    let value = self.decode(&value_serde_type.extract(py)?, scope, buf, offset)?;
+                    let end = value.end;
+                    (DecodedValue::Option(Some(Box::new(value))), end)
+                } else {
+                    (DecodedValue::Option(None), offset)
+                }
+            }
+            PyAnySerdeType::FROZENSET { items_serde_type }
+            | PyAnySerdeType::HOMOGENEOUSTUPLE { items_serde_type }
+            | PyAnySerdeType::LIST { items_serde_type }
+            | PyAnySerdeType::SET { items_serde_type } => {
+                let (n_items, offset) = read_usize(buf, start, &format!("the length of a {kind}"))?;
+                let (items, end) =
+                    self.decode_items(&items_serde_type.extract(py)?, n_items, scope, buf, offset)?;
+                (DecodedValue::Items(items), end)
+            }
+            PyAnySerdeType::PRIMITIVELIST {
+                items_serde_type,
+                length,
+                ..
+            } => {
+                let (n_items, offset) = match length {
+                    Some(length) => (*length, start),
+                    None => read_usize(buf, start, "the length of a PRIMITIVELIST")?,
+                };
+                let (items, end) =
+                    self.decode_items(&items_serde_type.extract(py)?, n_items, scope, buf, offset)?;
+                (DecodedValue::Items(items), end)
+            }
+            PyAnySerdeType::NAMEDTUPLE {
+                item_serde_types, ..
+            }
+            | PyAnySerdeType::TUPLE { item_serde_types } => {
+                let mut items = Vec::with_capacity(item_serde_types.len());
+                let mut offset = start;
+                for item_serde_type in item_serde_types.iter() {
+                    let item = self.decode(item_serde_type, scope, buf, offset)?;
+                    offset = item.end;
+                    items.push(item);
+                }
+                (DecodedValue::Items(items), offset)
+            }
+            PyAnySerdeType::DEQUE { items_serde_type } => {
+                let (has_maxlen, mut offset) = read_bool(buf, start, "the maxlen flag of a DEQUE")?;
+                let maxlen = if has_maxlen {
+                    let maxlen;
+                    (maxlen, offset) = read_usize(buf, offset, "the maxlen of a DEQUE")?;
+                    Some(maxlen)
+                } else {
+                    None
+                };
+                let (n_items, offset) = read_usize(buf, offset, "the length of a DEQUE")?;
+                let (items, end) =
+                    self.decode_items(&items_serde_type.extract(py)?, n_items, scope, buf, offset)?;
+                (DecodedValue::Deque { maxlen, items }, end)
+            }
+            PyAnySerdeType::DEFAULTDICT {
+                keys_serde_type,
+                values_serde_type,
+            } => {
+                let (has_default_factory, mut offset) =
+                    read_bool(buf, start, "the default_factory flag of a DEFAULTDICT")?;
+                let default_factory = if has_default_factory {
+                    let module;
+                    (module, offset) =
+                        read_string(buf, offset, "the default_factory module of a DEFAULTDICT")?;
+                    let qualname;
+                    (qualname, offset) =
+                        read_string(buf, offset, "the default_factory qualname of a DEFAULTDICT")?;
+                    Some((module, qualname))
+                } else {
+                    None
+                };
+                let (entries, end) = self.decode_entries(
+                    &keys_serde_type.extract(py)?,
+                    &values_serde_type.extract(py)?,
+                    kind,
+                    scope,
+                    buf,
+                    offset,
+                )?;
+                (
+                    DecodedValue::DefaultDict {
+                        default_factory,
+                        entries,
+                    },
+                    end,
+                )
+            }
+            PyAnySerdeType::DICT {
+                keys_serde_type,
+                values_serde_type,
+            }
+            | PyAnySerdeType::ORDEREDDICT {
+                keys_serde_type,
+                values_serde_type,
+            } => {
+                let (entries, end) = self.decode_entries(
+                    &keys_serde_type.extract(py)?,
+                    &values_serde_type.extract(py)?,
+                    kind,
+                    scope,
+                    buf,
+                    start,
+                )?;
+                (DecodedValue::Entries(entries), end)
+            }
+            PyAnySerdeType::DATACLASS {
+                field_serde_type_dict,
+                extra,
+                alphabetical_field_order,
+                schema,
+                ..
+            } => self.decode_fields(
+                kind,
+                field_serde_type_dict.serialization_order(*alphabetical_field_order),
+                &[],
+                extra,
+                schema,
+                scope,
+                buf,
+                start,
+            )?,
+            PyAnySerdeType::TYPEDDICT {
+                key_serde_type_dict,
+                optional_keys,
+                extra,
+                alphabetical_field_order,
+                schema,
+            } => self.decode_fields(
+                kind,
+                key_serde_type_dict.serialization_order(*alphabetical_field_order),
+                optional_keys,
+                extra,
+                schema,
+                scope,
+                buf,
+                start,
+            )?,
+            PyAnySerdeType::COLUMNARLIST {
+                items_serde_type, ..
+            } => {
+                let items_serde_type = items_serde_type.extract(py)?;
+                let (n_items, mut offset) = read_usize(buf, start, "the length of a COLUMNARLIST")?;
+                let mut columns = Vec::new();
+                for (field, field_serde_type) in get_field_entries(&items_serde_type)? {
+                    let items;
+                    (items, offset) =
+                        self.decode_items(field_serde_type, n_items, scope, buf, offset)?;
+                    columns.push((field.clone(), items));
+                }
+                (DecodedValue::Columns { n_items, columns }, offset)
+            }
+            PyAnySerdeType::UNION {
+                option_serde_types, ..
+            } => {
+                let n_options = option_serde_types.len();
+                let (discriminant, offset) =
+                    read_discriminant(buf, start, n_options, "the option idx of a UNION")?;
+                let option_serde_type = option_serde_types.get(discriminant).ok_or_else(|| {
+                    InvalidStateError::new_err(format!(
+                        "Decoded UNION option idx {discriminant} which is not a valid option index (there are {n_options} options)"
+                    ))
+                })?;
+                let value = self.decode(option_serde_type, scope, buf, offset)?;
+                let end = value.end;
+                (
+                    DecodedValue::Union {
+                        discriminant,
+                        value: Box::new(value),
+                    },
+                    end,
+                )
+            }
+            PyAnySerdeType::ENUM { values } => {
+                let n_values = values.len();
+                let (discriminant, end) =
+                    read_discriminant(buf, start, n_values, "the value idx of an ENUM")?;
+                if discriminant >= n_values {
+                    Err(InvalidStateError::new_err(format!(
+                        "Decoded ENUM value idx {discriminant} which is not a valid value index (there are {n_values} values)"
+                    )))?
+                }
+                (DecodedValue::Enum { discriminant }, end)
+            }
+            PyAnySerdeType::MEMO { value_serde_type } => {
+                let (tag, offset) = read_varint(buf, start, "the tag of a MEMO")?;
+                self.memo_table.enter();
+                let decoded = if tag == 0 {
+                    let value = self.decode(&value_serde_type.extract(py)?, scope, buf, offset)?;
+                    let end = value.end;
+                    (
+                        DecodedValue::Memo {
+                            memo_idx: self.memo_table.push(),
+                            value: Box::new(value),
+                        },
+                        end,
+                    )
+                } else {
+                    let memo_idx = tag - 1;
+                    if memo_idx >= self.memo_table.n_values {
+                        Err(InvalidStateError::new_err(format!(
+                            "MEMO found a reference to memo index {memo_idx}, but only {} values have been decoded",
+                            self.memo_table.n_values
+                        )))?
+                    }
+                    (DecodedValue::MemoRef { memo_idx }, offset)
+                };
+                self.memo_table.exit();
+                decoded
+            }
+            PyAnySerdeType::CHECKSUMMED { value_serde_type } => {
+                let (value_len, value_start) =
+                    read_usize(buf, start, "the length of a CHECKSUMMED")?;
+                let value = self.decode(&value_serde_type.extract(py)?, scope, buf, value_start)?;
+                if value.end - value_start != value_len {
+                    Err(InvalidStateError::new_err(format!(
+                        "CHECKSUMMED value has length {value_len}, but {} bytes were decoded",
+                        value.end - value_start
+                    )))?
+                }
+                let (checksum, end) = read_u32(buf, value.end, "the checksum of a CHECKSUMMED")?;
+                let valid = crc32c(&buf[start..value.end]) == checksum;
+                (
+                    DecodedValue::Checksummed {
+                        checksum,
+                        valid,
+                        value: Box::new(value),
+                    },
+                    end,
+                )
+            }
+            PyAnySerdeType::COMPRESSED {
+                value_serde_type,
+                codec,
+                ..
+            } => {
+                let (compressed, offset) = read_bool(buf, start, "the flag of a COMPRESSED")?;
+                let (len, offset) = if compressed {
+                    read_varint(buf, offset, "the uncompressed length of a COMPRESSED")?
+                } else {
+                    (0, offset)
+                };
+                let (data, end) = read_bytes(buf, offset, "the data of a COMPRESSED")?;
+                let value_buf = if compressed {
+                    decompress(*codec, data, len)?
+                } else {
+                    data.to_vec()
+                };
+                let value = self.decode(&value_serde_type.extract(py)?, scope, &value_buf, 0)?;
+                if value.end != value_buf.len() {
+                    Err(InvalidStateError::new_err(format!(
+                        "COMPRESSED data of {} bytes contained {} bytes of serialized data",
+                        value_buf.len(),
+                        value.end
+                    )))?
+                }
+                (
+                    DecodedValue::Compressed {
+                        compressed,
+                        data: value_buf,
+                        value: Box::new(value),
+                    },
+                    end,
+                )
+            }
+            PyAnySerdeType::NUMPY { dtype, config } => match config {
+                NumpySerdeConfig::DYNAMIC { .. } => decode_numpy(*dtype, None, buf, start)?,
+                NumpySerdeConfig::STATIC { shape, .. } => {
+                    decode_numpy(*dtype, Some(shape), buf, start)?
+                }
+            },
+            PyAnySerdeType::DYNAMIC {} => {
+                let (type_byte, offset) = read_u8(buf, start, "the type byte of a DYNAMIC")?;
+                let (python_type, _) = retrieve_python_type(&[type_byte], 0)?;
+                let value = self.decode_dynamic_value(python_type, buf, offset)?;
+                let end = value.end;
+                (DecodedValue::Dynamic(Box::new(value)), end)
+            }
+        };
+        Ok(DecodedNode {
+            kind: Some(kind),
+            start,
+            end,
+            value,
+        })
+    }
+
+    fn decode_items(
+        &mut self,
+        items_serde_type: &PyAnySerdeType,
+        n_items: usize,
+        scope: &Scope,
+        buf: &[u8],
+        mut offset: usize,
+    ) -> PyResult<(Vec<DecodedNode>, usize)> {
+        let mut items = Vec::new();
+        for _ in 0..n_items {
+            let item = self.decode(items_serde_type, scope, buf, offset)?;
+            offset = item.end;
+            items.push(item);
+        }
+        Ok((items, offset))
+    }
+
+    fn decode_entries(
+        &mut self,
+        keys_serde_type: &PyAnySerdeType,
+        values_serde_type: &PyAnySerdeType,
+        kind: PyAnySerdeTypeKind,
+        scope: &Scope,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Vec<(DecodedNode, DecodedNode)>, usize)> {
+        let (n_entries, mut offset) = read_usize(buf, offset, &format!("the length of a {kind}"))?;
+        let mut entries = Vec::new();
+        for _ in 0..n_entries {
+            let key = self.decode(keys_serde_type, scope, buf, offset)?;
+            let value = self.decode(values_serde_type, scope, buf, key.end)?;
+            offset = value.end;
+            entries.push((key, value));
+        }
+        Ok((entries, offset))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn decode_fields(
+        &mut self,
+        kind: PyAnySerdeTypeKind,
+        entries: Vec<(&String, &PyAnySerdeType)>,
+        optional_keys: &[String],
+        extra: &ExtraPolicy,
+        schema: &SchemaPolicy,
+        scope: &Scope,
+        buf: &[u8],
+        mut offset: usize,
+    ) -> PyResult<(DecodedValue, usize)> {
+        let mut fields = Vec::new();
+        match schema {
+            SchemaPolicy::FIXED {} => {
+                // Only TYPEDDICTs have optional keys, so the presence bitmap of a DATACLASS is empty
+                let n_optional_keys = entries
+                    .iter()
+                    .filter(|(field, _)| optional_keys.contains(field))
+                    .count();
+                let bitmap;
+                (bitmap, offset) = read(
+                    buf,
+                    offset,
+                    n_optional_keys.div_ceil(8),
+                    &format!("the presence bitmap of a {kind}"),
+                )?;
+                let mut bit_idx = 0;
+                for (field, field_serde_type) in entries {
+                    if optional_keys.contains(field) {
+                        bit_idx += 1;
+                        if bitmap[(bit_idx - 1) / 8] & (1 << ((bit_idx - 1) % 8)) == 0 {
+                            continue;
+                        }
+                    }
+                    let value = self.decode(field_serde_type, scope, buf, offset)?;
+                    offset = value.end;
+                    fields.push((field.clone(), value));
+                }
+            }
+            SchemaPolicy::VERSIONED { .. } => {
+                let n_fields;
+                (n_fields, offset) =
+                    read_varint(buf, offset, &format!("the field count of a {kind}"))?;
+                for _ in 0..n_fields {
+                    let field;
+                    (field, offset) =
+                        read_string(buf, offset, &format!("a field name of a {kind}"))?;
+                    let len;
+                    (len, offset) = read_usize(
+                        buf,
+                        offset,
+                        &format!("the length of field {field} of a {kind}"),
+                    )?;
+                    let value = match entries.iter().find(|(name, _)| **name == field) {
+                        Some((_, field_serde_type)) => {
+                            let value = self.decode(field_serde_type, scope, buf, offset)?;
+                            if value.end - offset != len {
+                                Err(InvalidStateError::new_err(format!(
+                                    "field {field} of serialized {kind} has length {len}, but {} bytes were decoded (did the type of the field change?)",
+                                    value.end - offset
+                                )))?
+                            }
+                            value
+                        }
+                        None => {
+                            let (data, end) = read(
+                                buf,
+                                offset,
+                                len,
+                                &format!("unknown field {field} of a {kind}"),
+                            )?;
+                            DecodedNode {
+                                kind: None,
+                                start: offset,
+                                end,
+                                value: DecodedValue::Bytes(data.to_vec()),
+                            }
+                        }
+                    };
+                    offset = value.end;
+                    fields.push((field, value));
+                }
+            }
+        }
+        let mut extras = Vec::new();
+        if let ExtraPolicy::PASSTHROUGH {
+            fallback_serde_type,
+        } = extra
+        {
+            let fallback_serde_type = fallback_serde_type.extract(self.py)?;
+            let n_extras;
+            (n_extras, offset) = read_usize(buf, offset, &format!("the extras count of a {kind}"))?;
+            for _ in 0..n_extras {
+                let key;
+                (key, offset) = read_string(buf, offset, &format!("an extra key of a {kind}"))?;
+                let value = self.decode(&fallback_serde_type, scope, buf, offset)?;
+                offset = value.end;
+                extras.push((key, value));
+            }
+        }
+        Ok((DecodedValue::Fields { fields, extras }, offset))
+    }
+
+    // Decodes the value following the type byte of a DYNAMIC, which is decoded like the serde
+    // type DynamicSerde uses for the Python type
+    fn decode_dynamic_value(
+        &mut self,
+        python_type: PythonType,
+        buf: &[u8],
+        start: usize,
+    ) -> PyResult<DecodedNode> {
+        let scope = Scope::default();
+        let serde_type = match python_type {
+            PythonType::BOOL => PyAnySerdeType::BOOL {},
+            PythonType::BYTES => PyAnySerdeType::BYTES {},
+            PythonType::COMPLEX => PyAnySerdeType::COMPLEX {},
+            PythonType::FLOAT => PyAnySerdeType::FLOAT {},
+            PythonType::INT => PyAnySerdeType::INT {},
+            PythonType::STRING => PyAnySerdeType::STRING {},
+            PythonType::OTHER => PyAnySerdeType::PICKLE {},
+            PythonType::NUMPY { dtype } => PyAnySerdeType::NUMPY {
+                dtype,
+                config: NumpySerdeConfig::DYNAMIC {
+                    preprocessor_fn: None,
+                    postprocessor_fn: None,
+                },
+            },
+            PythonType::DICT => {
+                let (n_entries, mut offset) =
+                    read_usize(buf, start, "the length of a DYNAMIC dict")?;
+                let mut entries = Vec::new();
+                for _ in 0..n_entries {
+                    let key = self.decode(&PyAnySerdeType::DYNAMIC {}, &scope, buf, offset)?;
+                    let value = self.decode(&PyAnySerdeType::DYNAMIC {}, &scope, buf, key.end)?;
+                    offset = value.end;
+                    entries.push((key, value));
+                }
+                return Ok(DecodedNode {
+                    kind: Some(PyAnySerdeTypeKind::DICT),
+                    start,
+                    end: offset,
+                    value: DecodedValue::Entries(entries),
+                });
+            }
+            PythonType::LIST | PythonType::SET | PythonType::TUPLE => {
+                let kind = match python_type {
+                    PythonType::LIST => PyAnySerdeTypeKind::LIST,
+                    PythonType::SET => PyAnySerdeTypeKind::SET,
+                    _ => PyAnySerdeTypeKind::TUPLE,
+                };
+                let (n_items, offset) =
+                    read_usize(buf, start, &format!("the length of a DYNAMIC {kind}"))?;
+                let (items, end) =
+                    self.decode_items(&PyAnySerdeType::DYNAMIC {}, n_items, &scope, buf, offset)?;
+                return Ok(DecodedNode {
+                    kind: Some(kind),
+                    start,
+                    end,
+                    value: DecodedValue::Items(items),
+                });
+            }
+        };
+        self.decode(&serde_type, &scope, buf, start)
+    }
+}
+
+fn decompress(codec: CompressionCodec, data: &[u8], len: usize) -> PyResult<Vec<u8>> {
+    let value_buf = match codec {
+        CompressionCodec::LZ4 => lz4_flex::block::decompress(data, len)
+            .map_err(|err| InvalidStateError::new_err(err.to_string()))?,
+        CompressionCodec::ZSTD => zstd::bulk::decompress(data, len)
+            .map_err(|err| InvalidStateError::new_err(err.to_string()))?,
+    };
+    if value_buf.len() != len {
+        Err(InvalidStateError::new_err(format!(
+            "COMPRESSED data decompressed to {} bytes instead of {len}",
+            value_buf.len()
+        )))?
+    }
+    Ok(value_buf)
+}
+
+fn compress(
+    codec: CompressionCodec,
+    level: Option<i32>,
+    min_size: usize,
+    scratch: &[u8],
+) -> PyResult<Option<Vec<u8>>> {
+    if scratch.len() < min_size {
+        return Ok(None);
+    }
+    let compressed = match codec {
+        CompressionCodec::LZ4 => lz4_flex::block::compress(scratch),
+        CompressionCodec::ZSTD => zstd::bulk::compress(scratch, level.unwrap_or(0))?,
+    };
+    Ok((compressed.len() < scratch.len()).then_some(compressed))
+}
+
+// Like NumpySerde, the alignment padding is relative to the start of the buffer
+fn decode_numpy(
+    dtype: NumpyDtype,
+    static_shape: Option<&[usize]>,
+    buf: &[u8],
+    start: usize,
+) -> PyResult<(DecodedValue, usize)> {
+    let mut offset = start;
+    let shape = match static_shape {
+        Some(shape) => shape.to_vec(),
+        None => {
+            let ndim;
+            (ndim, offset) = read_usize(buf, offset, "the number of dimensions of a NUMPY")?;
+            let mut shape = Vec::new();
+            for _ in 0..ndim {
+                let dim;
+                (dim, offset) = read_usize(buf, offset, "a dimension of a NUMPY")?;
+                shape.push(dim);
+            }
+            shape
+        }
+    };
+    let padding = get_numpy_padding(dtype, offset);
+    (_, offset) = read(buf, offset, padding, "the alignment padding of a NUMPY")?;
+    let (bytes, end) = read_bytes(buf, offset, "the data of a NUMPY")?;
+    let data = with_numpy_item!(dtype, T => {
+        let n_elements = shape.iter().product::<usize>();
+        if bytes.len() != n_elements * size_of::<T>() {
+            Err(InvalidStateError::new_err(format!(
+                "NUMPY data of {} bytes does not match shape {shape:?} with dtype {dtype}",
+                bytes.len()
+            )))?
+        }
+        bytes
+            .chunks_exact(size_of::<T>())
+            .map(|chunk| T::from_item_bytes(chunk).to_json())
+            .collect::<Vec<_>>()
+    });
+    Ok((
+        DecodedValue::Numpy {
+            dtype,
+            shape,
+            padding,
+            data,
+        },
+        end,
+    ))
+}
+
+fn bytes_to_json(bytes: &[u8]) -> Value {
+    Value::from(hex::encode(bytes))
+}
+
+fn fields_to_json(fields: &[(String, DecodedNode)]) -> Value {
+    fields
+        .iter()
+        .map(|(name, node)| json!({"name": name, "value": node.to_json()}))
+        .collect()
+}
+
+fn entries_to_json(entries: &[(DecodedNode, DecodedNode)]) -> Value {
+    entries
+        .iter()
+        .map(|(key, value)| json!({"key": key.to_json(), "value": value.to_json()}))
+        .collect()
+}
+
+// The paths and nodes of the keys and values of entries, for the hexdump
+fn entries_children<'a>(
+    path: &str,
+    entries: &'a [(DecodedNode, DecodedNode)],
+) -> Vec<(String, &'a DecodedNode)> {
+    entries
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, (key, value))| {
+            [
+                (format!("{path}[{idx}].key"), key),
+                (format!("{path}[{idx}].value"), value),
+            ]
+        })
+        .collect()
+}
+
+impl DecodedNode {
+    // Renders the node as a JSON object with the type, offset, and length of the node, along
+    // with keys for its value which depend on the type. This is also the format encode_json
+    // reads (where the type, offset, and length are optional).
+    pub fn to_json(&self) -> Value {
+        let mut node = json!({
+            "type": self.kind.map(|kind| kind.to_string()),
+            "offset": self.start,
+            "length": self.end - self.start,
+        });
+        let value_entries = match &self.value {
+            DecodedValue::Bool(val) => vec![("value", Value::from(*val))],
+            DecodedValue::Int(val) => vec![("value", Value::from(*val))],
+            DecodedValue::Float(val) => vec![("value", float_to_json(*val))],
+            DecodedValue::Complex { real, imag } => vec![(
+                "value",
+                json!({"real": float_to_json(*real), "imag": float_to_json(*imag)}),
+            )],
+            DecodedValue::String(val) => vec![("value", Value::from(val.as_str()))],
+            DecodedValue::Bytes(val) => vec![("value", bytes_to_json(val))],
+            DecodedValue::Option(value_option) => vec![(
+                "value",
+                value_option
+                    .as_ref()
+                    .map_or(Value::Null, |value| value.to_json()),
+            )],
+            DecodedValue::Items(items) => {
+                vec![("items", items.iter().map(DecodedNode::to_json).collect())]
+            }
+            DecodedValue::Deque { maxlen, items } => vec![
+                ("maxlen", Value::from(*maxlen)),
+                ("items", items.iter().map(DecodedNode::to_json).collect()),
+            ],
+            DecodedValue::Entries(entries) => vec![("entries", entries_to_json(entries))],
+            DecodedValue::DefaultDict {
+                default_factory,
+                entries,
+            } => vec![
+                (
+                    "default_factory",
+                    default_factory.as_ref().map_or(
+                        Value::Null,
+                        |(module, qualname)| json!({"module": module, "qualname": qualname}),
+                    ),
+                ),
+                ("entries", entries_to_json(entries)),
+            ],
+            DecodedValue::Fields { fields, extras } => vec![
+                ("fields", fields_to_json(fields)),
+                ("extras", fields_to_json(extras)),
+            ],
+            DecodedValue::Columns { n_items, columns } => vec![
+                ("n_items", Value::from(*n_items)),
+                (
+                    "columns",
+                    columns
+                        .iter()
+                        .map(|(name, items)| {
+                            json!({
+                                "name": name,
+                                "items": items.iter().map(DecodedNode::to_json).collect::<Vec<_>>(),
+                            })
+                        })
+                        .collect(),
+                ),
+            ],
+            DecodedValue::Union {
+                discriminant,
+                value,
+            } => vec![
+                ("discriminant", Value::from(*discriminant)),
+                ("value", value.to_json()),
+            ],
+            DecodedValue::Enum { discriminant } => {
+                vec![("discriminant", Value::from(*discriminant))]
+            }
+            DecodedValue::Memo { memo_idx, value } => vec![
+                ("memo_idx", Value::from(*memo_idx)),
+                ("value", value.to_json()),
+            ],
+            DecodedValue::MemoRef { memo_idx } => vec![("reference", Value::from(*memo_idx))],
+            DecodedValue::Checksummed {
+                checksum,
+                valid,
+                value,
+            } => vec![
+                ("checksum", Value::from(*checksum)),
+                ("checksum_valid", Value::from(*valid)),
+                ("value", value.to_json()),
+            ],
+            DecodedValue::Compressed {
+                compressed, value, ..
+            } => vec![
+                ("compressed", Value::from(*compressed)),
+                ("value", value.to_json()),
+            ],
+            DecodedValue::Numpy {
+                dtype,
+                shape,
+                padding,
+                data,
+            } => vec![
+                ("dtype", Value::from(dtype.to_string())),
+                ("shape", Value::from(shape.clone())),
+                ("padding", Value::from(*padding)),
+                ("data", Value::from(data.clone())),
+            ],
+            DecodedValue::Dynamic(value) => vec![("value", value.to_json())],
+        };
+        let object = node.as_object_mut().unwrap();
+        for (key, value) in value_entries {
+            object.insert(key.to_owned(), value);
+        }
+        node
+    }
+}
+
+const HEXDUMP_ROW_BYTES: usize = 16;
+
+// Writes the rows of an annotated hexdump. Each row has a byte range, the hex of those bytes (at
+// most HEXDUMP_ROW_BYTES of them), and an annotation indented by the depth of the value.
+struct Hexdump {
+    out: String,
+    max_bytes: Option<usize>,
+}
+
+impl Hexdump {
+    fn row(&mut self, start: usize, end: usize, bytes: &[u8], depth: usize, annotation: &str) {
+        let hex = bytes
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let row = format!(
+            "{start:>8}..{end:<8} {hex:<width$}  {:indent$}{annotation}",
+            "",
+            width = 3 * HEXDUMP_ROW_BYTES - 1,
+            indent = 2 * depth
+        );
+        self.out.push_str(row.trim_end());
+        self.out.push('\n');
+    }
+
+    // Writes the bytes of buf[start..end] as rows, with the annotation on the first row
+    fn bytes(&mut self, buf: &[u8], start: usize, end: usize, depth: usize, annotation: &str) {
+        let shown_end = self
+            .max_bytes
+            .map_or(end, |max_bytes| end.min(start + max_bytes));
+        let mut row_start = start;
+        let mut annotation = annotation;
+        loop {
+            let row_end = shown_end.min(row_start + HEXDUMP_ROW_BYTES);
+            self.row(
+                row_start,
+                row_end,
+                &buf[row_start..row_end],
+                depth,
+                annotation,
+            );
+            annotation = "";
+            row_start = row_end;
+            if row_start >= shown_end {
+                break;
+            }
+        }
+        if shown_end < end {
+            self.row(
+                shown_end,
+                end,
+                &[],
+                depth,
+                &format!("... ({} more bytes)", end - shown_end),
+            );
+        }
+    }
+
+    fn node(&mut self, node: &DecodedNode, buf: &[u8], path: &str, depth: usize) {
+        let kind = node
+            .kind
+            .map_or("unknown field".to_owned(), |kind| kind.to_string());
+        let (summary, children) = match &node.value {
+            DecodedValue::Bool(val) => (format!(" = {val}"), Vec::new()),
+            DecodedValue::Int(val) => (format!(" = {val}"), Vec::new()),
+            DecodedValue::Float(val) => (format!(" = {val:?}"), Vec::new()),
+            DecodedValue::Complex { real, imag } => {
+                (format!(" = ({real:?}, {imag:?}j)"), Vec::new())
+            }
+            DecodedValue::String(val) => (format!(" = {val:?}"), Vec::new()),
+            DecodedValue::Bytes(val) => (format!(" ({} bytes)", val.len()), Vec::new()),
+            DecodedValue::Option(value_option) => match value_option {
+                Some(value) => (String::new(), vec![(path.to_owned(), value.as_ref())]),
+                None => (" = None".to_owned(), Vec::new()),
+            },
+            DecodedValue::Items(items) => (
+                format!(" ({} items)", items.len()),
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, item)| (format!("{path}[{idx}]"), item))
+                    .collect(),
+            ),
+            DecodedValue::Deque { maxlen, items } => (
+                format!(" ({} items, maxlen {maxlen:?})", items.len()),
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, item)| (format!("{path}[{idx}]"), item))
+                    .collect(),
+            ),
+            DecodedValue::Entries(entries) => (
+                format!(" ({} entries)", entries.len()),
+                entries_children(path, entries),
+            ),
+            DecodedValue::DefaultDict {
+                default_factory,
+                entries,
+            } => (
+                match default_factory {
+                    Some((module, qualname)) => format!(
+                        " ({} entries, default_factory {module}.{qualname})",
+                        entries.len()
+                    ),
+                    None => format!(" ({} entries, no default_factory)", entries.len()),
+                },
+                entries_children(path, entries),
+            ),
+            DecodedValue::Fields { fields, extras } => (
+                format!(" ({} fields, {} extras)", fields.len(), extras.len()),
+                fields
+                    .iter()
+                    .chain(extras)
+                    .map(|(name, value)| (format!("{path}.{name}"), value))
+                    .collect(),
+            ),
+            DecodedValue::Columns { n_items, columns } => (
+                format!(" ({n_items} items)"),
+                columns
+                    .iter()
+                    .flat_map(|(name, items)| {
+                        items
+                            .iter()
+                            .enumerate()
+                            .map(move |(idx, item)| (format!("{path}.{name}[{idx}]"), item))
+                    })
+                    .collect(),
+            ),
+            DecodedValue::Union {
+                discriminant,
+                value,
+            } => (
+                format!(" (discriminant {discriminant})"),
+                vec![(path.to_owned(), value.as_ref())],
+            ),
+            DecodedValue::Enum { discriminant } => {
+                (format!(" = discriminant {discriminant}"), Vec::new())
+            }
+            DecodedValue::Memo { memo_idx, value } => (
+                format!(" (memo {memo_idx})"),
+                vec![(path.to_owned(), value.as_ref())],
+            ),
+            DecodedValue::MemoRef { memo_idx } => {
+                (format!(" = reference to memo {memo_idx}"), Vec::new())
+            }
+            DecodedValue::Checksummed {
+                checksum,
+                valid,
+                value,
+            } => (
+                format!(
+                    " (checksum {checksum:#010x}, {})",
+                    if *valid { "valid" } else { "INVALID" }
+                ),
+                vec![(path.to_owned(), value.as_ref())],
+            ),
+            DecodedValue::Compressed {
+                compressed,
+                data,
+                value,
+            } => {
+                // The value can't be shown within the compressed bytes, so it is shown within
+                // the decompressed data instead
+                let state = if *compressed {
+                    "compressed"
+                } else {
+                    "uncompressed"
+                };
+                self.row(
+                    node.start,
+                    node.end,
+                    &[],
+                    depth,
+                    &format!("{path}: {kind} ({state})"),
+                );
+                self.bytes(
+                    buf,
+                    node.start,
+                    node.end,
+                    depth + 1,
+                    &format!("{path} ({state} data)"),
+                );
+                self.row(
+                    0,
+                    data.len(),
+                    &[],
+                    depth + 1,
+                    &format!(
+                        "{path} (the decompressed data, which the offsets below are relative to)"
+                    ),
+                );
+                self.node(value, data, path, depth + 2);
+                return;
+            }
+            DecodedValue::Numpy {
+                dtype,
+                shape,
+                padding,
+                data,
+            } => {
+                let data_len = data.len() * with_numpy_item!(*dtype, T => size_of::<T>());
+                let padding_start = node.end - data_len - size_of::<usize>() - padding;
+                self.row(
+                    node.start,
+                    node.end,
+                    &[],
+                    depth,
+                    &format!(
+                        "{path}: {kind} {dtype} {shape:?} = {}",
+                        Value::from(data.clone())
+                    ),
+                );
+                if padding_start > node.start {
+                    self.bytes(
+                        buf,
+                        node.start,
+                        padding_start,
+                        depth + 1,
+                        &format!("{path} (shape)"),
+                    );
+                }
+                if *padding > 0 {
+                    self.bytes(
+                        buf,
+                        padding_start,
+                        padding_start + padding,
+                        depth + 1,
+                        &format!("{path} (alignment padding)"),
+                    );
+                }
+                self.bytes(
+                    buf,
+                    padding_start + padding,
+                    node.end,
+                    depth + 1,
+                    &format!("{path} (data)"),
+                );
+                return;
+            }
+            DecodedValue::Dynamic(value) => {
+                (String::new(), vec![(path.to_owned(), value.as_ref())])
+            }
+        };
+        let annotation = format!("{path}: {kind}{summary}");
+        if children.is_empty() {
+            self.bytes(buf, node.start, node.end, depth, &annotation);
+            return;
+        }
+        self.row(node.start, node.end, &[], depth, &annotation);
+        // The bytes between the children are the length prefixes, bitmaps, type bytes, keys, and
+        // so on of this node
+        let mut children = children;
+        children.sort_by_key(|(_, child)| child.start);
+        let mut offset = node.start;
+        for (child_path, child) in children {
+            if child.start > offset {
+                self.bytes(
+                    buf,
+                    offset,
+                    child.start,
+                    depth + 1,
+                    &format!("{path} (framing)"),
+                );
+            }
+            self.node(child, buf, &child_path, depth + 1);
+            offset = child.end;
+        }
+        if node.end > offset {
+            self.bytes(
+                buf,
+                offset,
+                node.end,
+                depth + 1,
+                &format!("{path} (framing)"),
+            );
+        }
+    }
+}
+
+impl DecodedNode {
+    // Renders an annotated hexdump of the node, where buf is the buffer the node was decoded
+    // from. There is a row for each value giving its path, byte range, and decoded value, and
+    // rows for the bytes which are not values of their own, like length prefixes and NUMPY
+    // alignment padding. At most max_bytes bytes are shown for each range.
+    pub fn hexdump(&self, buf: &[u8], max_bytes: Option<usize>) -> String {
+        let mut hexdump = Hexdump {
+            out: String::new(),
+            max_bytes,
+        };
+        hexdump.node(self, buf, "$", 0);
+        hexdump.out
+    }
+}
+
+// Decodes the value of serde_type at offset in buf, without creating any Python objects. NUMPY
+// alignment padding is computed relative to the start of buf, which matches the serdes for
+// buffers whose start is 8 byte aligned.
+pub fn decode(serde_type: &PyAnySerdeType, buf: &[u8], offset: usize) -> PyResult<DecodedNode> {
+    Python::attach(|py| {
+        Decoder {
+            py,
+            memo_table: MemoTable::default(),
+        }
+        .decode(serde_type, &Scope::default(), buf, offset)
+    })
+}
+
+pub fn decode_to_json(serde_type: &PyAnySerdeType, buf: &[u8]) -> PyResult<Value> {
+    Ok(decode(serde_type, buf, 0)?.to_json())
+}
+
+// The same document as decode_to_json, as MessagePack
+pub fn decode_to_msgpack(serde_type: &PyAnySerdeType, buf: &[u8]) -> PyResult<Vec<u8>> {
+    rmp_serde::to_vec(&decode_to_json(serde_type, buf)?)
+        .map_err(|err| PyValueError::new_err(format!("MessagePack error: {err}")))
+}
+
+// The hexdump of the value of serde_type at offset in buf, followed by a row for any bytes after
+// the value, which makes it easy to see where a producer and a consumer disagree about the format
+pub fn hexdump(
+    serde_type: &PyAnySerdeType,
+    buf: &[u8],
+    offset: usize,
+    max_bytes: Option<usize>,
+) -> PyResult<String> {
+    let node = decode(serde_type, buf, offset)?;
+    let mut out = node.hexdump(buf, max_bytes);
+    if node.end < buf.len() {
+        let mut trailing = Hexdump {
+            out: String::new(),
+            max_bytes,
+        };
+        trailing.bytes(buf, node.end, buf.len(), 0, "(trailing bytes)");
+        out.push_str(&trailing.out);
+    }
+    Ok(out)
+}
+
+fn get_key<'a>(node: &'a Value, key: &str, kind: PyAnySerdeTypeKind) -> PyResult<&'a Value> {
+    node.get(key)
+        .ok_or_else(|| PyValueError::new_err(format!("{kind} node is missing the key \"{key}\"")))
+}
+
+fn expect<T>(
+    option: Option<T>,
+    key: &str,
+    kind: PyAnySerdeTypeKind,
+    expected: &str,
+) -> PyResult<T> {
+    option.ok_or_else(|| {
+        PyValueError::new_err(format!("\"{key}\" of {kind} node must be {expected}"))
+    })
+}
+
+fn get_array<'a>(node: &'a Value, key: &str, kind: PyAnySerdeTypeKind) -> PyResult<&'a Vec<Value>> {
+    expect(get_key(node, key, kind)?.as_array(), key, kind, "an array")
+}
+
+fn get_usize(node: &Value, key: &str, kind: PyAnySerdeTypeKind) -> PyResult<usize> {
+    expect(
+        get_key(node, key, kind)?
+            .as_u64()
+            .and_then(|val| usize::try_from(val).ok()),
+        key,
+        kind,
+        "a non-negative integer",
+    )
+}
+
+fn get_str<'a>(node: &'a Value, key: &str, kind: PyAnySerdeTypeKind) -> PyResult<&'a str> {
+    expect(get_key(node, key, kind)?.as_str(), key, kind, "a string")
+}
+
+fn get_hex(node: &Value, key: &str, kind: PyAnySerdeTypeKind) -> PyResult<Vec<u8>> {
+    expect(
+        hex::decode(get_str(node, key, kind)?).ok(),
+        key,
+        kind,
+        "a hex string",
+    )
+}
+
+fn get_float(node: &Value, key: &str, kind: PyAnySerdeTypeKind) -> PyResult<f64> {
+    expect(
+        float_from_json(get_key(node, key, kind)?),
+        key,
+        kind,
+        "a number, \"nan\", \"inf\", or \"-inf\"",
+    )
+}
+
+// Checks the type of the node if it has one
+fn check_kind(node: &Value, kind: PyAnySerdeTypeKind) -> PyResult<()> {
+    if !node.is_object() {
+        Err(PyValueError::new_err(format!(
+            "expected a {kind} node but got {node}"
+        )))?
+    }
+    match node.get("type") {
+        None | Some(Value::Null) => Ok(()),
+        Some(Value::String(node_kind)) if *node_kind == kind.to_string() => Ok(()),
+        Some(node_kind) => Err(PyValueError::new_err(format!(
+            "expected a {kind} node but got a node of type {node_kind}"
+        ))),
+    }
+}
+
+struct Encoder<'py> {
+    py: Python<'py>,
+    memo_table: MemoTable,
+}
+
+impl<'py> Encoder<'py> {
+    fn encode(
+        &mut self,
+        serde_type: &PyAnySerdeType,
+        scope: &Scope,
+        node: &Value,
+        v: &mut Vec<u8>,
+    ) -> PyResult<()> {
+        let py = self.py;
+        let kind = PyAnySerdeTypeKind::from(serde_type);
+        if !matches!(
+            serde_type,
+            PyAnySerdeType::DEFINITIONS { .. } | PyAnySerdeType::REF { .. }
+        ) {
+            check_kind(node, kind)?;
+        }
+        match serde_type {
+            PyAnySerdeType::DEFINITIONS {
+                definitions,
+                root_serde_type,
+                max_depth,
+            } => self.encode(
+                &root_serde_type.extract(py)?,
+                &scope.enter_definitions(definitions, *max_depth),
+                node,
+                v,
+            )?,
+            PyAnySerdeType::REF { name } => {
+                let (definition, definition_scope) = scope.resolve(name)?;
+                self.encode(&definition, &definition_scope, node, v)?;
+            }
+            PyAnySerdeType::BOOL {} => append_bool_vec(
+                v,
+                expect(
+                    get_key(node, "value", kind)?.as_bool(),
+                    "value",
+                    kind,
+                    "a bool",
+                )?,
+            ),
+            PyAnySerdeType::INT {} => append_i64_vec(
+                v,
+                expect(
+                    get_key(node, "value", kind)?.as_i64(),
+                    "value",
+                    kind,
+                    "an integer",
+                )?,
+            ),
+            PyAnySerdeType::FLOAT {} => append_f64_vec(v, get_float(node, "value", kind)?),
+            PyAnySerdeType::COMPLEX {} => {
+                let value = get_key(node, "value", kind)?;
+                append_f64_vec(v, get_float(value, "real", kind)?);
+                append_f64_vec(v, get_float(value, "imag", kind)?);
+            }
+            PyAnySerdeType::STRING {} => {
+                append_bytes_vec(v, get_str(node, "value", kind)?.as_bytes())
+            }
+            PyAnySerdeType::BYTES {} | PyAnySerdeType::PICKLE {} => {
+                append_bytes_vec(v, &get_hex(node, "value", kind)?)
+            }
+            PyAnySerdeType::PYTHONSERDE { .. } => Err(python_serde_err())?,
+            PyAnySerdeType::OPTION { value_serde_type } => match get_key(node, "value", kind)? {
+                Value::Null => append_bool_vec(v, false),
+                value => {
+                    append_bool_vec(v, true);
+                    self.encode(&value_serde_type.extract(py)?, scope, value, v)?;
+                }
+            },
+            PyAnySerdeType::FROZENSET { items_serde_type }
+            | PyAnySerdeType::HOMOGENEOUSTUPLE { items_serde_type }
+            | PyAnySerdeType::LIST { items_serde_type }
+            | PyAnySerdeType::SET { items_serde_type } => {
+                let items = get_array(node, "items", kind)?;
+                append_usize_vec(v, items.len());
+                self.encode_items(&items_serde_type.extract(py)?, scope, items, v)?;
+            }
+            PyAnySerdeType::PRIMITIVELIST {
+                items_serde_type,
+                length,
+                ..
+            } => {
+                let items = get_array(node, "items", kind)?;
+                match length {
+                    Some(length) if *length != items.len() => Err(PyValueError::new_err(format!(
+                        "PRIMITIVELIST node has {} items but the length is {length}",
+                        items.len()
+                    )))?,
+                    Some(_) => (),
+                    None => append_usize_vec(v, items.len()),
+                }
+                self.encode_items(&items_serde_type.extract(py)?, scope, items, v)?;
+            }
+            PyAnySerdeType::NAMEDTUPLE {
+                item_serde_types, ..
+            }
+            | PyAnySerdeType::TUPLE { item_serde_types } => {
+                let items = get_array(node, "items", kind)?;
+                if items.len() != item_serde_types.len() {
+                    Err(PyValueError::new_err(format!(
+                        "{kind} node has {} items but the serde type has {}",
+                        items.len(),
+                        item_serde_types.len()
+                    )))?
+                }
+                for (item_serde_type, item) in item_serde_types.iter().zip(items.iter()) {
+                    self.encode(item_serde_type, scope, item, v)?;
+                }
+            }
+            PyAnySerdeType::DEQUE { items_serde_type } => {
+                match node.get("maxlen") {
+                    None | Some(Value::Null) => append_bool_vec(v, false),
+                    Some(_) => {
+                        append_bool_vec(v, true);
+                        append_usize_vec(v, get_usize(node, "maxlen", kind)?);
+                    }
+                }
+                let items = get_array(node, "items", kind)?;
+                append_usize_vec(v, items.len());
+                self.encode_items(&items_serde_type.extract(py)?, scope, items, v)?;
+            }
+            PyAnySerdeType::DEFAULTDICT {
+                keys_serde_type,
+                values_serde_type,
+            } => {
+                match get_key(node, "default_factory", kind)? {
+                    Value::Null => append_bool_vec(v, false),
+                    default_factory => {
+                        append_bool_vec(v, true);
+                        append_bytes_vec(v, get_str(default_factory, "module", kind)?.as_bytes());
+                        append_bytes_vec(v, get_str(default_factory, "qualname", kind)?.as_bytes());
+                    }
+                }
+                self.encode_entries(
+                    &keys_serde_type.extract(py)?,
+                    &values_serde_type.extract(py)?,
+                    scope,
+                    node,
+                    kind,
+                    v,
+                )?
+            }
+            PyAnySerdeType::DICT {
+                keys_serde_type,
+                values_serde_type,
+            }
+            | PyAnySerdeType::ORDEREDDICT {
+                keys_serde_type,
+                values_serde_type,
+            } => self.encode_entries(
+                &keys_serde_type.extract(py)?,
+                &values_serde_type.extract(py)?,
+                scope,
+                node,
+                kind,
+                v,
+            )?,
+            PyAnySerdeType::DATACLASS {
+                field_serde_type_dict,
+                extra,
+                alphabetical_field_order,
+                schema,
+                ..
+            } => self.encode_fields(
+                kind,
+                field_serde_type_dict.serialization_order(*alphabetical_field_order),
+                &[],
+                extra,
+                schema,
+                scope,
+                node,
+                v,
+            )?,
+            PyAnySerdeType::TYPEDDICT {
+                key_serde_type_dict,
+                optional_keys,
+                extra,
+                alphabetical_field_order,
+                schema,
+            } => self.encode_fields(
+                kind,
+                key_serde_type_dict.serialization_order(*alphabetical_field_order),
+                optional_keys,
+                extra,
+                schema,
+                scope,
+                node,
+                v,
+            )?,
+            PyAnySerdeType::COLUMNARLIST {
+                items_serde_type, ..
+            } => {
+                let items_serde_type = items_serde_type.extract(py)?;
+                let n_items = get_usize(node, "n_items", kind)?;
+                let columns = get_array(node, "columns", kind)?;
+                append_usize_vec(v, n_items);
+                for (field, field_serde_type) in get_field_entries(&items_serde_type)? {
+                    let column = columns
+                        .iter()
+                        .find(|column| column.get("name").and_then(Value::as_str) == Some(field))
+                        .ok_or_else(|| {
+                            PyValueError::new_err(format!(
+                                "COLUMNARLIST node is missing the column {field}"
+                            ))
+                        })?;
+                    let items = get_array(column, "items", kind)?;
+                    if items.len() != n_items {
+                        Err(PyValueError::new_err(format!(
+                            "COLUMNARLIST column {field} has {} items but n_items is {n_items}",
+                            items.len()
+                        )))?
+                    }
+                    self.encode_items(field_serde_type, scope, items, v)?;
+                }
+            }
+            PyAnySerdeType::UNION {
+                option_serde_types, ..
+            } => {
+                let discriminant = get_usize(node, "discriminant", kind)?;
+                let option_serde_type = option_serde_types.get(discriminant).ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "UNION node has discriminant {discriminant} but there are {} options",
+                        option_serde_types.len()
+                    ))
+                })?;
+                append_discriminant_vec(v, discriminant, option_serde_types.len());
+                self.encode(option_serde_type, scope, get_key(node, "value", kind)?, v)?;
+            }
+            PyAnySerdeType::ENUM { values } => {
+                let discriminant = get_usize(node, "discriminant", kind)?;
+                if discriminant >= values.len() {
+                    Err(PyValueError::new_err(format!(
+                        "ENUM node has discriminant {discriminant} but there are {} values",
+                        values.len()
+                    )))?
+                }
+                append_discriminant_vec(v, discriminant, values.len());
+            }
+            PyAnySerdeType::MEMO { value_serde_type } => {
+                self.memo_table.enter();
+                if node.get("reference").is_some() {
+                    let memo_idx = get_usize(node, "reference", kind)?;
+                    if memo_idx >= self.memo_table.n_values {
+                        Err(PyValueError::new_err(format!(
+                            "MEMO node references memo index {memo_idx}, but only {} values have been encoded",
+                            self.memo_table.n_values
+                        )))?
+                    }
+                    append_varint_vec(v, memo_idx + 1);
+                } else {
+                    append_varint_vec(v, 0);
+                    self.encode(
+                        &value_serde_type.extract(py)?,
+                        scope,
+                        get_key(node, "value", kind)?,
+                        v,
+                    )?;
+                    self.memo_table.push();
+                }
+                self.memo_table.exit();
+            }
+            PyAnySerdeType::CHECKSUMMED { value_serde_type } => {
+                let start = v.len();
+                append_usize_vec(v, 0);
+                let value_start = v.len();
+                self.encode(
+                    &value_serde_type.extract(py)?,
+                    scope,
+                    get_key(node, "value", kind)?,
+                    v,
+                )?;
+                let value_len = v.len() - value_start;
+                v[start..value_start].copy_from_slice(&value_len.to_ne_bytes());
+                append_u32_vec(v, crc32c(&v[start..]));
+            }
+            PyAnySerdeType::COMPRESSED {
+                value_serde_type,
+                codec,
+                level,
+                min_size,
+            } => {
+                let mut scratch = Vec::new();
+                self.encode(
+                    &value_serde_type.extract(py)?,
+                    scope,
+                    get_key(node, "value", kind)?,
+                    &mut scratch,
+                )?;
+                match compress(*codec, *level, *min_size, &scratch)? {
+                    Some(compressed) => {
+                        append_bool_vec(v, true);
+                        append_varint_vec(v, scratch.len());
+                        append_bytes_vec(v, &compressed);
+                    }
+                    None => {
+                        append_bool_vec(v, false);
+                        append_bytes_vec(v, &scratch);
+                    }
+                }
+            }
+            PyAnySerdeType::NUMPY { dtype, config } => {
+                let shape = match config {
+                    NumpySerdeConfig::DYNAMIC { .. } => {
+                        let shape = get_array(node, "shape", kind)?
+                            .iter()
+                            .map(|dim| {
+                                expect(
+                                    dim.as_u64().and_then(|dim| usize::try_from(dim).ok()),
+                                    "shape",
+                                    kind,
+                                    "an array of non-negative integers",
+                                )
+                            })
+                            .collect::<PyResult<Vec<_>>>()?;
+                        append_usize_vec(v, shape.len());
+                        for &dim in shape.iter() {
+                            append_usize_vec(v, dim);
+                        }
+                        shape
+                    }
+                    NumpySerdeConfig::STATIC { shape, .. } => shape.clone(),
+                };
+                encode_numpy(*dtype, &shape, get_array(node, "data", kind)?, v)?;
+            }
+            PyAnySerdeType::DYNAMIC {} => {
+                let value = get_key(node, "value", kind)?;
+                let python_type = get_dynamic_python_type(value)?;
+                v.push(get_python_type_byte(&python_type));
+                self.encode_dynamic_value(python_type, value, v)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_items(
+        &mut self,
+        items_serde_type: &PyAnySerdeType,
+        scope: &Scope,
+        items: &[Value],
+        v: &mut Vec<u8>,
+    ) -> PyResult<()> {
+        for item in items.iter() {
+            self.encode(items_serde_type, scope, item, v)?;
+        }
+        Ok(())
+    }
+
+    fn encode_entries(
+        &mut self,
+        keys_serde_type: &PyAnySerdeType,
+        values_serde_type: &PyAnySerdeType,
+        scope: &Scope,
+        node: &Value,
+        kind: PyAnySerdeTypeKind,
+        v: &mut Vec<u8>,
+    ) -> PyResult<()> {
+        let entries = get_array(node, "entries", kind)?;
+        append_usize_vec(v, entries.len());
+        for entry in entries.iter() {
+            self.encode(keys_serde_type, scope, get_key(entry, "key", kind)?, v)?;
+            self.encode(values_serde_type, scope, get_key(entry, "value", kind)?, v)?;
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn encode_fields(
+        &mut self,
+        kind: PyAnySerdeTypeKind,
+        entries: Vec<(&String, &PyAnySerdeType)>,
+        optional_keys: &[String],
+        extra: &ExtraPolicy,
+        schema: &SchemaPolicy,
+        scope: &Scope,
+        node: &Value,
+        v: &mut Vec<u8>,
+    ) -> PyResult<()> {
+        let fields = get_array(node, "fields", kind)?
+            .iter()
+            .map(|field| {
+                Ok((
+                    get_str(field, "name", kind)?,
+                    get_key(field, "value", kind)?,
+                ))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        match schema {
+            SchemaPolicy::FIXED {} => {
+                if let Some((name, _)) = fields
+                    .iter()
+                    .find(|(name, _)| !entries.iter().any(|(field, _)| field == name))
+                {
+                    Err(PyValueError::new_err(format!(
+                        "{kind} node has field {name} which is not a field of the serde type"
+                    )))?
+                }
+                let bitmap_start = v.len();
+                let n_optional_keys = entries
+                    .iter()
+                    .filter(|(field, _)| optional_keys.contains(field))
+                    .count();
+                v.resize(bitmap_start + n_optional_keys.div_ceil(8), 0);
+                let mut bit_idx = 0;
+                for (field, field_serde_type) in entries {
+                    let value_option = fields
+                        .iter()
+                        .find(|(name, _)| *name == field)
+                        .map(|(_, value)| value);
+                    if optional_keys.contains(field) {
+                        if value_option.is_some() {
+                            v[bitmap_start + bit_idx / 8] |= 1 << (bit_idx % 8);
+                        }
+                        bit_idx += 1;
+                    }
+                    match value_option {
+                        Some(value) => self.encode(field_serde_type, scope, value, v)?,
+                        None if optional_keys.contains(field) => (),
+                        None => Err(PyValueError::new_err(format!(
+                            "{kind} node is missing the field {field}"
+                        )))?,
+                    }
+                }
+            }
+            // The field table is written in the order of the node, and unknown fields (with a
+            // type of null) are written back as they were decoded
+            SchemaPolicy::VERSIONED { .. } => {
+                append_varint_vec(v, fields.len());
+                for (name, value) in fields {
+                    append_bytes_vec(v, name.as_bytes());
+                    let len_offset = v.len();
+                    append_usize_vec(v, 0);
+                    let start = v.len();
+                    match entries.iter().find(|(field, _)| *field == name) {
+                        Some((_, field_serde_type)) => {
+                            self.encode(field_serde_type, scope, value, v)?
+                        }
+                        None => v.extend_from_slice(&get_hex(value, "value", kind)?),
+                    }
+                    let len = v.len() - start;
+                    v[len_offset..start].copy_from_slice(&len.to_ne_bytes());
+                }
+            }
+        }
+        let no_extras = Vec::new();
+        let extras = match node.get("extras") {
+            None | Some(Value::Null) => &no_extras,
+            Some(_) => get_array(node, "extras", kind)?,
+        };
+        match extra {
+            ExtraPolicy::PASSTHROUGH {
+                fallback_serde_type,
+            } => {
+                let fallback_serde_type = fallback_serde_type.extract(self.py)?;
+                append_usize_vec(v, extras.len());
+                for extra in extras.iter() {
+                    append_bytes_vec(v, get_str(extra, "name", kind)?.as_bytes());
+                    self.encode(
+                        &fallback_serde_type,
+                        scope,
+                        get_key(extra, "value", kind)?,
+                        v,
+                    )?;
+                }
+            }
+            _ if !extras.is_empty() => Err(PyValueError::new_err(format!(
+                "{kind} node has extras, but only the PASSTHROUGH extra policy writes them"
+            )))?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn encode_dynamic_value(
+        &mut self,
+        python_type: PythonType,
+        node: &Value,
+        v: &mut Vec<u8>,
+    ) -> PyResult<()> {
+        let scope = Scope::default();
+        let serde_type = match python_type {
+            PythonType::BOOL => PyAnySerdeType::BOOL {},
+            PythonType::BYTES => PyAnySerdeType::BYTES {},
+            PythonType::COMPLEX => PyAnySerdeType::COMPLEX {},
+            PythonType::FLOAT => PyAnySerdeType::FLOAT {},
+            PythonType::INT => PyAnySerdeType::INT {},
+            PythonType::STRING => PyAnySerdeType::STRING {},
+            PythonType::OTHER => PyAnySerdeType::PICKLE {},
+            PythonType::NUMPY { dtype } => PyAnySerdeType::NUMPY {
+                dtype,
+                config: NumpySerdeConfig::DYNAMIC {
+                    preprocessor_fn: None,
+                    postprocessor_fn: None,
+                },
+            },
+            PythonType::DICT => {
+                return self.encode_entries(
+                    &PyAnySerdeType::DYNAMIC {},
+                    &PyAnySerdeType::DYNAMIC {},
+                    &scope,
+                    node,
+                    PyAnySerdeTypeKind::DICT,
+                    v,
+                );
+            }
+            PythonType::LIST | PythonType::SET | PythonType::TUPLE => {
+                let items = get_array(node, "items", PyAnySerdeTypeKind::DYNAMIC)?;
+                append_usize_vec(v, items.len());
+                return self.encode_items(&PyAnySerdeType::DYNAMIC {}, &scope, items, v);
+            }
+        };
+        self.encode(&serde_type, &scope, node, v)
+    }
+}
+
+// The Python type of the value of a DYNAMIC node, from the type of the value node (and its dtype
+// for NUMPY)
+fn get_dynamic_python_type(node: &Value) -> PyResult<PythonType> {
+    let kind = PyAnySerdeTypeKind::DYNAMIC;
+    Ok(match get_str(node, "type", kind)? {
+        "BOOL" => PythonType::BOOL,
+        "BYTES" => PythonType::BYTES,
+        "COMPLEX" => PythonType::COMPLEX,
+        "DICT" => PythonType::DICT,
+        "FLOAT" => PythonType::FLOAT,
+        "INT" => PythonType::INT,
+        "LIST" => PythonType::LIST,
+        "NUMPY" => PythonType::NUMPY {
+            dtype: expect(
+                get_str(node, "dtype", kind)?.parse().ok(),
+                "dtype",
+                kind,
+                "a numpy dtype such as \"float32\"",
+            )?,
+        },
+        "PICKLE" => PythonType::OTHER,
+        "SET" => PythonType::SET,
+        "STRING" => PythonType::STRING,
+        "TUPLE" => PythonType::TUPLE,
+        node_kind => Err(PyValueError::new_err(format!(
+            "DYNAMIC node has a value of type {node_kind}, which DYNAMIC does not write"
+        )))?,
+    })
+}
+
+fn encode_numpy(
+    dtype: NumpyDtype,
+    shape: &[usize],
+    data: &[Value],
+    v: &mut Vec<u8>,
+) -> PyResult<()> {
+    let kind = PyAnySerdeTypeKind::NUMPY;
+    if data.len() != shape.iter().product::<usize>() {
+        Err(PyValueError::new_err(format!(
+            "NUMPY node has {} elements, which does not match shape {shape:?}",
+            data.len()
+        )))?
+    }
+    v.resize(v.len() + get_numpy_padding(dtype, v.len()), 0);
+    with_numpy_item!(dtype, T => {
+        append_usize_vec(v, data.len() * size_of::<T>());
+        for item in data.iter() {
+            expect(
+                T::from_json(item),
+                "data",
+                kind,
+                &format!("an array of {dtype} values"),
+            )?
+            .append_item_vec(v);
+        }
+    });
+    Ok(())
+}
+
+// Encodes a document in the format of decode_to_json into the serialized format of serde_type,
+// which is useful for writing test fixtures by hand. The offsets and lengths in the document are
+// ignored, and checksums and compression are computed again.
+pub fn encode_json(serde_type: &PyAnySerdeType, node: &Value) -> PyResult<Vec<u8>> {
+    Python::attach(|py| {
+        let mut v = Vec::new();
+        Encoder {
+            py,
+            memo_table: MemoTable::default(),
+        }
+        .encode(serde_type, &Scope::default(), node, &mut v)?;
+        Ok(v)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use pyo3::ffi::c_str;
+    use pyo3::types::PyFunction;
+    use strum::IntoEnumIterator;
+
+    use crate::PyAnySerde;
+    use crate::pyany_serde_impl::InitStrategy;
+
+    use super::*;
+
+    fn typed_dict(
+        entries: Vec<(&str, PyAnySerdeType)>,
+        optional_keys: Vec<&str>,
+        extra: ExtraPolicy,
+        schema: SchemaPolicy,
+    ) -> PyAnySerdeType {
+        PyAnySerdeType::TYPEDDICT {
+            key_serde_type_dict: entries
+                .into_iter()
+                .map(|(key, serde_type)| (key.to_string(), serde_type))
+                .collect(),
+            optional_keys: optional_keys.into_iter().map(str::to_string).collect(),
+            extra,
+            alphabetical_field_order: false,
+            schema,
+        }
+    }
+
+    // Serializes obj with the serde of serde_type, and checks that decoding covers the whole
+    // buffer and that encoding the decoded document gives back the same buffer
+    fn check_roundtrip(serde_type: &PyAnySerdeType, obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+        let mut serde: Box<dyn PyAnySerde> = serde_type.try_into()?;
+        let mut buf = Vec::new();
+        serde.append_vec(&mut buf, Some(0), obj)?;
+        let node = decode_to_json(serde_type, &buf)?;
+        assert_eq!(node["offset"], 0);
+        assert_eq!(node["length"], buf.len(), "{node}");
+        assert_eq!(encode_json(serde_type, &node)?, buf, "{node}");
+        let msgpack = decode_to_msgpack(serde_type, &buf)?;
+        assert_eq!(rmp_serde::from_slice::<Value>(&msgpack).unwrap(), node);
+        Ok(node)
+    }
+
+    #[test]
+    fn test_debug_decode_roundtrip() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let serde_type = typed_dict(
+                vec![
+                    ("idx", PyAnySerdeType::INT {}),
+                    (
+                        "name",
+                        PyAnySerdeType::OPTION {
+                            value_serde_type: Py::new(py, PyAnySerdeType::STRING {})?,
+                        },
+                    ),
+                    (
+                        "values",
+                        PyAnySerdeType::LIST {
+                            items_serde_type: Py::new(py, PyAnySerdeType::FLOAT {})?,
+                        },
+                    ),
+                    (
+                        "counts",
+                        PyAnySerdeType::DICT {
+                            keys_serde_type: Py::new(py, PyAnySerdeType::STRING {})?,
+                            values_serde_type: Py::new(py, PyAnySerdeType::INT {})?,
+                        },
+                    ),
+                    (
+                        "flags",
+                        PyAnySerdeType::PRIMITIVELIST {
+                            items_serde_type: Py::new(py, PyAnySerdeType::BOOL {})?,
+                            length: Some(2),
+                            as_tuple: true,
+                        },
+                    ),
+                    ("extra", PyAnySerdeType::BYTES {}),
+                ],
+                vec!["extra"],
+                ExtraPolicy::IGNORE {},
+                SchemaPolicy::FIXED {},
+            );
+            let node = check_roundtrip(
+                &serde_type,
+                &py.eval(
+                    c_str!(
+                        "{'idx': 3, 'name': None, 'values': [1.5, float('nan')], 'counts': {'x': 1}, 'flags': (True, False)}"
+                    ),
+                    None,
+                    None,
+                )?,
+            )?;
+            let fields = node["fields"].as_array().unwrap();
+            assert_eq!(fields.len(), 5);
+            // The presence bitmap of the optional key comes first
+            assert_eq!(fields[0]["name"], "idx");
+            assert_eq!(fields[0]["value"]["offset"], 1);
+            assert_eq!(fields[0]["value"]["value"], 3);
+            assert_eq!(fields[1]["value"]["value"], Value::Null);
+            assert_eq!(fields[2]["value"]["items"][1]["value"], "nan");
+            assert_eq!(fields[3]["value"]["entries"][0]["key"]["value"], "x");
+
+            let union_serde_type = PyAnySerdeType::UNION {
+                option_serde_types: vec![PyAnySerdeType::INT {}, PyAnySerdeType::STRING {}],
+                option_choice_fn: py
+                    .eval(
+                        c_str!("lambda x: 0 if isinstance(x, int) else 1"),
+                        None,
+                        None,
+                    )?
+                    .cast_into::<PyFunction>()?
+                    .unbind(),
+            };
+            let node = check_roundtrip(
+                &PyAnySerdeType::TUPLE {
+                    item_serde_types: vec![union_serde_type, PyAnySerdeType::COMPLEX {}],
+                },
+                &py.eval(c_str!("('a', 1+2j)"), None, None)?,
+            )?;
+            assert_eq!(node["items"][0]["discriminant"], 1);
+            assert_eq!(node["items"][0]["value"]["value"], "a");
+            assert_eq!(node["items"][1]["value"]["imag"], 2.0);
+
+            let list_serde_type = PyAnySerdeType::LIST {
+                items_serde_type: Py::new(
+                    py,
+                    PyAnySerdeType::MEMO {
+                        value_serde_type: Py::new(py, PyAnySerdeType::STRING {})?,
+                    },
+                )?,
+            };
+            let node = check_roundtrip(
+                &PyAnySerdeType::MEMO {
+                    value_serde_type: Py::new(py, list_serde_type)?,
+                },
+                &py.eval(c_str!("(lambda s: [s, 'b', s])('a' * 10)"), None, None)?,
+            )?;
+            assert_eq!(node["memo_idx"], 2);
+            assert_eq!(node["value"]["items"][1]["memo_idx"], 1);
+            assert_eq!(node["value"]["items"][2]["reference"], 0);
+
+            let node = check_roundtrip(
+                &PyAnySerdeType::CHECKSUMMED {
+                    value_serde_type: Py::new(
+                        py,
+                        PyAnySerdeType::COMPRESSED {
+                            value_serde_type: Py::new(
+                                py,
+                                PyAnySerdeType::DEQUE {
+                                    items_serde_type: Py::new(py, PyAnySerdeType::INT {})?,
+                                },
+                            )?,
+                            codec: CompressionCodec::ZSTD,
+                            level: None,
+                            min_size: 0,
+                        },
+                    )?,
+                },
+                &py.eval(
+                    c_str!("__import__('collections').deque([0] * 100, maxlen=200)"),
+                    None,
+                    None,
+                )?,
+            )?;
+            assert_eq!(node["checksum_valid"], true);
+            assert_eq!(node["value"]["compressed"], true);
+            assert_eq!(node["value"]["value"]["maxlen"], 200);
+            assert_eq!(node["value"]["value"]["length"], 1 + 8 * 102);
+
+            let versioned_serde_type = typed_dict(
+                vec![
+                    ("a", PyAnySerdeType::INT {}),
+                    ("b", PyAnySerdeType::STRING {}),
+                ],
+                vec!["b"],
+                ExtraPolicy::PASSTHROUGH {
+                    fallback_serde_type: Py::new(py, PyAnySerdeType::PICKLE {})?,
+                },
+                SchemaPolicy::VERSIONED {
+                    field_defaults: HashMap::new(),
+                },
+            );
+            let node = check_roundtrip(
+                &versioned_serde_type,
+                &py.eval(c_str!("{'a': 1, 'z': [None]}"), None, None)?,
+            )?;
+            assert_eq!(node["fields"].as_array().unwrap().len(), 1);
+            assert_eq!(node["extras"][0]["name"], "z");
+            assert_eq!(node["extras"][0]["value"]["type"], "PICKLE");
+
+            let tree_serde_type = PyAnySerdeType::DEFINITIONS {
+                definitions: FieldSerdeTypeMap(vec![(
+                    "tree".to_string(),
+                    typed_dict(
+                        vec![
+                            ("value", PyAnySerdeType::INT {}),
+                            (
+                                "children",
+                                PyAnySerdeType::LIST {
+                                    items_serde_type: Py::new(
+                                        py,
+                                        PyAnySerdeType::REF {
+                                            name: "tree".to_string(),
+                                        },
+                                    )?,
+                                },
+                            ),
+                        ],
+                        vec![],
+                        ExtraPolicy::IGNORE {},
+                        SchemaPolicy::FIXED {},
+                    ),
+                )]),
+                root_serde_type: Py::new(
+                    py,
+                    PyAnySerdeType::REF {
+                        name: "tree".to_string(),
+                    },
+                )?,
+                max_depth: 8,
+            };
+            let node = check_roundtrip(
+                &tree_serde_type,
+                &py.eval(
+                    c_str!("{'value': 1, 'children': [{'value': 2, 'children': []}]}"),
+                    None,
+                    None,
+                )?,
+            )?;
+            assert_eq!(node["type"], "TYPEDDICT");
+            assert_eq!(
+                node["fields"][1]["value"]["items"][0]["fields"][0]["value"]["value"],
+                2
+            );
+            Ok(())
+        })
+    }
+
+    // Samples of every serde type, each paired with an expression for a value of that serde type.
+    // The match is exhaustive, so that a new serde type needs samples before this compiles.
+    fn samples_of_kind(
+        py: Python<'_>,
+        kind: PyAnySerdeTypeKind,
+    ) -> PyResult<Vec<(PyAnySerdeType, &'static str)>> {
+        let int = || Py::new(py, PyAnySerdeType::INT {});
+        let string = || Py::new(py, PyAnySerdeType::STRING {});
+        let eval = |expr: &str| -> PyResult<Py<PyAny>> {
+            Ok(py.eval(&CString::new(expr).unwrap(), None, None)?.unbind())
+        };
+        let numpy_serde_type = |config| PyAnySerdeType::NUMPY {
+            dtype: NumpyDtype::FLOAT64,
+            config,
+        };
+        let dynamic_numpy_config = || NumpySerdeConfig::DYNAMIC {
+            preprocessor_fn: None,
+            postprocessor_fn: None,
+        };
+        // numpy is an optional dependency, and DYNAMIC serdes need it to check for arrays
+        let has_numpy = py.import("numpy").is_ok();
+        Ok(match kind {
+            PyAnySerdeTypeKind::BOOL => vec![(PyAnySerdeType::BOOL {}, "True")],
+            PyAnySerdeTypeKind::BYTES => vec![(PyAnySerdeType::BYTES {}, "b'ab\\x00'")],
+            PyAnySerdeTypeKind::CHECKSUMMED => vec![(
+                PyAnySerdeType::CHECKSUMMED {
+                    value_serde_type: string()?,
+                },
+                "'abc'",
+            )],
+            PyAnySerdeTypeKind::COLUMNARLIST => [false, true]
+                .into_iter()
+                .map(|as_columns| {
+                    let items_serde_type = typed_dict(
+                        vec![
+                            ("a", PyAnySerdeType::INT {}),
+                            ("b", PyAnySerdeType::STRING {}),
+                        ],
+                        vec![],
+                        ExtraPolicy::IGNORE {},
+                        SchemaPolicy::FIXED {},
+                    );
+                    Ok((
+                        PyAnySerdeType::COLUMNARLIST {
+                            items_serde_type: Py::new(py, items_serde_type)?,
+                            as_columns,
+                        },
+                        if as_columns {
+                            "{'a': [1, 2], 'b': ['x', 'y']}"
+                        } else {
+                            "[{'a': 1, 'b': 'x'}, {'a': 2, 'b': 'y'}]"
+                        },
+                    ))
+                })
+                .collect::<PyResult<_>>()?,
+            PyAnySerdeTypeKind::COMPLEX => vec![(PyAnySerdeType::COMPLEX {}, "1.5-2j")],
+            PyAnySerdeTypeKind::COMPRESSED => [CompressionCodec::LZ4, CompressionCodec::ZSTD]
+                .into_iter()
+                .map(|codec| {
+                    Ok((
+                        PyAnySerdeType::COMPRESSED {
+                            value_serde_type: string()?,
+                            codec,
+                            level: None,
+                            min_size: 16,
+                        },
+                        "'a' * 100",
+                    ))
+                })
+                .chain([Ok((
+                    PyAnySerdeType::COMPRESSED {
+                        value_serde_type: string()?,
+                        codec: CompressionCodec::LZ4,
+                        level: None,
+                        min_size: 16,
+                    },
+                    "'short'",
+                ))])
+                .collect::<PyResult<_>>()?,
+            PyAnySerdeTypeKind::DATACLASS => vec![(
+                PyAnySerdeType::DATACLASS {
+                    clazz: eval("__import__('dataclasses').make_dataclass('Point', ['x', 'y'])")?,
+                    init_strategy: InitStrategy::ALL {},
+                    field_serde_type_dict: FieldSerdeTypeMap(vec![
+                        ("x".to_string(), PyAnySerdeType::INT {}),
+                        ("y".to_string(), PyAnySerdeType::STRING {}),
+                    ]),
+                    extra: ExtraPolicy::IGNORE {},
+                    alphabetical_field_order: false,
+                    schema: SchemaPolicy::FIXED {},
+                },
+                "__import__('dataclasses').make_dataclass('Point', ['x', 'y'])(1, 'a')",
+            )],
+            PyAnySerdeTypeKind::DEFAULTDICT => vec![(
+                PyAnySerdeType::DEFAULTDICT {
+                    keys_serde_type: string()?,
+                    values_serde_type: int()?,
+                },
+                "__import__('collections').defaultdict(int, {'a': 1})",
+            )],
+            PyAnySerdeTypeKind::DEFINITIONS | PyAnySerdeTypeKind::REF => vec![(
+                PyAnySerdeType::DEFINITIONS {
+                    definitions: FieldSerdeTypeMap(vec![(
+                        "nested".to_string(),
+                        PyAnySerdeType::LIST {
+                            items_serde_type: Py::new(
+                                py,
+                                PyAnySerdeType::REF {
+                                    name: "nested".to_string(),
+                                },
+                            )?,
+                        },
+                    )]),
+                    root_serde_type: Py::new(
+                        py,
+                        PyAnySerdeType::REF {
+                            name: "nested".to_string(),
+                        },
+                    )?,
+                    max_depth: 8,
+                },
+                "[[], [[]]]",
+            )],
+            PyAnySerdeTypeKind::DEQUE => vec![(
+                PyAnySerdeType::DEQUE {
+                    items_serde_type: int()?,
+                },
+                "__import__('collections').deque([1, 2])",
+            )],
+            PyAnySerdeTypeKind::DICT => vec![(
+                PyAnySerdeType::DICT {
+                    keys_serde_type: string()?,
+                    values_serde_type: int()?,
+                },
+                "{'a': 1, 'b': 2}",
+            )],
+            PyAnySerdeTypeKind::DYNAMIC if !has_numpy => vec![],
+            PyAnySerdeTypeKind::DYNAMIC => vec![(
+                PyAnySerdeType::DYNAMIC {},
+                "[None, True, 1, 1.5, 2j, 'a', b'b', (1,), {'c': [2]}, {3}, frozenset()]",
+            )],
+            PyAnySerdeTypeKind::ENUM => vec![(
+                PyAnySerdeType::ENUM {
+                    values: vec![eval("1")?, eval("'a'")?],
+                },
+                "'a'",
+            )],
+            PyAnySerdeTypeKind::FLOAT => vec![(PyAnySerdeType::FLOAT {}, "-0.25")],
+            PyAnySerdeTypeKind::FROZENSET => vec![(
+                PyAnySerdeType::FROZENSET {
+                    items_serde_type: int()?,
+                },
+                "frozenset([1, 2])",
+            )],
+            PyAnySerdeTypeKind::HOMOGENEOUSTUPLE => vec![(
+                PyAnySerdeType::HOMOGENEOUSTUPLE {
+                    items_serde_type: int()?,
+                },
+                "(1, 2, 3)",
+            )],
+            PyAnySerdeTypeKind::INT => vec![(PyAnySerdeType::INT {}, "-123456789")],
+            PyAnySerdeTypeKind::LIST => vec![(
+                PyAnySerdeType::LIST {
+                    items_serde_type: string()?,
+                },
+                "['a', 'b']",
+            )],
+            PyAnySerdeTypeKind::MEMO => vec![(
+                PyAnySerdeType::TUPLE {
+                    item_serde_types: vec![
+                        PyAnySerdeType::MEMO {
+                            value_serde_type: string()?,
+                        },
+                        PyAnySerdeType::MEMO {
+                            value_serde_type: string()?,
+                        },
+                    ],
+                },
+                "(lambda s: (s, s))('a' * 10)",
+            )],
+            PyAnySerdeTypeKind::NAMEDTUPLE => vec![(
+                PyAnySerdeType::NAMEDTUPLE {
+                    clazz: eval("__import__('collections').namedtuple('Pair', ['a', 'b'])")?,
+                    item_serde_types: vec![PyAnySerdeType::INT {}, PyAnySerdeType::STRING {}],
+                },
+                "__import__('collections').namedtuple('Pair', ['a', 'b'])(1, 'x')",
+            )],
+            PyAnySerdeTypeKind::NUMPY if !has_numpy => vec![],
+            PyAnySerdeTypeKind::NUMPY => vec![
+                (
+                    numpy_serde_type(dynamic_numpy_config()),
+                    "__import__('numpy').arange(6.0).reshape(2, 3)",
+                ),
+                (
+                    numpy_serde_type(NumpySerdeConfig::STATIC {
+                        shape: vec![3],
+                        preprocessor_fn: None,
+                        postprocessor_fn: None,
+                        allocation_pool_min_size: 0,
+                        allocation_pool_max_size: None,
+                        allocation_pool_warning_size: None,
+                    }),
+                    "__import__('numpy').arange(3.0)",
+                ),
+            ],
+            PyAnySerdeTypeKind::OPTION => ["None", "'a'"]
+                .into_iter()
+                .map(|expr| {
+                    Ok((
+                        PyAnySerdeType::OPTION {
+                            value_serde_type: string()?,
+                        },
+                        expr,
+                    ))
+                })
+                .collect::<PyResult<_>>()?,
+            PyAnySerdeTypeKind::ORDEREDDICT => vec![(
+                PyAnySerdeType::ORDEREDDICT {
+                    keys_serde_type: string()?,
+                    values_serde_type: int()?,
+                },
+                "__import__('collections').OrderedDict([('b', 1), ('a', 2)])",
+            )],
+            PyAnySerdeTypeKind::PICKLE => vec![(PyAnySerdeType::PICKLE {}, "{'a': [1, (2,)]}")],
+            PyAnySerdeTypeKind::PRIMITIVELIST => vec![
+                (
+                    PyAnySerdeType::PRIMITIVELIST {
+                        items_serde_type: Py::new(py, PyAnySerdeType::FLOAT {})?,
+                        length: None,
+                        as_tuple: false,
+                    },
+                    "[1.0, 2.5]",
+                ),
+                (
+                    PyAnySerdeType::PRIMITIVELIST {
+                        items_serde_type: int()?,
+                        length: Some(2),
+                        as_tuple: true,
+                    },
+                    "(1, 2)",
+                ),
+            ],
+            // PYTHONSERDE values can only be read by their python serde
+            PyAnySerdeTypeKind::PYTHONSERDE => vec![],
+            PyAnySerdeTypeKind::SET => vec![(
+                PyAnySerdeType::SET {
+                    items_serde_type: int()?,
+                },
+                "{1, 2}",
+            )],
+            PyAnySerdeTypeKind::STRING => vec![(PyAnySerdeType::STRING {}, "'h\\u00e9llo'")],
+            PyAnySerdeTypeKind::TUPLE => vec![(
+                PyAnySerdeType::TUPLE {
+                    item_serde_types: vec![PyAnySerdeType::INT {}, PyAnySerdeType::BOOL {}],
+                },
+                "(1, False)",
+            )],
+            PyAnySerdeTypeKind::TYPEDDICT => vec![
+                (
+                    typed_dict(
+                        vec![
+                            ("a", PyAnySerdeType::INT {}),
+                            ("b", PyAnySerdeType::STRING {}),
+                        ],
+                        vec!["b"],
+                        ExtraPolicy::IGNORE {},
+                        SchemaPolicy::FIXED {},
+                    ),
+                    "{'a': 1}",
+                ),
+                (
+                    typed_dict(
+                        vec![("a", PyAnySerdeType::INT {})],
+                        vec![],
+                        ExtraPolicy::PASSTHROUGH {
+                            fallback_serde_type: Py::new(py, PyAnySerdeType::PICKLE {})?,
+                        },
+                        SchemaPolicy::VERSIONED {
+                            field_defaults: HashMap::new(),
+                        },
+                    ),
+                    "{'a': 1, 'z': 'extra'}",
+                ),
+            ],
+            PyAnySerdeTypeKind::UNION => vec![(
+                PyAnySerdeType::UNION {
+                    option_serde_types: vec![PyAnySerdeType::INT {}, PyAnySerdeType::STRING {}],
+                    option_choice_fn: eval("lambda x: 0 if isinstance(x, int) else 1")?
+                        .into_bound(py)
+                        .cast_into::<PyFunction>()?
+                        .unbind(),
+                },
+                "'a'",
+            )],
+        })
+    }
+
+    #[test]
+    fn test_debug_decode_every_serde_type() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            for kind in PyAnySerdeTypeKind::iter() {
+                for (serde_type, expr) in samples_of_kind(py, kind)? {
+                    let obj = py.eval(&CString::new(expr).unwrap(), None, None)?;
+                    check_roundtrip(&serde_type, &obj)
+                        .map_err(|err| PyValueError::new_err(format!("{kind} {expr}: {err}")))?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_debug_encode_json() -> PyResult<()> {
+        Python::initialize();
+        let serde_type = PyAnySerdeType::TUPLE {
+            item_serde_types: vec![
+                PyAnySerdeType::BOOL {},
+                PyAnySerdeType::NUMPY {
+                    dtype: NumpyDtype::FLOAT32,
+                    config: NumpySerdeConfig::DYNAMIC {
+                        preprocessor_fn: None,
+                        postprocessor_fn: None,
+                    },
+                },
+            ],
+        };
+        let buf = encode_json(
+            &serde_type,
+            &json!({"items": [
+                {"value": true},
+                {"shape": [2], "data": [0.5, "-inf"]},
+            ]}),
+        )?;
+        // The bool, the number of dimensions and the dimensions take 17 bytes, so 3 bytes of
+        // padding align the data for float32
+        assert_eq!(buf.len(), 17 + 3 + 8 + 8);
+        let node = decode_to_json(&serde_type, &buf)?;
+        let array_node = &node["items"][1];
+        assert_eq!(array_node["dtype"], "float32");
+        assert_eq!(array_node["shape"], json!([2]));
+        assert_eq!(array_node["padding"], 3);
+        assert_eq!(array_node["data"], json!([0.5, "-inf"]));
+        assert_eq!(encode_json(&serde_type, &node)?, buf);
+
+        // The items of DYNAMIC containers are DYNAMIC nodes themselves
+        let dynamic_node = |value: Value| json!({"type": "DYNAMIC", "value": value});
+        let buf = encode_json(
+            &PyAnySerdeType::DYNAMIC {},
+            &dynamic_node(json!({"type": "DICT", "entries": [{
+                "key": dynamic_node(json!({"type": "STRING", "value": "a"})),
+                "value": dynamic_node(json!({"type": "TUPLE", "items": [
+                    dynamic_node(json!({"type": "INT", "value": -1})),
+                    dynamic_node(json!({"type": "NUMPY", "dtype": "uint16", "shape": [], "data": [7]})),
+                ]})),
+            }]})),
+        )?;
+        assert_eq!(buf[0], 3);
+        let node = decode_to_json(&PyAnySerdeType::DYNAMIC {}, &buf)?;
+        assert_eq!(node["length"], buf.len());
+        let items = &node["value"]["entries"][0]["value"]["value"]["items"];
+        assert_eq!(items[0]["value"]["value"], -1);
+        assert_eq!(items[1]["value"]["data"], json!([7]));
+        assert_eq!(encode_json(&PyAnySerdeType::DYNAMIC {}, &node)?, buf);
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug_hexdump() -> PyResult<()> {
+        Python::initialize();
+        let serde_type = PyAnySerdeType::TUPLE {
+            item_serde_types: vec![
+                PyAnySerdeType::STRING {},
+                PyAnySerdeType::NUMPY {
+                    dtype: NumpyDtype::INT32,
+                    config: NumpySerdeConfig::DYNAMIC {
+                        preprocessor_fn: None,
+                        postprocessor_fn: None,
+                    },
+                },
+            ],
+        };
+        let mut buf = encode_json(
+            &serde_type,
+            &json!({"items": [
+                {"value": "abcdefghijklmnopqrstu"},
+                {"shape": [2], "data": [1, -1]},
+            ]}),
+        )?;
+        buf.extend([0xAB, 0xCD]);
+        let out = hexdump(&serde_type, &buf, 0, Some(20))?;
+        let rows = out.lines().map(str::trim_start).collect::<Vec<_>>();
+        assert_eq!(rows.len(), 9, "{out}");
+        assert!(rows[0].starts_with("0..64 "), "{out}");
+        assert!(rows[0].ends_with("  $: TUPLE (2 items)"), "{out}");
+        assert!(
+            rows[1]
+                .ends_with("61 62 63 64 65 66 67 68    $[0]: STRING = \"abcdefghijklmnopqrstu\""),
+            "{out}"
+        );
+        // Only the first 20 bytes of the string are shown
+        assert_eq!(rows[2], "16..20       69 6a 6b 6c");
+        assert!(rows[3].ends_with("  ... (9 more bytes)"), "{out}");
+        assert!(
+            rows[4].ends_with("  $[1]: NUMPY int32 [2] = [1,-1]"),
+            "{out}"
+        );
+        assert!(rows[5].starts_with("29..45 "), "{out}");
+        assert!(rows[5].ends_with("  $[1] (shape)"), "{out}");
+        // The string and the shape take 45 bytes, so 3 bytes of padding align the data for int32
+        assert!(
+            rows[6].starts_with("45..48       00 00 00 ")
+                && rows[6].ends_with("  $[1] (alignment padding)"),
+            "{out}"
+        );
+        assert!(rows[7].starts_with("48..64 "), "{out}");
+        assert!(rows[7].ends_with("  $[1] (data)"), "{out}");
+        assert!(
+            rows[8].starts_with("64..66       ab cd ") && rows[8].ends_with("  (trailing bytes)"),
+            "{out}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_debug_decode_errors() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let serde_type = PyAnySerdeType::CHECKSUMMED {
+                value_serde_type: Py::new(py, PyAnySerdeType::STRING {})?,
+            };
+            let mut buf = encode_json(
+                &serde_type,
+                &json!({"value": {"type": "STRING", "value": "abc"}}),
+            )?;
+            let err = decode(&serde_type, &buf[..buf.len() - 2], 0).unwrap_err();
+            assert!(err.is_instance_of::<InvalidStateError>(py));
+            assert!(
+                err.to_string().contains(
+                    "tried to decode the checksum of a CHECKSUMMED (4 bytes) at offset 19"
+                ),
+                "{err}"
+            );
+            buf[17] = b'x';
+            let node = decode_to_json(&serde_type, &buf)?;
+            assert_eq!(node["checksum_valid"], false);
+            assert_eq!(node["value"]["value"], "axc");
+
+            let err = encode_json(&serde_type, &json!({"value": {"type": "INT", "value": 1}}))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(
+                err.to_string()
+                    .contains("expected a STRING node but got a node of type \"INT\""),
+                "{err}"
+            );
+
+            let python_serde_type = PyAnySerdeType::PYTHONSERDE {
+                python_serde: py.None(),
+            };
+            assert!(decode(&python_serde_type, &buf, 0).is_err());
+            Ok(())
+        })
+    }
+}
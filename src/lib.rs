@@ -1,15 +1,19 @@
+pub mod arrow_export;
 pub mod common;
 pub mod communication;
+pub mod debug_decoder;
 pub mod pyany_serde_impl;
+pub mod record_file;
 
 mod pickling;
 mod pyany_serde;
 mod pyany_serde_type;
 mod pydantic;
+mod type_hint;
 
 #[cfg(test)]
 mod tests;
 
 pub use pyany_serde::DynPyAnySerdeOption;
 pub use pyany_serde::PyAnySerde;
-pub use pyany_serde_type::{PyAnySerdeType, PyAnySerdeTypeKind};
+pub use pyany_serde_type::{FieldSerdeTypeMap, PyAnySerdeType, PyAnySerdeTypeKind};
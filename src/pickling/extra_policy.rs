@@ -0,0 +1,23 @@
+use pyo3::{
+    prelude::*,
+    types::{PyTuple, PyType},
+};
+
+use crate::pyany_serde_impl::{ExtraPolicy, ExtraPolicyKind};
+
+#[pymethods]
+impl ExtraPolicy {
+    fn __reduce__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        let class = ExtraPolicyKind::from(self).type_object(py);
+        let args = match self {
+            ExtraPolicy::PASSTHROUGH {
+                fallback_serde_type,
+            } => PyTuple::new(py, [fallback_serde_type])?,
+            _ => PyTuple::empty(py),
+        };
+        Ok((class, args))
+    }
+}
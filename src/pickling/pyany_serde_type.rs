@@ -14,18 +14,76 @@ impl PyAnySerdeType {
     ) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
         let class = PyAnySerdeTypeKind::from(self).type_object(py);
         let args = match self {
+            PyAnySerdeType::CHECKSUMMED { value_serde_type } => {
+                PyTuple::new(py, [value_serde_type.into_bound_py_any(py)?])?
+            }
+            PyAnySerdeType::COLUMNARLIST {
+                items_serde_type,
+                as_columns,
+            } => PyTuple::new(
+                py,
+                [
+                    items_serde_type.into_bound_py_any(py)?,
+                    as_columns.into_bound_py_any(py)?,
+                ],
+            )?,
+            PyAnySerdeType::COMPRESSED {
+                value_serde_type,
+                codec,
+                level,
+                min_size,
+            } => PyTuple::new(
+                py,
+                [
+                    value_serde_type.into_bound_py_any(py)?,
+                    (*codec).into_bound_py_any(py)?,
+                    level.into_bound_py_any(py)?,
+                    min_size.into_bound_py_any(py)?,
+                ],
+            )?,
             PyAnySerdeType::DATACLASS {
                 clazz,
                 init_strategy,
                 field_serde_type_dict,
+                extra,
+                alphabetical_field_order,
+                schema,
             } => PyTuple::new(
                 py,
                 [
                     clazz.into_bound_py_any(py)?,
                     init_strategy.clone().into_bound_py_any(py)?,
                     field_serde_type_dict.clone().into_bound_py_any(py)?,
+                    extra.clone().into_bound_py_any(py)?,
+                    alphabetical_field_order.into_bound_py_any(py)?,
+                    schema.clone().into_bound_py_any(py)?,
+                ],
+            )?,
+            PyAnySerdeType::DEFAULTDICT {
+                keys_serde_type,
+                values_serde_type,
+            } => PyTuple::new(
+                py,
+                [
+                    keys_serde_type.into_bound_py_any(py)?,
+                    values_serde_type.into_bound_py_any(py)?,
                 ],
             )?,
+            PyAnySerdeType::DEFINITIONS {
+                definitions,
+                root_serde_type,
+                max_depth,
+            } => PyTuple::new(
+                py,
+                [
+                    definitions.clone().into_bound_py_any(py)?,
+                    root_serde_type.into_bound_py_any(py)?,
+                    max_depth.into_bound_py_any(py)?,
+                ],
+            )?,
+            PyAnySerdeType::DEQUE { items_serde_type } => {
+                PyTuple::new(py, [items_serde_type.into_bound_py_any(py)?])?
+            }
             PyAnySerdeType::DICT {
                 keys_serde_type,
                 values_serde_type,
@@ -36,9 +94,19 @@ impl PyAnySerdeType {
                     values_serde_type.into_bound_py_any(py)?,
                 ],
             )?,
+            PyAnySerdeType::ENUM { values } => PyTuple::new(py, [values.into_bound_py_any(py)?])?,
+            PyAnySerdeType::FROZENSET { items_serde_type } => {
+                PyTuple::new(py, [items_serde_type.into_bound_py_any(py)?])?
+            }
+            PyAnySerdeType::HOMOGENEOUSTUPLE { items_serde_type } => {
+                PyTuple::new(py, [items_serde_type.into_bound_py_any(py)?])?
+            }
             PyAnySerdeType::LIST { items_serde_type } => {
                 PyTuple::new(py, [items_serde_type.into_bound_py_any(py)?])?
             }
+            PyAnySerdeType::MEMO { value_serde_type } => {
+                PyTuple::new(py, [value_serde_type.into_bound_py_any(py)?])?
+            }
             PyAnySerdeType::NUMPY { dtype, config } => PyTuple::new(
                 py,
                 [
@@ -49,18 +117,64 @@ impl PyAnySerdeType {
             PyAnySerdeType::OPTION { value_serde_type } => {
                 PyTuple::new(py, [value_serde_type.into_bound_py_any(py)?])?
             }
+            PyAnySerdeType::ORDEREDDICT {
+                keys_serde_type,
+                values_serde_type,
+            } => PyTuple::new(
+                py,
+                [
+                    keys_serde_type.into_bound_py_any(py)?,
+                    values_serde_type.into_bound_py_any(py)?,
+                ],
+            )?,
+            PyAnySerdeType::PRIMITIVELIST {
+                items_serde_type,
+                length,
+                as_tuple,
+            } => PyTuple::new(
+                py,
+                [
+                    items_serde_type.into_bound_py_any(py)?,
+                    length.into_bound_py_any(py)?,
+                    as_tuple.into_bound_py_any(py)?,
+                ],
+            )?,
             PyAnySerdeType::PYTHONSERDE { python_serde } => {
                 PyTuple::new(py, [python_serde.into_bound_py_any(py)?])?
             }
+            PyAnySerdeType::REF { name } => PyTuple::new(py, [name.into_bound_py_any(py)?])?,
             PyAnySerdeType::SET { items_serde_type } => {
                 PyTuple::new(py, [items_serde_type.into_bound_py_any(py)?])?
             }
+            PyAnySerdeType::NAMEDTUPLE {
+                clazz,
+                item_serde_types,
+            } => PyTuple::new(
+                py,
+                [
+                    clazz.into_bound_py_any(py)?,
+                    item_serde_types.clone().into_bound_py_any(py)?,
+                ],
+            )?,
             PyAnySerdeType::TUPLE { item_serde_types } => {
                 PyTuple::new(py, [item_serde_types.clone().into_bound_py_any(py)?])?
             }
             PyAnySerdeType::TYPEDDICT {
                 key_serde_type_dict,
-            } => PyTuple::new(py, [key_serde_type_dict.clone().into_bound_py_any(py)?])?,
+                optional_keys,
+                extra,
+                alphabetical_field_order,
+                schema,
+            } => PyTuple::new(
+                py,
+                [
+                    key_serde_type_dict.clone().into_bound_py_any(py)?,
+                    optional_keys.clone().into_bound_py_any(py)?,
+                    extra.clone().into_bound_py_any(py)?,
+                    alphabetical_field_order.into_bound_py_any(py)?,
+                    schema.clone().into_bound_py_any(py)?,
+                ],
+            )?,
             PyAnySerdeType::UNION {
                 option_serde_types,
                 option_choice_fn,
@@ -0,0 +1,24 @@
+use pyo3::{
+    IntoPyObjectExt,
+    prelude::*,
+    types::{PyTuple, PyType},
+};
+
+use crate::pyany_serde_impl::{SchemaPolicy, SchemaPolicyKind};
+
+#[pymethods]
+impl SchemaPolicy {
+    fn __reduce__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyType>, Bound<'py, PyTuple>)> {
+        let class = SchemaPolicyKind::from(self).type_object(py);
+        let args = match self {
+            SchemaPolicy::VERSIONED { field_defaults } => {
+                PyTuple::new(py, [field_defaults.clone().into_bound_py_any(py)?])?
+            }
+            _ => PyTuple::empty(py),
+        };
+        Ok((class, args))
+    }
+}
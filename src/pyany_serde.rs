@@ -1,13 +1,22 @@
+use std::io::{Read, Write};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
 use pyo3::prelude::*;
-use pyo3::types::PyString;
+use pyo3::types::{PyList, PyString};
 
 use dyn_clone::{DynClone, clone_trait_object};
 
-use crate::communication::{append_bool, append_bool_vec, retrieve_bool};
+use crate::communication::{
+    StreamReader, StreamWriter, append_bool, append_bool_vec, retrieve_bool,
+};
 use crate::pyany_serde_impl::{
-    BoolSerde, BytesSerde, ComplexSerde, DataclassSerde, DictSerde, DynamicSerde, FloatSerde,
-    IntSerde, ListSerde, OptionSerde, PickleSerde, PythonSerdeSerde, SetSerde, StringSerde,
-    TupleSerde, TypedDictSerde, UnionSerde, get_numpy_serde,
+    BoolSerde, BytesSerde, ChecksummedSerde, ComplexSerde, CompressedSerde, DataclassSerde,
+    DefaultDictSerde, DequeSerde, DictSerde, DynamicSerde, EnumSerde, FloatSerde, FrozenSetSerde,
+    HomogeneousTupleSerde, IntSerde, InternalExtraPolicy, ListSerde, MemoSerde, NamedTupleSerde,
+    OptionSerde, OrderedDictSerde, PickleSerde, PythonSerdeSerde, RefSerde, SetSerde, StringSerde,
+    TupleSerde, TypedDictSerde, UnionSerde, get_columnar_list_serde, get_definitions_serde,
+    get_numpy_serde, get_primitive_list_serde,
 };
 use crate::pyany_serde_type::PyAnySerdeType;
 
@@ -74,6 +83,150 @@ pub trait PyAnySerde: DynClone {
             Ok((None, offset))
         }
     }
+    // Appends each object of the iterable objs, in the same format as calling append on each of
+    // them in turn. Serdes can override this and the other *_many methods to avoid the overhead of
+    // handling each object separately.
+    fn append_many<'py>(
+        &mut self,
+        buf: &mut [u8],
+        mut offset: usize,
+        objs: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        for obj in objs.try_iter()? {
+            offset = self.append(buf, offset, &obj?)?;
+        }
+        Ok(offset)
+    }
+    fn append_many_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        objs: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        for obj in objs.try_iter()? {
+            self.append_vec(v, start_addr, &obj?)?;
+        }
+        Ok(())
+    }
+    // Retrieves n objects written by append_many (or by n calls to append) as a list
+    fn retrieve_many<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        mut offset: usize,
+        n: usize,
+    ) -> PyResult<(Bound<'py, PyList>, usize)> {
+        let mut objs = Vec::with_capacity(n);
+        for _ in 0..n {
+            let obj;
+            (obj, offset) = self.retrieve(py, buf, offset)?;
+            objs.push(obj);
+        }
+        Ok((PyList::new(py, objs)?, offset))
+    }
+    // Retrieves n objects like retrieve_many, but stacked into a single numpy array along a new
+    // first axis
+    fn retrieve_many_stacked<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+        n: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let (objs, offset) = self.retrieve_many(py, buf, offset, n)?;
+        let stacked = py
+            .import(intern!(py, "numpy"))?
+            .call_method1(intern!(py, "stack"), (objs,))?;
+        Ok((stacked, offset))
+    }
+    // Appends obj to a stream in the same format as append_vec, with numpy alignment padding
+    // relative to the position of the stream writer. Containers write their items to the stream one
+    // at a time, so only a small part of obj is serialized in memory at once. By default obj is
+    // serialized in memory with append_vec and then written.
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let mut v = Vec::new();
+        self.append_vec(&mut v, Some(writer.position()), obj)?;
+        writer.write_all(&v)
+    }
+    // Retrieves an object written by append_stream from a stream. The length of the object isn't
+    // known in general, so by default the rest of the stream is read into memory and the object is
+    // retrieved from it with retrieve. Later reads from the same StreamReader continue after the
+    // object, but the underlying reader is at the end of the stream.
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        reader.retrieve_from_rest(|buf, offset| self.retrieve(py, buf, offset))
+    }
+    fn append_many_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        objs: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        for obj in objs.try_iter()? {
+            self.append_stream(writer, &obj?)?;
+        }
+        Ok(())
+    }
+    fn retrieve_many_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+        n: usize,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let objs = PyList::empty(py);
+        for _ in 0..n {
+            objs.append(self.retrieve_stream(py, reader)?)?;
+        }
+        Ok(objs)
+    }
+    fn retrieve_many_stacked_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+        n: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let objs = self.retrieve_many_stream(py, reader, n)?;
+        py.import(intern!(py, "numpy"))?
+            .call_method1(intern!(py, "stack"), (objs,))
+    }
+    // Writes obj to writer in the same format as append_vec with a start_addr of 0, so numpy
+    // alignment padding is relative to the start of obj in the stream. There is no framing, so a
+    // stream of objects is just the serialized objects back to back. Writes go directly to writer,
+    // so wrapping it in a BufWriter is recommended.
+    fn append_to_writer<'py>(
+        &mut self,
+        writer: &mut dyn Write,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        self.append_stream(&mut StreamWriter::new(writer), obj)
+    }
+    // Reads an object written by append_to_writer from reader, returning None if the reader is
+    // already at the end of the stream. If the serde (or a serde nested in it) uses the default
+    // retrieve_stream, the rest of reader is read, so the object must be the last one in it.
+    fn retrieve_from_reader<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut dyn Read,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let mut stream_reader = StreamReader::new(reader);
+        let obj = match self.retrieve_stream(py, &mut stream_reader) {
+            Ok(obj) => obj,
+            Err(_) if stream_reader.is_at_end() => return Ok(None),
+            Err(err) => Err(err)?,
+        };
+        if stream_reader.has_unused_rest() {
+            Err(PyValueError::new_err(
+                "the rest of the reader was read to retrieve an object with a serde that doesn't implement retrieve_stream, but there is more data after the object",
+            ))?
+        }
+        Ok(Some(obj))
+    }
 }
 
 clone_trait_object!(PyAnySerde);
@@ -109,25 +262,73 @@ impl<'a> TryFrom<&'a PyAnySerdeType> for Box<dyn PyAnySerde> {
         Ok(match value {
             PyAnySerdeType::BOOL {} => Box::new(BoolSerde {}),
             PyAnySerdeType::BYTES {} => Box::new(BytesSerde {}),
+            PyAnySerdeType::CHECKSUMMED { value_serde_type } => Box::new(ChecksummedSerde {
+                value_serde: value_serde_type.try_into()?,
+            }),
+            PyAnySerdeType::COLUMNARLIST {
+                items_serde_type,
+                as_columns,
+            } => Python::attach(|py| {
+                get_columnar_list_serde(
+                    &items_serde_type.extract::<PyAnySerdeType>(py)?,
+                    *as_columns,
+                )
+            })?,
             PyAnySerdeType::COMPLEX {} => Box::new(ComplexSerde {}),
+            PyAnySerdeType::COMPRESSED {
+                value_serde_type,
+                codec,
+                level,
+                min_size,
+            } => Box::new(CompressedSerde::new(
+                value_serde_type.try_into()?,
+                *codec,
+                *level,
+                *min_size,
+            )?),
             PyAnySerdeType::DATACLASS {
                 clazz,
                 init_strategy,
                 field_serde_type_dict,
+                extra,
+                alphabetical_field_order,
+                schema,
             } => Python::attach::<_, PyResult<_>>(|py| {
                 Ok(Box::new(DataclassSerde::new(
                     clazz.clone_ref(py),
                     init_strategy.clone(),
                     field_serde_type_dict
-                        .iter()
+                        .serialization_order(*alphabetical_field_order)
+                        .into_iter()
                         .map(|(field, field_serde_type)| {
                             field_serde_type.try_into().map(|pyany_serde| {
                                 (PyString::new(py, field.as_str()).unbind(), pyany_serde)
                             })
                         })
                         .collect::<PyResult<_>>()?,
+                    InternalExtraPolicy::new(extra)?,
+                    schema,
                 )?))
             })?,
+            PyAnySerdeType::DEFAULTDICT {
+                keys_serde_type,
+                values_serde_type,
+            } => Python::attach::<_, PyResult<_>>(|py| {
+                Ok(Box::new(DefaultDictSerde::new(
+                    keys_serde_type.bind(py).try_into()?,
+                    values_serde_type.bind(py).try_into()?,
+                )?))
+            })?,
+            PyAnySerdeType::DEFINITIONS {
+                definitions,
+                root_serde_type,
+                max_depth,
+            } => Python::attach::<_, PyResult<_>>(|py| {
+                get_definitions_serde(definitions, &root_serde_type.extract(py)?, *max_depth)
+            })?,
+            PyAnySerdeType::DEQUE { items_serde_type } => {
+                Box::new(DequeSerde::new(items_serde_type.try_into()?)?)
+            }
             PyAnySerdeType::DICT {
                 keys_serde_type,
                 values_serde_type,
@@ -138,17 +339,82 @@ impl<'a> TryFrom<&'a PyAnySerdeType> for Box<dyn PyAnySerde> {
                 }))
             })?,
             PyAnySerdeType::DYNAMIC {} => Box::new(DynamicSerde::new()?),
+            PyAnySerdeType::ENUM { values } => Python::attach::<_, PyResult<_>>(|py| {
+                Ok(Box::new(EnumSerde::new(
+                    values.iter().map(|value| value.clone_ref(py)).collect(),
+                )?))
+            })?,
             PyAnySerdeType::FLOAT {} => Box::new(FloatSerde {}),
-            PyAnySerdeType::INT {} => Box::new(IntSerde {}),
-            PyAnySerdeType::LIST { items_serde_type } => Box::new(ListSerde {
+            PyAnySerdeType::FROZENSET { items_serde_type } => Box::new(FrozenSetSerde {
                 items_serde: items_serde_type.try_into()?,
             }),
+            PyAnySerdeType::HOMOGENEOUSTUPLE { items_serde_type } => {
+                let items_serde_type = Python::attach::<_, PyResult<PyAnySerdeType>>(|py| {
+                    Ok(items_serde_type.extract(py)?)
+                })?;
+                match get_primitive_list_serde(&items_serde_type, None, true) {
+                    Some(pyany_serde) => pyany_serde,
+                    None => Box::new(HomogeneousTupleSerde {
+                        items_serde: items_serde_type.try_into()?,
+                    }),
+                }
+            }
+            PyAnySerdeType::INT {} => Box::new(IntSerde {}),
+            PyAnySerdeType::LIST { items_serde_type } => {
+                let items_serde_type = Python::attach::<_, PyResult<PyAnySerdeType>>(|py| {
+                    Ok(items_serde_type.extract(py)?)
+                })?;
+                match get_primitive_list_serde(&items_serde_type, None, false) {
+                    Some(pyany_serde) => pyany_serde,
+                    None => Box::new(ListSerde {
+                        items_serde: items_serde_type.try_into()?,
+                    }),
+                }
+            }
+            PyAnySerdeType::MEMO { value_serde_type } => Box::new(MemoSerde {
+                value_serde: value_serde_type.try_into()?,
+            }),
+            PyAnySerdeType::NAMEDTUPLE {
+                clazz,
+                item_serde_types,
+            } => Python::attach::<_, PyResult<_>>(|py| {
+                Ok(Box::new(NamedTupleSerde::new(
+                    clazz.clone_ref(py),
+                    item_serde_types
+                        .iter()
+                        .map(|item| item.try_into())
+                        .collect::<PyResult<_>>()?,
+                )?))
+            })?,
             PyAnySerdeType::NUMPY { dtype, config } => get_numpy_serde(*dtype, config.clone()),
 
             PyAnySerdeType::OPTION { value_serde_type } => Box::new(OptionSerde {
                 value_serde: value_serde_type.try_into()?,
             }),
+            PyAnySerdeType::ORDEREDDICT {
+                keys_serde_type,
+                values_serde_type,
+            } => Python::attach::<_, PyResult<_>>(|py| {
+                Ok(Box::new(OrderedDictSerde::new(
+                    keys_serde_type.bind(py).try_into()?,
+                    values_serde_type.bind(py).try_into()?,
+                )?))
+            })?,
             PyAnySerdeType::PICKLE {} => Box::new(PickleSerde::new()?),
+            PyAnySerdeType::PRIMITIVELIST {
+                items_serde_type,
+                length,
+                as_tuple,
+            } => {
+                let items_serde_type = Python::attach::<_, PyResult<PyAnySerdeType>>(|py| {
+                    Ok(items_serde_type.extract(py)?)
+                })?;
+                get_primitive_list_serde(&items_serde_type, *length, *as_tuple).ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "PRIMITIVELIST items_serde_type must be one of BOOL, FLOAT, or INT, but was {items_serde_type}"
+                    ))
+                })?
+            }
             PyAnySerdeType::PYTHONSERDE { python_serde } => {
                 Python::attach::<_, PyResult<_>>(|py| {
                     Ok(Box::new(PythonSerdeSerde {
@@ -156,6 +422,7 @@ impl<'a> TryFrom<&'a PyAnySerdeType> for Box<dyn PyAnySerde> {
                     }))
                 })?
             }
+            PyAnySerdeType::REF { name } => Box::new(RefSerde::new(name.clone())?),
             PyAnySerdeType::SET { items_serde_type } => Box::new(SetSerde {
                 items_serde: items_serde_type.try_into()?,
             }),
@@ -168,16 +435,26 @@ impl<'a> TryFrom<&'a PyAnySerdeType> for Box<dyn PyAnySerde> {
             }),
             PyAnySerdeType::TYPEDDICT {
                 key_serde_type_dict,
+                optional_keys,
+                extra,
+                alphabetical_field_order,
+                schema,
             } => Python::attach::<_, PyResult<_>>(|py| {
                 let serde_kv_list = key_serde_type_dict
-                    .iter()
+                    .serialization_order(*alphabetical_field_order)
+                    .into_iter()
                     .map(|(key, item_serde_type)| {
                         item_serde_type.try_into().map(|pyany_serde| {
                             (PyString::new(py, key.as_str()).unbind(), pyany_serde)
                         })
                     })
                     .collect::<PyResult<_>>()?;
-                Ok(Box::new(TypedDictSerde { serde_kv_list }) as Box<dyn PyAnySerde>)
+                Ok(Box::new(TypedDictSerde::new(
+                    serde_kv_list,
+                    optional_keys,
+                    InternalExtraPolicy::new(extra)?,
+                    schema,
+                )?) as Box<dyn PyAnySerde>)
             })?,
             PyAnySerdeType::UNION {
                 option_serde_types,
@@ -231,3 +508,222 @@ impl<'py> FromPyObject<'_, 'py> for DynPyAnySerdeOption {
             })?
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    use pyo3::exceptions::PyEOFError;
+    use pyo3::types::PyList;
+
+    use super::*;
+    use crate::common::CompressionCodec;
+    use crate::pyany_serde_impl::{ExtraPolicy, SchemaPolicy};
+    use crate::pyany_serde_type::FieldSerdeTypeMap;
+
+    #[test]
+    fn test_stream_roundtrip() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let list_serde_type = |items_serde_type| {
+                Py::new(py, items_serde_type)
+                    .map(|items_serde_type| PyAnySerdeType::LIST { items_serde_type })
+            };
+            let cases = [
+                (
+                    list_serde_type(PyAnySerdeType::STRING {})?,
+                    c"[[], ['item0'], ['item0', 'item1']]",
+                ),
+                (
+                    PyAnySerdeType::DICT {
+                        keys_serde_type: Py::new(py, PyAnySerdeType::STRING {})?,
+                        values_serde_type: Py::new(
+                            py,
+                            PyAnySerdeType::CHECKSUMMED {
+                                value_serde_type: Py::new(
+                                    py,
+                                    list_serde_type(PyAnySerdeType::INT {})?,
+                                )?,
+                            },
+                        )?,
+                    },
+                    c"[{'a': [1, 2], 'b': []}, {}]",
+                ),
+                (
+                    PyAnySerdeType::COMPRESSED {
+                        value_serde_type: Py::new(py, list_serde_type(PyAnySerdeType::STRING {})?)?,
+                        codec: CompressionCodec::LZ4,
+                        level: None,
+                        min_size: 0,
+                    },
+                    c"[['abc'] * 10, []]",
+                ),
+                (
+                    PyAnySerdeType::TUPLE {
+                        item_serde_types: vec![
+                            PyAnySerdeType::BYTES {},
+                            PyAnySerdeType::OPTION {
+                                value_serde_type: Py::new(py, PyAnySerdeType::FLOAT {})?,
+                            },
+                        ],
+                    },
+                    c"[(b'a', 3.5), (b'', None)]",
+                ),
+                (
+                    PyAnySerdeType::TYPEDDICT {
+                        key_serde_type_dict: FieldSerdeTypeMap(vec![
+                            ("a".to_string(), PyAnySerdeType::INT {}),
+                            ("b".to_string(), list_serde_type(PyAnySerdeType::STRING {})?),
+                        ]),
+                        optional_keys: vec!["b".to_string()],
+                        extra: ExtraPolicy::IGNORE {},
+                        alphabetical_field_order: false,
+                        schema: SchemaPolicy::VERSIONED {
+                            field_defaults: HashMap::new(),
+                        },
+                    },
+                    c"[{'a': 1, 'b': ['x', 'y']}, {'a': 2}]",
+                ),
+            ];
+            for (serde_type, objs_code) in cases {
+                let mut serde: Box<dyn PyAnySerde> = serde_type.try_into()?;
+                let objs = py.eval(objs_code, None, None)?.cast_into::<PyList>()?;
+                let mut stream = Vec::new();
+                let mut expected = Vec::new();
+                for obj in objs.iter() {
+                    serde.append_to_writer(&mut stream, &obj)?;
+                    serde.append_vec(&mut expected, Some(0), &obj)?;
+                }
+                assert_eq!(stream, expected);
+
+                let mut reader = Cursor::new(&stream);
+                for obj in objs.iter() {
+                    let retrieved = serde.retrieve_from_reader(py, &mut reader)?.unwrap();
+                    assert!(retrieved.eq(obj)?);
+                }
+                assert!(serde.retrieve_from_reader(py, &mut reader)?.is_none());
+
+                let mut truncated_reader = Cursor::new(&stream[..stream.len() - 1]);
+                for _ in 0..objs.len() - 1 {
+                    serde.retrieve_from_reader(py, &mut truncated_reader)?;
+                }
+                let err = serde
+                    .retrieve_from_reader(py, &mut truncated_reader)
+                    .unwrap_err();
+                assert!(err.is_instance_of::<PyEOFError>(py));
+            }
+            Ok(())
+        })
+    }
+
+    // A serde which only implements the required methods, like a serde defined outside this crate
+    #[derive(Clone)]
+    struct RequiredMethodsSerde(StringSerde);
+
+    impl PyAnySerde for RequiredMethodsSerde {
+        fn append<'py>(
+            &mut self,
+            buf: &mut [u8],
+            offset: usize,
+            obj: &Bound<'py, PyAny>,
+        ) -> PyResult<usize> {
+            self.0.append(buf, offset, obj)
+        }
+
+        fn append_vec<'py>(
+            &mut self,
+            v: &mut Vec<u8>,
+            start_addr: Option<usize>,
+            obj: &Bound<'py, PyAny>,
+        ) -> PyResult<()> {
+            self.0.append_vec(v, start_addr, obj)
+        }
+
+        fn retrieve<'py>(
+            &mut self,
+            py: Python<'py>,
+            buf: &[u8],
+            offset: usize,
+        ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+            self.0.retrieve(py, buf, offset)
+        }
+    }
+
+    #[test]
+    fn test_default_stream_methods() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let mut serde = RequiredMethodsSerde(StringSerde {});
+            let mut list_serde = ListSerde {
+                items_serde: Box::new(serde.clone()),
+            };
+            let obj = PyList::new(py, ["a", "bc", ""])?.into_any();
+            let mut stream = Vec::new();
+            list_serde.append_to_writer(&mut stream, &obj)?;
+            let mut expected = Vec::new();
+            list_serde.append_vec(&mut expected, Some(0), &obj)?;
+            assert_eq!(stream, expected);
+            let retrieved = list_serde
+                .retrieve_from_reader(py, &mut Cursor::new(&stream))?
+                .unwrap();
+            assert!(retrieved.eq(&obj)?);
+
+            let item = PyString::new(py, "item").into_any();
+            let mut stream = Vec::new();
+            serde.append_to_writer(&mut stream, &item)?;
+            let mut reader = Cursor::new(&stream);
+            assert!(
+                serde
+                    .retrieve_from_reader(py, &mut reader)?
+                    .unwrap()
+                    .eq(&item)?
+            );
+            assert!(serde.retrieve_from_reader(py, &mut reader)?.is_none());
+            // The rest of the reader is read, so there can't be more data after the object
+            serde.append_to_writer(&mut stream, &item)?;
+            let err = serde
+                .retrieve_from_reader(py, &mut Cursor::new(&stream))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_many_roundtrip() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let cases = [
+                (PyAnySerdeType::INT {}, PyList::new(py, [3, -1, 7])?),
+                (PyAnySerdeType::FLOAT {}, PyList::new(py, [0.5, 2.0])?),
+                (
+                    PyAnySerdeType::BOOL {},
+                    PyList::new(py, [true, false, true])?,
+                ),
+                (PyAnySerdeType::STRING {}, PyList::new(py, ["a", "bc"])?),
+                (PyAnySerdeType::INT {}, PyList::empty(py)),
+            ];
+            for (serde_type, objs) in cases {
+                let mut serde: Box<dyn PyAnySerde> = serde_type.try_into()?;
+                let mut expected = Vec::new();
+                for obj in objs.iter() {
+                    serde.append_vec(&mut expected, Some(0), &obj)?;
+                }
+
+                let mut v = Vec::new();
+                serde.append_many_vec(&mut v, Some(0), &objs)?;
+                assert_eq!(v, expected);
+                let mut buf = vec![0; expected.len()];
+                let end = serde.append_many(&mut buf, 0, &objs)?;
+                assert_eq!(end, expected.len());
+                assert_eq!(buf, expected);
+
+                let (retrieved, offset) = serde.retrieve_many(py, &buf, 0, objs.len())?;
+                assert_eq!(offset, buf.len());
+                assert!(retrieved.eq(&objs)?);
+            }
+            Ok(())
+        })
+    }
+}
@@ -1,7 +1,11 @@
 use pyo3::prelude::*;
 
+use crate::impl_packed_many;
 use crate::{
-    communication::{append_bool, append_bool_vec, retrieve_bool},
+    communication::{
+        append_bool, append_bool_stream, append_bool_vec, retrieve_bool, retrieve_bool_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -37,4 +41,23 @@ impl PyAnySerde for BoolSerde {
         let (val, offset) = retrieve_bool(buf, offset)?;
         Ok((val.into_pyobject(py)?.to_owned().into_any(), offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        append_bool_stream(writer, obj.extract::<bool>()?)
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let val = retrieve_bool_stream(reader)?;
+        Ok(val.into_pyobject(py)?.to_owned().into_any())
+    }
+
+    impl_packed_many!(bool);
 }
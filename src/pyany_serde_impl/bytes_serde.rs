@@ -2,7 +2,10 @@ use pyo3::prelude::*;
 use pyo3::types::PyBytes;
 
 use crate::{
-    communication::{append_bytes, append_bytes_vec, retrieve_bytes},
+    communication::{
+        append_bytes, append_bytes_stream, append_bytes_vec, retrieve_bytes, retrieve_bytes_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -38,4 +41,21 @@ impl PyAnySerde for BytesSerde {
         let (obj_bytes, offset) = retrieve_bytes(buf, offset)?;
         Ok((PyBytes::new(py, obj_bytes).into_any(), offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        append_bytes_stream(writer, obj.cast::<PyBytes>()?.as_bytes())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let obj_bytes = retrieve_bytes_stream(reader)?;
+        Ok(PyBytes::new(py, &obj_bytes).into_any())
+    }
 }
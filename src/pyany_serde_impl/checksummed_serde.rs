@@ -0,0 +1,142 @@
+use std::mem::size_of;
+
+use pyo3::exceptions::asyncio::InvalidStateError;
+use pyo3::prelude::*;
+
+use crate::{
+    PyAnySerde,
+    common::{ChecksumError, crc32c, crc32c_update},
+    communication::{
+        StreamReader, StreamWriter, append_u32, append_u32_stream, append_u32_vec, append_usize,
+        append_usize_stream, append_usize_vec, retrieve_u32_stream, retrieve_usize_stream,
+    },
+};
+
+// The serialized format is the usize length of the value's data, the value's data (serialized in
+// place, so its alignment is the same as without the checksum), and then the u32 CRC-32C of the
+// length and the data. The checksum is verified before the value is retrieved, so a partially
+// written or otherwise corrupted buffer raises ChecksumError instead of being deserialized.
+#[derive(Clone)]
+pub struct ChecksummedSerde {
+    pub value_serde: Box<dyn PyAnySerde>,
+}
+
+impl ChecksummedSerde {
+    fn check_value_len(&self, value_len: usize, value_end: usize) -> PyResult<()> {
+        if value_end != value_len {
+            Err(InvalidStateError::new_err(format!(
+                "CHECKSUMMED data of {value_len} bytes contained {value_end} bytes of serialized data"
+            )))?
+        }
+        Ok(())
+    }
+}
+
+impl PyAnySerde for ChecksummedSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let value_start = offset + size_of::<usize>();
+        let value_end = self.value_serde.append(buf, value_start, obj)?;
+        append_usize(buf, offset, value_end - value_start);
+        Ok(append_u32(buf, value_end, crc32c(&buf[offset..value_end])))
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let offset = v.len();
+        append_usize_vec(v, 0);
+        self.value_serde.append_vec(v, start_addr, obj)?;
+        let value_len = v.len() - offset - size_of::<usize>();
+        append_usize(v, offset, value_len);
+        append_u32_vec(v, crc32c(&v[offset..]));
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let value_start = offset + size_of::<usize>();
+        if value_start > buf.len() {
+            Err(ChecksumError::new_err(
+                "CHECKSUMMED data is truncated before the length of its value",
+            ))?
+        }
+        let value_len = usize::from_ne_bytes(buf[offset..value_start].try_into()?);
+        let end = value_start
+            .checked_add(value_len)
+            .and_then(|value_end| value_end.checked_add(size_of::<u32>()))
+            .filter(|&end| end <= buf.len())
+            .ok_or_else(|| {
+                ChecksumError::new_err(format!(
+                    "CHECKSUMMED value of {value_len} bytes and its checksum do not fit in the remaining {} bytes of the buffer",
+                    buf.len() - value_start
+                ))
+            })?;
+        let value_end = end - size_of::<u32>();
+        let checksum = u32::from_ne_bytes(buf[value_end..end].try_into()?);
+        let actual_checksum = crc32c(&buf[offset..value_end]);
+        if checksum != actual_checksum {
+            Err(ChecksumError::new_err(format!(
+                "CHECKSUMMED data has checksum {actual_checksum:#010x} but expected {checksum:#010x}"
+            )))?
+        }
+        // The value is retrieved from the buffer up to the end of its data, so that a serde which
+        // doesn't agree with the data can't read past it
+        let (obj, offset) = self
+            .value_serde
+            .retrieve(py, &buf[..value_end], value_start)?;
+        self.check_value_len(value_len, offset - value_start)?;
+        Ok((obj, end))
+    }
+
+    // The length has to be written before the value, so the value is serialized in memory (with
+    // the alignment it will have in the stream) before it is written
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let mut value = Vec::new();
+        self.value_serde.append_vec(
+            &mut value,
+            Some(writer.position() + size_of::<usize>()),
+            obj,
+        )?;
+        let len_bytes = value.len().to_ne_bytes();
+        append_usize_stream(writer, value.len())?;
+        writer.write_all(&value)?;
+        append_u32_stream(writer, crc32c_update(crc32c(&len_bytes), &value))
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let value_len = retrieve_usize_stream(reader)?;
+        let (value_buffer, value_start) = reader.read_aligned(value_len)?;
+        let checksum = retrieve_u32_stream(reader)?;
+        let value_buf = value_buffer.as_slice();
+        let actual_checksum =
+            crc32c_update(crc32c(&value_len.to_ne_bytes()), &value_buf[value_start..]);
+        if checksum != actual_checksum {
+            Err(ChecksumError::new_err(format!(
+                "CHECKSUMMED data has checksum {actual_checksum:#010x} but expected {checksum:#010x}"
+            )))?
+        }
+        let (obj, value_end) = self.value_serde.retrieve(py, value_buf, value_start)?;
+        self.check_value_len(value_len, value_end - value_start)?;
+        Ok(obj)
+    }
+}
@@ -0,0 +1,376 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyDict, PyList, PyString};
+
+use crate::{
+    PyAnySerde, PyAnySerdeType,
+    communication::{
+        StreamReader, StreamWriter, append_usize, append_usize_stream, append_usize_vec,
+        retrieve_usize, retrieve_usize_stream,
+    },
+};
+
+use super::{DataclassSerde, ExtraPolicy, InternalExtraPolicy, SchemaPolicy};
+
+#[derive(Clone)]
+struct Column {
+    field: Py<PyString>,
+    serde: Box<dyn PyAnySerde>,
+    // Whether the column can be retrieved as a single numpy array
+    stackable: bool,
+}
+
+// The serialized format is the usize number of items, followed by the values of each field of the
+// items (in serialization order) appended using append_many of the field's serde. This means
+// columns of bools, floats, and ints are packed arrays of the values.
+#[derive(Clone)]
+pub struct ColumnarListSerde {
+    // The serde used to construct the items, or None if the items are typed dicts
+    dataclass_serde: Option<DataclassSerde>,
+    columns: Vec<Column>,
+    as_columns: bool,
+}
+
+fn get_columns<'py>(
+    py: Python<'py>,
+    field_serde_types: Vec<(&String, &PyAnySerdeType)>,
+) -> PyResult<Vec<Column>> {
+    field_serde_types
+        .into_iter()
+        .map(|(field, field_serde_type)| {
+            Ok(Column {
+                field: PyString::new(py, field).unbind(),
+                serde: field_serde_type.try_into()?,
+                stackable: matches!(
+                    field_serde_type,
+                    PyAnySerdeType::BOOL {}
+                        | PyAnySerdeType::FLOAT {}
+                        | PyAnySerdeType::INT {}
+                        | PyAnySerdeType::NUMPY { .. }
+                ),
+            })
+        })
+        .collect()
+}
+
+fn check_policies(extra: &ExtraPolicy, schema: &SchemaPolicy) -> PyResult<()> {
+    if !matches!(extra, ExtraPolicy::IGNORE {}) {
+        Err(PyValueError::new_err(format!(
+            "COLUMNARLIST items must use ExtraPolicy.IGNORE, but used {extra}"
+        )))?
+    }
+    if !matches!(schema, SchemaPolicy::FIXED {}) {
+        Err(PyValueError::new_err(format!(
+            "COLUMNARLIST items must use SchemaPolicy.FIXED, but used {schema}"
+        )))?
+    }
+    Ok(())
+}
+
+pub fn get_columnar_list_serde(
+    items_serde_type: &PyAnySerdeType,
+    as_columns: bool,
+) -> PyResult<Box<dyn PyAnySerde>> {
+    Python::attach(|py| {
+        let (dataclass_serde, columns) = match items_serde_type {
+            PyAnySerdeType::DATACLASS {
+                clazz,
+                init_strategy,
+                field_serde_type_dict,
+                extra,
+                alphabetical_field_order,
+                schema,
+            } => {
+                check_policies(extra, schema)?;
+                let columns = get_columns(
+                    py,
+                    field_serde_type_dict.serialization_order(*alphabetical_field_order),
+                )?;
+                let dataclass_serde = DataclassSerde::new(
+                    clazz.clone_ref(py),
+                    init_strategy.clone(),
+                    columns
+                        .iter()
+                        .map(|column| (column.field.clone_ref(py), column.serde.clone()))
+                        .collect(),
+                    InternalExtraPolicy::new(extra)?,
+                    schema,
+                )?;
+                (Some(dataclass_serde), columns)
+            }
+            PyAnySerdeType::TYPEDDICT {
+                key_serde_type_dict,
+                optional_keys,
+                extra,
+                alphabetical_field_order,
+                schema,
+            } => {
+                check_policies(extra, schema)?;
+                if !optional_keys.is_empty() {
+                    Err(PyValueError::new_err(
+                        "COLUMNARLIST items cannot be TYPEDDICTs with optional keys",
+                    ))?
+                }
+                let columns = get_columns(
+                    py,
+                    key_serde_type_dict.serialization_order(*alphabetical_field_order),
+                )?;
+                (None, columns)
+            }
+            _ => Err(PyValueError::new_err(format!(
+                "COLUMNARLIST items_serde_type must be DATACLASS or TYPEDDICT, but was {items_serde_type}"
+            )))?,
+        };
+        Ok(Box::new(ColumnarListSerde {
+            dataclass_serde,
+            columns,
+            as_columns,
+        }) as Box<dyn PyAnySerde>)
+    })
+}
+
+impl ColumnarListSerde {
+    // Returns the number of items and the values of each column, which are either taken from the
+    // fields of each item or (if as_columns is true) from a dict of the columns
+    fn get_column_values<'py>(
+        &self,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<(usize, Vec<Bound<'py, PyAny>>)> {
+        let py = obj.py();
+        if self.as_columns {
+            let column_dict = obj.cast::<PyDict>()?;
+            let mut n_items_option = None;
+            let mut column_values = Vec::with_capacity(self.columns.len());
+            for column in self.columns.iter() {
+                let field = column.field.bind(py);
+                let values = column_dict.get_item(field)?.ok_or_else(|| {
+                    PyValueError::new_err(format!("COLUMNARLIST column {field} is missing"))
+                })?;
+                let n_items = values.len()?;
+                if let Some(expected_n_items) = n_items_option
+                    && n_items != expected_n_items
+                {
+                    Err(PyValueError::new_err(format!(
+                        "COLUMNARLIST column {field} has {n_items} items, but the previous columns have {expected_n_items}"
+                    )))?
+                }
+                n_items_option = Some(n_items);
+                column_values.push(values);
+            }
+            return Ok((n_items_option.unwrap_or(0), column_values));
+        }
+        let items = obj.cast::<PyList>()?;
+        let column_values = self
+            .columns
+            .iter()
+            .map(|column| {
+                let field = column.field.bind(py);
+                let values = items
+                    .iter()
+                    .map(|item| {
+                        if self.dataclass_serde.is_some() {
+                            item.getattr(field)
+                        } else {
+                            item.get_item(field)
+                        }
+                    })
+                    .collect::<PyResult<Vec<_>>>()?;
+                Ok(PyList::new(py, values)?.into_any())
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok((items.len(), column_values))
+    }
+
+    // Builds the list of items from the retrieved values of each column
+    fn get_items<'py>(
+        &self,
+        py: Python<'py>,
+        n_items: usize,
+        column_values: Vec<Bound<'py, PyList>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let items = (0..n_items)
+            .map(|idx| {
+                let kv_list = self
+                    .columns
+                    .iter()
+                    .zip(column_values.iter())
+                    .map(|(column, values)| {
+                        Ok((column.field.bind(py).clone(), values.get_item(idx)?))
+                    })
+                    .collect::<PyResult<Vec<_>>>()?;
+                match &self.dataclass_serde {
+                    Some(dataclass_serde) => dataclass_serde.construct(py, kv_list),
+                    None => Ok(kv_list.into_py_dict(py)?.into_any()),
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(PyList::new(py, items)?.into_any())
+    }
+}
+
+impl PyAnySerde for ColumnarListSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let (n_items, column_values) = self.get_column_values(obj)?;
+        let mut offset = append_usize(buf, offset, n_items);
+        for (column, values) in self.columns.iter_mut().zip(column_values.iter()) {
+            offset = column.serde.append_many(buf, offset, values)?;
+        }
+        Ok(offset)
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let (n_items, column_values) = self.get_column_values(obj)?;
+        append_usize_vec(v, n_items);
+        for (column, values) in self.columns.iter_mut().zip(column_values.iter()) {
+            column.serde.append_many_vec(v, start_addr, values)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let (n_items, mut offset) = retrieve_usize(buf, offset)?;
+        if self.as_columns {
+            let column_dict = PyDict::new(py);
+            for column in self.columns.iter_mut() {
+                let values = if column.stackable {
+                    let values;
+                    (values, offset) = column
+                        .serde
+                        .retrieve_many_stacked(py, buf, offset, n_items)?;
+                    values
+                } else {
+                    let values;
+                    (values, offset) = column.serde.retrieve_many(py, buf, offset, n_items)?;
+                    values.into_any()
+                };
+                column_dict.set_item(column.field.bind(py), values)?;
+            }
+            return Ok((column_dict.into_any(), offset));
+        }
+        let mut column_values = Vec::with_capacity(self.columns.len());
+        for column in self.columns.iter_mut() {
+            let values;
+            (values, offset) = column.serde.retrieve_many(py, buf, offset, n_items)?;
+            column_values.push(values);
+        }
+        Ok((self.get_items(py, n_items, column_values)?, offset))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let (n_items, column_values) = self.get_column_values(obj)?;
+        append_usize_stream(writer, n_items)?;
+        for (column, values) in self.columns.iter_mut().zip(column_values.iter()) {
+            column.serde.append_many_stream(writer, values)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let n_items = retrieve_usize_stream(reader)?;
+        if self.as_columns {
+            let column_dict = PyDict::new(py);
+            for column in self.columns.iter_mut() {
+                let values = if column.stackable {
+                    column
+                        .serde
+                        .retrieve_many_stacked_stream(py, reader, n_items)?
+                } else {
+                    column
+                        .serde
+                        .retrieve_many_stream(py, reader, n_items)?
+                        .into_any()
+                };
+                column_dict.set_item(column.field.bind(py), values)?;
+            }
+            return Ok(column_dict.into_any());
+        }
+        let mut column_values = Vec::with_capacity(self.columns.len());
+        for column in self.columns.iter_mut() {
+            column_values.push(column.serde.retrieve_many_stream(py, reader, n_items)?);
+        }
+        self.get_items(py, n_items, column_values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use numpy::{PyArrayDyn, PyUntypedArrayMethods};
+
+    use super::*;
+    use crate::FieldSerdeTypeMap;
+    use crate::common::NumpyDtype;
+    use crate::pyany_serde_impl::NumpySerdeConfig;
+
+    #[test]
+    fn test_as_columns_empty() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let items_serde_type = PyAnySerdeType::TYPEDDICT {
+                key_serde_type_dict: FieldSerdeTypeMap(vec![
+                    ("idx".to_string(), PyAnySerdeType::INT {}),
+                    (
+                        "obs".to_string(),
+                        PyAnySerdeType::NUMPY {
+                            dtype: NumpyDtype::FLOAT32,
+                            config: NumpySerdeConfig::DYNAMIC {
+                                preprocessor_fn: None,
+                                postprocessor_fn: None,
+                            },
+                        },
+                    ),
+                ]),
+                optional_keys: vec![],
+                extra: ExtraPolicy::IGNORE {},
+                alphabetical_field_order: false,
+                schema: SchemaPolicy::FIXED {},
+            };
+            let mut serde: Box<dyn PyAnySerde> = (&PyAnySerdeType::COLUMNARLIST {
+                items_serde_type: Py::new(py, items_serde_type)?,
+                as_columns: true,
+            })
+                .try_into()?;
+            let columns = PyDict::new(py);
+            columns.set_item("idx", PyList::empty(py))?;
+            columns.set_item("obs", PyList::empty(py))?;
+            let mut v = Vec::new();
+            serde.append_vec(&mut v, Some(0), &columns)?;
+
+            let (retrieved, offset) = serde.retrieve(py, &v, 0)?;
+            assert_eq!(offset, v.len());
+            let streamed = serde.retrieve_stream(py, &mut StreamReader::new(&mut &v[..]))?;
+            for retrieved in [retrieved, streamed] {
+                let retrieved = retrieved.cast_into::<PyDict>()?;
+                let obs = retrieved
+                    .get_item("obs")?
+                    .unwrap()
+                    .cast_into::<PyArrayDyn<f32>>()?;
+                assert_eq!(obs.shape(), &[0]);
+                assert_eq!(retrieved.get_item("idx")?.unwrap().len()?, 0);
+            }
+            Ok(())
+        })
+    }
+}
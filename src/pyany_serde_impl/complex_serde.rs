@@ -2,7 +2,10 @@ use pyo3::prelude::*;
 use pyo3::types::PyComplex;
 
 use crate::{
-    communication::{append_c_double, append_c_double_vec, retrieve_c_double},
+    communication::{
+        append_c_double, append_c_double_stream, append_c_double_vec, retrieve_c_double,
+        retrieve_c_double_stream, StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -45,4 +48,24 @@ impl PyAnySerde for ComplexSerde {
         (imag, offset) = retrieve_c_double(buf, offset)?;
         Ok((PyComplex::from_doubles(py, real, imag).into_any(), offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let complex = obj.cast::<PyComplex>()?;
+        append_c_double_stream(writer, complex.real())?;
+        append_c_double_stream(writer, complex.imag())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let real = retrieve_c_double_stream(reader)?;
+        let imag = retrieve_c_double_stream(reader)?;
+        Ok(PyComplex::from_doubles(py, real, imag).into_any())
+    }
 }
@@ -0,0 +1,273 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::asyncio::InvalidStateError;
+use pyo3::prelude::*;
+
+use crate::{
+    PyAnySerde,
+    common::{AlignedBuffer, CompressionCodec},
+    communication::{
+        StreamReader, StreamWriter, append_bool, append_bool_stream, append_bool_vec, append_bytes,
+        append_bytes_stream, append_bytes_vec, append_varint, append_varint_stream,
+        append_varint_vec, retrieve_bool, retrieve_bool_stream, retrieve_bytes,
+        retrieve_bytes_stream, retrieve_varint, retrieve_varint_stream,
+    },
+};
+
+pub const DEFAULT_MIN_COMPRESSED_SIZE: usize = 256;
+
+// The most bytes a single byte of an LZ4 block can decompress to
+const LZ4_MAX_EXPANSION: usize = 255;
+
+// The value is serialized into a scratch buffer (with numpy alignment relative to the start of
+// the buffer), which is compressed if it is at least min_size bytes. The serialized format is a
+// bool which is true if the data is compressed, the varint length of the uncompressed data (only
+// if it is compressed), and then the (possibly compressed) data as bytes. Data which doesn't get
+// smaller when compressed is stored uncompressed.
+#[derive(Clone)]
+pub struct CompressedSerde {
+    value_serde: Box<dyn PyAnySerde>,
+    codec: CompressionCodec,
+    level: Option<i32>,
+    min_size: usize,
+    scratch: Vec<u8>,
+}
+
+impl CompressedSerde {
+    pub fn new(
+        value_serde: Box<dyn PyAnySerde>,
+        codec: CompressionCodec,
+        level: Option<i32>,
+        min_size: usize,
+    ) -> PyResult<Self> {
+        match (codec, level) {
+            (CompressionCodec::LZ4, Some(_)) => Err(PyValueError::new_err(
+                "COMPRESSED level is not supported by the lz4 codec",
+            ))?,
+            (CompressionCodec::ZSTD, Some(level))
+                if !zstd::compression_level_range().contains(&level) =>
+            {
+                let range = zstd::compression_level_range();
+                Err(PyValueError::new_err(format!(
+                    "COMPRESSED level {level} is invalid for the zstd codec (expected {} to {})",
+                    range.start(),
+                    range.end()
+                )))?
+            }
+            _ => (),
+        }
+        Ok(CompressedSerde {
+            value_serde,
+            codec,
+            level,
+            min_size,
+            scratch: Vec::new(),
+        })
+    }
+
+    // Serializes obj into the scratch buffer, returning the compressed scratch buffer if it should
+    // be stored compressed
+    fn serialize<'py>(&mut self, obj: &Bound<'py, PyAny>) -> PyResult<Option<Vec<u8>>> {
+        self.scratch.clear();
+        self.value_serde
+            .append_vec(&mut self.scratch, Some(0), obj)?;
+        if self.scratch.len() < self.min_size {
+            return Ok(None);
+        }
+        let compressed = match self.codec {
+            CompressionCodec::LZ4 => lz4_flex::block::compress(&self.scratch),
+            // Level 0 is zstd's default level
+            CompressionCodec::ZSTD => zstd::bulk::compress(&self.scratch, self.level.unwrap_or(0))?,
+        };
+        Ok((compressed.len() < self.scratch.len()).then_some(compressed))
+    }
+
+    // The uncompressed length is read from the buffer, so it is checked against what the data can
+    // decompress to before anything is allocated for it
+    fn check_decompressed_len(&self, data: &[u8], len: usize) -> PyResult<()> {
+        let max_len = match self.codec {
+            CompressionCodec::LZ4 => data.len().saturating_mul(LZ4_MAX_EXPANSION),
+            // zstd frames record the length of their decompressed data
+            CompressionCodec::ZSTD => match zstd::zstd_safe::get_frame_content_size(data) {
+                Ok(Some(content_size)) => usize::try_from(content_size).unwrap_or(usize::MAX),
+                _ => 0,
+            },
+        };
+        if len > max_len {
+            Err(InvalidStateError::new_err(format!(
+                "COMPRESSED data of {} bytes cannot decompress to {len} bytes",
+                data.len()
+            )))?
+        }
+        Ok(())
+    }
+
+    fn decompress(&self, data: &[u8], buf: &mut [u8]) -> PyResult<()> {
+        let decompressed_len = match self.codec {
+            CompressionCodec::LZ4 => lz4_flex::block::decompress_into(data, buf)
+                .map_err(|err| InvalidStateError::new_err(err.to_string()))?,
+            CompressionCodec::ZSTD => zstd::bulk::decompress_to_buffer(data, buf)
+                .map_err(|err| InvalidStateError::new_err(err.to_string()))?,
+        };
+        if decompressed_len != buf.len() {
+            Err(InvalidStateError::new_err(format!(
+                "COMPRESSED data decompressed to {decompressed_len} bytes instead of {}",
+                buf.len()
+            )))?
+        }
+        Ok(())
+    }
+
+    // Retrieves the value from its (possibly compressed) data, where len is the uncompressed
+    // length read before compressed data
+    fn retrieve_value<'py>(
+        &mut self,
+        py: Python<'py>,
+        is_compressed: bool,
+        len: usize,
+        data: &[u8],
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let len = if is_compressed {
+            self.check_decompressed_len(data, len)?;
+            len
+        } else {
+            data.len()
+        };
+        let mut aligned = AlignedBuffer::new(len);
+        let value_buf = aligned.as_mut_slice();
+        if is_compressed {
+            self.decompress(data, value_buf)?;
+        } else {
+            value_buf.copy_from_slice(data);
+        }
+        let (obj, value_end) = self.value_serde.retrieve(py, value_buf, 0)?;
+        if value_end != len {
+            Err(InvalidStateError::new_err(format!(
+                "COMPRESSED data of {len} bytes contained {value_end} bytes of serialized data"
+            )))?
+        }
+        Ok(obj)
+    }
+}
+
+impl PyAnySerde for CompressedSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        mut offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let compressed_option = self.serialize(obj)?;
+        offset = append_bool(buf, offset, compressed_option.is_some());
+        Ok(match compressed_option {
+            Some(compressed) => {
+                offset = append_varint(buf, offset, self.scratch.len());
+                append_bytes(buf, offset, &compressed)
+            }
+            None => append_bytes(buf, offset, &self.scratch),
+        })
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        _start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let compressed_option = self.serialize(obj)?;
+        append_bool_vec(v, compressed_option.is_some());
+        match compressed_option {
+            Some(compressed) => {
+                append_varint_vec(v, self.scratch.len());
+                append_bytes_vec(v, &compressed);
+            }
+            None => append_bytes_vec(v, &self.scratch),
+        }
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let (is_compressed, offset) = retrieve_bool(buf, offset)?;
+        let (len, offset) = if is_compressed {
+            retrieve_varint(buf, offset)?
+        } else {
+            (0, offset)
+        };
+        let (data, offset) = retrieve_bytes(buf, offset)?;
+        Ok((self.retrieve_value(py, is_compressed, len, data)?, offset))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let compressed_option = self.serialize(obj)?;
+        append_bool_stream(writer, compressed_option.is_some())?;
+        match compressed_option {
+            Some(compressed) => {
+                append_varint_stream(writer, self.scratch.len())?;
+                append_bytes_stream(writer, &compressed)
+            }
+            None => append_bytes_stream(writer, &self.scratch),
+        }
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let is_compressed = retrieve_bool_stream(reader)?;
+        let len = if is_compressed {
+            retrieve_varint_stream(reader)?
+        } else {
+            0
+        };
+        let data = retrieve_bytes_stream(reader)?;
+        self.retrieve_value(py, is_compressed, len, &data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::PyList;
+
+    use super::*;
+    use crate::pyany_serde_impl::{IntSerde, ListSerde};
+
+    #[test]
+    fn test_corrupted_decompressed_len() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            for codec in [CompressionCodec::LZ4, CompressionCodec::ZSTD] {
+                let mut serde = CompressedSerde::new(
+                    Box::new(ListSerde {
+                        items_serde: Box::new(IntSerde {}),
+                    }),
+                    codec,
+                    None,
+                    0,
+                )?;
+                let obj = PyList::new(py, vec![7; 100])?;
+                let mut v = Vec::new();
+                serde.append_vec(&mut v, Some(0), &obj)?;
+                let (retrieved, _) = serde.retrieve(py, &v, 0)?;
+                assert!(retrieved.eq(&obj)?);
+                assert_eq!(v[0], 1, "the data should be stored compressed");
+                // The uncompressed length follows the flag byte
+                let mut corrupted = vec![1];
+                append_varint_vec(&mut corrupted, usize::MAX / 2);
+                let (_, data_start) = retrieve_varint(&v, 1)?;
+                corrupted.extend_from_slice(&v[data_start..]);
+                let err = serde.retrieve(py, &corrupted, 0).err().unwrap();
+                assert!(err.is_instance_of::<InvalidStateError>(py), "{err}");
+            }
+            Ok(())
+        })
+    }
+}
@@ -1,20 +1,28 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use enum_kinds::EnumKind;
-use pyo3::exceptions::asyncio::InvalidStateError;
 use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::asyncio::InvalidStateError;
 use pyo3::types::{PyDict, PyString, PyTuple, PyType};
-use pyo3::{prelude::*, PyTypeInfo};
+use pyo3::{PyTypeInfo, intern, prelude::*};
 use strum_macros::{Display, EnumIter};
 
-use crate::communication::{append_string_vec, retrieve_string, retrieve_usize};
 use crate::PyAnySerde;
+use crate::communication::{
+    StreamReader, StreamWriter, append_string_vec, retrieve_string, retrieve_usize,
+};
+
+use super::extra_policy::{Extras, InternalExtraPolicy, RetrievedExtras};
+use super::schema_policy::{InternalSchemaPolicy, SchemaPolicy, missing_field_err};
 
 #[derive(Clone)]
 pub struct DataclassSerde {
     class: Py<PyAny>,
     init_strategy: InternalInitStrategy,
     field_serde_kv_list: Vec<(Py<PyString>, Box<dyn PyAnySerde>)>,
+    fields: HashSet<String>,
+    extra_policy: InternalExtraPolicy,
+    schema_policy: InternalSchemaPolicy,
 }
 
 #[pyclass(from_py_object)]
@@ -53,6 +61,9 @@ impl PickleableInitStrategy {
                 bytes
             }
             InitStrategy::NONE {} => vec![2],
+            InitStrategy::AUTO {} => vec![3],
+            InitStrategy::BYPASS {} => vec![4],
+            InitStrategy::MODELCONSTRUCT {} => vec![5],
         }
     }
     pub fn __setstate__(&mut self, state: Vec<u8>) -> PyResult<()> {
@@ -73,6 +84,9 @@ impl PickleableInitStrategy {
                 InitStrategy::SOME { kwargs }
             }
             2 => InitStrategy::NONE {},
+            3 => InitStrategy::AUTO {},
+            4 => InitStrategy::BYPASS {},
+            5 => InitStrategy::MODELCONSTRUCT {},
             v => Err(InvalidStateError::new_err(format!(
                 "Got invalid type byte for InitStrategy: {v}"
             )))?,
@@ -88,6 +102,9 @@ pub enum InitStrategy {
     ALL {},
     SOME { kwargs: Vec<String> },
     NONE {},
+    AUTO {},
+    BYPASS {},
+    MODELCONSTRUCT {},
 }
 
 impl InitStrategyKind {
@@ -96,6 +113,9 @@ impl InitStrategyKind {
             InitStrategyKind::ALL => InitStrategy_ALL::type_object(py),
             InitStrategyKind::SOME => InitStrategy_SOME::type_object(py),
             InitStrategyKind::NONE => InitStrategy_NONE::type_object(py),
+            InitStrategyKind::AUTO => InitStrategy_AUTO::type_object(py),
+            InitStrategyKind::BYPASS => InitStrategy_BYPASS::type_object(py),
+            InitStrategyKind::MODELCONSTRUCT => InitStrategy_MODELCONSTRUCT::type_object(py),
         }
     }
     pub fn from_type_object<'py>(to: &Bound<'py, PyType>) -> PyResult<Option<InitStrategyKind>> {
@@ -112,6 +132,15 @@ impl InitStrategyKind {
         if to.eq(InitStrategy_NONE::type_object(py))? {
             return Ok(Some(InitStrategyKind::NONE));
         }
+        if to.eq(InitStrategy_AUTO::type_object(py))? {
+            return Ok(Some(InitStrategyKind::AUTO));
+        }
+        if to.eq(InitStrategy_BYPASS::type_object(py))? {
+            return Ok(Some(InitStrategyKind::BYPASS));
+        }
+        if to.eq(InitStrategy_MODELCONSTRUCT::type_object(py))? {
+            return Ok(Some(InitStrategyKind::MODELCONSTRUCT));
+        }
         Err(PyValueError::new_err(format!(
             "Unexpected value PyType {}",
             to.repr()?
@@ -122,8 +151,140 @@ impl InitStrategyKind {
 #[derive(Clone, Debug)]
 pub enum InternalInitStrategy {
     All(Py<PyDict>),
-    Some(Py<PyDict>, HashSet<usize>),
+    Some(Py<PyDict>, HashMap<usize, Py<PyString>>),
     None,
+    Bypass,
+    ModelConstruct(Py<PyDict>),
+}
+
+// The init field idxs are mapped to the name of the keyword argument used for the field
+fn get_some_init_strategy<'py>(
+    py: Python<'py>,
+    field_serde_kv_list: &[(Py<PyString>, Box<dyn PyAnySerde>)],
+    init_field_kwargs: HashMap<usize, Py<PyString>>,
+) -> PyResult<InternalInitStrategy> {
+    let kwargs_kv_list = field_serde_kv_list
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, _)| init_field_kwargs.get(&idx))
+        .map(|kwarg| (kwarg, None::<Py<PyAny>>))
+        .collect::<Vec<_>>();
+    let kwargs = PyDict::from_sequence(&kwargs_kv_list.into_pyobject(py)?)?.unbind();
+    Ok(InternalInitStrategy::Some(kwargs, init_field_kwargs))
+}
+
+struct ClassField {
+    name: String,
+    init: bool,
+    // The keyword argument used for the field in __init__, which differs from the name for
+    // private attrs attributes
+    kwarg: String,
+    has_default: bool,
+}
+
+fn get_dataclass_fields<'py>(class: &Bound<'py, PyAny>) -> PyResult<Vec<ClassField>> {
+    let dataclasses = class.py().import("dataclasses")?;
+    let missing = dataclasses.getattr("MISSING")?;
+    dataclasses
+        .call_method1("fields", (class,))?
+        .try_iter()?
+        .map(|class_field| {
+            let class_field = class_field?;
+            let name = class_field.getattr("name")?.extract::<String>()?;
+            Ok(ClassField {
+                init: class_field.getattr("init")?.extract::<bool>()?,
+                kwarg: name.clone(),
+                name,
+                has_default: !class_field.getattr("default")?.is(&missing)
+                    || !class_field.getattr("default_factory")?.is(&missing),
+            })
+        })
+        .collect()
+}
+
+fn get_attrs_fields<'py>(class: &Bound<'py, PyAny>) -> PyResult<Vec<ClassField>> {
+    let nothing = class.py().import("attr")?.getattr("NOTHING")?;
+    class
+        .getattr("__attrs_attrs__")?
+        .try_iter()?
+        .map(|attribute| {
+            let attribute = attribute?;
+            let name = attribute.getattr("name")?.extract::<String>()?;
+            // Older versions of attrs don't have aliases, but always strip leading underscores
+            let kwarg = match attribute.getattr("alias") {
+                Ok(alias) if !alias.is_none() => alias.extract::<String>()?,
+                _ => name.trim_start_matches('_').to_owned(),
+            };
+            Ok(ClassField {
+                name,
+                init: attribute.getattr("init")?.extract::<bool>()?,
+                kwarg,
+                has_default: !attribute.getattr("default")?.is(&nothing),
+            })
+        })
+        .collect()
+}
+
+// Uses dataclasses.fields (or the attrs attributes) to find which of the serde's fields are init
+// fields of the class. Also checks that every field of the serde is a field of the class, and that
+// the serde provides every init field of the class which doesn't have a default.
+fn get_auto_init_field_kwargs<'py>(
+    class: &Bound<'py, PyAny>,
+    field_serde_kv_list: &[(Py<PyString>, Box<dyn PyAnySerde>)],
+) -> PyResult<HashMap<usize, Py<PyString>>> {
+    let py = class.py();
+    let class_fields = if class.hasattr("__attrs_attrs__")? {
+        get_attrs_fields(class)?
+    } else if py
+        .import("dataclasses")?
+        .call_method1("is_dataclass", (class,))?
+        .extract::<bool>()?
+    {
+        get_dataclass_fields(class)?
+    } else {
+        Err(PyValueError::new_err(format!(
+            "InitStrategy_AUTO can only be used with dataclasses and attrs classes, but {} is neither",
+            class.repr()?
+        )))?
+    };
+    let serde_fields = field_serde_kv_list
+        .iter()
+        .map(|(field, _)| field.bind(py).to_str().map(|field| field.to_owned()))
+        .collect::<PyResult<Vec<_>>>()?;
+    let mut init_field_kwargs = HashMap::new();
+    for class_field in class_fields.iter() {
+        match serde_fields
+            .iter()
+            .position(|field| *field == class_field.name)
+        {
+            Some(idx) => {
+                if class_field.init {
+                    init_field_kwargs.insert(idx, PyString::new(py, &class_field.kwarg).unbind());
+                }
+            }
+            None => {
+                if class_field.init && !class_field.has_default {
+                    Err(PyValueError::new_err(format!(
+                        "field {} of {} is required by __init__ but is not contained in field_serde_kv_list",
+                        class_field.name,
+                        class.repr()?
+                    )))?
+                }
+            }
+        }
+    }
+    for field in serde_fields.iter() {
+        if !class_fields
+            .iter()
+            .any(|class_field| class_field.name == *field)
+        {
+            Err(PyValueError::new_err(format!(
+                "field name {field} contained in field_serde_kv_list is not a field of {}",
+                class.repr()?
+            )))?
+        }
+    }
+    Ok(init_field_kwargs)
 }
 
 impl DataclassSerde {
@@ -131,6 +292,8 @@ impl DataclassSerde {
         class: Py<PyAny>,
         init_strategy: InitStrategy,
         field_serde_kv_list: Vec<(Py<PyString>, Box<dyn PyAnySerde>)>,
+        extra_policy: InternalExtraPolicy,
+        schema_policy: &SchemaPolicy,
     ) -> PyResult<Self> {
         let internal_init_strategy = match &init_strategy {
             InitStrategy::ALL {} => Python::attach::<_, PyResult<_>>(|py| {
@@ -142,65 +305,131 @@ impl DataclassSerde {
                 Ok(InternalInitStrategy::All(kwargs))
             })?,
             InitStrategy::SOME { kwargs } => Python::attach::<_, PyResult<_>>(|py| {
-                let init_field_idxs = kwargs.iter().map(|init_field| field_serde_kv_list.iter().position(|(field, _)| field.to_string() == *init_field).ok_or_else(|| PyValueError::new_err(format!("field name {} provided in InitStrategy_SOME not contained in field_serde_kv_list", init_field)))).collect::<PyResult<HashSet<_>>>()?;
+                let mut init_field_kwargs = HashMap::new();
+                for init_field in kwargs.iter() {
+                    let idx = field_serde_kv_list
+                        .iter()
+                        .position(|(field, _)| field.to_string() == *init_field)
+                        .ok_or_else(|| {
+                            PyValueError::new_err(format!(
+                                "field name {init_field} provided in InitStrategy_SOME not contained in field_serde_kv_list"
+                            ))
+                        })?;
+                    init_field_kwargs.insert(idx, PyString::new(py, init_field).unbind());
+                }
+                get_some_init_strategy(py, &field_serde_kv_list, init_field_kwargs)
+            })?,
+            InitStrategy::NONE {} => InternalInitStrategy::None,
+            InitStrategy::AUTO {} => Python::attach::<_, PyResult<_>>(|py| {
+                let init_field_kwargs =
+                    get_auto_init_field_kwargs(class.bind(py), &field_serde_kv_list)?;
+                get_some_init_strategy(py, &field_serde_kv_list, init_field_kwargs)
+            })?,
+            InitStrategy::BYPASS {} => InternalInitStrategy::Bypass,
+            InitStrategy::MODELCONSTRUCT {} => Python::attach::<_, PyResult<_>>(|py| {
+                if !class.bind(py).hasattr(intern!(py, "model_construct"))? {
+                    Err(PyValueError::new_err(format!(
+                        "InitStrategy_MODELCONSTRUCT can only be used with pydantic models, but {} has no model_construct method",
+                        class.bind(py).repr()?
+                    )))?
+                }
                 let kwargs_kv_list = field_serde_kv_list
                     .iter()
-                    .enumerate()
-                    .filter(|(idx, _)| init_field_idxs.contains(idx))
-                    .map(|(_, (field, _))| (field, None::<Py<PyAny>>))
+                    .map(|(field, _)| (field, None::<Py<PyAny>>))
                     .collect::<Vec<_>>();
                 let kwargs = PyDict::from_sequence(&kwargs_kv_list.into_pyobject(py)?)?.unbind();
-                Ok(InternalInitStrategy::Some(kwargs, init_field_idxs))
+                Ok(InternalInitStrategy::ModelConstruct(kwargs))
             })?,
-            InitStrategy::NONE {} => InternalInitStrategy::None,
         };
+        let fields = field_serde_kv_list
+            .iter()
+            .map(|(field, _)| field.to_string())
+            .collect::<Vec<_>>();
+        let schema_policy = InternalSchemaPolicy::new(schema_policy, &fields, "DATACLASS")?;
         Ok(DataclassSerde {
             class,
             init_strategy: internal_init_strategy,
             field_serde_kv_list,
+            fields: fields.into_iter().collect(),
+            extra_policy,
+            schema_policy,
         })
     }
-}
 
-impl PyAnySerde for DataclassSerde {
-    fn append<'py>(
-        &mut self,
-        buf: &mut [u8],
-        mut offset: usize,
+    // Extras are the instance attributes (from the instance __dict__, if there is one) which are
+    // not fields of the serde
+    fn get_extras<'py>(&self, obj: &Bound<'py, PyAny>) -> PyResult<Extras<'py>> {
+        if self.extra_policy.is_ignore() {
+            return Ok(Vec::new());
+        }
+        let Ok(obj_dict) = obj.getattr("__dict__") else {
+            return Ok(Vec::new());
+        };
+        Ok(obj_dict
+            .cast_into::<PyDict>()?
+            .iter()
+            .filter(|(attr, _)| {
+                !attr
+                    .cast::<PyString>()
+                    .ok()
+                    .and_then(|attr| attr.to_str().ok().map(|attr| self.fields.contains(attr)))
+                    .unwrap_or(false)
+            })
+            .collect())
+    }
+
+    fn get_versioned_kv_list<'py>(
+        &self,
+        py: Python<'py>,
+        field_values: Vec<Option<Bound<'py, PyAny>>>,
+    ) -> PyResult<Vec<(Bound<'py, PyString>, Bound<'py, PyAny>)>> {
+        self.field_serde_kv_list
+            .iter()
+            .zip(field_values)
+            .map(|((field, _), field_value_option)| {
+                let field = field.bind(py);
+                let field_value = field_value_option
+                    .ok_or_else(|| missing_field_err(&field.to_string(), "DATACLASS"))?;
+                Ok((field.clone(), field_value))
+            })
+            .collect()
+    }
+
+    fn set_attr<'py>(
+        &self,
         obj: &Bound<'py, PyAny>,
-    ) -> PyResult<usize> {
-        for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
-            offset = pyany_serde.append(buf, offset, &obj.getattr(&*field)?)?;
+        attr: &Bound<'py, PyString>,
+        value: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        if let InternalInitStrategy::Bypass = self.init_strategy {
+            // Bypass skips __init__ and __setattr__ overrides, so that frozen dataclasses work
+            let py = obj.py();
+            PyAny::type_object(py).call_method1(intern!(py, "__setattr__"), (obj, attr, value))?;
+            Ok(())
+        } else {
+            obj.setattr(attr, value)
         }
-        Ok(offset)
     }
 
-    fn append_vec<'py>(
-        &mut self,
-        v: &mut Vec<u8>,
-        start_addr: Option<usize>,
+    fn set_extras<'py>(
+        &self,
         obj: &Bound<'py, PyAny>,
+        extras: RetrievedExtras<'py>,
     ) -> PyResult<()> {
-        for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
-            pyany_serde.append_vec(v, start_addr, &obj.getattr(&*field)?)?;
+        for (attr, value) in extras.into_iter() {
+            self.set_attr(obj, &attr, &value)?;
         }
         Ok(())
     }
 
-    fn retrieve<'py>(
-        &mut self,
+    // Creates an instance of the class from its field values using the init strategy
+    pub fn construct<'py>(
+        &self,
         py: Python<'py>,
-        buf: &[u8],
-        mut offset: usize,
-    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
-        let mut kv_list = Vec::with_capacity(self.field_serde_kv_list.len());
-        for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
-            let field_value;
-            (field_value, offset) = pyany_serde.retrieve(py, buf, offset)?;
-            kv_list.push((field.clone_ref(py).into_bound(py), field_value));
-        }
+        kv_list: Vec<(Bound<'py, PyString>, Bound<'py, PyAny>)>,
+    ) -> PyResult<Bound<'py, PyAny>> {
         let class = self.class.bind(py);
-        let obj = match &self.init_strategy {
+        Ok(match &self.init_strategy {
             InternalInitStrategy::All(py_kwargs) => {
                 let kwargs = py_kwargs.bind(py);
                 for (field, field_value) in kv_list.iter() {
@@ -208,14 +437,14 @@ impl PyAnySerde for DataclassSerde {
                 }
                 class.call((), Some(kwargs))?
             }
-            InternalInitStrategy::Some(py_kwargs, init_field_idxs) => {
+            InternalInitStrategy::Some(py_kwargs, init_field_kwargs) => {
                 let kwargs = py_kwargs.bind(py);
                 let (init_kv_list, other_kv_list) = kv_list
                     .into_iter()
                     .enumerate()
-                    .partition::<Vec<_>, _>(|(idx, _)| init_field_idxs.contains(idx));
-                for (_, (field, field_value)) in init_kv_list.iter() {
-                    kwargs.set_item(field, field_value)?;
+                    .partition::<Vec<_>, _>(|(idx, _)| init_field_kwargs.contains_key(idx));
+                for (idx, (_, field_value)) in init_kv_list.iter() {
+                    kwargs.set_item(init_field_kwargs[idx].bind(py), field_value)?;
                 }
                 let obj = class.call((), Some(kwargs))?;
                 for (_, (field, field_value)) in other_kv_list.iter() {
@@ -230,7 +459,165 @@ impl PyAnySerde for DataclassSerde {
                 }
                 obj
             }
-        };
+            InternalInitStrategy::ModelConstruct(py_kwargs) => {
+                let kwargs = py_kwargs.bind(py);
+                for (field, field_value) in kv_list.iter() {
+                    kwargs.set_item(field, field_value)?;
+                }
+                class.call_method(intern!(py, "model_construct"), (), Some(kwargs))?
+            }
+            InternalInitStrategy::Bypass => {
+                let obj = PyAny::type_object(py).call_method1(intern!(py, "__new__"), (class,))?;
+                for (field, field_value) in kv_list.iter() {
+                    self.set_attr(&obj, field, field_value)?;
+                }
+                obj
+            }
+        })
+    }
+}
+
+impl PyAnySerde for DataclassSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        mut offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        if self.schema_policy.is_fixed() {
+            for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
+                offset = pyany_serde.append(buf, offset, &obj.getattr(&*field)?)?;
+            }
+        } else {
+            offset = self
+                .schema_policy
+                .append_header(buf, offset, self.field_serde_kv_list.len());
+            for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
+                let field = field.bind(obj.py());
+                offset = self.schema_policy.append_field(
+                    buf,
+                    offset,
+                    field.to_str()?,
+                    pyany_serde,
+                    &obj.getattr(field)?,
+                )?;
+            }
+        }
+        let extras = self.get_extras(obj)?;
+        self.extra_policy.append(buf, offset, &extras, "DATACLASS")
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        if self.schema_policy.is_fixed() {
+            for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
+                pyany_serde.append_vec(v, start_addr, &obj.getattr(&*field)?)?;
+            }
+        } else {
+            self.schema_policy
+                .append_header_vec(v, self.field_serde_kv_list.len());
+            for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
+                let field = field.bind(obj.py());
+                self.schema_policy.append_field_vec(
+                    v,
+                    start_addr,
+                    field.to_str()?,
+                    pyany_serde,
+                    &obj.getattr(field)?,
+                )?;
+            }
+        }
+        let extras = self.get_extras(obj)?;
+        self.extra_policy
+            .append_vec(v, start_addr, &extras, "DATACLASS")
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        mut offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let mut kv_list = Vec::with_capacity(self.field_serde_kv_list.len());
+        if self.schema_policy.is_fixed() {
+            for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
+                let field_value;
+                (field_value, offset) = pyany_serde.retrieve(py, buf, offset)?;
+                kv_list.push((field.clone_ref(py).into_bound(py), field_value));
+            }
+        } else {
+            let field_values;
+            (field_values, offset) = self.schema_policy.retrieve_fields(
+                py,
+                buf,
+                offset,
+                &mut self.field_serde_kv_list,
+                "DATACLASS",
+            )?;
+            kv_list = self.get_versioned_kv_list(py, field_values)?;
+        }
+        let obj = self.construct(py, kv_list)?;
+        let extras;
+        (extras, offset) = self.extra_policy.retrieve(py, buf, offset)?;
+        self.set_extras(&obj, extras)?;
         Ok((obj, offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        if self.schema_policy.is_fixed() {
+            for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
+                pyany_serde.append_stream(writer, &obj.getattr(&*field)?)?;
+            }
+        } else {
+            self.schema_policy
+                .append_header_stream(writer, self.field_serde_kv_list.len())?;
+            for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
+                let field = field.bind(obj.py());
+                self.schema_policy.append_field_stream(
+                    writer,
+                    field.to_str()?,
+                    pyany_serde,
+                    &obj.getattr(field)?,
+                )?;
+            }
+        }
+        let extras = self.get_extras(obj)?;
+        self.extra_policy
+            .append_stream(writer, &extras, "DATACLASS")
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let kv_list = if self.schema_policy.is_fixed() {
+            let mut kv_list = Vec::with_capacity(self.field_serde_kv_list.len());
+            for (field, pyany_serde) in self.field_serde_kv_list.iter_mut() {
+                let field_value = pyany_serde.retrieve_stream(py, reader)?;
+                kv_list.push((field.clone_ref(py).into_bound(py), field_value));
+            }
+            kv_list
+        } else {
+            let field_values = self.schema_policy.retrieve_fields_stream(
+                py,
+                reader,
+                &mut self.field_serde_kv_list,
+                "DATACLASS",
+            )?;
+            self.get_versioned_kv_list(py, field_values)?
+        };
+        let obj = self.construct(py, kv_list)?;
+        let extras = self.extra_policy.retrieve_stream(py, reader)?;
+        self.set_extras(&obj, extras)?;
+        Ok(obj)
+    }
 }
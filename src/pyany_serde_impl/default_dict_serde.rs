@@ -0,0 +1,201 @@
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::{
+    PyAnySerde,
+    communication::{
+        StreamReader, StreamWriter, append_bool, append_bool_stream, append_bool_vec, append_bytes,
+        append_bytes_stream, append_string_vec, append_usize, append_usize_stream,
+        append_usize_vec, retrieve_bool, retrieve_bool_stream, retrieve_string,
+        retrieve_string_stream, retrieve_usize, retrieve_usize_stream,
+    },
+};
+
+// The default_factory of each defaultdict is serialized by reference, as a flag for whether it is
+// set followed by its module and qualname, and is imported again when the defaultdict is retrieved.
+#[derive(Clone)]
+pub struct DefaultDictSerde {
+    keys_serde: Box<dyn PyAnySerde>,
+    values_serde: Box<dyn PyAnySerde>,
+    default_dict_type: Py<PyAny>,
+}
+
+// Imports the object with the given qualname from the given module
+fn import_default_factory<'py>(
+    py: Python<'py>,
+    module: &str,
+    qualname: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut default_factory = py.import(module)?.into_any();
+    for name in qualname.split('.') {
+        default_factory = default_factory.getattr(name)?;
+    }
+    Ok(default_factory)
+}
+
+impl DefaultDictSerde {
+    pub fn new(
+        keys_serde: Box<dyn PyAnySerde>,
+        values_serde: Box<dyn PyAnySerde>,
+    ) -> PyResult<Self> {
+        Python::attach(|py| {
+            Ok(DefaultDictSerde {
+                keys_serde,
+                values_serde,
+                default_dict_type: py.import("collections")?.getattr("defaultdict")?.unbind(),
+            })
+        })
+    }
+
+    // Returns the module and qualname of the defaultdict's default_factory
+    fn get_default_factory<'py>(
+        &self,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<(String, String)>> {
+        let py = obj.py();
+        if !obj.is_instance(self.default_dict_type.bind(py))? {
+            Err(PyTypeError::new_err(format!(
+                "Expected a collections.defaultdict but received {}",
+                obj.get_type().name()?
+            )))?
+        }
+        let default_factory = obj.getattr("default_factory")?;
+        if default_factory.is_none() {
+            return Ok(None);
+        }
+        let not_importable_err = || {
+            PyValueError::new_err(format!(
+                "DEFAULTDICT default_factory {} is serialized by reference, so it must be importable by its module and qualname",
+                default_factory
+                    .repr()
+                    .map_or("<unknown>".to_owned(), |repr| repr.to_string())
+            ))
+        };
+        let (Ok(module), Ok(qualname)) = (
+            default_factory.getattr("__module__")?.extract::<String>(),
+            default_factory.getattr("__qualname__")?.extract::<String>(),
+        ) else {
+            Err(not_importable_err())?
+        };
+        match import_default_factory(py, &module, &qualname) {
+            Ok(imported) if imported.is(&default_factory) => Ok(Some((module, qualname))),
+            _ => Err(not_importable_err()),
+        }
+    }
+}
+
+impl PyAnySerde for DefaultDictSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let default_factory = self.get_default_factory(obj)?;
+        let dict = obj.cast::<PyDict>()?;
+        let mut offset = append_bool(buf, offset, default_factory.is_some());
+        if let Some((module, qualname)) = default_factory {
+            offset = append_bytes(buf, offset, module.as_bytes());
+            offset = append_bytes(buf, offset, qualname.as_bytes());
+        }
+        offset = append_usize(buf, offset, dict.len());
+        for (key, value) in dict.iter() {
+            offset = self.keys_serde.append(buf, offset, &key)?;
+            offset = self.values_serde.append(buf, offset, &value)?;
+        }
+        Ok(offset)
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let default_factory = self.get_default_factory(obj)?;
+        let dict = obj.cast::<PyDict>()?;
+        append_bool_vec(v, default_factory.is_some());
+        if let Some((module, qualname)) = default_factory {
+            append_string_vec(v, &module);
+            append_string_vec(v, &qualname);
+        }
+        append_usize_vec(v, dict.len());
+        for (key, value) in dict.iter() {
+            self.keys_serde.append_vec(v, start_addr, &key)?;
+            self.values_serde.append_vec(v, start_addr, &value)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let (has_default_factory, mut offset) = retrieve_bool(buf, offset)?;
+        let default_factory = if has_default_factory {
+            let module;
+            (module, offset) = retrieve_string(buf, offset)?;
+            let qualname;
+            (qualname, offset) = retrieve_string(buf, offset)?;
+            Some(import_default_factory(py, &module, &qualname)?)
+        } else {
+            None
+        };
+        let default_dict = self.default_dict_type.bind(py).call1((default_factory,))?;
+        let n_items;
+        (n_items, offset) = retrieve_usize(buf, offset)?;
+        for _ in 0..n_items {
+            let key;
+            (key, offset) = self.keys_serde.retrieve(py, buf, offset)?;
+            let value;
+            (value, offset) = self.values_serde.retrieve(py, buf, offset)?;
+            default_dict.set_item(key, value)?;
+        }
+        Ok((default_dict, offset))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let default_factory = self.get_default_factory(obj)?;
+        let dict = obj.cast::<PyDict>()?;
+        append_bool_stream(writer, default_factory.is_some())?;
+        if let Some((module, qualname)) = default_factory {
+            append_bytes_stream(writer, module.as_bytes())?;
+            append_bytes_stream(writer, qualname.as_bytes())?;
+        }
+        append_usize_stream(writer, dict.len())?;
+        for (key, value) in dict.iter() {
+            self.keys_serde.append_stream(writer, &key)?;
+            self.values_serde.append_stream(writer, &value)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let default_factory = if retrieve_bool_stream(reader)? {
+            let module = retrieve_string_stream(reader)?;
+            let qualname = retrieve_string_stream(reader)?;
+            Some(import_default_factory(py, &module, &qualname)?)
+        } else {
+            None
+        };
+        let default_dict = self.default_dict_type.bind(py).call1((default_factory,))?;
+        let n_items = retrieve_usize_stream(reader)?;
+        for _ in 0..n_items {
+            let key = self.keys_serde.retrieve_stream(py, reader)?;
+            let value = self.values_serde.retrieve_stream(py, reader)?;
+            default_dict.set_item(key, value)?;
+        }
+        Ok(default_dict)
+    }
+}
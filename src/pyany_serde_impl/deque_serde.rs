@@ -0,0 +1,137 @@
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::{
+    PyAnySerde,
+    communication::{
+        StreamReader, StreamWriter, append_bool, append_bool_stream, append_bool_vec, append_usize,
+        append_usize_stream, append_usize_vec, retrieve_bool, retrieve_bool_stream, retrieve_usize,
+        retrieve_usize_stream,
+    },
+};
+
+#[derive(Clone)]
+pub struct DequeSerde {
+    items_serde: Box<dyn PyAnySerde>,
+    deque_type: Py<PyAny>,
+}
+
+impl DequeSerde {
+    pub fn new(items_serde: Box<dyn PyAnySerde>) -> PyResult<Self> {
+        Python::attach(|py| {
+            Ok(DequeSerde {
+                items_serde,
+                deque_type: py.import("collections")?.getattr("deque")?.unbind(),
+            })
+        })
+    }
+
+    fn get_maxlen<'py>(&self, obj: &Bound<'py, PyAny>) -> PyResult<Option<usize>> {
+        if !obj.is_instance(self.deque_type.bind(obj.py()))? {
+            Err(PyTypeError::new_err(format!(
+                "Expected a collections.deque but received {}",
+                obj.get_type().name()?
+            )))?
+        }
+        obj.getattr("maxlen")?.extract::<Option<usize>>()
+    }
+}
+
+impl PyAnySerde for DequeSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let maxlen = self.get_maxlen(obj)?;
+        let mut offset = append_bool(buf, offset, maxlen.is_some());
+        if let Some(maxlen) = maxlen {
+            offset = append_usize(buf, offset, maxlen);
+        }
+        offset = append_usize(buf, offset, obj.len()?);
+        for item in obj.try_iter()? {
+            offset = self.items_serde.append(buf, offset, &item?)?;
+        }
+        Ok(offset)
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let maxlen = self.get_maxlen(obj)?;
+        append_bool_vec(v, maxlen.is_some());
+        if let Some(maxlen) = maxlen {
+            append_usize_vec(v, maxlen);
+        }
+        append_usize_vec(v, obj.len()?);
+        for item in obj.try_iter()? {
+            self.items_serde.append_vec(v, start_addr, &item?)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let (has_maxlen, mut offset) = retrieve_bool(buf, offset)?;
+        let maxlen = if has_maxlen {
+            let maxlen;
+            (maxlen, offset) = retrieve_usize(buf, offset)?;
+            Some(maxlen)
+        } else {
+            None
+        };
+        let list = PyList::empty(py);
+        let n_items;
+        (n_items, offset) = retrieve_usize(buf, offset)?;
+        for _ in 0..n_items {
+            let item;
+            (item, offset) = self.items_serde.retrieve(py, buf, offset)?;
+            list.append(item)?;
+        }
+        Ok((self.deque_type.bind(py).call1((list, maxlen))?, offset))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let maxlen = self.get_maxlen(obj)?;
+        append_bool_stream(writer, maxlen.is_some())?;
+        if let Some(maxlen) = maxlen {
+            append_usize_stream(writer, maxlen)?;
+        }
+        append_usize_stream(writer, obj.len()?)?;
+        for item in obj.try_iter()? {
+            self.items_serde.append_stream(writer, &item?)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let maxlen = if retrieve_bool_stream(reader)? {
+            Some(retrieve_usize_stream(reader)?)
+        } else {
+            None
+        };
+        let list = PyList::empty(py);
+        let n_items = retrieve_usize_stream(reader)?;
+        for _ in 0..n_items {
+            list.append(self.items_serde.retrieve_stream(py, reader)?)?;
+        }
+        self.deque_type.bind(py).call1((list, maxlen))
+    }
+}
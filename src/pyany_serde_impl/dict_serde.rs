@@ -2,7 +2,10 @@ use pyo3::prelude::*;
 use pyo3::types::PyDict;
 
 use crate::{
-    communication::{append_usize, append_usize_vec, retrieve_usize},
+    communication::{
+        append_usize, append_usize_stream, append_usize_vec, retrieve_usize, retrieve_usize_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -60,4 +63,33 @@ impl PyAnySerde for DictSerde {
         }
         Ok((dict.into_any(), offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let dict = obj.cast::<PyDict>()?;
+        append_usize_stream(writer, dict.len())?;
+        for (key, value) in dict.iter() {
+            self.keys_serde.append_stream(writer, &key)?;
+            self.values_serde.append_stream(writer, &value)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let dict = PyDict::new(py);
+        let n_items = retrieve_usize_stream(reader)?;
+        for _ in 0..n_items {
+            let key = self.keys_serde.retrieve_stream(py, reader)?;
+            let value = self.values_serde.retrieve_stream(py, reader)?;
+            dict.set_item(key, value)?;
+        }
+        Ok(dict.into_any())
+    }
 }
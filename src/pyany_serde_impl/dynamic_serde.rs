@@ -7,7 +7,10 @@ use crate::{
     common::{
         detect_python_type, get_python_type_byte, retrieve_python_type, NumpyDtype, PythonType,
     },
-    communication::{append_usize, retrieve_usize},
+    communication::{
+        append_u8_stream, append_usize, append_usize_stream, retrieve_u8_stream, retrieve_usize,
+        retrieve_usize_stream, StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -385,4 +388,163 @@ impl PyAnySerde for DynamicSerde {
         };
         Ok((obj, offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let python_type = detect_python_type(obj)?;
+        append_u8_stream(writer, get_python_type_byte(&python_type))?;
+        match python_type {
+            PythonType::BOOL => {
+                self.boolean_serde.append_stream(writer, obj)?;
+            }
+            PythonType::INT => {
+                self.int_serde.append_stream(writer, obj)?;
+            }
+            PythonType::FLOAT => {
+                self.float_serde.append_stream(writer, obj)?;
+            }
+            PythonType::COMPLEX => {
+                self.complex_serde.append_stream(writer, obj)?;
+            }
+            PythonType::STRING => {
+                self.string_serde.append_stream(writer, obj)?;
+            }
+            PythonType::BYTES => {
+                self.bytes_serde.append_stream(writer, obj)?;
+            }
+            PythonType::NUMPY { dtype } => match dtype {
+                NumpyDtype::INT8 => {
+                    self.numpy_i8_serde.append_stream(writer, obj)?;
+                }
+                NumpyDtype::INT16 => {
+                    self.numpy_i16_serde.append_stream(writer, obj)?;
+                }
+                NumpyDtype::INT32 => {
+                    self.numpy_i32_serde.append_stream(writer, obj)?;
+                }
+                NumpyDtype::INT64 => {
+                    self.numpy_i64_serde.append_stream(writer, obj)?;
+                }
+                NumpyDtype::UINT8 => {
+                    self.numpy_u8_serde.append_stream(writer, obj)?;
+                }
+                NumpyDtype::UINT16 => {
+                    self.numpy_u16_serde.append_stream(writer, obj)?;
+                }
+                NumpyDtype::UINT32 => {
+                    self.numpy_u32_serde.append_stream(writer, obj)?;
+                }
+                NumpyDtype::UINT64 => {
+                    self.numpy_u64_serde.append_stream(writer, obj)?;
+                }
+                NumpyDtype::FLOAT32 => {
+                    self.numpy_f32_serde.append_stream(writer, obj)?;
+                }
+                NumpyDtype::FLOAT64 => {
+                    self.numpy_f64_serde.append_stream(writer, obj)?;
+                }
+            },
+            PythonType::LIST => {
+                let list = obj.cast::<PyList>()?;
+                append_usize_stream(writer, list.len())?;
+                for item in list.iter() {
+                    self.append_stream(writer, &item)?;
+                }
+            }
+            PythonType::SET => {
+                let set = obj.cast::<PySet>()?;
+                append_usize_stream(writer, set.len())?;
+                for item in set.iter() {
+                    self.append_stream(writer, &item)?;
+                }
+            }
+            PythonType::TUPLE => {
+                let tuple = obj.cast::<PyTuple>()?;
+                append_usize_stream(writer, tuple.len())?;
+                for item in tuple.iter() {
+                    self.append_stream(writer, &item)?;
+                }
+            }
+            PythonType::DICT => {
+                let dict = obj.cast::<PyDict>()?;
+                append_usize_stream(writer, dict.len())?;
+                for (key, value) in dict.iter() {
+                    self.append_stream(writer, &key)?;
+                    self.append_stream(writer, &value)?;
+                }
+            }
+            PythonType::OTHER => {
+                self.pickle_serde.append_stream(writer, obj)?;
+            }
+        };
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let (python_type, _) = retrieve_python_type(&[retrieve_u8_stream(reader)?], 0)?;
+        let obj = match python_type {
+            PythonType::BOOL => self.boolean_serde.retrieve_stream(py, reader)?,
+            PythonType::INT => self.int_serde.retrieve_stream(py, reader)?,
+            PythonType::FLOAT => self.float_serde.retrieve_stream(py, reader)?,
+            PythonType::COMPLEX => self.complex_serde.retrieve_stream(py, reader)?,
+            PythonType::STRING => self.string_serde.retrieve_stream(py, reader)?,
+            PythonType::BYTES => self.bytes_serde.retrieve_stream(py, reader)?,
+            PythonType::NUMPY { dtype } => match dtype {
+                NumpyDtype::INT8 => self.numpy_i8_serde.retrieve_stream(py, reader)?,
+                NumpyDtype::INT16 => self.numpy_i16_serde.retrieve_stream(py, reader)?,
+                NumpyDtype::INT32 => self.numpy_i32_serde.retrieve_stream(py, reader)?,
+                NumpyDtype::INT64 => self.numpy_i64_serde.retrieve_stream(py, reader)?,
+                NumpyDtype::UINT8 => self.numpy_u8_serde.retrieve_stream(py, reader)?,
+                NumpyDtype::UINT16 => self.numpy_u16_serde.retrieve_stream(py, reader)?,
+                NumpyDtype::UINT32 => self.numpy_u32_serde.retrieve_stream(py, reader)?,
+                NumpyDtype::UINT64 => self.numpy_u64_serde.retrieve_stream(py, reader)?,
+                NumpyDtype::FLOAT32 => self.numpy_f32_serde.retrieve_stream(py, reader)?,
+                NumpyDtype::FLOAT64 => self.numpy_f64_serde.retrieve_stream(py, reader)?,
+            },
+            PythonType::LIST => {
+                let list = PyList::empty(py);
+                let n_items = retrieve_usize_stream(reader)?;
+                for _ in 0..n_items {
+                    list.append(self.retrieve_stream(py, reader)?)?;
+                }
+                list.into_any()
+            }
+            PythonType::SET => {
+                let set = PySet::empty(py)?;
+                let n_items = retrieve_usize_stream(reader)?;
+                for _ in 0..n_items {
+                    set.add(self.retrieve_stream(py, reader)?)?;
+                }
+                set.into_any()
+            }
+            PythonType::TUPLE => {
+                let n_items = retrieve_usize_stream(reader)?;
+                // The number of items comes from the stream, so the items vec grows as they are read
+                let mut tuple_vec = Vec::new();
+                for _ in 0..n_items {
+                    tuple_vec.push(self.retrieve_stream(py, reader)?);
+                }
+                PyTuple::new(py, tuple_vec)?.into_any()
+            }
+            PythonType::DICT => {
+                let dict = PyDict::new(py);
+                let n_items = retrieve_usize_stream(reader)?;
+                for _ in 0..n_items {
+                    let key = self.retrieve_stream(py, reader)?;
+                    let value = self.retrieve_stream(py, reader)?;
+                    dict.set_item(key, value)?;
+                }
+                dict.into_any()
+            }
+            PythonType::OTHER => self.pickle_serde.retrieve_stream(py, reader)?,
+        };
+        Ok(obj)
+    }
 }
@@ -0,0 +1,127 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::asyncio::InvalidStateError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+
+use crate::{
+    PyAnySerde,
+    communication::{
+        StreamReader, StreamWriter, append_discriminant, append_discriminant_stream,
+        append_discriminant_vec, retrieve_discriminant, retrieve_discriminant_stream,
+    },
+};
+
+// Values are looked up by their type along with the value, since equal values of different types
+// (such as 1, 1.0, and True) have the same hash and would otherwise be the same key
+#[derive(Clone)]
+pub struct EnumSerde {
+    values: Vec<Py<PyAny>>,
+    value_idx_dict: Py<PyDict>,
+}
+
+fn get_value_key<'py>(value: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyTuple>> {
+    PyTuple::new(value.py(), [value.get_type().into_any(), value.clone()])
+}
+
+impl EnumSerde {
+    pub fn new(values: Vec<Py<PyAny>>) -> PyResult<Self> {
+        Python::attach(|py| {
+            let value_idx_dict = PyDict::new(py);
+            for (idx, value) in values.iter().enumerate() {
+                let value_key = get_value_key(value.bind(py))?;
+                if value_idx_dict.contains(&value_key)? {
+                    Err(PyValueError::new_err(format!(
+                        "ENUM values must be unique, but {} was provided more than once",
+                        value.bind(py).repr()?
+                    )))?
+                }
+                value_idx_dict.set_item(value_key, idx)?;
+            }
+            Ok(EnumSerde {
+                values,
+                value_idx_dict: value_idx_dict.unbind(),
+            })
+        })
+    }
+
+    fn get_value_idx<'py>(&self, obj: &Bound<'py, PyAny>) -> PyResult<usize> {
+        self.value_idx_dict
+            .bind(obj.py())
+            .get_item(get_value_key(obj)?)?
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "{} is not one of the values of this ENUM serde",
+                    obj.repr()
+                        .map(|repr| repr.to_string())
+                        .unwrap_or("<unrepresentable object>".to_owned())
+                ))
+            })?
+            .extract::<usize>()
+    }
+
+    fn get_value<'py>(&self, py: Python<'py>, value_idx: usize) -> PyResult<Bound<'py, PyAny>> {
+        let n_values = self.values.len();
+        let value = self.values.get(value_idx).ok_or_else(|| {
+            InvalidStateError::new_err(format!(
+                "Deserialized ENUM value idx {value_idx} which is not a valid value index (there are {n_values} values)"
+            ))
+        })?;
+        Ok(value.bind(py).clone())
+    }
+}
+
+impl PyAnySerde for EnumSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let value_idx = self.get_value_idx(obj)?;
+        Ok(append_discriminant(
+            buf,
+            offset,
+            value_idx,
+            self.values.len(),
+        ))
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        _start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let value_idx = self.get_value_idx(obj)?;
+        append_discriminant_vec(v, value_idx, self.values.len());
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let (value_idx, offset) = retrieve_discriminant(buf, offset, self.values.len())?;
+        Ok((self.get_value(py, value_idx)?, offset))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let value_idx = self.get_value_idx(obj)?;
+        append_discriminant_stream(writer, value_idx, self.values.len())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let value_idx = retrieve_discriminant_stream(reader, self.values.len())?;
+        self.get_value(py, value_idx)
+    }
+}
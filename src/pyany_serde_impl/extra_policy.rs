@@ -0,0 +1,213 @@
+use enum_kinds::EnumKind;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::{PyString, PyType};
+use pyo3::{PyTypeInfo, prelude::*};
+use strum_macros::{Display, EnumIter};
+
+use crate::{
+    PyAnySerde, PyAnySerdeType,
+    communication::{
+        StreamReader, StreamWriter, append_bytes, append_bytes_stream, append_bytes_vec,
+        append_usize, append_usize_stream, append_usize_vec, retrieve_string,
+        retrieve_string_stream, retrieve_usize, retrieve_usize_stream,
+    },
+};
+
+#[pyclass(from_py_object)]
+#[derive(Clone, Debug, Display, EnumKind)]
+#[enum_kind(ExtraPolicyKind, derive(Display, EnumIter))]
+pub enum ExtraPolicy {
+    IGNORE {},
+    FORBID {},
+    PASSTHROUGH {
+        fallback_serde_type: Py<PyAnySerdeType>,
+    },
+}
+
+impl ExtraPolicyKind {
+    pub fn type_object<'py>(self, py: Python<'py>) -> Bound<'py, PyType> {
+        match self {
+            ExtraPolicyKind::IGNORE => ExtraPolicy_IGNORE::type_object(py),
+            ExtraPolicyKind::FORBID => ExtraPolicy_FORBID::type_object(py),
+            ExtraPolicyKind::PASSTHROUGH => ExtraPolicy_PASSTHROUGH::type_object(py),
+        }
+    }
+    pub fn from_type_object<'py>(to: &Bound<'py, PyType>) -> PyResult<Option<ExtraPolicyKind>> {
+        let py = to.py();
+        if to.eq(ExtraPolicy::type_object(py))? {
+            return Ok(None);
+        }
+        if to.eq(ExtraPolicy_IGNORE::type_object(py))? {
+            return Ok(Some(ExtraPolicyKind::IGNORE));
+        }
+        if to.eq(ExtraPolicy_FORBID::type_object(py))? {
+            return Ok(Some(ExtraPolicyKind::FORBID));
+        }
+        if to.eq(ExtraPolicy_PASSTHROUGH::type_object(py))? {
+            return Ok(Some(ExtraPolicyKind::PASSTHROUGH));
+        }
+        Err(PyValueError::new_err(format!(
+            "Unexpected value PyType {}",
+            to.repr()?
+        )))
+    }
+}
+
+pub type Extras<'py> = Vec<(Bound<'py, PyAny>, Bound<'py, PyAny>)>;
+pub type RetrievedExtras<'py> = Vec<(Bound<'py, PyString>, Bound<'py, PyAny>)>;
+
+// The serde-side counterpart of ExtraPolicy, shared by the TYPEDDICT and DATACLASS serdes. The
+// ignore policy writes nothing, so serdes using it are wire compatible with those from before
+// extra policies existed. The passthrough policy writes the number of extras followed by each
+// (key, value) pair, with the value written using the fallback serde.
+#[derive(Clone)]
+pub enum InternalExtraPolicy {
+    Ignore,
+    Forbid,
+    Passthrough(Box<dyn PyAnySerde>),
+}
+
+impl InternalExtraPolicy {
+    pub fn new(extra_policy: &ExtraPolicy) -> PyResult<Self> {
+        Ok(match extra_policy {
+            ExtraPolicy::IGNORE {} => InternalExtraPolicy::Ignore,
+            ExtraPolicy::FORBID {} => InternalExtraPolicy::Forbid,
+            ExtraPolicy::PASSTHROUGH {
+                fallback_serde_type,
+            } => InternalExtraPolicy::Passthrough(fallback_serde_type.try_into()?),
+        })
+    }
+
+    pub fn is_ignore(&self) -> bool {
+        matches!(self, InternalExtraPolicy::Ignore)
+    }
+
+    // Checks the extras against the policy, returning the keys as strings for passthrough.
+    fn validate_extras<'py>(
+        &self,
+        extras: &[(Bound<'py, PyAny>, Bound<'py, PyAny>)],
+        container: &str,
+    ) -> PyResult<Vec<String>> {
+        match self {
+            InternalExtraPolicy::Ignore => Ok(Vec::new()),
+            InternalExtraPolicy::Forbid => {
+                if let Some((key, _)) = extras.first() {
+                    Err(PyValueError::new_err(format!(
+                        "Found unexpected key {} in {container} with extra policy FORBID",
+                        key.repr()?
+                    )))?
+                }
+                Ok(Vec::new())
+            }
+            InternalExtraPolicy::Passthrough(_) => extras
+                .iter()
+                .map(|(key, _)| {
+                    key.cast::<PyString>()
+                        .map_err(|_| {
+                            PyValueError::new_err(format!(
+                                "Found extra key {} in {container} which is not a string, but extra policy PASSTHROUGH only supports string keys",
+                                key.repr()
+                                    .map(|repr| repr.to_string())
+                                    .unwrap_or("<unrepresentable object>".to_owned())
+                            ))
+                        })?
+                        .to_str()
+                        .map(|key| key.to_owned())
+                })
+                .collect(),
+        }
+    }
+
+    pub fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        mut offset: usize,
+        extras: &[(Bound<'py, PyAny>, Bound<'py, PyAny>)],
+        container: &str,
+    ) -> PyResult<usize> {
+        let keys = self.validate_extras(extras, container)?;
+        if let InternalExtraPolicy::Passthrough(fallback_serde) = self {
+            offset = append_usize(buf, offset, keys.len());
+            for (key, (_, value)) in keys.iter().zip(extras.iter()) {
+                offset = append_bytes(buf, offset, key.as_bytes());
+                offset = fallback_serde.append(buf, offset, value)?;
+            }
+        }
+        Ok(offset)
+    }
+
+    pub fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        extras: &[(Bound<'py, PyAny>, Bound<'py, PyAny>)],
+        container: &str,
+    ) -> PyResult<()> {
+        let keys = self.validate_extras(extras, container)?;
+        if let InternalExtraPolicy::Passthrough(fallback_serde) = self {
+            append_usize_vec(v, keys.len());
+            for (key, (_, value)) in keys.iter().zip(extras.iter()) {
+                append_bytes_vec(v, key.as_bytes());
+                fallback_serde.append_vec(v, start_addr, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        mut offset: usize,
+    ) -> PyResult<(RetrievedExtras<'py>, usize)> {
+        let InternalExtraPolicy::Passthrough(fallback_serde) = self else {
+            return Ok((Vec::new(), offset));
+        };
+        let n_extras;
+        (n_extras, offset) = retrieve_usize(buf, offset)?;
+        let mut extras = Vec::with_capacity(n_extras);
+        for _ in 0..n_extras {
+            let key;
+            (key, offset) = retrieve_string(buf, offset)?;
+            let value;
+            (value, offset) = fallback_serde.retrieve(py, buf, offset)?;
+            extras.push((PyString::new(py, &key), value));
+        }
+        Ok((extras, offset))
+    }
+
+    pub fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        extras: &[(Bound<'py, PyAny>, Bound<'py, PyAny>)],
+        container: &str,
+    ) -> PyResult<()> {
+        let keys = self.validate_extras(extras, container)?;
+        if let InternalExtraPolicy::Passthrough(fallback_serde) = self {
+            append_usize_stream(writer, keys.len())?;
+            for (key, (_, value)) in keys.iter().zip(extras.iter()) {
+                append_bytes_stream(writer, key.as_bytes())?;
+                fallback_serde.append_stream(writer, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<RetrievedExtras<'py>> {
+        let InternalExtraPolicy::Passthrough(fallback_serde) = self else {
+            return Ok(Vec::new());
+        };
+        let n_extras = retrieve_usize_stream(reader)?;
+        let mut extras = Vec::new();
+        for _ in 0..n_extras {
+            let key = retrieve_string_stream(reader)?;
+            let value = fallback_serde.retrieve_stream(py, reader)?;
+            extras.push((PyString::new(py, &key), value));
+        }
+        Ok(extras)
+    }
+}
@@ -1,7 +1,11 @@
 use pyo3::prelude::*;
 
+use crate::impl_packed_many;
 use crate::{
-    communication::{append_f64, append_f64_vec, retrieve_f64},
+    communication::{
+        append_f64, append_f64_stream, append_f64_vec, retrieve_f64, retrieve_f64_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -37,4 +41,23 @@ impl PyAnySerde for FloatSerde {
         let (val, offset) = retrieve_f64(buf, offset)?;
         Ok((val.into_pyobject(py)?.into_any(), offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        append_f64_stream(writer, obj.extract::<f64>()?)
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let val = retrieve_f64_stream(reader)?;
+        Ok(val.into_pyobject(py)?.into_any())
+    }
+
+    impl_packed_many!(f64);
 }
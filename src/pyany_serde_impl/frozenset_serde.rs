@@ -0,0 +1,88 @@
+use pyo3::prelude::*;
+use pyo3::types::PyFrozenSet;
+
+use crate::{
+    PyAnySerde,
+    communication::{
+        StreamReader, StreamWriter, append_usize, append_usize_stream, append_usize_vec,
+        retrieve_usize, retrieve_usize_stream,
+    },
+};
+
+#[derive(Clone)]
+pub struct FrozenSetSerde {
+    pub items_serde: Box<dyn PyAnySerde>,
+}
+
+impl PyAnySerde for FrozenSetSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let frozenset = obj.cast::<PyFrozenSet>()?;
+        let mut offset = append_usize(buf, offset, frozenset.len());
+        for item in frozenset.iter() {
+            offset = self.items_serde.append(buf, offset, &item)?;
+        }
+        Ok(offset)
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let frozenset = obj.cast::<PyFrozenSet>()?;
+        append_usize_vec(v, frozenset.len());
+        for item in frozenset.iter() {
+            self.items_serde.append_vec(v, start_addr, &item)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let (n_items, mut offset) = retrieve_usize(buf, offset)?;
+        let mut items = Vec::with_capacity(n_items);
+        for _ in 0..n_items {
+            let item;
+            (item, offset) = self.items_serde.retrieve(py, buf, offset)?;
+            items.push(item);
+        }
+        Ok((PyFrozenSet::new(py, items)?.into_any(), offset))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let frozenset = obj.cast::<PyFrozenSet>()?;
+        append_usize_stream(writer, frozenset.len())?;
+        for item in frozenset.iter() {
+            self.items_serde.append_stream(writer, &item)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        // The number of items comes from the stream, so the items vec grows as they are read
+        let n_items = retrieve_usize_stream(reader)?;
+        let mut items = Vec::new();
+        for _ in 0..n_items {
+            items.push(self.items_serde.retrieve_stream(py, reader)?);
+        }
+        Ok(PyFrozenSet::new(py, items)?.into_any())
+    }
+}
@@ -1,7 +1,11 @@
 use pyo3::prelude::*;
 
+use crate::impl_packed_many;
 use crate::{
-    communication::{append_i64, append_i64_vec, retrieve_i64},
+    communication::{
+        append_i64, append_i64_stream, append_i64_vec, retrieve_i64, retrieve_i64_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -37,4 +41,23 @@ impl PyAnySerde for IntSerde {
         let (val, offset) = retrieve_i64(buf, offset)?;
         Ok((val.into_pyobject(py)?.to_owned().into_any(), offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        append_i64_stream(writer, obj.extract::<i64>()?)
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let val = retrieve_i64_stream(reader)?;
+        Ok(val.into_pyobject(py)?.to_owned().into_any())
+    }
+
+    impl_packed_many!(i64);
 }
@@ -2,7 +2,10 @@ use pyo3::prelude::*;
 use pyo3::types::PyList;
 
 use crate::{
-    communication::{append_usize, append_usize_vec, retrieve_usize},
+    communication::{
+        append_usize, append_usize_stream, append_usize_vec, retrieve_usize, retrieve_usize_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -55,4 +58,30 @@ impl PyAnySerde for ListSerde {
         }
         Ok((list.into_any(), offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let list = obj.cast::<PyList>()?;
+        append_usize_stream(writer, list.len())?;
+        for item in list.iter() {
+            self.items_serde.append_stream(writer, &item)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let list = PyList::empty(py);
+        let n_items = retrieve_usize_stream(reader)?;
+        for _ in 0..n_items {
+            list.append(self.items_serde.retrieve_stream(py, reader)?)?;
+        }
+        Ok(list.into_any())
+    }
 }
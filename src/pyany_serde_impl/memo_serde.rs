@@ -0,0 +1,204 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::asyncio::InvalidStateError;
+use pyo3::prelude::*;
+
+use crate::{
+    PyAnySerde,
+    communication::{
+        StreamReader, StreamWriter, append_varint, append_varint_stream, append_varint_vec,
+        retrieve_varint, retrieve_varint_stream,
+    },
+};
+
+#[derive(Default)]
+struct MemoTable {
+    // Maps the address of each appended object to its memo index, or None while the object is
+    // still being appended. The objects are kept alive so that their addresses can't be reused by
+    // other objects while the table exists.
+    appended: HashMap<usize, (Py<PyAny>, Option<usize>)>,
+    n_appended: usize,
+    retrieved: Vec<Py<PyAny>>,
+}
+
+thread_local! {
+    static MEMO_TABLE: RefCell<Option<MemoTable>> = const { RefCell::new(None) };
+}
+
+// The outermost MEMO serde in an append or retrieve creates the memo table, and all MEMO serdes
+// nested inside it share the table. The table is dropped again (even if the append or retrieve
+// fails) when the outermost MEMO serde is done.
+struct MemoScope {
+    owner: bool,
+}
+
+impl MemoScope {
+    fn enter() -> Self {
+        MEMO_TABLE.with_borrow_mut(|table_option| {
+            let owner = table_option.is_none();
+            if owner {
+                *table_option = Some(MemoTable::default());
+            }
+            MemoScope { owner }
+        })
+    }
+
+    fn with_table<R>(&self, f: impl FnOnce(&mut MemoTable) -> R) -> R {
+        MEMO_TABLE.with_borrow_mut(|table_option| f(table_option.as_mut().unwrap()))
+    }
+}
+
+impl Drop for MemoScope {
+    fn drop(&mut self) {
+        if self.owner {
+            MEMO_TABLE.with_borrow_mut(|table_option| *table_option = None);
+        }
+    }
+}
+
+// Each value is preceded by a varint tag, which is 0 if the value follows and otherwise is one
+// more than the memo index of an object which was already serialized in the same memo scope.
+// Objects are added to the memo once they are completely serialized (or retrieved), so that the
+// memo indices agree between append and retrieve.
+#[derive(Clone)]
+pub struct MemoSerde {
+    pub value_serde: Box<dyn PyAnySerde>,
+}
+
+impl MemoSerde {
+    // Returns the tag to write for obj, marking obj as being appended if it is new
+    fn get_append_tag(&self, scope: &MemoScope, obj: &Bound<'_, PyAny>) -> PyResult<usize> {
+        scope.with_table(|table| {
+            let key = obj.as_ptr() as usize;
+            match table.appended.get(&key) {
+                Some((_, Some(idx))) => Ok(idx + 1),
+                Some((_, None)) => Err(PyValueError::new_err(format!(
+                    "MEMO found a cyclic reference to {}, which is not supported",
+                    obj.repr()?
+                ))),
+                None => {
+                    table.appended.insert(key, (obj.clone().unbind(), None));
+                    Ok(0)
+                }
+            }
+        })
+    }
+
+    fn finish_append(&self, scope: &MemoScope, obj: &Bound<'_, PyAny>) {
+        scope.with_table(|table| {
+            let idx = table.n_appended;
+            table.n_appended += 1;
+            if let Some((_, idx_option)) = table.appended.get_mut(&(obj.as_ptr() as usize)) {
+                *idx_option = Some(idx);
+            }
+        })
+    }
+
+    fn finish_retrieve(&self, scope: &MemoScope, obj: &Bound<'_, PyAny>) {
+        scope.with_table(|table| table.retrieved.push(obj.clone().unbind()));
+    }
+
+    // Returns the already retrieved object which the (nonzero) tag refers to
+    fn get_retrieved<'py>(
+        &self,
+        py: Python<'py>,
+        scope: &MemoScope,
+        tag: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        scope.with_table(|table| {
+            table
+                .retrieved
+                .get(tag - 1)
+                .map(|obj| obj.bind(py).clone())
+                .ok_or_else(|| {
+                    InvalidStateError::new_err(format!(
+                        "MEMO found a reference to memo index {}, but only {} objects have been retrieved",
+                        tag - 1,
+                        table.retrieved.len()
+                    ))
+                })
+        })
+    }
+}
+
+impl PyAnySerde for MemoSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let scope = MemoScope::enter();
+        let tag = self.get_append_tag(&scope, obj)?;
+        let mut offset = append_varint(buf, offset, tag);
+        if tag == 0 {
+            offset = self.value_serde.append(buf, offset, obj)?;
+            self.finish_append(&scope, obj);
+        }
+        Ok(offset)
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let scope = MemoScope::enter();
+        let tag = self.get_append_tag(&scope, obj)?;
+        append_varint_vec(v, tag);
+        if tag == 0 {
+            self.value_serde.append_vec(v, start_addr, obj)?;
+            self.finish_append(&scope, obj);
+        }
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let scope = MemoScope::enter();
+        let (tag, offset) = retrieve_varint(buf, offset)?;
+        if tag == 0 {
+            let (obj, offset) = self.value_serde.retrieve(py, buf, offset)?;
+            self.finish_retrieve(&scope, &obj);
+            return Ok((obj, offset));
+        }
+        Ok((self.get_retrieved(py, &scope, tag)?, offset))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let scope = MemoScope::enter();
+        let tag = self.get_append_tag(&scope, obj)?;
+        append_varint_stream(writer, tag)?;
+        if tag == 0 {
+            self.value_serde.append_stream(writer, obj)?;
+            self.finish_append(&scope, obj);
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let scope = MemoScope::enter();
+        let tag = retrieve_varint_stream(reader)?;
+        if tag == 0 {
+            let obj = self.value_serde.retrieve_stream(py, reader)?;
+            self.finish_retrieve(&scope, &obj);
+            return Ok(obj);
+        }
+        self.get_retrieved(py, &scope, tag)
+    }
+}
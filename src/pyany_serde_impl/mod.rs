@@ -1,16 +1,31 @@
 mod bool_serde;
 mod bytes_serde;
+mod checksummed_serde;
+mod columnar_list_serde;
 mod complex_serde;
+mod compressed_serde;
 mod dataclass_serde;
+mod default_dict_serde;
+mod deque_serde;
 mod dict_serde;
 mod dynamic_serde;
+mod enum_serde;
+mod extra_policy;
 mod float_serde;
+mod frozenset_serde;
+mod homogeneous_tuple_serde;
 mod int_serde;
 mod list_serde;
+mod memo_serde;
+mod named_tuple_serde;
 mod numpy_serde;
 mod option_serde;
+mod ordered_dict_serde;
 mod pickle_serde;
+mod primitive_list_serde;
 mod python_serde_serde;
+mod ref_serde;
+mod schema_policy;
 mod set_serde;
 mod string_serde;
 mod tuple_serde;
@@ -19,17 +34,32 @@ mod union_serde;
 
 pub use bool_serde::BoolSerde;
 pub use bytes_serde::BytesSerde;
+pub use checksummed_serde::ChecksummedSerde;
+pub use columnar_list_serde::{ColumnarListSerde, get_columnar_list_serde};
 pub use complex_serde::ComplexSerde;
+pub use compressed_serde::{CompressedSerde, DEFAULT_MIN_COMPRESSED_SIZE};
 pub use dataclass_serde::{DataclassSerde, InitStrategy, InitStrategyKind, PickleableInitStrategy};
+pub use default_dict_serde::DefaultDictSerde;
+pub use deque_serde::DequeSerde;
 pub use dict_serde::DictSerde;
 pub use dynamic_serde::DynamicSerde;
+pub use enum_serde::EnumSerde;
+pub use extra_policy::{ExtraPolicy, ExtraPolicyKind, InternalExtraPolicy};
 pub use float_serde::FloatSerde;
+pub use frozenset_serde::FrozenSetSerde;
+pub use homogeneous_tuple_serde::HomogeneousTupleSerde;
 pub use int_serde::IntSerde;
 pub use list_serde::ListSerde;
+pub use memo_serde::MemoSerde;
+pub use named_tuple_serde::NamedTupleSerde;
 pub use numpy_serde::{get_numpy_serde, NumpySerde, NumpySerdeConfig, NumpySerdeConfigKind};
 pub use option_serde::OptionSerde;
+pub use ordered_dict_serde::OrderedDictSerde;
 pub use pickle_serde::PickleSerde;
+pub use primitive_list_serde::{PrimitiveListSerde, get_primitive_list_serde};
 pub use python_serde_serde::PythonSerdeSerde;
+pub use ref_serde::{DEFAULT_MAX_DEPTH, RefSerde, get_definitions_serde};
+pub use schema_policy::{InternalSchemaPolicy, SchemaPolicy, SchemaPolicyKind};
 pub use set_serde::SetSerde;
 pub use string_serde::StringSerde;
 pub use tuple_serde::TupleSerde;
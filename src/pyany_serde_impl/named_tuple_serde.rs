@@ -0,0 +1,97 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyTuple, PyType};
+
+use crate::PyAnySerde;
+use crate::communication::{StreamReader, StreamWriter};
+
+use super::TupleSerde;
+
+// Wire compatible with TUPLE, since the items are written positionally. The named tuple class is
+// called with the items as positional arguments when retrieving.
+#[derive(Clone)]
+pub struct NamedTupleSerde {
+    class: Py<PyAny>,
+    tuple_serde: TupleSerde,
+}
+
+impl NamedTupleSerde {
+    pub fn new(class: Py<PyAny>, item_serdes: Vec<Box<dyn PyAnySerde>>) -> PyResult<Self> {
+        Python::attach(|py| {
+            let class_bound = class.bind(py);
+            let is_named_tuple = class_bound
+                .cast::<PyType>()
+                .map(|cls| cls.is_subclass_of::<PyTuple>())
+                .unwrap_or(Ok(false))?
+                && class_bound.hasattr("_fields")?;
+            if !is_named_tuple {
+                Err(PyValueError::new_err(format!(
+                    "NAMEDTUPLE clazz must be a named tuple class, but was {}",
+                    class_bound.repr()?
+                )))?
+            }
+            let n_fields = class_bound.getattr("_fields")?.len()?;
+            if n_fields != item_serdes.len() {
+                Err(PyValueError::new_err(format!(
+                    "NAMEDTUPLE clazz {} has {n_fields} fields, but {} item serde types were provided",
+                    class_bound.repr()?,
+                    item_serdes.len()
+                )))?
+            }
+            Ok(NamedTupleSerde {
+                class,
+                tuple_serde: TupleSerde { item_serdes },
+            })
+        })
+    }
+}
+
+impl PyAnySerde for NamedTupleSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        self.tuple_serde.append(buf, offset, obj)
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        self.tuple_serde.append_vec(v, start_addr, obj)
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let (tuple, offset) = self.tuple_serde.retrieve(py, buf, offset)?;
+        Ok((
+            self.class.bind(py).call1(tuple.cast_into::<PyTuple>()?)?,
+            offset,
+        ))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        self.tuple_serde.append_stream(writer, obj)
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let tuple = self.tuple_serde.retrieve_stream(py, reader)?;
+        self.class.bind(py).call1(tuple.cast_into::<PyTuple>()?)
+    }
+}
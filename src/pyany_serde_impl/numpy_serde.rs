@@ -1,6 +1,6 @@
 use std::env;
 
-use bytemuck::{AnyBitPattern, NoUninit, cast_slice};
+use bytemuck::{AnyBitPattern, NoUninit, cast_slice, try_cast_slice};
 use enum_kinds::EnumKind;
 use numpy::ndarray::ArrayD;
 use numpy::{Element, PyArrayDyn, PyArrayMethods, PyUntypedArrayMethods};
@@ -12,7 +12,11 @@ use pyo3::types::{PyDict, PyList, PyType};
 use pyo3::{PyTypeInfo, intern, prelude::*};
 use strum_macros::{Display, EnumIter};
 
-use crate::communication::{append_bytes_vec, append_usize, append_usize_vec, retrieve_usize};
+use crate::communication::{
+    StreamReader, StreamWriter, append_bytes_stream, append_bytes_vec, append_padding_stream,
+    append_usize, append_usize_stream, append_usize_vec, retrieve_padding_stream, retrieve_usize,
+    retrieve_usize_stream,
+};
 use crate::{
     PyAnySerde,
     common::{NumpyDtype, get_bytes_to_alignment},
@@ -67,6 +71,14 @@ impl NumpySerdeConfigKind {
     }
 }
 
+fn get_start_addr(start_addr: Option<usize>) -> PyResult<usize> {
+    start_addr.ok_or_else(|| {
+        InvalidStateError::new_err(
+            "Tried to serialize numpy data, but there was no start_addr provided so there's no way to know how to align the data. (was this called from inside a preprocessor function?)",
+        )
+    })
+}
+
 #[derive(Clone)]
 pub struct NumpySerde<T: Element> {
     pub config: NumpySerdeConfig,
@@ -106,11 +118,7 @@ impl<T: Element + AnyBitPattern + NoUninit> NumpySerde<T> {
         start_addr: Option<usize>,
         array: &Bound<'py, PyArrayDyn<T>>,
     ) -> PyResult<()> {
-        let Some(start_addr) = start_addr else {
-            Err(InvalidStateError::new_err(
-                "Tried to serialize numpy data, but there was no start_addr provided so there's no way to know how to align the data. (was this called from inside a preprocessor function?)",
-            ))?
-        };
+        let start_addr = get_start_addr(start_addr)?;
         match &self.config {
             NumpySerdeConfig::DYNAMIC { .. } => {
                 let shape = array.shape();
@@ -137,6 +145,79 @@ impl<T: Element + AnyBitPattern + NoUninit> NumpySerde<T> {
         Ok(())
     }
 
+    fn append_inner_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        array: &Bound<'py, PyArrayDyn<T>>,
+    ) -> PyResult<()> {
+        if let NumpySerdeConfig::DYNAMIC { .. } = &self.config {
+            let shape = array.shape();
+            append_usize_stream(writer, shape.len())?;
+            for &dim in shape.iter() {
+                append_usize_stream(writer, dim)?;
+            }
+        }
+        append_array_vec_stream(writer, &array.to_vec()?)
+    }
+
+    fn get_preprocessor_fn(&self) -> &Option<Py<PyAny>> {
+        match &self.config {
+            NumpySerdeConfig::DYNAMIC {
+                preprocessor_fn, ..
+            } => preprocessor_fn,
+            NumpySerdeConfig::STATIC {
+                preprocessor_fn, ..
+            } => preprocessor_fn,
+        }
+    }
+
+    fn get_postprocessor_fn(&self) -> &Option<Py<PyAny>> {
+        match &self.config {
+            NumpySerdeConfig::DYNAMIC {
+                postprocessor_fn, ..
+            } => postprocessor_fn,
+            NumpySerdeConfig::STATIC {
+                postprocessor_fn, ..
+            } => postprocessor_fn,
+        }
+    }
+
+    // Arrays with a STATIC config (and no preprocessor or postprocessor fn) all have the same shape,
+    // so many of them can be appended from or retrieved into a single stacked array
+    fn get_stackable_shape(&self) -> Option<&[usize]> {
+        match &self.config {
+            NumpySerdeConfig::STATIC {
+                shape,
+                preprocessor_fn: None,
+                postprocessor_fn: None,
+                ..
+            } => Some(shape),
+            _ => None,
+        }
+    }
+
+    // Returns the data of the arrays if objs is a single array of the arrays stacked along its
+    // first axis, as well as the number of arrays and the number of elements in each array
+    fn get_stacked_data<'py>(
+        &self,
+        objs: &Bound<'py, PyAny>,
+    ) -> PyResult<Option<(Vec<T>, usize, usize)>> {
+        let Some(shape) = self.get_stackable_shape() else {
+            return Ok(None);
+        };
+        let Ok(stacked) = objs.cast::<PyArrayDyn<T>>() else {
+            return Ok(None);
+        };
+        if stacked.shape().get(1..) != Some(shape) {
+            return Ok(None);
+        }
+        Ok(Some((
+            stacked.to_vec()?,
+            stacked.shape()[0],
+            shape.iter().product(),
+        )))
+    }
+
     pub fn retrieve_inner<'py>(
         &mut self,
         py: Python<'py>,
@@ -156,98 +237,168 @@ impl<T: Element + AnyBitPattern + NoUninit> NumpySerde<T> {
                 offset = offset + get_bytes_to_alignment::<T>(buf.as_ptr() as usize + offset);
                 let obj_bytes;
                 (obj_bytes, offset) = retrieve_bytes(buf, offset)?;
-                let array_vec = cast_slice::<u8, T>(obj_bytes).to_vec();
-                ArrayD::from_shape_vec(shape, array_vec)
-                    .map_err(|err| {
-                        InvalidStateError::new_err(format!(
-                            "Failed create Numpy array of T from shape and Vec<T>: {err}"
-                        ))
-                    })?
-                    .into_pyarray(py)
+                let array_vec = cast_array_bytes::<T>(obj_bytes)?.to_vec();
+                array_from_shape_vec(py, shape, array_vec)?
             }
-            NumpySerdeConfig::STATIC {
-                shape,
-                allocation_pool_min_size,
-                allocation_pool_max_size,
-                allocation_pool_warning_size,
-                ..
-            } => {
+            NumpySerdeConfig::STATIC { .. } => {
                 offset = offset + get_bytes_to_alignment::<T>(buf.as_ptr() as usize + offset);
                 let obj_bytes;
                 (obj_bytes, offset) = retrieve_bytes(buf, offset)?;
-                let array_vec = cast_slice::<u8, T>(obj_bytes).to_vec();
-                let py_array;
-                if allocation_pool_max_size.is_none() || allocation_pool_max_size.unwrap() > 0 {
-                    // Take two random elements from the pool
-                    let pool_size = self.allocation_pool.len();
-                    let idx1 = fastrand::usize(..pool_size);
-                    let idx2 = fastrand::usize(..pool_size);
-                    let e1 = &self.allocation_pool[idx1];
-                    let e2 = &self.allocation_pool[idx2];
-                    let e1_free = unsafe { pyo3::ffi::Py_REFCNT(e1.as_ptr()) } == 1;
-                    let e2_free = unsafe { pyo3::ffi::Py_REFCNT(e2.as_ptr()) } == 1;
-                    if e1_free && e2_free {
-                        py_array = e1.clone_ref(py).into_bound(py);
-                        if self.allocation_pool.len() > *allocation_pool_min_size {
-                            self.allocation_pool.swap_remove(idx2);
-                        }
-                    } else if e1_free {
-                        py_array = e1.clone_ref(py).into_bound(py);
-                    } else if e2_free {
-                        py_array = e2.clone_ref(py).into_bound(py);
-                    } else {
-                        let arr: Bound<'_, PyArray<T, _>> =
-                            unsafe { PyArrayDyn::new(py, &shape[..], false) };
-                        if allocation_pool_max_size.is_none()
-                            || self.allocation_pool.len() < allocation_pool_max_size.unwrap()
-                        {
-                            self.allocation_pool.push(arr.clone().unbind());
-                        }
-                        py_array = arr;
-                        if let Some(allocation_pool_warning_size) = allocation_pool_warning_size
-                            && pool_size > *allocation_pool_warning_size
-                            && pool_size.is_multiple_of(100)
-                        {
-                            let recursion_depth =
-                                env::var("PYANY_SERDE_NUMPY_ALLOCATION_WARNING_RECUSION_DEPTH")
-                                    .map(|v| v.parse::<usize>().unwrap_or(5))
-                                    .unwrap_or(5);
-                            println!(
-                                "Warning: the allocation pool for this Numpy PyAny serde instance is currently {pool_size}, which is larger than the warning limit set ({allocation_pool_warning_size}). Here is a random element from the allocation pool and a dict of the types of its referrers (and the referrers of those referrers, etc, up to the recursion depth set by PYANY_SERDE_NUMPY_ALLOCATION_WARNING_RECUSION_DEPTH (5 by default)):"
-                            );
-                            let mut total_in_use = 0;
-                            for item in self.allocation_pool.iter() {
-                                if unsafe { pyo3::ffi::Py_REFCNT(item.as_ptr()) } > 1 {
-                                    total_in_use += 1;
-                                }
-                            }
-                            println!(
-                                "Number of elements in allocation pool which are currently in use: {total_in_use}"
-                            );
-                            let idx = fastrand::usize(..pool_size);
-                            let e = &self.allocation_pool[idx];
-                            println!(
-                                "{}\n\n",
-                                get_ref_types(e.bind(py), recursion_depth)?.repr()?
-                            );
-                        }
-                    }
-                    unsafe { py_array.as_slice_mut().unwrap().copy_from_slice(&array_vec) };
-                } else {
-                    py_array = ArrayD::from_shape_vec(&shape[..], array_vec)
-                        .map_err(|err| {
-                            InvalidStateError::new_err(format!(
-                                "Failed create Numpy array of T from shape and Vec<T>: {err}"
-                            ))
-                        })?
-                        .into_pyarray(py);
-                }
-                py_array
+                let array_vec = cast_array_bytes::<T>(obj_bytes)?.to_vec();
+                self.get_static_array(py, array_vec)?
             }
         };
 
         Ok((py_array, offset))
     }
+
+    // The stream format is the same as the buffer format, with the alignment padding relative to
+    // the position of the stream
+    fn retrieve_inner_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyArrayDyn<T>>> {
+        match &self.config {
+            NumpySerdeConfig::DYNAMIC { .. } => {
+                let shape_len = retrieve_usize_stream(reader)?;
+                let mut shape = Vec::new();
+                for _ in 0..shape_len {
+                    shape.push(retrieve_usize_stream(reader)?);
+                }
+                let array_vec = retrieve_array_vec_stream(reader)?;
+                array_from_shape_vec(py, shape, array_vec)
+            }
+            NumpySerdeConfig::STATIC { .. } => {
+                let array_vec = retrieve_array_vec_stream(reader)?;
+                self.get_static_array(py, array_vec)
+            }
+        }
+    }
+
+    // Returns an array with the STATIC shape containing array_vec, reusing an array from the
+    // allocation pool if there is one which is no longer in use
+    fn get_static_array<'py>(
+        &mut self,
+        py: Python<'py>,
+        array_vec: Vec<T>,
+    ) -> PyResult<Bound<'py, PyArrayDyn<T>>> {
+        let NumpySerdeConfig::STATIC {
+            shape,
+            allocation_pool_min_size,
+            allocation_pool_max_size,
+            allocation_pool_warning_size,
+            ..
+        } = &self.config
+        else {
+            return Err(InvalidStateError::new_err(
+                "get_static_array called with a DYNAMIC config",
+            ));
+        };
+        let py_array;
+        if allocation_pool_max_size.is_none() || allocation_pool_max_size.unwrap() > 0 {
+            // Take two random elements from the pool
+            let pool_size = self.allocation_pool.len();
+            let idx1 = fastrand::usize(..pool_size);
+            let idx2 = fastrand::usize(..pool_size);
+            let e1 = &self.allocation_pool[idx1];
+            let e2 = &self.allocation_pool[idx2];
+            let e1_free = unsafe { pyo3::ffi::Py_REFCNT(e1.as_ptr()) } == 1;
+            let e2_free = unsafe { pyo3::ffi::Py_REFCNT(e2.as_ptr()) } == 1;
+            if e1_free && e2_free {
+                py_array = e1.clone_ref(py).into_bound(py);
+                if self.allocation_pool.len() > *allocation_pool_min_size {
+                    self.allocation_pool.swap_remove(idx2);
+                }
+            } else if e1_free {
+                py_array = e1.clone_ref(py).into_bound(py);
+            } else if e2_free {
+                py_array = e2.clone_ref(py).into_bound(py);
+            } else {
+                let arr: Bound<'_, PyArray<T, _>> =
+                    unsafe { PyArrayDyn::new(py, &shape[..], false) };
+                if allocation_pool_max_size.is_none()
+                    || self.allocation_pool.len() < allocation_pool_max_size.unwrap()
+                {
+                    self.allocation_pool.push(arr.clone().unbind());
+                }
+                py_array = arr;
+                if let Some(allocation_pool_warning_size) = allocation_pool_warning_size
+                    && pool_size > *allocation_pool_warning_size
+                    && pool_size.is_multiple_of(100)
+                {
+                    let recursion_depth =
+                        env::var("PYANY_SERDE_NUMPY_ALLOCATION_WARNING_RECUSION_DEPTH")
+                            .map(|v| v.parse::<usize>().unwrap_or(5))
+                            .unwrap_or(5);
+                    println!(
+                        "Warning: the allocation pool for this Numpy PyAny serde instance is currently {pool_size}, which is larger than the warning limit set ({allocation_pool_warning_size}). Here is a random element from the allocation pool and a dict of the types of its referrers (and the referrers of those referrers, etc, up to the recursion depth set by PYANY_SERDE_NUMPY_ALLOCATION_WARNING_RECUSION_DEPTH (5 by default)):"
+                    );
+                    let mut total_in_use = 0;
+                    for item in self.allocation_pool.iter() {
+                        if unsafe { pyo3::ffi::Py_REFCNT(item.as_ptr()) } > 1 {
+                            total_in_use += 1;
+                        }
+                    }
+                    println!(
+                        "Number of elements in allocation pool which are currently in use: {total_in_use}"
+                    );
+                    let idx = fastrand::usize(..pool_size);
+                    let e = &self.allocation_pool[idx];
+                    println!(
+                        "{}\n\n",
+                        get_ref_types(e.bind(py), recursion_depth)?.repr()?
+                    );
+                }
+            }
+            unsafe { py_array.as_slice_mut().unwrap().copy_from_slice(&array_vec) };
+        } else {
+            py_array = array_from_shape_vec(py, shape.clone(), array_vec)?;
+        }
+        Ok(py_array)
+    }
+}
+
+fn array_from_shape_vec<'py, T: Element>(
+    py: Python<'py>,
+    shape: Vec<usize>,
+    array_vec: Vec<T>,
+) -> PyResult<Bound<'py, PyArrayDyn<T>>> {
+    Ok(ArrayD::from_shape_vec(shape, array_vec)
+        .map_err(|err| {
+            InvalidStateError::new_err(format!(
+                "Failed create Numpy array of T from shape and Vec<T>: {err}"
+            ))
+        })?
+        .into_pyarray(py))
+}
+
+// Writes the alignment padding and the bytes of an array's data to a stream
+fn append_array_vec_stream<T: NoUninit>(
+    writer: &mut StreamWriter,
+    array_vec: &[T],
+) -> PyResult<()> {
+    append_padding_stream::<T>(writer)?;
+    append_bytes_stream(writer, cast_slice::<T, u8>(array_vec))
+}
+
+// Reinterprets the bytes of an array's data as its elements, which fails if the bytes are not
+// aligned for T or are not a whole number of elements
+fn cast_array_bytes<T: AnyBitPattern>(obj_bytes: &[u8]) -> PyResult<&[T]> {
+    try_cast_slice::<u8, T>(obj_bytes).map_err(|err| {
+        InvalidStateError::new_err(format!(
+            "Failed to retrieve Numpy array data of {} bytes: {err}",
+            obj_bytes.len()
+        ))
+    })
+}
+
+// Reads the alignment padding and the bytes of an array's data from a stream
+fn retrieve_array_vec_stream<T: AnyBitPattern>(reader: &mut StreamReader) -> PyResult<Vec<T>> {
+    retrieve_padding_stream::<T>(reader)?;
+    let len = retrieve_usize_stream(reader)?;
+    let (buffer, offset) = reader.read_aligned(len)?;
+    Ok(cast_array_bytes::<T>(&buffer.as_slice()[offset..])?.to_vec())
 }
 
 #[macro_export]
@@ -337,15 +488,7 @@ impl<T: Element + AnyBitPattern + NoUninit> PyAnySerde for NumpySerde<T> {
         offset: usize,
         obj: &Bound<'py, PyAny>,
     ) -> PyResult<usize> {
-        let preprocessor_fn_option = match &self.config {
-            NumpySerdeConfig::DYNAMIC {
-                preprocessor_fn, ..
-            } => preprocessor_fn,
-            NumpySerdeConfig::STATIC {
-                preprocessor_fn, ..
-            } => preprocessor_fn,
-        };
-        match preprocessor_fn_option {
+        match self.get_preprocessor_fn() {
             Some(preprocessor_fn) => self.append_inner(
                 buf,
                 offset,
@@ -364,15 +507,7 @@ impl<T: Element + AnyBitPattern + NoUninit> PyAnySerde for NumpySerde<T> {
         start_addr: Option<usize>,
         obj: &Bound<'py, PyAny>,
     ) -> PyResult<()> {
-        let preprocessor_fn_option = match &self.config {
-            NumpySerdeConfig::DYNAMIC {
-                preprocessor_fn, ..
-            } => preprocessor_fn,
-            NumpySerdeConfig::STATIC {
-                preprocessor_fn, ..
-            } => preprocessor_fn,
-        };
-        match preprocessor_fn_option {
+        match self.get_preprocessor_fn() {
             Some(preprocessor_fn) => self.append_inner_vec(
                 v,
                 start_addr,
@@ -392,21 +527,170 @@ impl<T: Element + AnyBitPattern + NoUninit> PyAnySerde for NumpySerde<T> {
         offset: usize,
     ) -> PyResult<(Bound<'py, PyAny>, usize)> {
         let (array, offset) = self.retrieve_inner(py, buf, offset)?;
-
-        let postprocessor_fn_option = match &self.config {
-            NumpySerdeConfig::DYNAMIC {
-                postprocessor_fn, ..
-            } => postprocessor_fn,
-            NumpySerdeConfig::STATIC {
-                postprocessor_fn, ..
-            } => postprocessor_fn,
-        };
-
-        Ok(match postprocessor_fn_option {
+        Ok(match self.get_postprocessor_fn() {
             Some(postprocessor_fn) => (postprocessor_fn.bind(py).call1((array, offset))?, offset),
             None => (array.into_any(), offset),
         })
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        match self.get_preprocessor_fn() {
+            Some(preprocessor_fn) => self.append_inner_stream(
+                writer,
+                preprocessor_fn
+                    .bind(obj.py())
+                    .call1((obj,))?
+                    .cast::<PyArrayDyn<T>>()?,
+            ),
+            None => self.append_inner_stream(writer, obj.cast::<PyArrayDyn<T>>()?),
+        }
+    }
+
+    // The postprocessor fn is called with the position of the stream in place of the offset
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let array = self.retrieve_inner_stream(py, reader)?;
+        Ok(match self.get_postprocessor_fn() {
+            Some(postprocessor_fn) => postprocessor_fn
+                .bind(py)
+                .call1((array, reader.position()))?,
+            None => array.into_any(),
+        })
+    }
+
+    // The format is the same as appending each array separately, but a stacked array is written
+    // without creating an array object for each of the arrays in it
+    fn append_many<'py>(
+        &mut self,
+        buf: &mut [u8],
+        mut offset: usize,
+        objs: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        if let Some((stacked_vec, n_arrays, n_elements)) = self.get_stacked_data(objs)? {
+            for idx in 0..n_arrays {
+                let array_vec = &stacked_vec[idx * n_elements..(idx + 1) * n_elements];
+                offset = offset + get_bytes_to_alignment::<T>(buf.as_ptr() as usize + offset);
+                offset = append_bytes(buf, offset, cast_slice::<T, u8>(array_vec));
+            }
+        } else {
+            for obj in objs.try_iter()? {
+                offset = self.append(buf, offset, &obj?)?;
+            }
+        }
+        Ok(offset)
+    }
+
+    fn append_many_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        objs: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        if let Some((stacked_vec, n_arrays, n_elements)) = self.get_stacked_data(objs)? {
+            let start_addr = get_start_addr(start_addr)?;
+            for idx in 0..n_arrays {
+                let array_vec = &stacked_vec[idx * n_elements..(idx + 1) * n_elements];
+                v.append(&mut vec![
+                    0;
+                    get_bytes_to_alignment::<T>(start_addr + v.len())
+                ]);
+                append_bytes_vec(v, cast_slice::<T, u8>(array_vec));
+            }
+        } else {
+            for obj in objs.try_iter()? {
+                self.append_vec(v, start_addr, &obj?)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn retrieve_many_stacked<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        mut offset: usize,
+        n: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let Some(shape) = self.get_stackable_shape() else {
+            // numpy.stack needs at least one array
+            if n == 0 {
+                let stacked = array_from_shape_vec(py, vec![0], Vec::<T>::new())?;
+                return Ok((stacked.into_any(), offset));
+            }
+            let (arrays, offset) = self.retrieve_many(py, buf, offset, n)?;
+            let stacked = py
+                .import(intern!(py, "numpy"))?
+                .call_method1(intern!(py, "stack"), (arrays,))?;
+            return Ok((stacked, offset));
+        };
+        let n_elements = shape.iter().product::<usize>();
+        let mut stacked_vec = Vec::with_capacity(n * n_elements);
+        for _ in 0..n {
+            offset = offset + get_bytes_to_alignment::<T>(buf.as_ptr() as usize + offset);
+            let obj_bytes;
+            (obj_bytes, offset) = retrieve_bytes(buf, offset)?;
+            if obj_bytes.len() != n_elements * size_of::<T>() {
+                Err(InvalidStateError::new_err(format!(
+                    "Retrieved Numpy array data of {} bytes, but arrays of shape {shape:?} have {} bytes",
+                    obj_bytes.len(),
+                    n_elements * size_of::<T>()
+                )))?
+            }
+            stacked_vec.extend_from_slice(cast_array_bytes::<T>(obj_bytes)?);
+        }
+        let stacked_shape = [&[n], shape].concat();
+        let stacked = array_from_shape_vec(py, stacked_shape, stacked_vec)?;
+        Ok((stacked.into_any(), offset))
+    }
+
+    fn append_many_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        objs: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        if let Some((stacked_vec, n_arrays, n_elements)) = self.get_stacked_data(objs)? {
+            for idx in 0..n_arrays {
+                let array_vec = &stacked_vec[idx * n_elements..(idx + 1) * n_elements];
+                append_array_vec_stream(writer, array_vec)?;
+            }
+        } else {
+            for obj in objs.try_iter()? {
+                self.append_stream(writer, &obj?)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn retrieve_many_stacked_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+        n: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let Some(shape) = self.get_stackable_shape() else {
+            if n == 0 {
+                return Ok(array_from_shape_vec(py, vec![0], Vec::<T>::new())?.into_any());
+            }
+            let arrays = self.retrieve_many_stream(py, reader, n)?;
+            return py
+                .import(intern!(py, "numpy"))?
+                .call_method1(intern!(py, "stack"), (arrays,));
+        };
+        let shape = shape.to_vec();
+        let mut stacked_vec = Vec::new();
+        for _ in 0..n {
+            stacked_vec.extend(retrieve_array_vec_stream::<T>(reader)?);
+        }
+        let stacked_shape = [&[n], &shape[..]].concat();
+        Ok(array_from_shape_vec(py, stacked_shape, stacked_vec)?.into_any())
+    }
 }
 
 static GC: PyOnceLock<Py<PyModule>> = PyOnceLock::new();
@@ -440,3 +724,106 @@ fn get_ref_types<'py>(o: &Bound<'py, PyAny>, recursion: usize) -> PyResult<Bound
             .into_pyobject(py)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::mem::align_of;
+
+    use numpy::PyArray1;
+
+    use super::*;
+    use crate::common::AlignedBuffer;
+
+    #[test]
+    fn test_static_many_stacked_roundtrip() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let shape = vec![2, 3];
+            let config = NumpySerdeConfig::STATIC {
+                shape: shape.clone(),
+                preprocessor_fn: None,
+                postprocessor_fn: None,
+                allocation_pool_min_size: 0,
+                allocation_pool_max_size: Some(0),
+                allocation_pool_warning_size: None,
+            };
+            let mut serde = *create_numpy_pyany_serde!(f64, config);
+            let n = 4;
+            let stacked =
+                PyArray1::<f64>::arange(py, 0.0, (n * 6) as f64, 1.0).reshape(vec![n, 2, 3])?;
+            let arrays = PyList::new(py, stacked.try_iter()?.collect::<PyResult<Vec<_>>>()?)?;
+
+            // The stacked data must be laid out like the arrays appended one at a time, with
+            // each array's data aligned relative to start_addr
+            for start_addr in 0..align_of::<f64>() {
+                let mut v = Vec::new();
+                serde.append_many_vec(&mut v, Some(start_addr), &stacked)?;
+                let mut expected = Vec::new();
+                for array in arrays.iter() {
+                    serde.append_vec(&mut expected, Some(start_addr), &array)?;
+                }
+                assert_eq!(v, expected);
+
+                let mut buffer = AlignedBuffer::new(start_addr + v.len());
+                let buf = buffer.as_mut_slice();
+                let end = serde.append_many(buf, start_addr, &stacked)?;
+                assert_eq!(end, buf.len());
+                assert_eq!(&buf[start_addr..], &v[..]);
+
+                let mut offset = start_addr;
+                for _ in 0..n {
+                    offset += get_bytes_to_alignment::<f64>(buf.as_ptr() as usize + offset);
+                    let data;
+                    (data, offset) = retrieve_bytes(buf, offset)?;
+                    assert_eq!(data.as_ptr() as usize % align_of::<f64>(), 0);
+                    assert_eq!(data.len(), 6 * size_of::<f64>());
+                }
+                assert_eq!(offset, buf.len());
+
+                let (retrieved, offset) = serde.retrieve_many_stacked(py, buf, start_addr, n)?;
+                assert_eq!(offset, buf.len());
+                let retrieved = retrieved.cast_into::<PyArrayDyn<f64>>()?;
+                assert_eq!(retrieved.shape(), &[n, 2, 3]);
+                assert_eq!(retrieved.to_vec()?, stacked.to_vec()?);
+
+                let (retrieved, offset) = serde.retrieve_many(py, buf, start_addr, n)?;
+                assert_eq!(offset, buf.len());
+                for (retrieved, array) in retrieved.iter().zip(arrays.iter()) {
+                    let retrieved = retrieved.cast_into::<PyArrayDyn<f64>>()?;
+                    assert_eq!(retrieved.shape(), &shape[..]);
+                    assert_eq!(
+                        retrieved.to_vec()?,
+                        array.cast_into::<PyArrayDyn<f64>>()?.to_vec()?
+                    );
+                }
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_static_many_stacked_wrong_length() -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let config = NumpySerdeConfig::STATIC {
+                shape: vec![2],
+                preprocessor_fn: None,
+                postprocessor_fn: None,
+                allocation_pool_min_size: 0,
+                allocation_pool_max_size: Some(0),
+                allocation_pool_warning_size: None,
+            };
+            let mut serde = *create_numpy_pyany_serde!(f64, config);
+            // The data of 3 elements, and of 1.5 elements
+            for n_bytes in [24, 12] {
+                let mut buffer = AlignedBuffer::new(8 + n_bytes);
+                append_bytes(buffer.as_mut_slice(), 0, &vec![0; n_bytes]);
+                let err = serde
+                    .retrieve_many_stacked(py, buffer.as_slice(), 0, 1)
+                    .unwrap_err();
+                assert!(err.is_instance_of::<InvalidStateError>(py), "{err}");
+            }
+            Ok(())
+        })
+    }
+}
@@ -2,7 +2,10 @@ use pyo3::prelude::*;
 use pyo3::types::PyNone;
 
 use crate::{
-    communication::{append_bool, append_bool_vec, retrieve_bool},
+    communication::{
+        append_bool, append_bool_stream, append_bool_vec, retrieve_bool, retrieve_bool_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -55,4 +58,29 @@ impl PyAnySerde for OptionSerde {
             Ok((PyNone::get(py).to_owned().into_any(), offset))
         }
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        if obj.is_none() {
+            append_bool_stream(writer, false)
+        } else {
+            append_bool_stream(writer, true)?;
+            self.value_serde.append_stream(writer, obj)
+        }
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if retrieve_bool_stream(reader)? {
+            self.value_serde.retrieve_stream(py, reader)
+        } else {
+            Ok(PyNone::get(py).to_owned().into_any())
+        }
+    }
 }
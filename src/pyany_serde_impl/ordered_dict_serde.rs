@@ -0,0 +1,129 @@
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+
+use crate::{
+    PyAnySerde,
+    communication::{
+        StreamReader, StreamWriter, append_usize, append_usize_stream, append_usize_vec,
+        retrieve_usize, retrieve_usize_stream,
+    },
+};
+
+#[derive(Clone)]
+pub struct OrderedDictSerde {
+    keys_serde: Box<dyn PyAnySerde>,
+    values_serde: Box<dyn PyAnySerde>,
+    ordered_dict_type: Py<PyAny>,
+}
+
+impl OrderedDictSerde {
+    pub fn new(
+        keys_serde: Box<dyn PyAnySerde>,
+        values_serde: Box<dyn PyAnySerde>,
+    ) -> PyResult<Self> {
+        Python::attach(|py| {
+            Ok(OrderedDictSerde {
+                keys_serde,
+                values_serde,
+                ordered_dict_type: py.import("collections")?.getattr("OrderedDict")?.unbind(),
+            })
+        })
+    }
+
+    // The storage order of the underlying dict can diverge from the OrderedDict order (e.g. after
+    // move_to_end), so the items must be read through the OrderedDict's own iteration.
+    fn get_items<'py>(
+        &self,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<Vec<(Bound<'py, PyAny>, Bound<'py, PyAny>)>> {
+        if !obj.is_instance(self.ordered_dict_type.bind(obj.py()))? {
+            Err(PyTypeError::new_err(format!(
+                "Expected a collections.OrderedDict but received {}",
+                obj.get_type().name()?
+            )))?
+        }
+        obj.call_method0("items")?
+            .try_iter()?
+            .map(|item| item?.extract())
+            .collect()
+    }
+}
+
+impl PyAnySerde for OrderedDictSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let items = self.get_items(obj)?;
+        let mut offset = append_usize(buf, offset, items.len());
+        for (key, value) in items.iter() {
+            offset = self.keys_serde.append(buf, offset, key)?;
+            offset = self.values_serde.append(buf, offset, value)?;
+        }
+        Ok(offset)
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let items = self.get_items(obj)?;
+        append_usize_vec(v, items.len());
+        for (key, value) in items.iter() {
+            self.keys_serde.append_vec(v, start_addr, key)?;
+            self.values_serde.append_vec(v, start_addr, value)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let ordered_dict = self.ordered_dict_type.bind(py).call0()?;
+        let (n_items, mut offset) = retrieve_usize(buf, offset)?;
+        for _ in 0..n_items {
+            let key;
+            (key, offset) = self.keys_serde.retrieve(py, buf, offset)?;
+            let value;
+            (value, offset) = self.values_serde.retrieve(py, buf, offset)?;
+            ordered_dict.set_item(key, value)?;
+        }
+        Ok((ordered_dict, offset))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let items = self.get_items(obj)?;
+        append_usize_stream(writer, items.len())?;
+        for (key, value) in items.iter() {
+            self.keys_serde.append_stream(writer, key)?;
+            self.values_serde.append_stream(writer, value)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ordered_dict = self.ordered_dict_type.bind(py).call0()?;
+        let n_items = retrieve_usize_stream(reader)?;
+        for _ in 0..n_items {
+            let key = self.keys_serde.retrieve_stream(py, reader)?;
+            let value = self.values_serde.retrieve_stream(py, reader)?;
+            ordered_dict.set_item(key, value)?;
+        }
+        Ok(ordered_dict)
+    }
+}
@@ -2,7 +2,10 @@ use pyo3::prelude::*;
 use pyo3::types::PyBytes;
 
 use crate::{
-    communication::{append_bytes, append_bytes_vec, retrieve_bytes},
+    communication::{
+        append_bytes, append_bytes_stream, append_bytes_vec, retrieve_bytes, retrieve_bytes_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -72,4 +75,30 @@ impl PyAnySerde for PickleSerde {
             offset,
         ))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        append_bytes_stream(
+            writer,
+            self.pickle_dumps
+                .bind(obj.py())
+                .call1((obj,))?
+                .cast_into::<PyBytes>()?
+                .as_bytes(),
+        )
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let bytes = retrieve_bytes_stream(reader)?;
+        self.pickle_loads
+            .bind(py)
+            .call1((PyBytes::new(py, &bytes),))
+    }
 }
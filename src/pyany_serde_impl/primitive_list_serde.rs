@@ -0,0 +1,163 @@
+use std::marker::PhantomData;
+use std::mem::size_of;
+
+use bytemuck::cast_slice;
+use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::asyncio::InvalidStateError;
+use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
+
+use crate::{
+    PyAnySerde, PyAnySerdeType,
+    common::PackedPrimitive,
+    communication::{
+        StreamReader, StreamWriter, append_usize, append_usize_stream, append_usize_vec,
+        insert_bytes, retrieve_usize, retrieve_usize_stream,
+    },
+};
+
+#[derive(Clone)]
+pub struct PrimitiveListSerde<T: PackedPrimitive> {
+    pub length: Option<usize>,
+    pub as_tuple: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T: PackedPrimitive> PrimitiveListSerde<T> {
+    pub fn new(length: Option<usize>, as_tuple: bool) -> Self {
+        PrimitiveListSerde {
+            length,
+            as_tuple,
+            _marker: PhantomData,
+        }
+    }
+
+    fn extract_items<'py>(&self, obj: &Bound<'py, PyAny>) -> PyResult<Vec<T>> {
+        let items = if let Ok(list) = obj.cast::<PyList>() {
+            list.iter()
+                .map(|item| T::extract_item(&item))
+                .collect::<PyResult<Vec<_>>>()?
+        } else {
+            obj.cast::<PyTuple>()?
+                .iter()
+                .map(|item| T::extract_item(&item))
+                .collect::<PyResult<Vec<_>>>()?
+        };
+        if let Some(length) = self.length
+            && items.len() != length
+        {
+            Err(PyValueError::new_err(format!(
+                "Expected a sequence of length {length} but received one of length {}",
+                items.len()
+            )))?
+        }
+        Ok(items)
+    }
+
+    fn get_obj<'py>(&self, py: Python<'py>, items: Vec<T>) -> PyResult<Bound<'py, PyAny>> {
+        let items = items
+            .into_iter()
+            .map(|item| item.into_item(py))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(if self.as_tuple {
+            PyTuple::new(py, items)?.into_any()
+        } else {
+            PyList::new(py, items)?.into_any()
+        })
+    }
+}
+
+pub fn get_primitive_list_serde(
+    items_serde_type: &PyAnySerdeType,
+    length: Option<usize>,
+    as_tuple: bool,
+) -> Option<Box<dyn PyAnySerde>> {
+    match items_serde_type {
+        PyAnySerdeType::BOOL {} => {
+            Some(Box::new(PrimitiveListSerde::<bool>::new(length, as_tuple)))
+        }
+        PyAnySerdeType::FLOAT {} => {
+            Some(Box::new(PrimitiveListSerde::<f64>::new(length, as_tuple)))
+        }
+        PyAnySerdeType::INT {} => Some(Box::new(PrimitiveListSerde::<i64>::new(length, as_tuple))),
+        _ => None,
+    }
+}
+
+impl<T: PackedPrimitive> PyAnySerde for PrimitiveListSerde<T> {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        mut offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let items = self.extract_items(obj)?;
+        if self.length.is_none() {
+            offset = append_usize(buf, offset, items.len());
+        }
+        Ok(insert_bytes(buf, offset, cast_slice::<T, u8>(&items)))
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        _start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let items = self.extract_items(obj)?;
+        if self.length.is_none() {
+            append_usize_vec(v, items.len());
+        }
+        v.extend_from_slice(cast_slice::<T, u8>(&items));
+        Ok(())
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        mut offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        let n_items = match self.length {
+            Some(length) => length,
+            None => {
+                let n_items;
+                (n_items, offset) = retrieve_usize(buf, offset)?;
+                n_items
+            }
+        };
+        let end = offset + n_items * size_of::<T>();
+        let items = T::retrieve_packed(&buf[offset..end])?;
+        Ok((self.get_obj(py, items)?, end))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let items = self.extract_items(obj)?;
+        if self.length.is_none() {
+            append_usize_stream(writer, items.len())?;
+        }
+        writer.write_all(cast_slice::<T, u8>(&items))
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let n_items = match self.length {
+            Some(length) => length,
+            None => retrieve_usize_stream(reader)?,
+        };
+        let len = n_items.checked_mul(size_of::<T>()).ok_or_else(|| {
+            InvalidStateError::new_err(format!(
+                "packed list of {n_items} items is too large to retrieve"
+            ))
+        })?;
+        let items = T::retrieve_packed(&reader.read_vec(len)?)?;
+        self.get_obj(py, items)
+    }
+}
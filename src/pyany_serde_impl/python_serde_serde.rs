@@ -3,7 +3,7 @@ use pyo3::types::PyBytes;
 use pyo3::{intern, prelude::*};
 use std::os::raw::c_char;
 
-use crate::PyAnySerde;
+use crate::{PyAnySerde, communication::StreamWriter};
 
 #[derive(Clone)]
 pub struct PythonSerdeSerde {
@@ -72,4 +72,18 @@ impl PyAnySerde for PythonSerdeSerde {
             .call_method1(intern!(py, "retrieve"), (memory_view, offset))?
             .extract()
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        writer.write_all(
+            self.python_serde
+                .bind(obj.py())
+                .call_method1(intern!(obj.py(), "get_bytes"), (writer.position(), obj))?
+                .cast::<PyBytes>()?
+                .as_bytes(),
+        )
+    }
 }
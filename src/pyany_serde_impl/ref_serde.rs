@@ -0,0 +1,175 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use pyo3::exceptions::{PyRecursionError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::communication::{StreamReader, StreamWriter};
+use crate::{FieldSerdeTypeMap, PyAnySerde, PyAnySerdeType};
+
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+// The definitions of a DEFINITIONS serde type. Scopes are nested lexically, so a REF inside a
+// DEFINITIONS can refer to the definitions of any DEFINITIONS it is (transitively) contained in.
+struct DefinitionsScope {
+    definitions: HashMap<String, PyAnySerdeType>,
+    max_depth: usize,
+    parent: Option<Rc<DefinitionsScope>>,
+}
+
+impl DefinitionsScope {
+    fn resolve(self: &Rc<Self>, name: &str) -> Option<Rc<DefinitionsScope>> {
+        if self.definitions.contains_key(name) {
+            Some(self.clone())
+        } else {
+            self.parent.as_ref().and_then(|parent| parent.resolve(name))
+        }
+    }
+}
+
+thread_local! {
+    // The scopes (and REF depth) which are active while converting a PyAnySerdeType into a
+    // PyAnySerde. The conversion is a plain TryFrom, so this is how REF serdes find the
+    // definitions they refer to.
+    static ACTIVE_SCOPES: RefCell<Vec<(Rc<DefinitionsScope>, usize)>> = const { RefCell::new(Vec::new()) };
+}
+
+// Pops the active scope when dropped, so that the stack stays balanced when a conversion fails
+struct ActiveScopeGuard;
+
+impl ActiveScopeGuard {
+    fn push(scope: Rc<DefinitionsScope>, depth: usize) -> Self {
+        ACTIVE_SCOPES.with_borrow_mut(|scopes| scopes.push((scope, depth)));
+        ActiveScopeGuard
+    }
+}
+
+impl Drop for ActiveScopeGuard {
+    fn drop(&mut self) {
+        ACTIVE_SCOPES.with_borrow_mut(|scopes| scopes.pop());
+    }
+}
+
+fn get_active_scope() -> Option<(Rc<DefinitionsScope>, usize)> {
+    ACTIVE_SCOPES.with_borrow(|scopes| scopes.last().cloned())
+}
+
+// DEFINITIONS adds nothing to the serialized format, so its serde is just the serde of the root
+// serde type. Each definition is also converted once here so that invalid definitions (including
+// REFs to unknown names) are reported immediately instead of the first time they are used.
+pub fn get_definitions_serde(
+    definitions: &FieldSerdeTypeMap,
+    root_serde_type: &PyAnySerdeType,
+    max_depth: usize,
+) -> PyResult<Box<dyn PyAnySerde>> {
+    let (parent, depth) = match get_active_scope() {
+        Some((parent, depth)) => (Some(parent), depth),
+        None => (None, 0),
+    };
+    let scope = Rc::new(DefinitionsScope {
+        definitions: definitions
+            .iter()
+            .map(|(name, serde_type)| (name.clone(), serde_type.clone()))
+            .collect(),
+        max_depth,
+        parent,
+    });
+    let _guard = ActiveScopeGuard::push(scope, depth);
+    for (_, serde_type) in definitions.iter() {
+        Box::<dyn PyAnySerde>::try_from(serde_type)?;
+    }
+    root_serde_type.try_into()
+}
+
+// The serde of the definition is only created when it is first used, which is what allows a
+// definition to (indirectly) contain a REF to itself. Each REF serde keeps its own copy of the
+// definition's serde, so the nesting of REF serdes follows the nesting of the data, and this
+// nesting is limited by the max_depth of the DEFINITIONS.
+#[derive(Clone)]
+pub struct RefSerde {
+    name: String,
+    scope: Rc<DefinitionsScope>,
+    depth: usize,
+    serde_option: Option<Box<dyn PyAnySerde>>,
+}
+
+impl RefSerde {
+    pub fn new(name: String) -> PyResult<Self> {
+        let Some((active_scope, depth)) = get_active_scope() else {
+            Err(PyValueError::new_err(format!(
+                "REF {name} must be contained in a DEFINITIONS which defines it"
+            )))?
+        };
+        let scope = active_scope.resolve(&name).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "REF {name} does not refer to the name of a definition in any DEFINITIONS containing it"
+            ))
+        })?;
+        Ok(RefSerde {
+            name,
+            scope,
+            depth,
+            serde_option: None,
+        })
+    }
+
+    fn get_serde(&mut self) -> PyResult<&mut Box<dyn PyAnySerde>> {
+        if self.serde_option.is_none() {
+            if self.depth >= self.scope.max_depth {
+                Err(PyRecursionError::new_err(format!(
+                    "REF {} exceeded the max_depth of {} of its DEFINITIONS",
+                    self.name, self.scope.max_depth
+                )))?
+            }
+            let _guard = ActiveScopeGuard::push(self.scope.clone(), self.depth + 1);
+            self.serde_option = Some((&self.scope.definitions[&self.name]).try_into()?);
+        }
+        Ok(self.serde_option.as_mut().unwrap())
+    }
+}
+
+impl PyAnySerde for RefSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        self.get_serde()?.append(buf, offset, obj)
+    }
+
+    fn append_vec<'py>(
+        &mut self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        self.get_serde()?.append_vec(v, start_addr, obj)
+    }
+
+    fn retrieve<'py>(
+        &mut self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+    ) -> PyResult<(Bound<'py, PyAny>, usize)> {
+        self.get_serde()?.retrieve(py, buf, offset)
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        self.get_serde()?.append_stream(writer, obj)
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.get_serde()?.retrieve_stream(py, reader)
+    }
+}
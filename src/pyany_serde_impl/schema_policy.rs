@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::mem::size_of;
+
+use enum_kinds::EnumKind;
+use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::asyncio::InvalidStateError;
+use pyo3::types::PyType;
+use pyo3::{PyTypeInfo, intern, prelude::*};
+use strum_macros::{Display, EnumIter};
+
+use crate::{
+    PyAnySerde,
+    communication::{
+        StreamReader, StreamWriter, append_bytes, append_bytes_stream, append_bytes_vec,
+        append_usize, append_usize_stream, append_usize_vec, append_varint, append_varint_stream,
+        append_varint_vec, retrieve_string, retrieve_string_stream, retrieve_usize,
+        retrieve_usize_stream, retrieve_varint, retrieve_varint_stream,
+    },
+};
+
+#[pyclass(from_py_object)]
+#[derive(Clone, Debug, Display, EnumKind)]
+#[enum_kind(SchemaPolicyKind, derive(Display, EnumIter))]
+pub enum SchemaPolicy {
+    FIXED {},
+    #[pyo3(constructor = (field_defaults = HashMap::new()))]
+    VERSIONED {
+        field_defaults: HashMap<String, Py<PyAny>>,
+    },
+}
+
+impl SchemaPolicyKind {
+    pub fn type_object<'py>(self, py: Python<'py>) -> Bound<'py, PyType> {
+        match self {
+            SchemaPolicyKind::FIXED => SchemaPolicy_FIXED::type_object(py),
+            SchemaPolicyKind::VERSIONED => SchemaPolicy_VERSIONED::type_object(py),
+        }
+    }
+    pub fn from_type_object<'py>(to: &Bound<'py, PyType>) -> PyResult<Option<SchemaPolicyKind>> {
+        let py = to.py();
+        if to.eq(SchemaPolicy::type_object(py))? {
+            return Ok(None);
+        }
+        if to.eq(SchemaPolicy_FIXED::type_object(py))? {
+            return Ok(Some(SchemaPolicyKind::FIXED));
+        }
+        if to.eq(SchemaPolicy_VERSIONED::type_object(py))? {
+            return Ok(Some(SchemaPolicyKind::VERSIONED));
+        }
+        Err(PyValueError::new_err(format!(
+            "Unexpected value PyType {}",
+            to.repr()?
+        )))
+    }
+}
+
+// The serde-side counterpart of SchemaPolicy, shared by the TYPEDDICT and DATACLASS serdes. The
+// fixed policy writes the fields back to back, so the reader must use exactly the same fields as
+// the writer. The versioned policy writes a field table instead: the number of fields present
+// followed by each field's name and length-prefixed value. Readers look fields up by name, skip
+// fields they don't know about using the length, and fill fields missing from the table from the
+// declared defaults.
+#[derive(Clone)]
+pub enum InternalSchemaPolicy {
+    Fixed,
+    Versioned {
+        field_idx_map: HashMap<String, usize>,
+        // For each field, the default used when it is missing from the field table
+        field_default_list: Vec<Option<Py<PyAny>>>,
+    },
+}
+
+impl InternalSchemaPolicy {
+    pub fn new(schema_policy: &SchemaPolicy, fields: &[String], container: &str) -> PyResult<Self> {
+        Ok(match schema_policy {
+            SchemaPolicy::FIXED {} => InternalSchemaPolicy::Fixed,
+            SchemaPolicy::VERSIONED { field_defaults } => {
+                for field in field_defaults.keys() {
+                    if !fields.contains(field) {
+                        Err(PyValueError::new_err(format!(
+                            "{container} field default {field} provided in SchemaPolicy_VERSIONED is not a field of the {container}"
+                        )))?
+                    }
+                }
+                InternalSchemaPolicy::Versioned {
+                    field_idx_map: fields
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, field)| (field.clone(), idx))
+                        .collect(),
+                    field_default_list: fields
+                        .iter()
+                        .map(|field| field_defaults.get(field).cloned())
+                        .collect(),
+                }
+            }
+        })
+    }
+
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, InternalSchemaPolicy::Fixed)
+    }
+
+    // Writes the header of the versioned field table, which must be followed by n_fields calls to
+    // append_field.
+    pub fn append_header(&self, buf: &mut [u8], offset: usize, n_fields: usize) -> usize {
+        append_varint(buf, offset, n_fields)
+    }
+
+    pub fn append_header_vec(&self, v: &mut Vec<u8>, n_fields: usize) {
+        append_varint_vec(v, n_fields)
+    }
+
+    pub fn append_header_stream(&self, writer: &mut StreamWriter, n_fields: usize) -> PyResult<()> {
+        append_varint_stream(writer, n_fields)
+    }
+
+    pub fn append_field<'py>(
+        &self,
+        buf: &mut [u8],
+        offset: usize,
+        field: &str,
+        pyany_serde: &mut Box<dyn PyAnySerde>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let len_offset = append_bytes(buf, offset, field.as_bytes());
+        let start = len_offset + size_of::<usize>();
+        let end = pyany_serde.append(buf, start, obj)?;
+        append_usize(buf, len_offset, end - start);
+        Ok(end)
+    }
+
+    pub fn append_field_vec<'py>(
+        &self,
+        v: &mut Vec<u8>,
+        start_addr: Option<usize>,
+        field: &str,
+        pyany_serde: &mut Box<dyn PyAnySerde>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        append_bytes_vec(v, field.as_bytes());
+        let len_offset = v.len();
+        append_usize_vec(v, 0);
+        let start = v.len();
+        pyany_serde.append_vec(v, start_addr, obj)?;
+        let len = v.len() - start;
+        v[len_offset..start].copy_from_slice(&len.to_ne_bytes());
+        Ok(())
+    }
+
+    // The length of the field's value has to be written before the value, so the value is
+    // serialized in memory (with the alignment it will have in the stream) before it is written
+    pub fn append_field_stream<'py>(
+        &self,
+        writer: &mut StreamWriter,
+        field: &str,
+        pyany_serde: &mut Box<dyn PyAnySerde>,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        append_bytes_stream(writer, field.as_bytes())?;
+        let mut value = Vec::new();
+        pyany_serde.append_vec(
+            &mut value,
+            Some(writer.position() + size_of::<usize>()),
+            obj,
+        )?;
+        append_usize_stream(writer, value.len())?;
+        writer.write_all(&value)
+    }
+
+    // Reads the versioned field table, returning the value of each field in the order of the
+    // serde list. Fields missing from the table are filled using a copy of their default if they
+    // have one, and are None otherwise.
+    pub fn retrieve_fields<'py, K>(
+        &self,
+        py: Python<'py>,
+        buf: &[u8],
+        offset: usize,
+        serde_kv_list: &mut [(K, Box<dyn PyAnySerde>)],
+        container: &str,
+    ) -> PyResult<(Vec<Option<Bound<'py, PyAny>>>, usize)> {
+        let field_idx_map = self.get_field_idx_map()?;
+        let (n_fields, mut offset) = retrieve_varint(buf, offset)?;
+        let mut items = vec![None; serde_kv_list.len()];
+        for _ in 0..n_fields {
+            let field;
+            (field, offset) = retrieve_string(buf, offset)?;
+            let len;
+            (len, offset) = retrieve_usize(buf, offset)?;
+            let end = offset + len;
+            if let Some(&idx) = field_idx_map.get(&field) {
+                let (item, item_end) = serde_kv_list[idx].1.retrieve(py, buf, offset)?;
+                if item_end != end {
+                    Err(InvalidStateError::new_err(format!(
+                        "field {field} of serialized {container} has length {len}, but {} bytes were read (did the type of the field change?)",
+                        item_end - offset
+                    )))?
+                }
+                items[idx] = Some(item);
+            }
+            offset = end;
+        }
+        self.fill_defaults(py, &mut items)?;
+        Ok((items, offset))
+    }
+
+    pub fn retrieve_fields_stream<'py, K>(
+        &self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+        serde_kv_list: &mut [(K, Box<dyn PyAnySerde>)],
+        container: &str,
+    ) -> PyResult<Vec<Option<Bound<'py, PyAny>>>> {
+        let field_idx_map = self.get_field_idx_map()?;
+        let n_fields = retrieve_varint_stream(reader)?;
+        let mut items = vec![None; serde_kv_list.len()];
+        for _ in 0..n_fields {
+            let field = retrieve_string_stream(reader)?;
+            let len = retrieve_usize_stream(reader)?;
+            let Some(&idx) = field_idx_map.get(&field) else {
+                reader.skip(len)?;
+                continue;
+            };
+            let start = reader.position();
+            let item = serde_kv_list[idx].1.retrieve_stream(py, reader)?;
+            if reader.position() - start != len {
+                Err(InvalidStateError::new_err(format!(
+                    "field {field} of serialized {container} has length {len}, but {} bytes were read (did the type of the field change?)",
+                    reader.position() - start
+                )))?
+            }
+            items[idx] = Some(item);
+        }
+        self.fill_defaults(py, &mut items)?;
+        Ok(items)
+    }
+
+    fn get_field_idx_map(&self) -> PyResult<&HashMap<String, usize>> {
+        match self {
+            InternalSchemaPolicy::Versioned { field_idx_map, .. } => Ok(field_idx_map),
+            InternalSchemaPolicy::Fixed => Err(InvalidStateError::new_err(
+                "retrieve_fields called with the FIXED schema policy",
+            )),
+        }
+    }
+
+    // Fills the fields missing from the table using a copy of their default, if they have one
+    fn fill_defaults<'py>(
+        &self,
+        py: Python<'py>,
+        items: &mut [Option<Bound<'py, PyAny>>],
+    ) -> PyResult<()> {
+        let InternalSchemaPolicy::Versioned {
+            field_default_list, ..
+        } = self
+        else {
+            return Ok(());
+        };
+        let deepcopy = py
+            .import(intern!(py, "copy"))?
+            .getattr(intern!(py, "deepcopy"))?;
+        for (item, default_option) in items.iter_mut().zip(field_default_list.iter()) {
+            if item.is_none()
+                && let Some(default) = default_option
+            {
+                *item = Some(deepcopy.call1((default,))?);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn missing_field_err(field: &str, container: &str) -> PyErr {
+    InvalidStateError::new_err(format!(
+        "field {field} is missing from the serialized {container} and has no default in SchemaPolicy_VERSIONED"
+    ))
+}
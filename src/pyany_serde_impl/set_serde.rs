@@ -2,7 +2,10 @@ use pyo3::prelude::*;
 use pyo3::types::PySet;
 
 use crate::{
-    communication::{append_usize, append_usize_vec, retrieve_usize},
+    communication::{
+        append_usize, append_usize_stream, append_usize_vec, retrieve_usize, retrieve_usize_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -55,4 +58,30 @@ impl PyAnySerde for SetSerde {
         }
         Ok((set.into_any(), offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let set = obj.cast::<PySet>()?;
+        append_usize_stream(writer, set.len())?;
+        for item in set.iter() {
+            self.items_serde.append_stream(writer, &item)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let set = PySet::empty(py)?;
+        let n_items = retrieve_usize_stream(reader)?;
+        for _ in 0..n_items {
+            set.add(self.items_serde.retrieve_stream(py, reader)?)?;
+        }
+        Ok(set.into_any())
+    }
 }
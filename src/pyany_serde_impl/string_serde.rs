@@ -3,7 +3,10 @@ use pyo3::types::PyString;
 use pyo3::{exceptions::PyUnicodeDecodeError, prelude::*};
 
 use crate::{
-    communication::{append_bytes, append_bytes_vec, retrieve_bytes},
+    communication::{
+        append_bytes, append_bytes_stream, append_bytes_vec, retrieve_bytes, retrieve_bytes_stream,
+        StreamReader, StreamWriter,
+    },
     PyAnySerde,
 };
 
@@ -51,4 +54,26 @@ impl PyAnySerde for StringSerde {
             offset,
         ))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        append_bytes_stream(writer, obj.cast::<PyString>()?.to_str()?.as_bytes())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let obj_bytes = retrieve_bytes_stream(reader)?;
+        Ok(PyString::new(
+            py,
+            str::from_utf8(&obj_bytes)
+                .map_err(|e| PyUnicodeDecodeError::new_err_from_utf8(py, &obj_bytes, e))?,
+        )
+        .into_any())
+    }
 }
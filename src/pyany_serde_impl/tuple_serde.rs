@@ -2,6 +2,7 @@ use pyo3::prelude::*;
 use pyo3::types::PyTuple;
 
 use crate::PyAnySerde;
+use crate::communication::{StreamReader, StreamWriter};
 
 #[derive(Clone)]
 pub struct TupleSerde {
@@ -48,4 +49,28 @@ impl PyAnySerde for TupleSerde {
         }
         Ok((PyTuple::new(py, tuple_vec)?.into_any(), offset))
     }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let tuple = obj.cast::<PyTuple>()?;
+        for (pyany_serde, item) in self.item_serdes.iter_mut().zip(tuple.iter()) {
+            pyany_serde.append_stream(writer, &item)?;
+        }
+        Ok(())
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut tuple_vec = Vec::with_capacity(self.item_serdes.len());
+        for pyany_serde in self.item_serdes.iter_mut() {
+            tuple_vec.push(pyany_serde.retrieve_stream(py, reader)?);
+        }
+        Ok(PyTuple::new(py, tuple_vec)?.into_any())
+    }
 }
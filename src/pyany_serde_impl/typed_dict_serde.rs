@@ -1,11 +1,157 @@
+use std::collections::HashSet;
+
+use pyo3::exceptions::{PyKeyError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyString};
 
-use crate::PyAnySerde;
+use crate::{
+    PyAnySerde,
+    communication::{StreamReader, StreamWriter, insert_bytes},
+};
+
+use super::extra_policy::{Extras, InternalExtraPolicy, RetrievedExtras};
+use super::schema_policy::{InternalSchemaPolicy, SchemaPolicy, missing_field_err};
+
+// Whether the key with the given bit in the presence bitmap is present (keys which aren't optional
+// are always present)
+fn is_present(bitmap: &[u8], bit_idx_option: &Option<usize>) -> bool {
+    match bit_idx_option {
+        Some(bit_idx) => bitmap[bit_idx / 8] & (1 << (bit_idx % 8)) != 0,
+        None => true,
+    }
+}
 
 #[derive(Clone)]
 pub struct TypedDictSerde {
     pub serde_kv_list: Vec<(Py<PyString>, Box<dyn PyAnySerde>)>,
+    // For each entry of serde_kv_list, the index of its bit in the presence bitmap if the key is optional
+    optional_bit_idx_list: Vec<Option<usize>>,
+    n_bitmap_bytes: usize,
+    keys: HashSet<String>,
+    extra_policy: InternalExtraPolicy,
+    schema_policy: InternalSchemaPolicy,
+}
+
+impl TypedDictSerde {
+    pub fn new(
+        serde_kv_list: Vec<(Py<PyString>, Box<dyn PyAnySerde>)>,
+        optional_keys: &[String],
+        extra_policy: InternalExtraPolicy,
+        schema_policy: &SchemaPolicy,
+    ) -> PyResult<Self> {
+        Python::attach(|py| {
+            let keys = serde_kv_list
+                .iter()
+                .map(|(key, _)| key.bind(py).to_str().map(|key| key.to_owned()))
+                .collect::<PyResult<Vec<_>>>()?;
+            for optional_key in optional_keys.iter() {
+                if !keys.contains(optional_key) {
+                    Err(PyValueError::new_err(format!(
+                        "TYPEDDICT optional key {optional_key} is not a key of key_serde_type_dict"
+                    )))?
+                }
+            }
+            let mut n_optional_keys = 0;
+            let optional_bit_idx_list = keys
+                .iter()
+                .map(|key| {
+                    optional_keys.contains(key).then(|| {
+                        n_optional_keys += 1;
+                        n_optional_keys - 1
+                    })
+                })
+                .collect();
+            let schema_policy = InternalSchemaPolicy::new(schema_policy, &keys, "TYPEDDICT")?;
+            Ok(TypedDictSerde {
+                serde_kv_list,
+                optional_bit_idx_list,
+                n_bitmap_bytes: n_optional_keys.div_ceil(8),
+                keys: keys.into_iter().collect(),
+                extra_policy,
+                schema_policy,
+            })
+        })
+    }
+
+    fn get_items<'py>(&self, obj: &Bound<'py, PyAny>) -> PyResult<Vec<Option<Bound<'py, PyAny>>>> {
+        let py = obj.py();
+        self.serde_kv_list
+            .iter()
+            .zip(self.optional_bit_idx_list.iter())
+            .map(
+                |((key, _), bit_idx_option)| match obj.get_item(key.bind(py)) {
+                    Ok(item) => Ok(Some(item)),
+                    Err(err)
+                        if bit_idx_option.is_some() && err.is_instance_of::<PyKeyError>(py) =>
+                    {
+                        Ok(None)
+                    }
+                    Err(err) => Err(err),
+                },
+            )
+            .collect()
+    }
+
+    fn get_extras<'py>(&self, obj: &Bound<'py, PyAny>) -> PyResult<Extras<'py>> {
+        if self.extra_policy.is_ignore() {
+            return Ok(Vec::new());
+        }
+        Ok(obj
+            .cast::<PyDict>()?
+            .iter()
+            .filter(|(key, _)| {
+                !key.cast::<PyString>()
+                    .ok()
+                    .and_then(|key| key.to_str().ok().map(|key| self.keys.contains(key)))
+                    .unwrap_or(false)
+            })
+            .collect())
+    }
+
+    fn get_bitmap(&self, items: &[Option<Bound<'_, PyAny>>]) -> Vec<u8> {
+        let mut bitmap = vec![0_u8; self.n_bitmap_bytes];
+        for (item_option, bit_idx_option) in items.iter().zip(self.optional_bit_idx_list.iter()) {
+            if let (Some(_), Some(bit_idx)) = (item_option, bit_idx_option) {
+                bitmap[bit_idx / 8] |= 1 << (bit_idx % 8);
+            }
+        }
+        bitmap
+    }
+
+    // Optional keys are simply left out of the field table when they are absent
+    fn get_versioned_kv_list<'py>(
+        &self,
+        py: Python<'py>,
+        items: Vec<Option<Bound<'py, PyAny>>>,
+    ) -> PyResult<Vec<(Py<PyString>, Bound<'py, PyAny>)>> {
+        let mut kv_list = Vec::with_capacity(self.serde_kv_list.len());
+        for (((key, _), bit_idx_option), item_option) in self
+            .serde_kv_list
+            .iter()
+            .zip(self.optional_bit_idx_list.iter())
+            .zip(items)
+        {
+            match item_option {
+                Some(item) => kv_list.push((key.clone_ref(py), item)),
+                None if bit_idx_option.is_some() => (),
+                None => Err(missing_field_err(key.bind(py).to_str()?, "TYPEDDICT"))?,
+            }
+        }
+        Ok(kv_list)
+    }
+
+    fn get_dict<'py>(
+        &self,
+        py: Python<'py>,
+        kv_list: Vec<(Py<PyString>, Bound<'py, PyAny>)>,
+        extras: RetrievedExtras<'py>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let dict = PyDict::from_sequence(&kv_list.into_pyobject(py)?)?;
+        for (key, value) in extras.into_iter() {
+            dict.set_item(key, value)?;
+        }
+        Ok(dict.into_any())
+    }
 }
 
 impl PyAnySerde for TypedDictSerde {
@@ -15,10 +161,32 @@ impl PyAnySerde for TypedDictSerde {
         mut offset: usize,
         obj: &Bound<'py, PyAny>,
     ) -> PyResult<usize> {
-        for (key, pyany_serde) in self.serde_kv_list.iter_mut() {
-            offset = pyany_serde.append(buf, offset, &obj.get_item(key.bind(obj.py()))?)?;
+        let items = self.get_items(obj)?;
+        if self.schema_policy.is_fixed() {
+            offset = insert_bytes(buf, offset, &self.get_bitmap(&items));
+            for ((_, pyany_serde), item_option) in self.serde_kv_list.iter_mut().zip(items.iter()) {
+                if let Some(item) = item_option {
+                    offset = pyany_serde.append(buf, offset, item)?;
+                }
+            }
+        } else {
+            let n_items = items.iter().filter(|item| item.is_some()).count();
+            offset = self.schema_policy.append_header(buf, offset, n_items);
+            for ((key, pyany_serde), item_option) in self.serde_kv_list.iter_mut().zip(items.iter())
+            {
+                if let Some(item) = item_option {
+                    offset = self.schema_policy.append_field(
+                        buf,
+                        offset,
+                        key.bind(obj.py()).to_str()?,
+                        pyany_serde,
+                        item,
+                    )?;
+                }
+            }
         }
-        Ok(offset)
+        let extras = self.get_extras(obj)?;
+        self.extra_policy.append(buf, offset, &extras, "TYPEDDICT")
     }
 
     fn append_vec<'py>(
@@ -27,10 +195,33 @@ impl PyAnySerde for TypedDictSerde {
         start_addr: Option<usize>,
         obj: &Bound<'py, PyAny>,
     ) -> PyResult<()> {
-        for (key, pyany_serde) in self.serde_kv_list.iter_mut() {
-            pyany_serde.append_vec(v, start_addr, &obj.get_item(key.bind(obj.py()))?)?;
+        let items = self.get_items(obj)?;
+        if self.schema_policy.is_fixed() {
+            v.extend_from_slice(&self.get_bitmap(&items));
+            for ((_, pyany_serde), item_option) in self.serde_kv_list.iter_mut().zip(items.iter()) {
+                if let Some(item) = item_option {
+                    pyany_serde.append_vec(v, start_addr, item)?;
+                }
+            }
+        } else {
+            let n_items = items.iter().filter(|item| item.is_some()).count();
+            self.schema_policy.append_header_vec(v, n_items);
+            for ((key, pyany_serde), item_option) in self.serde_kv_list.iter_mut().zip(items.iter())
+            {
+                if let Some(item) = item_option {
+                    self.schema_policy.append_field_vec(
+                        v,
+                        start_addr,
+                        key.bind(obj.py()).to_str()?,
+                        pyany_serde,
+                        item,
+                    )?;
+                }
+            }
         }
-        Ok(())
+        let extras = self.get_extras(obj)?;
+        self.extra_policy
+            .append_vec(v, start_addr, &extras, "TYPEDDICT")
     }
 
     fn retrieve<'py>(
@@ -39,15 +230,102 @@ impl PyAnySerde for TypedDictSerde {
         buf: &[u8],
         mut offset: usize,
     ) -> PyResult<(Bound<'py, PyAny>, usize)> {
-        let mut kv_list = Vec::with_capacity(self.serde_kv_list.len());
-        for (key, pyany_serde) in self.serde_kv_list.iter_mut() {
-            let item;
-            (item, offset) = pyany_serde.retrieve(py, buf, offset)?;
-            kv_list.push((key.clone_ref(py), item));
+        let kv_list = if self.schema_policy.is_fixed() {
+            let mut kv_list = Vec::with_capacity(self.serde_kv_list.len());
+            let bitmap = &buf[offset..offset + self.n_bitmap_bytes];
+            offset += self.n_bitmap_bytes;
+            for ((key, pyany_serde), bit_idx_option) in self
+                .serde_kv_list
+                .iter_mut()
+                .zip(self.optional_bit_idx_list.iter())
+            {
+                if !is_present(bitmap, bit_idx_option) {
+                    continue;
+                }
+                let item;
+                (item, offset) = pyany_serde.retrieve(py, buf, offset)?;
+                kv_list.push((key.clone_ref(py), item));
+            }
+            kv_list
+        } else {
+            let items;
+            (items, offset) = self.schema_policy.retrieve_fields(
+                py,
+                buf,
+                offset,
+                &mut self.serde_kv_list,
+                "TYPEDDICT",
+            )?;
+            self.get_versioned_kv_list(py, items)?
+        };
+        let extras;
+        (extras, offset) = self.extra_policy.retrieve(py, buf, offset)?;
+        Ok((self.get_dict(py, kv_list, extras)?, offset))
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let items = self.get_items(obj)?;
+        if self.schema_policy.is_fixed() {
+            writer.write_all(&self.get_bitmap(&items))?;
+            for ((_, pyany_serde), item_option) in self.serde_kv_list.iter_mut().zip(items.iter()) {
+                if let Some(item) = item_option {
+                    pyany_serde.append_stream(writer, item)?;
+                }
+            }
+        } else {
+            let n_items = items.iter().filter(|item| item.is_some()).count();
+            self.schema_policy.append_header_stream(writer, n_items)?;
+            for ((key, pyany_serde), item_option) in self.serde_kv_list.iter_mut().zip(items.iter())
+            {
+                if let Some(item) = item_option {
+                    self.schema_policy.append_field_stream(
+                        writer,
+                        key.bind(obj.py()).to_str()?,
+                        pyany_serde,
+                        item,
+                    )?;
+                }
+            }
         }
-        Ok((
-            PyDict::from_sequence(&kv_list.into_pyobject(py)?)?.into_any(),
-            offset,
-        ))
+        let extras = self.get_extras(obj)?;
+        self.extra_policy
+            .append_stream(writer, &extras, "TYPEDDICT")
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let kv_list = if self.schema_policy.is_fixed() {
+            let mut kv_list = Vec::with_capacity(self.serde_kv_list.len());
+            let mut bitmap = vec![0; self.n_bitmap_bytes];
+            reader.read_into(&mut bitmap)?;
+            for ((key, pyany_serde), bit_idx_option) in self
+                .serde_kv_list
+                .iter_mut()
+                .zip(self.optional_bit_idx_list.iter())
+            {
+                if !is_present(&bitmap, bit_idx_option) {
+                    continue;
+                }
+                kv_list.push((key.clone_ref(py), pyany_serde.retrieve_stream(py, reader)?));
+            }
+            kv_list
+        } else {
+            let items = self.schema_policy.retrieve_fields_stream(
+                py,
+                reader,
+                &mut self.serde_kv_list,
+                "TYPEDDICT",
+            )?;
+            self.get_versioned_kv_list(py, items)?
+        };
+        let extras = self.extra_policy.retrieve_stream(py, reader)?;
+        self.get_dict(py, kv_list, extras)
     }
 }
@@ -3,8 +3,11 @@ use pyo3::prelude::*;
 use pyo3::types::PyFunction;
 
 use crate::{
-    communication::{append_usize, append_usize_vec, retrieve_usize},
     PyAnySerde,
+    communication::{
+        StreamReader, StreamWriter, append_discriminant, append_discriminant_stream,
+        append_discriminant_vec, retrieve_discriminant, retrieve_discriminant_stream,
+    },
 };
 
 #[derive(Clone)]
@@ -13,25 +16,42 @@ pub struct UnionSerde {
     pub option_choice_fn: Py<PyFunction>,
 }
 
-impl PyAnySerde for UnionSerde {
-    fn append<'py>(
-        &mut self,
-        buf: &mut [u8],
-        offset: usize,
-        obj: &Bound<'py, PyAny>,
-    ) -> PyResult<usize> {
+impl UnionSerde {
+    fn choose_serde_idx<'py>(&self, obj: &Bound<'py, PyAny>) -> PyResult<usize> {
         let serde_idx = self
             .option_choice_fn
             .bind(obj.py())
             .call1((obj,))?
             .extract::<usize>()?;
-        let offset = append_usize(buf, offset, serde_idx);
-        let pyany_serde = self.option_serdes.get_mut(serde_idx).ok_or_else(|| {
+        if serde_idx >= self.option_serdes.len() {
+            Err(InvalidStateError::new_err(format!(
+                "Serde choice function returned {serde_idx} which is not a valid choice index (there are {} options)",
+                self.option_serdes.len()
+            )))?
+        }
+        Ok(serde_idx)
+    }
+
+    fn get_option_serde(&mut self, serde_idx: usize) -> PyResult<&mut Box<dyn PyAnySerde>> {
+        let n_options = self.option_serdes.len();
+        self.option_serdes.get_mut(serde_idx).ok_or_else(|| {
             InvalidStateError::new_err(format!(
-                "Serde choice function returned {serde_idx} which is not a valid choice index"
+                "Deserialized serde idx {serde_idx} which is not a valid choice index (there are {n_options} options)"
             ))
-        })?;
-        pyany_serde.append(buf, offset, obj)
+        })
+    }
+}
+
+impl PyAnySerde for UnionSerde {
+    fn append<'py>(
+        &mut self,
+        buf: &mut [u8],
+        offset: usize,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<usize> {
+        let serde_idx = self.choose_serde_idx(obj)?;
+        let offset = append_discriminant(buf, offset, serde_idx, self.option_serdes.len());
+        self.option_serdes[serde_idx].append(buf, offset, obj)
     }
 
     fn append_vec<'py>(
@@ -40,18 +60,9 @@ impl PyAnySerde for UnionSerde {
         start_addr: Option<usize>,
         obj: &Bound<'py, PyAny>,
     ) -> PyResult<()> {
-        let serde_idx = self
-            .option_choice_fn
-            .bind(obj.py())
-            .call1((obj,))?
-            .extract::<usize>()?;
-        append_usize_vec(v, serde_idx);
-        let pyany_serde = self.option_serdes.get_mut(serde_idx).ok_or_else(|| {
-            InvalidStateError::new_err(format!(
-                "Serde choice function returned {serde_idx} which is not a valid choice index"
-            ))
-        })?;
-        pyany_serde.append_vec(v, start_addr, obj)
+        let serde_idx = self.choose_serde_idx(obj)?;
+        append_discriminant_vec(v, serde_idx, self.option_serdes.len());
+        self.option_serdes[serde_idx].append_vec(v, start_addr, obj)
     }
 
     fn retrieve<'py>(
@@ -60,12 +71,27 @@ impl PyAnySerde for UnionSerde {
         buf: &[u8],
         offset: usize,
     ) -> PyResult<(Bound<'py, PyAny>, usize)> {
-        let (serde_idx, offset) = retrieve_usize(buf, offset)?;
-        let pyany_serde = self.option_serdes.get_mut(serde_idx).ok_or_else(|| {
-            InvalidStateError::new_err(format!(
-                "Deserialized serde idx {serde_idx} which is not a valid choice index"
-            ))
-        })?;
-        pyany_serde.retrieve(py, buf, offset)
+        let (serde_idx, offset) = retrieve_discriminant(buf, offset, self.option_serdes.len())?;
+        self.get_option_serde(serde_idx)?.retrieve(py, buf, offset)
+    }
+
+    fn append_stream<'py>(
+        &mut self,
+        writer: &mut StreamWriter,
+        obj: &Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        let serde_idx = self.choose_serde_idx(obj)?;
+        append_discriminant_stream(writer, serde_idx, self.option_serdes.len())?;
+        self.option_serdes[serde_idx].append_stream(writer, obj)
+    }
+
+    fn retrieve_stream<'py>(
+        &mut self,
+        py: Python<'py>,
+        reader: &mut StreamReader,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let serde_idx = retrieve_discriminant_stream(reader, self.option_serdes.len())?;
+        self.get_option_serde(serde_idx)?
+            .retrieve_stream(py, reader)
     }
 }
@@ -1,13 +1,15 @@
 use enum_kinds::EnumKind;
 use pyo3::exceptions::PyValueError;
 use pyo3::types::PyGenericAlias;
-use pyo3::types::{PyFunction, PyType};
-use pyo3::{prelude::*, PyTypeInfo};
-use std::collections::BTreeMap;
+use pyo3::types::{PyDict, PyFunction, PyType};
+use pyo3::{PyTypeInfo, prelude::*};
 use strum_macros::{Display, EnumIter};
 
-use crate::common::NumpyDtype;
-use crate::pyany_serde_impl::{InitStrategy, NumpySerdeConfig};
+use crate::common::{CompressionCodec, NumpyDtype};
+use crate::pyany_serde_impl::{
+    DEFAULT_MAX_DEPTH, DEFAULT_MIN_COMPRESSED_SIZE, ExtraPolicy, InitStrategy, NumpySerdeConfig,
+    SchemaPolicy,
+};
 
 #[pyclass(from_py_object)]
 #[derive(Debug, Clone, Display, EnumKind)]
@@ -15,22 +17,70 @@ use crate::pyany_serde_impl::{InitStrategy, NumpySerdeConfig};
 pub enum PyAnySerdeType {
     BOOL {},
     BYTES {},
+    CHECKSUMMED {
+        value_serde_type: Py<PyAnySerdeType>,
+    },
+    #[pyo3(constructor = (items_serde_type, as_columns = false))]
+    COLUMNARLIST {
+        items_serde_type: Py<PyAnySerdeType>,
+        as_columns: bool,
+    },
     COMPLEX {},
+    #[pyo3(constructor = (value_serde_type, codec = CompressionCodec::LZ4, level = None, min_size = DEFAULT_MIN_COMPRESSED_SIZE))]
+    COMPRESSED {
+        value_serde_type: Py<PyAnySerdeType>,
+        codec: CompressionCodec,
+        level: Option<i32>,
+        min_size: usize,
+    },
+    #[pyo3(constructor = (clazz, init_strategy, field_serde_type_dict, extra = ExtraPolicy::IGNORE {}, alphabetical_field_order = false, schema = SchemaPolicy::FIXED {}))]
     DATACLASS {
         clazz: Py<PyAny>,
         init_strategy: InitStrategy,
-        field_serde_type_dict: BTreeMap<String, PyAnySerdeType>,
+        field_serde_type_dict: FieldSerdeTypeMap,
+        extra: ExtraPolicy,
+        alphabetical_field_order: bool,
+        schema: SchemaPolicy,
+    },
+    DEFAULTDICT {
+        keys_serde_type: Py<PyAnySerdeType>,
+        values_serde_type: Py<PyAnySerdeType>,
+    },
+    #[pyo3(constructor = (definitions, root_serde_type, max_depth = DEFAULT_MAX_DEPTH))]
+    DEFINITIONS {
+        definitions: FieldSerdeTypeMap,
+        root_serde_type: Py<PyAnySerdeType>,
+        max_depth: usize,
+    },
+    DEQUE {
+        items_serde_type: Py<PyAnySerdeType>,
     },
     DICT {
         keys_serde_type: Py<PyAnySerdeType>,
         values_serde_type: Py<PyAnySerdeType>,
     },
     DYNAMIC {},
+    ENUM {
+        values: Vec<Py<PyAny>>,
+    },
     FLOAT {},
+    FROZENSET {
+        items_serde_type: Py<PyAnySerdeType>,
+    },
+    HOMOGENEOUSTUPLE {
+        items_serde_type: Py<PyAnySerdeType>,
+    },
     INT {},
     LIST {
         items_serde_type: Py<PyAnySerdeType>,
     },
+    MEMO {
+        value_serde_type: Py<PyAnySerdeType>,
+    },
+    NAMEDTUPLE {
+        clazz: Py<PyAny>,
+        item_serde_types: Vec<PyAnySerdeType>,
+    },
     #[pyo3(constructor = (dtype, config = NumpySerdeConfig::DYNAMIC { preprocessor_fn: None, postprocessor_fn: None }))]
     NUMPY {
         dtype: NumpyDtype,
@@ -39,10 +89,23 @@ pub enum PyAnySerdeType {
     OPTION {
         value_serde_type: Py<PyAnySerdeType>,
     },
+    ORDEREDDICT {
+        keys_serde_type: Py<PyAnySerdeType>,
+        values_serde_type: Py<PyAnySerdeType>,
+    },
     PICKLE {},
+    #[pyo3(constructor = (items_serde_type, length = None, as_tuple = false))]
+    PRIMITIVELIST {
+        items_serde_type: Py<PyAnySerdeType>,
+        length: Option<usize>,
+        as_tuple: bool,
+    },
     PYTHONSERDE {
         python_serde: Py<PyAny>,
     },
+    REF {
+        name: String,
+    },
     SET {
         items_serde_type: Py<PyAnySerdeType>,
     },
@@ -50,8 +113,13 @@ pub enum PyAnySerdeType {
     TUPLE {
         item_serde_types: Vec<PyAnySerdeType>,
     },
+    #[pyo3(constructor = (key_serde_type_dict, optional_keys = Vec::new(), extra = ExtraPolicy::IGNORE {}, alphabetical_field_order = false, schema = SchemaPolicy::FIXED {}))]
     TYPEDDICT {
-        key_serde_type_dict: BTreeMap<String, PyAnySerdeType>,
+        key_serde_type_dict: FieldSerdeTypeMap,
+        optional_keys: Vec<String>,
+        extra: ExtraPolicy,
+        alphabetical_field_order: bool,
+        schema: SchemaPolicy,
     },
     UNION {
         option_serde_types: Vec<PyAnySerdeType>,
@@ -59,22 +127,115 @@ pub enum PyAnySerdeType {
     },
 }
 
+// A map from field names to serde types which preserves insertion order, so that fields are
+// serialized in the order they were declared in. Converts to and from a Python dict.
+#[derive(Debug, Clone, Default)]
+pub struct FieldSerdeTypeMap(pub Vec<(String, PyAnySerdeType)>);
+
+impl FieldSerdeTypeMap {
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &PyAnySerdeType)> {
+        self.0.iter().map(|(key, serde_type)| (key, serde_type))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.0.iter().map(|(key, _)| key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&PyAnySerdeType> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, serde_type)| serde_type)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // The entries in the order they are serialized in. Before declaration order was preserved,
+    // fields were always serialized in alphabetical order, so alphabetical_field_order allows
+    // reading payloads written by older versions.
+    pub fn serialization_order(
+        &self,
+        alphabetical_field_order: bool,
+    ) -> Vec<(&String, &PyAnySerdeType)> {
+        let mut entries = self.iter().collect::<Vec<_>>();
+        if alphabetical_field_order {
+            entries.sort_by_key(|&(key, _)| key);
+        }
+        entries
+    }
+}
+
+impl FromIterator<(String, PyAnySerdeType)> for FieldSerdeTypeMap {
+    fn from_iter<T: IntoIterator<Item = (String, PyAnySerdeType)>>(iter: T) -> Self {
+        FieldSerdeTypeMap(iter.into_iter().collect())
+    }
+}
+
+impl<'py> IntoPyObject<'py> for FieldSerdeTypeMap {
+    type Target = PyDict;
+
+    type Output = Bound<'py, PyDict>;
+
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let dict = PyDict::new(py);
+        for (key, serde_type) in self.0.into_iter() {
+            dict.set_item(key, serde_type)?;
+        }
+        Ok(dict)
+    }
+}
+
+impl<'py> FromPyObject<'_, 'py> for FieldSerdeTypeMap {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'_, 'py, PyAny>) -> PyResult<Self> {
+        ob.cast::<PyDict>()?
+            .iter()
+            .map(|(key, serde_type)| Ok((key.extract()?, serde_type.extract()?)))
+            .collect()
+    }
+}
+
 impl PyAnySerdeTypeKind {
     pub fn type_object<'py>(self, py: Python<'py>) -> Bound<'py, PyType> {
         match self {
             PyAnySerdeTypeKind::BOOL => PyAnySerdeType_BOOL::type_object(py),
             PyAnySerdeTypeKind::BYTES => PyAnySerdeType_BYTES::type_object(py),
+            PyAnySerdeTypeKind::CHECKSUMMED => PyAnySerdeType_CHECKSUMMED::type_object(py),
+            PyAnySerdeTypeKind::COLUMNARLIST => PyAnySerdeType_COLUMNARLIST::type_object(py),
             PyAnySerdeTypeKind::COMPLEX => PyAnySerdeType_COMPLEX::type_object(py),
+            PyAnySerdeTypeKind::COMPRESSED => PyAnySerdeType_COMPRESSED::type_object(py),
             PyAnySerdeTypeKind::DATACLASS => PyAnySerdeType_DATACLASS::type_object(py),
+            PyAnySerdeTypeKind::DEFAULTDICT => PyAnySerdeType_DEFAULTDICT::type_object(py),
+            PyAnySerdeTypeKind::DEFINITIONS => PyAnySerdeType_DEFINITIONS::type_object(py),
+            PyAnySerdeTypeKind::DEQUE => PyAnySerdeType_DEQUE::type_object(py),
             PyAnySerdeTypeKind::DICT => PyAnySerdeType_DICT::type_object(py),
             PyAnySerdeTypeKind::DYNAMIC => PyAnySerdeType_DYNAMIC::type_object(py),
+            PyAnySerdeTypeKind::ENUM => PyAnySerdeType_ENUM::type_object(py),
             PyAnySerdeTypeKind::FLOAT => PyAnySerdeType_FLOAT::type_object(py),
+            PyAnySerdeTypeKind::FROZENSET => PyAnySerdeType_FROZENSET::type_object(py),
+            PyAnySerdeTypeKind::HOMOGENEOUSTUPLE => {
+                PyAnySerdeType_HOMOGENEOUSTUPLE::type_object(py)
+            }
             PyAnySerdeTypeKind::INT => PyAnySerdeType_INT::type_object(py),
             PyAnySerdeTypeKind::LIST => PyAnySerdeType_LIST::type_object(py),
+            PyAnySerdeTypeKind::MEMO => PyAnySerdeType_MEMO::type_object(py),
+            PyAnySerdeTypeKind::NAMEDTUPLE => PyAnySerdeType_NAMEDTUPLE::type_object(py),
             PyAnySerdeTypeKind::NUMPY => PyAnySerdeType_NUMPY::type_object(py),
             PyAnySerdeTypeKind::OPTION => PyAnySerdeType_OPTION::type_object(py),
+            PyAnySerdeTypeKind::ORDEREDDICT => PyAnySerdeType_ORDEREDDICT::type_object(py),
             PyAnySerdeTypeKind::PICKLE => PyAnySerdeType_PICKLE::type_object(py),
+            PyAnySerdeTypeKind::PRIMITIVELIST => PyAnySerdeType_PRIMITIVELIST::type_object(py),
             PyAnySerdeTypeKind::PYTHONSERDE => PyAnySerdeType_PYTHONSERDE::type_object(py),
+            PyAnySerdeTypeKind::REF => PyAnySerdeType_REF::type_object(py),
             PyAnySerdeTypeKind::SET => PyAnySerdeType_SET::type_object(py),
             PyAnySerdeTypeKind::STRING => PyAnySerdeType_STRING::type_object(py),
             PyAnySerdeTypeKind::TUPLE => PyAnySerdeType_TUPLE::type_object(py),
@@ -93,39 +254,81 @@ impl PyAnySerdeTypeKind {
         if to.eq(PyAnySerdeType_BYTES::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::BYTES));
         }
+        if to.eq(PyAnySerdeType_CHECKSUMMED::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::CHECKSUMMED));
+        }
+        if to.eq(PyAnySerdeType_COLUMNARLIST::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::COLUMNARLIST));
+        }
         if to.eq(PyAnySerdeType_COMPLEX::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::COMPLEX));
         }
+        if to.eq(PyAnySerdeType_COMPRESSED::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::COMPRESSED));
+        }
         if to.eq(PyAnySerdeType_DATACLASS::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::DATACLASS));
         }
+        if to.eq(PyAnySerdeType_DEFAULTDICT::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::DEFAULTDICT));
+        }
+        if to.eq(PyAnySerdeType_DEFINITIONS::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::DEFINITIONS));
+        }
+        if to.eq(PyAnySerdeType_DEQUE::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::DEQUE));
+        }
         if to.eq(PyAnySerdeType_DICT::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::DICT));
         }
         if to.eq(PyAnySerdeType_DYNAMIC::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::DYNAMIC));
         }
+        if to.eq(PyAnySerdeType_ENUM::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::ENUM));
+        }
         if to.eq(PyAnySerdeType_FLOAT::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::FLOAT));
         }
+        if to.eq(PyAnySerdeType_FROZENSET::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::FROZENSET));
+        }
+        if to.eq(PyAnySerdeType_HOMOGENEOUSTUPLE::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::HOMOGENEOUSTUPLE));
+        }
         if to.eq(PyAnySerdeType_INT::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::INT));
         }
         if to.eq(PyAnySerdeType_LIST::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::LIST));
         }
+        if to.eq(PyAnySerdeType_MEMO::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::MEMO));
+        }
+        if to.eq(PyAnySerdeType_NAMEDTUPLE::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::NAMEDTUPLE));
+        }
         if to.eq(PyAnySerdeType_NUMPY::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::NUMPY));
         }
         if to.eq(PyAnySerdeType_OPTION::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::OPTION));
         }
+        if to.eq(PyAnySerdeType_ORDEREDDICT::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::ORDEREDDICT));
+        }
         if to.eq(PyAnySerdeType_PICKLE::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::PICKLE));
         }
+        if to.eq(PyAnySerdeType_PRIMITIVELIST::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::PRIMITIVELIST));
+        }
         if to.eq(PyAnySerdeType_PYTHONSERDE::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::PYTHONSERDE));
         }
+        if to.eq(PyAnySerdeType_REF::type_object(py))? {
+            return Ok(Some(PyAnySerdeTypeKind::REF));
+        }
         if to.eq(PyAnySerdeType_SET::type_object(py))? {
             return Ok(Some(PyAnySerdeTypeKind::SET));
         }
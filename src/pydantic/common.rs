@@ -1,4 +1,4 @@
-use pyo3::prelude::*;
+use pyo3::{exceptions::PyKeyError, prelude::*};
 
 #[pyclass(skip_from_py_object)]
 #[derive(Debug, Clone)]
@@ -34,3 +34,15 @@ impl ValidationContext {
         })
     }
 }
+
+// For fields which are not required so that configs from before the field existed remain valid
+pub fn get_optional_item<'py>(
+    data: &Bound<'py, PyAny>,
+    field: &str,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    match data.get_item(field) {
+        Ok(item) => Ok(Some(item)),
+        Err(err) if err.is_instance_of::<PyKeyError>(data.py()) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
@@ -0,0 +1,188 @@
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyType},
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    pyany_serde_impl::{ExtraPolicy, ExtraPolicyKind},
+    pydantic::{
+        common::ValidationContext,
+        pyany_serde_type::{
+            get_pyany_serde_type_typed_dict_schema, pyany_serde_type_constructor_aux,
+            pyany_serde_type_serializer,
+        },
+    },
+};
+
+pub fn extra_policy_constructor_aux<'py>(
+    py: Python<'py>,
+    data: Bound<'py, PyAny>,
+    context: &mut ValidationContext,
+) -> PyResult<ExtraPolicy> {
+    let type_field = data
+        .get_item("type")?
+        .extract::<String>()?
+        .to_ascii_lowercase();
+    match type_field.as_str() {
+        "ignore" => Ok(ExtraPolicy::IGNORE {}),
+        "forbid" => Ok(ExtraPolicy::FORBID {}),
+        "passthrough" => {
+            let fallback_serde_type_data = data.get_item("fallback_serde_type")?;
+            context.path = format!("{}.fallback_serde_type", context.path);
+            let fallback_serde_type =
+                pyany_serde_type_constructor_aux(py, &fallback_serde_type_data, context)?;
+            Ok(ExtraPolicy::PASSTHROUGH {
+                fallback_serde_type: Py::new(py, fallback_serde_type)?,
+            })
+        }
+        v => Err(PyValueError::new_err(format!(
+            "Unexpected value '{}' for field: {}.type. Allowed values are 'ignore', 'forbid', or 'passthrough'.",
+            v, context.path
+        ))),
+    }
+}
+
+#[pyfunction]
+fn extra_policy_constructor_with_info<'py>(
+    py: Python<'py>,
+    data: Bound<'py, PyAny>,
+    info: Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, ExtraPolicy>> {
+    let mut context = ValidationContext::from_info(&info)?;
+    Bound::new(py, extra_policy_constructor_aux(py, data, &mut context)?)
+}
+
+#[pyfunction]
+pub fn extra_policy_serializer<'py>(
+    py: Python<'py>,
+    extra_policy: &ExtraPolicy,
+) -> PyResult<Bound<'py, PyDict>> {
+    let data = PyDict::new(py);
+    data.set_item("type", extra_policy.to_string().to_ascii_lowercase())?;
+    if let ExtraPolicy::PASSTHROUGH {
+        fallback_serde_type,
+    } = extra_policy
+    {
+        data.set_item(
+            "fallback_serde_type",
+            pyany_serde_type_serializer(py, &fallback_serde_type.extract(py)?)?,
+        )?;
+    }
+    Ok(data)
+}
+
+// The PASSTHROUGH schema refers to the PyAnySerdeType schema by reference, so whatever uses this
+// schema must make sure the pyany_serde_type_schema definition is available.
+pub fn get_extra_policy_typed_dict_schema<'py>(
+    py: Python<'py>,
+    kind: &Option<ExtraPolicyKind>,
+    core_schema: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    if kind.is_none() {
+        return core_schema.call_method1(
+            "union_schema",
+            (ExtraPolicyKind::iter()
+                .map(|k| get_extra_policy_typed_dict_schema(py, &Some(k), core_schema))
+                .collect::<PyResult<Vec<_>>>()?,),
+        );
+    }
+    let kind = kind.unwrap();
+    let typed_dict_schema = core_schema.getattr("typed_dict_schema")?;
+    let typed_dict_field = core_schema.getattr("typed_dict_field")?;
+    let str_schema = core_schema.getattr("str_schema")?;
+    let typed_dict_fields = PyDict::new(py);
+    typed_dict_fields.set_item(
+        "type",
+        typed_dict_field.call1((str_schema.call(
+            (),
+            Some(&PyDict::from_sequence(
+                &[(
+                    "pattern",
+                    [
+                        "^".to_owned(),
+                        kind.to_string().to_ascii_lowercase(),
+                        "$".to_owned(),
+                    ]
+                    .join("")
+                    .into_pyobject(py)?
+                    .into_any(),
+                )]
+                .into_pyobject(py)?,
+            )?),
+        )?,))?,
+    )?;
+    if kind == ExtraPolicyKind::PASSTHROUGH {
+        typed_dict_fields.set_item(
+            "fallback_serde_type",
+            typed_dict_field.call1((core_schema
+                .call_method1("definition_reference_schema", ("pyany_serde_type_schema",))?,))?,
+        )?;
+    }
+    typed_dict_schema.call1((typed_dict_fields,))
+}
+
+#[pymethods]
+impl ExtraPolicy {
+    // pydantic methods
+    #[classmethod]
+    fn __get_pydantic_core_schema__<'py>(
+        cls: &Bound<'py, PyType>,
+        _source_type: Bound<'py, PyAny>,
+        _handler: Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = cls.py();
+        let core_schema = py.import("pydantic_core")?.getattr("core_schema")?;
+        let kind = ExtraPolicyKind::from_type_object(cls)?;
+        let base_schema = get_extra_policy_typed_dict_schema(py, &kind, &core_schema)?;
+        let is_instance_schema = core_schema.call_method1("is_instance_schema", (cls,))?;
+        let json_schema = core_schema.call_method1(
+            "chain_schema",
+            ([
+                base_schema.clone(),
+                core_schema.call_method1(
+                    "with_info_before_validator_function",
+                    (
+                        wrap_pyfunction!(extra_policy_constructor_with_info, py)?,
+                        core_schema.call_method0("any_schema")?,
+                    ),
+                )?,
+            ],),
+        )?;
+        let python_schema =
+            core_schema.call_method1("union_schema", ([&is_instance_schema, &json_schema],))?;
+        let json_or_python_schema = core_schema.call_method(
+            "json_or_python_schema",
+            (json_schema, python_schema),
+            Some(&PyDict::from_sequence(
+                &[(
+                    "serialization",
+                    core_schema.call_method(
+                        "plain_serializer_function_ser_schema",
+                        (wrap_pyfunction!(extra_policy_serializer, py)?,),
+                        Some(&PyDict::from_sequence(
+                            &[("return_schema", base_schema)].into_pyobject(py)?,
+                        )?),
+                    )?,
+                )]
+                .into_pyobject(py)?,
+            )?),
+        )?;
+        core_schema.call_method(
+            "definitions_schema",
+            (&json_or_python_schema,),
+            Some(&PyDict::from_sequence(
+                &[(
+                    "definitions",
+                    [get_pyany_serde_type_typed_dict_schema(
+                        py,
+                        &None,
+                        &core_schema,
+                    )?],
+                )]
+                .into_pyobject(py)?,
+            )?),
+        )
+    }
+}
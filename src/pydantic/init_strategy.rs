@@ -25,8 +25,11 @@ pub fn init_strategy_constructor_aux<'py>(
             Ok(InitStrategy::SOME { kwargs })
         }
         "none" => Ok(InitStrategy::NONE {}),
+        "auto" => Ok(InitStrategy::AUTO {}),
+        "bypass" => Ok(InitStrategy::BYPASS {}),
+        "modelconstruct" => Ok(InitStrategy::MODELCONSTRUCT {}),
         v => Err(PyValueError::new_err(format!(
-            "Unexpected value '{}' for field: {}.type. Allowed values are 'all', 'some', or 'none'.",
+            "Unexpected value '{}' for field: {}.type. Allowed values are 'all', 'some', 'none', 'auto', 'bypass', or 'modelconstruct'.",
             v,
             context.path
         ))),
@@ -1,5 +1,10 @@
 mod common;
+mod extra_policy;
 mod init_strategy;
 mod numpy_serde_config;
 mod pyany_serde_type;
+mod schema_policy;
 mod unpickling;
+
+pub use common::ValidationContext;
+pub use pyany_serde_type::{pyany_serde_type_constructor_aux, pyany_serde_type_serializer};
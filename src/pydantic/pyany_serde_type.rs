@@ -1,19 +1,24 @@
-use std::{collections::BTreeMap, str::FromStr};
+use std::str::FromStr;
 
 use pyo3::{
     PyTypeInfo,
     exceptions::PyValueError,
     prelude::*,
-    types::{PyDict, PyFunction, PyType},
+    types::{IntoPyDict, PyDict, PyFunction, PyType},
 };
 use strum::IntoEnumIterator;
 
 use crate::{
     PyAnySerdeType,
-    common::NumpyDtype,
-    pyany_serde_type::PyAnySerdeTypeKind,
+    common::{CompressionCodec, NumpyDtype},
+    pyany_serde_impl::{DEFAULT_MAX_DEPTH, DEFAULT_MIN_COMPRESSED_SIZE, ExtraPolicy, SchemaPolicy},
+    pyany_serde_type::{FieldSerdeTypeMap, PyAnySerdeTypeKind},
     pydantic::{
-        common::ValidationContext,
+        common::{ValidationContext, get_optional_item},
+        extra_policy::{
+            extra_policy_constructor_aux, extra_policy_serializer,
+            get_extra_policy_typed_dict_schema,
+        },
         init_strategy::{
             get_init_strategy_typed_dict_schema, init_strategy_constructor_aux,
             init_strategy_serializer,
@@ -22,11 +27,24 @@ use crate::{
             get_numpy_serde_config_typed_dict_schema, numpy_serde_config_constructor_aux,
             numpy_serde_config_serializer,
         },
+        schema_policy::{
+            get_schema_policy_typed_dict_schema, schema_policy_constructor_aux,
+            schema_policy_serializer,
+        },
         unpickling::unpickle_field,
     },
 };
 
-fn pyany_serde_type_constructor_aux<'py>(
+// Configs serialized before declaration order was preserved stored their field dicts in
+// alphabetical order, so they keep the same field order when this defaults to false.
+fn get_alphabetical_field_order<'py>(data: &Bound<'py, PyAny>) -> PyResult<bool> {
+    Ok(get_optional_item(data, "alphabetical_field_order")?
+        .map(|alphabetical_field_order| alphabetical_field_order.extract::<bool>())
+        .transpose()?
+        .unwrap_or(false))
+}
+
+pub fn pyany_serde_type_constructor_aux<'py>(
     py: Python<'py>,
     data: &Bound<'py, PyAny>,
     context: &mut ValidationContext,
@@ -39,13 +57,60 @@ fn pyany_serde_type_constructor_aux<'py>(
     let pyany_serde_type = match pyany_serde_type_field.as_str() {
         "bool" => PyAnySerdeType::BOOL {},
         "bytes" => PyAnySerdeType::BYTES {},
+        "checksummed" => {
+            let value_serde_type_data = data.get_item("value_serde_type")?;
+            context.path = format!("{cur_path}.value_serde_type");
+            let value_serde_type =
+                pyany_serde_type_constructor_aux(py, &value_serde_type_data, context)?;
+            PyAnySerdeType::CHECKSUMMED {
+                value_serde_type: Py::new(py, value_serde_type)?,
+            }
+        }
+        "columnarlist" => {
+            let items_serde_type_data = data.get_item("items_serde_type")?;
+            context.path = format!("{cur_path}.items_serde_type");
+            let items_serde_type =
+                pyany_serde_type_constructor_aux(py, &items_serde_type_data, context)?;
+            let as_columns = get_optional_item(data, "as_columns")?
+                .map(|as_columns| as_columns.extract::<bool>())
+                .transpose()?
+                .unwrap_or(false);
+            PyAnySerdeType::COLUMNARLIST {
+                items_serde_type: Py::new(py, items_serde_type)?,
+                as_columns,
+            }
+        }
         "complex" => PyAnySerdeType::COMPLEX {},
+        "compressed" => {
+            let value_serde_type_data = data.get_item("value_serde_type")?;
+            context.path = format!("{cur_path}.value_serde_type");
+            let value_serde_type =
+                pyany_serde_type_constructor_aux(py, &value_serde_type_data, context)?;
+            let codec = get_optional_item(data, "codec")?
+                .map(|codec| codec.extract::<CompressionCodec>())
+                .transpose()?
+                .unwrap_or(CompressionCodec::LZ4);
+            let level = get_optional_item(data, "level")?
+                .map(|level| level.extract::<Option<i32>>())
+                .transpose()?
+                .flatten();
+            let min_size = get_optional_item(data, "min_size")?
+                .map(|min_size| min_size.extract::<usize>())
+                .transpose()?
+                .unwrap_or(DEFAULT_MIN_COMPRESSED_SIZE);
+            PyAnySerdeType::COMPRESSED {
+                value_serde_type: Py::new(py, value_serde_type)?,
+                codec,
+                level,
+                min_size,
+            }
+        }
         "dataclass" => {
             let clazz = unpickle_field(py, data, "dataclass_pkl", context)?.unbind();
             context.path = format!("{cur_path}.init_strategy");
             let init_strategy =
                 init_strategy_constructor_aux(data.get_item("init_strategy")?, context)?;
-            let mut field_serde_type_dict = BTreeMap::new();
+            let mut field_serde_type_dict = FieldSerdeTypeMap::default();
             for (key, serde_type_data) in data
                 .get_item("field_serde_type_dict")?
                 .cast_into::<PyDict>()?
@@ -54,12 +119,75 @@ fn pyany_serde_type_constructor_aux<'py>(
                 let key = key.extract::<String>()?;
                 context.path = format!("{cur_path}[{key}]");
                 let value = pyany_serde_type_constructor_aux(py, &serde_type_data, context)?;
-                field_serde_type_dict.insert(key, value);
+                field_serde_type_dict.0.push((key, value));
             }
+            context.path = format!("{cur_path}.extra");
+            let extra = get_optional_item(data, "extra")?
+                .map(|extra_data| extra_policy_constructor_aux(py, extra_data, context))
+                .transpose()?
+                .unwrap_or(ExtraPolicy::IGNORE {});
+            let alphabetical_field_order = get_alphabetical_field_order(data)?;
+            context.path = format!("{cur_path}.schema");
+            let schema = get_optional_item(data, "schema")?
+                .map(|schema_data| schema_policy_constructor_aux(py, schema_data, context))
+                .transpose()?
+                .unwrap_or(SchemaPolicy::FIXED {});
             PyAnySerdeType::DATACLASS {
                 clazz,
                 init_strategy,
                 field_serde_type_dict,
+                extra,
+                alphabetical_field_order,
+                schema,
+            }
+        }
+        "defaultdict" => {
+            let keys_serde_type_data = data.get_item("keys_serde_type")?;
+            context.path = format!("{cur_path}.keys_serde_type");
+            let keys_serde_type =
+                pyany_serde_type_constructor_aux(py, &keys_serde_type_data, context)?;
+            let values_serde_type_data = data.get_item("values_serde_type")?;
+            context.path = format!("{cur_path}.values_serde_type");
+            let values_serde_type =
+                pyany_serde_type_constructor_aux(py, &values_serde_type_data, context)?;
+            PyAnySerdeType::DEFAULTDICT {
+                keys_serde_type: Py::new(py, keys_serde_type)?,
+                values_serde_type: Py::new(py, values_serde_type)?,
+            }
+        }
+        "definitions" => {
+            let mut definitions = FieldSerdeTypeMap::default();
+            for (name, serde_type_data) in data
+                .get_item("definitions")?
+                .cast_into::<PyDict>()?
+                .into_iter()
+            {
+                let name = name.extract::<String>()?;
+                context.path = format!("{cur_path}.definitions[{name}]");
+                let value = pyany_serde_type_constructor_aux(py, &serde_type_data, context)?;
+                definitions.0.push((name, value));
+            }
+            let root_serde_type_data = data.get_item("root_serde_type")?;
+            context.path = format!("{cur_path}.root_serde_type");
+            let root_serde_type =
+                pyany_serde_type_constructor_aux(py, &root_serde_type_data, context)?;
+            let max_depth = get_optional_item(data, "max_depth")?
+                .map(|max_depth| max_depth.extract::<usize>())
+                .transpose()?
+                .unwrap_or(DEFAULT_MAX_DEPTH);
+            PyAnySerdeType::DEFINITIONS {
+                definitions,
+                root_serde_type: Py::new(py, root_serde_type)?,
+                max_depth,
+            }
+        }
+        "deque" => {
+            let items_serde_type_data = data.get_item("items_serde_type")?;
+            context.path = format!("{cur_path}.items_serde_type");
+            let items_serde_type =
+                pyany_serde_type_constructor_aux(py, &items_serde_type_data, context)?;
+            PyAnySerdeType::DEQUE {
+                items_serde_type: Py::new(py, items_serde_type)?,
             }
         }
         "dict" => {
@@ -77,7 +205,28 @@ fn pyany_serde_type_constructor_aux<'py>(
             }
         }
         "dynamic" => PyAnySerdeType::DYNAMIC {},
+        "enum" => PyAnySerdeType::ENUM {
+            values: unpickle_field(py, data, "values_pkl", context)?.extract()?,
+        },
         "float" => PyAnySerdeType::FLOAT {},
+        "frozenset" => {
+            let items_serde_type_data = data.get_item("items_serde_type")?;
+            context.path = format!("{cur_path}.items_serde_type");
+            let items_serde_type =
+                pyany_serde_type_constructor_aux(py, &items_serde_type_data, context)?;
+            PyAnySerdeType::FROZENSET {
+                items_serde_type: Py::new(py, items_serde_type)?,
+            }
+        }
+        "homogeneoustuple" => {
+            let items_serde_type_data = data.get_item("items_serde_type")?;
+            context.path = format!("{cur_path}.items_serde_type");
+            let items_serde_type =
+                pyany_serde_type_constructor_aux(py, &items_serde_type_data, context)?;
+            PyAnySerdeType::HOMOGENEOUSTUPLE {
+                items_serde_type: Py::new(py, items_serde_type)?,
+            }
+        }
         "int" => PyAnySerdeType::INT {},
         "list" => {
             let items_serde_type_data = data.get_item("items_serde_type")?;
@@ -88,6 +237,35 @@ fn pyany_serde_type_constructor_aux<'py>(
                 items_serde_type: Py::new(py, items_serde_type)?,
             }
         }
+        "memo" => {
+            let value_serde_type_data = data.get_item("value_serde_type")?;
+            context.path = format!("{cur_path}.value_serde_type");
+            let value_serde_type =
+                pyany_serde_type_constructor_aux(py, &value_serde_type_data, context)?;
+            PyAnySerdeType::MEMO {
+                value_serde_type: Py::new(py, value_serde_type)?,
+            }
+        }
+        "namedtuple" => {
+            let clazz = unpickle_field(py, data, "namedtuple_pkl", context)?.unbind();
+            let item_serde_types_data = data
+                .get_item("item_serde_types")?
+                .extract::<Vec<Bound<'_, PyAny>>>()?;
+            let item_serde_types = item_serde_types_data
+                .iter()
+                .enumerate()
+                .map(|(idx, item_serde_type_data)| {
+                    context.path = format!("{cur_path}[{idx}]");
+                    let item_serde_type =
+                        pyany_serde_type_constructor_aux(py, item_serde_type_data, context)?;
+                    Ok(item_serde_type)
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            PyAnySerdeType::NAMEDTUPLE {
+                clazz,
+                item_serde_types,
+            }
+        }
         "numpy" => {
             let dtype_string = data.get_item("dtype")?.extract::<String>()?;
             let dtype = NumpyDtype::from_str(dtype_string.as_str()).map_err(|_| {
@@ -112,10 +290,40 @@ fn pyany_serde_type_constructor_aux<'py>(
                 value_serde_type: Py::new(py, value_serde_type)?,
             }
         }
+        "ordereddict" => {
+            let keys_serde_type_data = data.get_item("keys_serde_type")?;
+            context.path = format!("{cur_path}.keys_serde_type");
+            let keys_serde_type =
+                pyany_serde_type_constructor_aux(py, &keys_serde_type_data, context)?;
+            let values_serde_type_data = data.get_item("values_serde_type")?;
+            context.path = format!("{cur_path}.values_serde_type");
+            let values_serde_type =
+                pyany_serde_type_constructor_aux(py, &values_serde_type_data, context)?;
+            PyAnySerdeType::ORDEREDDICT {
+                keys_serde_type: Py::new(py, keys_serde_type)?,
+                values_serde_type: Py::new(py, values_serde_type)?,
+            }
+        }
         "pickle" => PyAnySerdeType::PICKLE {},
+        "primitivelist" => {
+            let items_serde_type_data = data.get_item("items_serde_type")?;
+            context.path = format!("{cur_path}.items_serde_type");
+            let items_serde_type =
+                pyany_serde_type_constructor_aux(py, &items_serde_type_data, context)?;
+            let length = data.get_item("length")?.extract::<Option<usize>>()?;
+            let as_tuple = data.get_item("as_tuple")?.extract::<bool>()?;
+            PyAnySerdeType::PRIMITIVELIST {
+                items_serde_type: Py::new(py, items_serde_type)?,
+                length,
+                as_tuple,
+            }
+        }
         "pythonserde" => PyAnySerdeType::PYTHONSERDE {
             python_serde: unpickle_field(py, data, "pythonserde_pkl", context)?.unbind(),
         },
+        "ref" => PyAnySerdeType::REF {
+            name: data.get_item("name")?.extract::<String>()?,
+        },
         "set" => {
             let items_serde_type_data = data.get_item("items_serde_type")?;
             context.path = format!("{cur_path}.items_serde_type");
@@ -143,7 +351,7 @@ fn pyany_serde_type_constructor_aux<'py>(
             PyAnySerdeType::TUPLE { item_serde_types }
         }
         "typeddict" => {
-            let mut key_serde_type_dict = BTreeMap::new();
+            let mut key_serde_type_dict = FieldSerdeTypeMap::default();
             for (key, serde_type_data) in data
                 .get_item("key_serde_type_dict")?
                 .cast_into::<PyDict>()?
@@ -152,10 +360,29 @@ fn pyany_serde_type_constructor_aux<'py>(
                 let key = key.extract::<String>()?;
                 context.path = format!("{cur_path}[{key}]");
                 let value = pyany_serde_type_constructor_aux(py, &serde_type_data, context)?;
-                key_serde_type_dict.insert(key, value);
+                key_serde_type_dict.0.push((key, value));
             }
+            let optional_keys = get_optional_item(data, "optional_keys")?
+                .map(|optional_keys| optional_keys.extract::<Vec<String>>())
+                .transpose()?
+                .unwrap_or_default();
+            context.path = format!("{cur_path}.extra");
+            let extra = get_optional_item(data, "extra")?
+                .map(|extra_data| extra_policy_constructor_aux(py, extra_data, context))
+                .transpose()?
+                .unwrap_or(ExtraPolicy::IGNORE {});
+            let alphabetical_field_order = get_alphabetical_field_order(data)?;
+            context.path = format!("{cur_path}.schema");
+            let schema = get_optional_item(data, "schema")?
+                .map(|schema_data| schema_policy_constructor_aux(py, schema_data, context))
+                .transpose()?
+                .unwrap_or(SchemaPolicy::FIXED {});
             PyAnySerdeType::TYPEDDICT {
                 key_serde_type_dict,
+                optional_keys,
+                extra,
+                alphabetical_field_order,
+                schema,
             }
         }
         "union" => {
@@ -207,10 +434,42 @@ pub fn pyany_serde_type_serializer<'py>(
 ) -> PyResult<Bound<'py, PyDict>> {
     let data = PyDict::new(py);
     data.set_item("type", pyany_serde_type.to_string().to_ascii_lowercase())?;
-    if let PyAnySerdeType::DATACLASS {
+    if let PyAnySerdeType::CHECKSUMMED { value_serde_type } = pyany_serde_type {
+        data.set_item(
+            "value_serde_type",
+            pyany_serde_type_serializer(py, &value_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+    } else if let PyAnySerdeType::COLUMNARLIST {
+        items_serde_type,
+        as_columns,
+    } = pyany_serde_type
+    {
+        data.set_item(
+            "items_serde_type",
+            pyany_serde_type_serializer(py, &items_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+        data.set_item("as_columns", as_columns)?;
+    } else if let PyAnySerdeType::COMPRESSED {
+        value_serde_type,
+        codec,
+        level,
+        min_size,
+    } = pyany_serde_type
+    {
+        data.set_item(
+            "value_serde_type",
+            pyany_serde_type_serializer(py, &value_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+        data.set_item("codec", *codec)?;
+        data.set_item("level", level)?;
+        data.set_item("min_size", min_size)?;
+    } else if let PyAnySerdeType::DATACLASS {
         clazz,
         init_strategy,
         field_serde_type_dict,
+        extra,
+        alphabetical_field_order,
+        schema,
     } = pyany_serde_type
     {
         data.set_item(
@@ -231,7 +490,29 @@ pub fn pyany_serde_type_serializer<'py>(
                 .map(|(key, field_serde_type)| {
                     Ok((key, pyany_serde_type_serializer(py, field_serde_type)?))
                 })
-                .collect::<PyResult<BTreeMap<_, _>>>()?,
+                .collect::<PyResult<Vec<_>>>()?
+                .into_py_dict(py)?,
+        )?;
+        data.set_item("extra", extra_policy_serializer(py, extra)?)?;
+        data.set_item("alphabetical_field_order", alphabetical_field_order)?;
+        data.set_item("schema", schema_policy_serializer(py, schema)?)?;
+    } else if let PyAnySerdeType::DEFAULTDICT {
+        keys_serde_type,
+        values_serde_type,
+    } = pyany_serde_type
+    {
+        data.set_item(
+            "keys_serde_type",
+            pyany_serde_type_serializer(py, &keys_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+        data.set_item(
+            "values_serde_type",
+            pyany_serde_type_serializer(py, &values_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+    } else if let PyAnySerdeType::DEQUE { items_serde_type } = pyany_serde_type {
+        data.set_item(
+            "items_serde_type",
+            pyany_serde_type_serializer(py, &items_serde_type.extract::<PyAnySerdeType>(py)?)?,
         )?;
     } else if let PyAnySerdeType::DICT {
         keys_serde_type,
@@ -246,11 +527,53 @@ pub fn pyany_serde_type_serializer<'py>(
             "values_serde_type",
             pyany_serde_type_serializer(py, &values_serde_type.extract::<PyAnySerdeType>(py)?)?,
         )?;
+    } else if let PyAnySerdeType::ENUM { values } = pyany_serde_type {
+        data.set_item(
+            "values_pkl",
+            py.import("pickle")?
+                .getattr("dumps")?
+                .call1((values,))?
+                .call_method0("hex")?,
+        )?;
+    } else if let PyAnySerdeType::FROZENSET { items_serde_type } = pyany_serde_type {
+        data.set_item(
+            "items_serde_type",
+            pyany_serde_type_serializer(py, &items_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+    } else if let PyAnySerdeType::HOMOGENEOUSTUPLE { items_serde_type } = pyany_serde_type {
+        data.set_item(
+            "items_serde_type",
+            pyany_serde_type_serializer(py, &items_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
     } else if let PyAnySerdeType::LIST { items_serde_type } = pyany_serde_type {
         data.set_item(
             "items_serde_type",
             pyany_serde_type_serializer(py, &items_serde_type.extract::<PyAnySerdeType>(py)?)?,
         )?;
+    } else if let PyAnySerdeType::MEMO { value_serde_type } = pyany_serde_type {
+        data.set_item(
+            "value_serde_type",
+            pyany_serde_type_serializer(py, &value_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+    } else if let PyAnySerdeType::NAMEDTUPLE {
+        clazz,
+        item_serde_types,
+    } = pyany_serde_type
+    {
+        data.set_item(
+            "namedtuple_pkl",
+            py.import("pickle")?
+                .getattr("dumps")?
+                .call1((clazz,))?
+                .call_method0("hex")?,
+        )?;
+        data.set_item(
+            "item_serde_types",
+            item_serde_types
+                .iter()
+                .map(|item_serde_type| pyany_serde_type_serializer(py, item_serde_type))
+                .collect::<PyResult<Vec<_>>>()?,
+        )?;
     } else if let PyAnySerdeType::NUMPY { dtype, config } = pyany_serde_type {
         data.set_item("dtype", dtype.to_string())?;
         data.set_item("config", numpy_serde_config_serializer(py, config)?)?;
@@ -259,6 +582,50 @@ pub fn pyany_serde_type_serializer<'py>(
             "value_serde_type",
             pyany_serde_type_serializer(py, &value_serde_type.extract::<PyAnySerdeType>(py)?)?,
         )?;
+    } else if let PyAnySerdeType::ORDEREDDICT {
+        keys_serde_type,
+        values_serde_type,
+    } = pyany_serde_type
+    {
+        data.set_item(
+            "keys_serde_type",
+            pyany_serde_type_serializer(py, &keys_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+        data.set_item(
+            "values_serde_type",
+            pyany_serde_type_serializer(py, &values_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+    } else if let PyAnySerdeType::PRIMITIVELIST {
+        items_serde_type,
+        length,
+        as_tuple,
+    } = pyany_serde_type
+    {
+        data.set_item(
+            "items_serde_type",
+            pyany_serde_type_serializer(py, &items_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+        data.set_item("length", length)?;
+        data.set_item("as_tuple", as_tuple)?;
+    } else if let PyAnySerdeType::DEFINITIONS {
+        definitions,
+        root_serde_type,
+        max_depth,
+    } = pyany_serde_type
+    {
+        data.set_item(
+            "definitions",
+            definitions
+                .iter()
+                .map(|(name, serde_type)| Ok((name, pyany_serde_type_serializer(py, serde_type)?)))
+                .collect::<PyResult<Vec<_>>>()?
+                .into_py_dict(py)?,
+        )?;
+        data.set_item(
+            "root_serde_type",
+            pyany_serde_type_serializer(py, &root_serde_type.extract::<PyAnySerdeType>(py)?)?,
+        )?;
+        data.set_item("max_depth", max_depth)?;
     } else if let PyAnySerdeType::PYTHONSERDE { python_serde } = pyany_serde_type {
         data.set_item(
             "pythonserde_pkl",
@@ -267,6 +634,8 @@ pub fn pyany_serde_type_serializer<'py>(
                 .call1((python_serde,))?
                 .call_method0("hex")?,
         )?;
+    } else if let PyAnySerdeType::REF { name } = pyany_serde_type {
+        data.set_item("name", name)?;
     } else if let PyAnySerdeType::SET { items_serde_type } = pyany_serde_type {
         data.set_item(
             "items_serde_type",
@@ -282,6 +651,10 @@ pub fn pyany_serde_type_serializer<'py>(
         )?;
     } else if let PyAnySerdeType::TYPEDDICT {
         key_serde_type_dict,
+        optional_keys,
+        extra,
+        alphabetical_field_order,
+        schema,
     } = pyany_serde_type
     {
         data.set_item(
@@ -291,8 +664,13 @@ pub fn pyany_serde_type_serializer<'py>(
                 .map(|(key, field_serde_type)| {
                     Ok((key, pyany_serde_type_serializer(py, field_serde_type)?))
                 })
-                .collect::<PyResult<BTreeMap<_, _>>>()?,
+                .collect::<PyResult<Vec<_>>>()?
+                .into_py_dict(py)?,
         )?;
+        data.set_item("optional_keys", optional_keys)?;
+        data.set_item("extra", extra_policy_serializer(py, extra)?)?;
+        data.set_item("alphabetical_field_order", alphabetical_field_order)?;
+        data.set_item("schema", schema_policy_serializer(py, schema)?)?;
     } else if let PyAnySerdeType::UNION {
         option_serde_types,
         option_choice_fn,
@@ -337,6 +715,9 @@ pub fn get_pyany_serde_type_typed_dict_schema<'py>(
     let typed_dict_field = core_schema.getattr("typed_dict_field")?;
     let str_schema = core_schema.getattr("str_schema")?;
     let list_schema = core_schema.getattr("list_schema")?;
+    let int_schema = core_schema.getattr("int_schema")?;
+    let bool_schema = core_schema.getattr("bool_schema")?;
+    let nullable_schema = core_schema.getattr("nullable_schema")?;
     let dict_schema = core_schema.getattr("dict_schema")?;
     let pyany_serde_type_reference_schema =
         core_schema.call_method1("definition_reference_schema", ("pyany_serde_type_schema",))?;
@@ -366,6 +747,62 @@ pub fn get_pyany_serde_type_typed_dict_schema<'py>(
     )?;
 
     match kind {
+        PyAnySerdeTypeKind::CHECKSUMMED => {
+            typed_dict_fields
+                .set_item("value_serde_type", &pyany_serde_type_reference_schema_field)?;
+        }
+        PyAnySerdeTypeKind::COLUMNARLIST => {
+            typed_dict_fields
+                .set_item("items_serde_type", &pyany_serde_type_reference_schema_field)?;
+            typed_dict_fields.set_item(
+                "as_columns",
+                typed_dict_field.call(
+                    (bool_schema.call0()?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+        }
+        PyAnySerdeTypeKind::COMPRESSED => {
+            typed_dict_fields
+                .set_item("value_serde_type", &pyany_serde_type_reference_schema_field)?;
+            typed_dict_fields.set_item(
+                "codec",
+                typed_dict_field.call(
+                    (str_schema.call(
+                        (),
+                        Some(&PyDict::from_sequence(
+                            &[("pattern", "^(?i:lz4|zstd)$")].into_pyobject(py)?,
+                        )?),
+                    )?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+            typed_dict_fields.set_item(
+                "level",
+                typed_dict_field.call(
+                    (nullable_schema.call1((int_schema.call0()?,))?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+            typed_dict_fields.set_item(
+                "min_size",
+                typed_dict_field.call(
+                    (int_schema.call(
+                        (),
+                        Some(&PyDict::from_sequence(&[("ge", 0)].into_pyobject(py)?)?),
+                    )?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+        }
         PyAnySerdeTypeKind::DATACLASS => {
             typed_dict_fields.set_item(
                 "dataclass_pkl",
@@ -385,6 +822,64 @@ pub fn get_pyany_serde_type_typed_dict_schema<'py>(
                     .call1((dict_schema
                         .call1((str_schema.call0()?, &pyany_serde_type_reference_schema))?,))?,
             )?;
+            typed_dict_fields.set_item(
+                "extra",
+                typed_dict_field.call(
+                    (get_extra_policy_typed_dict_schema(py, &None, core_schema)?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+            typed_dict_fields.set_item(
+                "alphabetical_field_order",
+                typed_dict_field.call(
+                    (bool_schema.call0()?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+            typed_dict_fields.set_item(
+                "schema",
+                typed_dict_field.call(
+                    (get_schema_policy_typed_dict_schema(py, &None, core_schema)?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+        }
+        PyAnySerdeTypeKind::DEFAULTDICT => {
+            typed_dict_fields
+                .set_item("keys_serde_type", &pyany_serde_type_reference_schema_field)?;
+            typed_dict_fields.set_item(
+                "values_serde_type",
+                &pyany_serde_type_reference_schema_field,
+            )?;
+        }
+        PyAnySerdeTypeKind::DEFINITIONS => {
+            typed_dict_fields.set_item(
+                "definitions",
+                typed_dict_field
+                    .call1((dict_schema
+                        .call1((str_schema.call0()?, &pyany_serde_type_reference_schema))?,))?,
+            )?;
+            typed_dict_fields
+                .set_item("root_serde_type", &pyany_serde_type_reference_schema_field)?;
+            typed_dict_fields.set_item(
+                "max_depth",
+                typed_dict_field.call(
+                    (int_schema.call0()?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+        }
+        PyAnySerdeTypeKind::DEQUE => {
+            typed_dict_fields
+                .set_item("items_serde_type", &pyany_serde_type_reference_schema_field)?;
         }
         PyAnySerdeTypeKind::DICT => {
             typed_dict_fields
@@ -394,10 +889,39 @@ pub fn get_pyany_serde_type_typed_dict_schema<'py>(
                 &pyany_serde_type_reference_schema_field,
             )?;
         }
+        PyAnySerdeTypeKind::ENUM => {
+            typed_dict_fields.set_item(
+                "values_pkl",
+                typed_dict_field.call1((str_schema.call0()?,))?,
+            )?;
+        }
+        PyAnySerdeTypeKind::FROZENSET => {
+            typed_dict_fields
+                .set_item("items_serde_type", &pyany_serde_type_reference_schema_field)?;
+        }
+        PyAnySerdeTypeKind::HOMOGENEOUSTUPLE => {
+            typed_dict_fields
+                .set_item("items_serde_type", &pyany_serde_type_reference_schema_field)?;
+        }
         PyAnySerdeTypeKind::LIST => {
             typed_dict_fields
                 .set_item("items_serde_type", &pyany_serde_type_reference_schema_field)?;
         }
+        PyAnySerdeTypeKind::MEMO => {
+            typed_dict_fields
+                .set_item("value_serde_type", &pyany_serde_type_reference_schema_field)?;
+        }
+        PyAnySerdeTypeKind::NAMEDTUPLE => {
+            typed_dict_fields.set_item(
+                "namedtuple_pkl",
+                typed_dict_field.call1((str_schema.call0()?,))?,
+            )?;
+            typed_dict_fields.set_item(
+                "item_serde_types",
+                typed_dict_field
+                    .call1((list_schema.call1((&pyany_serde_type_reference_schema,))?,))?,
+            )?;
+        }
         PyAnySerdeTypeKind::NUMPY => {
             typed_dict_fields.set_item(
                 "dtype",
@@ -433,12 +957,36 @@ pub fn get_pyany_serde_type_typed_dict_schema<'py>(
             typed_dict_fields
                 .set_item("value_serde_type", &pyany_serde_type_reference_schema_field)?;
         }
+        PyAnySerdeTypeKind::ORDEREDDICT => {
+            typed_dict_fields
+                .set_item("keys_serde_type", &pyany_serde_type_reference_schema_field)?;
+            typed_dict_fields.set_item(
+                "values_serde_type",
+                &pyany_serde_type_reference_schema_field,
+            )?;
+        }
+        PyAnySerdeTypeKind::PRIMITIVELIST => {
+            typed_dict_fields
+                .set_item("items_serde_type", &pyany_serde_type_reference_schema_field)?;
+            typed_dict_fields.set_item(
+                "length",
+                typed_dict_field.call1((nullable_schema.call1((int_schema.call(
+                    (),
+                    Some(&PyDict::from_sequence(&[("ge", 0)].into_pyobject(py)?)?),
+                )?,))?,))?,
+            )?;
+            typed_dict_fields
+                .set_item("as_tuple", typed_dict_field.call1((bool_schema.call0()?,))?)?;
+        }
         PyAnySerdeTypeKind::PYTHONSERDE => {
             typed_dict_fields.set_item(
                 "pythonserde_pkl",
                 typed_dict_field.call1((str_schema.call0()?,))?,
             )?;
         }
+        PyAnySerdeTypeKind::REF => {
+            typed_dict_fields.set_item("name", typed_dict_field.call1((str_schema.call0()?,))?)?;
+        }
         PyAnySerdeTypeKind::SET => {
             typed_dict_fields
                 .set_item("items_serde_type", &pyany_serde_type_reference_schema_field)?;
@@ -457,6 +1005,42 @@ pub fn get_pyany_serde_type_typed_dict_schema<'py>(
                     .call1((dict_schema
                         .call1((str_schema.call0()?, &pyany_serde_type_reference_schema))?,))?,
             )?;
+            typed_dict_fields.set_item(
+                "optional_keys",
+                typed_dict_field.call(
+                    (list_schema.call1((str_schema.call0()?,))?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+            typed_dict_fields.set_item(
+                "extra",
+                typed_dict_field.call(
+                    (get_extra_policy_typed_dict_schema(py, &None, core_schema)?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+            typed_dict_fields.set_item(
+                "alphabetical_field_order",
+                typed_dict_field.call(
+                    (bool_schema.call0()?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
+            typed_dict_fields.set_item(
+                "schema",
+                typed_dict_field.call(
+                    (get_schema_policy_typed_dict_schema(py, &None, core_schema)?,),
+                    Some(&PyDict::from_sequence(
+                        &[("required", false)].into_pyobject(py)?,
+                    )?),
+                )?,
+            )?;
         }
         PyAnySerdeTypeKind::UNION => {
             typed_dict_fields.set_item(
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyType},
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    pyany_serde_impl::{SchemaPolicy, SchemaPolicyKind},
+    pydantic::{common::ValidationContext, unpickling::unpickle_field},
+};
+
+pub fn schema_policy_constructor_aux<'py>(
+    py: Python<'py>,
+    data: Bound<'py, PyAny>,
+    context: &mut ValidationContext,
+) -> PyResult<SchemaPolicy> {
+    let type_field = data
+        .get_item("type")?
+        .extract::<String>()?
+        .to_ascii_lowercase();
+    match type_field.as_str() {
+        "fixed" => Ok(SchemaPolicy::FIXED {}),
+        "versioned" => {
+            let field_defaults = unpickle_field(py, &data, "field_defaults_pkl", context)?
+                .extract::<HashMap<String, Py<PyAny>>>()?;
+            Ok(SchemaPolicy::VERSIONED { field_defaults })
+        }
+        v => Err(PyValueError::new_err(format!(
+            "Unexpected value '{}' for field: {}.type. Allowed values are 'fixed' or 'versioned'.",
+            v, context.path
+        ))),
+    }
+}
+
+#[pyfunction]
+fn schema_policy_constructor_with_info<'py>(
+    py: Python<'py>,
+    data: Bound<'py, PyAny>,
+    info: Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, SchemaPolicy>> {
+    let mut context = ValidationContext::from_info(&info)?;
+    Bound::new(py, schema_policy_constructor_aux(py, data, &mut context)?)
+}
+
+#[pyfunction]
+pub fn schema_policy_serializer<'py>(
+    py: Python<'py>,
+    schema_policy: &SchemaPolicy,
+) -> PyResult<Bound<'py, PyDict>> {
+    let data = PyDict::new(py);
+    data.set_item("type", schema_policy.to_string().to_ascii_lowercase())?;
+    if let SchemaPolicy::VERSIONED { field_defaults } = schema_policy {
+        data.set_item(
+            "field_defaults_pkl",
+            py.import("pickle")?
+                .getattr("dumps")?
+                .call1((field_defaults.clone(),))?
+                .call_method0("hex")?,
+        )?;
+    }
+    Ok(data)
+}
+
+pub fn get_schema_policy_typed_dict_schema<'py>(
+    py: Python<'py>,
+    kind: &Option<SchemaPolicyKind>,
+    core_schema: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    if kind.is_none() {
+        return core_schema.call_method1(
+            "union_schema",
+            (SchemaPolicyKind::iter()
+                .map(|k| get_schema_policy_typed_dict_schema(py, &Some(k), core_schema))
+                .collect::<PyResult<Vec<_>>>()?,),
+        );
+    }
+    let kind = kind.unwrap();
+    let typed_dict_schema = core_schema.getattr("typed_dict_schema")?;
+    let typed_dict_field = core_schema.getattr("typed_dict_field")?;
+    let str_schema = core_schema.getattr("str_schema")?;
+    let typed_dict_fields = PyDict::new(py);
+    typed_dict_fields.set_item(
+        "type",
+        typed_dict_field.call1((str_schema.call(
+            (),
+            Some(&PyDict::from_sequence(
+                &[(
+                    "pattern",
+                    [
+                        "^".to_owned(),
+                        kind.to_string().to_ascii_lowercase(),
+                        "$".to_owned(),
+                    ]
+                    .join("")
+                    .into_pyobject(py)?
+                    .into_any(),
+                )]
+                .into_pyobject(py)?,
+            )?),
+        )?,))?,
+    )?;
+    if kind == SchemaPolicyKind::VERSIONED {
+        typed_dict_fields.set_item(
+            "field_defaults_pkl",
+            typed_dict_field.call1((str_schema.call0()?,))?,
+        )?;
+    }
+    typed_dict_schema.call1((typed_dict_fields,))
+}
+
+#[pymethods]
+impl SchemaPolicy {
+    // pydantic methods
+    #[classmethod]
+    fn __get_pydantic_core_schema__<'py>(
+        cls: &Bound<'py, PyType>,
+        _source_type: Bound<'py, PyAny>,
+        _handler: Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = cls.py();
+        let core_schema = py.import("pydantic_core")?.getattr("core_schema")?;
+        let kind = SchemaPolicyKind::from_type_object(cls)?;
+        let base_schema = get_schema_policy_typed_dict_schema(py, &kind, &core_schema)?;
+        let is_instance_schema = core_schema.call_method1("is_instance_schema", (cls,))?;
+        let json_schema = core_schema.call_method1(
+            "chain_schema",
+            ([
+                base_schema.clone(),
+                core_schema.call_method1(
+                    "with_info_before_validator_function",
+                    (
+                        wrap_pyfunction!(schema_policy_constructor_with_info, py)?,
+                        core_schema.call_method0("any_schema")?,
+                    ),
+                )?,
+            ],),
+        )?;
+        let python_schema =
+            core_schema.call_method1("union_schema", ([&is_instance_schema, &json_schema],))?;
+        core_schema.call_method(
+            "json_or_python_schema",
+            (json_schema, python_schema),
+            Some(&PyDict::from_sequence(
+                &[(
+                    "serialization",
+                    core_schema.call_method(
+                        "plain_serializer_function_ser_schema",
+                        (wrap_pyfunction!(schema_policy_serializer, py)?,),
+                        Some(&PyDict::from_sequence(
+                            &[("return_schema", base_schema)].into_pyobject(py)?,
+                        )?),
+                    )?,
+                )]
+                .into_pyobject(py)?,
+            )?),
+        )
+    }
+}
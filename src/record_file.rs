@@ -0,0 +1,609 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+
+use pyo3::exceptions::{PyEOFError, PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+
+use crate::common::{AlignedBuffer, ChecksumError, crc32c};
+use crate::communication::read_exact_or_eof;
+use crate::pydantic::{
+    ValidationContext, pyany_serde_type_constructor_aux, pyany_serde_type_serializer,
+};
+use crate::{PyAnySerde, PyAnySerdeType};
+
+// A record file is a header followed by the records and, if the file was written with an index,
+// the index. The integers of the container are little-endian u64s (and u32 checksums), while the
+// records themselves use the serialized format of the file's serde type.
+//
+// header: MAGIC, flags (u8), fingerprint, schema length, schema
+// record: length, checksum of the data (if FLAG_CHECKSUMS is set), data
+// index: the offset of each record, the number of records, the offset of the index, INDEX_MAGIC
+//
+// The schema is the JSON of the serde type's pydantic serialization (with sorted keys), and the
+// fingerprint is a hash of the schema. The index is written when the writer is closed, so a file
+// which was not closed properly can still be read by scanning the records.
+const MAGIC: &[u8; 8] = b"PYASREC1";
+const INDEX_MAGIC: &[u8; 8] = b"PYASIDX1";
+const FLAG_CHECKSUMS: u8 = 1;
+const FLAG_INDEX: u8 = 2;
+const INDEX_TRAILER_SIZE: u64 = 2 * size_of::<u64>() as u64 + INDEX_MAGIC.len() as u64;
+
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+fn get_schema(py: Python<'_>, serde_type: &PyAnySerdeType) -> PyResult<String> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("sort_keys", true)?;
+    kwargs.set_item("separators", PyTuple::new(py, [",", ":"])?)?;
+    py.import("json")?
+        .getattr("dumps")?
+        .call(
+            (pyany_serde_type_serializer(py, serde_type)?,),
+            Some(&kwargs),
+        )?
+        .extract()
+}
+
+// The inverse of get_schema, which also accepts schemas which aren't in the canonical form (such
+// as the JSON of the pydantic serialization of the serde type from Python)
+pub fn get_serde_type_from_schema(py: Python<'_>, schema: &str) -> PyResult<PyAnySerdeType> {
+    let data = py.import("json")?.getattr("loads")?.call1((schema,))?;
+    pyany_serde_type_constructor_aux(
+        py,
+        &data,
+        &mut ValidationContext {
+            prompt_for_unpickle: false,
+            model_field: None,
+            path: "$".to_string(),
+        },
+    )
+}
+
+// 64-bit FNV-1a
+fn get_fingerprint(schema: &str) -> u64 {
+    schema.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+pub fn get_schema_fingerprint(py: Python<'_>, serde_type: &PyAnySerdeType) -> PyResult<u64> {
+    Ok(get_fingerprint(&get_schema(py, serde_type)?))
+}
+
+fn read_u64(reader: &mut dyn Read) -> PyResult<u64> {
+    let mut bytes = [0; size_of::<u64>()];
+    reader.read_exact(&mut bytes).map_err(eof_err)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+// Returns None if the reader is already at the end of the stream
+fn read_u64_or_eof(reader: &mut dyn Read) -> PyResult<Option<u64>> {
+    let mut bytes = [0; size_of::<u64>()];
+    if read_exact_or_eof(reader, &mut bytes).map_err(eof_err)? {
+        Ok(Some(u64::from_le_bytes(bytes)))
+    } else {
+        Ok(None)
+    }
+}
+
+fn eof_err(err: std::io::Error) -> PyErr {
+    if err.kind() == ErrorKind::UnexpectedEof {
+        PyEOFError::new_err("record file ended in the middle of a record")
+    } else {
+        err.into()
+    }
+}
+
+// Writes a sequence of objects serialized by one PyAnySerdeType to a record file. The index (if
+// enabled) is written when the writer is closed, which also happens when it is dropped.
+#[pyclass(unsendable)]
+pub struct RecordWriter {
+    writer_option: Option<Box<dyn Write>>,
+    serde: Box<dyn PyAnySerde>,
+    checksums: bool,
+    index: bool,
+    fingerprint: u64,
+    offset: u64,
+    record_offsets: Vec<u64>,
+    buf: Vec<u8>,
+}
+
+impl RecordWriter {
+    pub fn new(
+        py: Python<'_>,
+        mut writer: Box<dyn Write>,
+        serde_type: &PyAnySerdeType,
+        checksums: bool,
+        index: bool,
+    ) -> PyResult<Self> {
+        let schema = get_schema(py, serde_type)?;
+        let fingerprint = get_fingerprint(&schema);
+        let flags = if checksums { FLAG_CHECKSUMS } else { 0 } | if index { FLAG_INDEX } else { 0 };
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[flags])?;
+        writer.write_all(&fingerprint.to_le_bytes())?;
+        writer.write_all(&(schema.len() as u64).to_le_bytes())?;
+        writer.write_all(schema.as_bytes())?;
+        Ok(RecordWriter {
+            writer_option: Some(writer),
+            serde: serde_type.try_into()?,
+            checksums,
+            index,
+            fingerprint,
+            offset: (MAGIC.len() + 1 + 2 * size_of::<u64>() + schema.len()) as u64,
+            record_offsets: Vec::new(),
+            buf: Vec::new(),
+        })
+    }
+
+    pub fn create(
+        py: Python<'_>,
+        path: &Path,
+        serde_type: &PyAnySerdeType,
+        checksums: bool,
+        index: bool,
+    ) -> PyResult<Self> {
+        RecordWriter::new(
+            py,
+            Box::new(BufWriter::new(File::create(path)?)),
+            serde_type,
+            checksums,
+            index,
+        )
+    }
+
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    pub fn write<'py>(&mut self, obj: &Bound<'py, PyAny>) -> PyResult<()> {
+        let writer = self
+            .writer_option
+            .as_mut()
+            .ok_or_else(|| PyValueError::new_err("RecordWriter is closed"))?;
+        self.buf.clear();
+        // Numpy alignment is relative to the start of the record data, which RecordReader reads
+        // into an aligned buffer
+        self.serde.append_vec(&mut self.buf, Some(0), obj)?;
+        writer.write_all(&(self.buf.len() as u64).to_le_bytes())?;
+        if self.checksums {
+            writer.write_all(&crc32c(&self.buf).to_le_bytes())?;
+        }
+        writer.write_all(&self.buf)?;
+        self.record_offsets.push(self.offset);
+        self.offset += (size_of::<u64>() + self.buf.len()) as u64;
+        if self.checksums {
+            self.offset += size_of::<u32>() as u64;
+        }
+        Ok(())
+    }
+
+    // Writes the index (if enabled) and flushes the underlying writer. Closing a closed writer
+    // does nothing.
+    pub fn close(&mut self) -> PyResult<()> {
+        let Some(mut writer) = self.writer_option.take() else {
+            return Ok(());
+        };
+        if self.index {
+            for record_offset in self.record_offsets.iter() {
+                writer.write_all(&record_offset.to_le_bytes())?;
+            }
+            writer.write_all(&(self.record_offsets.len() as u64).to_le_bytes())?;
+            writer.write_all(&self.offset.to_le_bytes())?;
+            writer.write_all(INDEX_MAGIC)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for RecordWriter {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+#[pymethods]
+impl RecordWriter {
+    #[new]
+    #[pyo3(signature = (path, serde_type, checksums = true, index = true))]
+    fn new_py(
+        py: Python<'_>,
+        path: PathBuf,
+        serde_type: PyAnySerdeType,
+        checksums: bool,
+        index: bool,
+    ) -> PyResult<Self> {
+        RecordWriter::create(py, &path, &serde_type, checksums, index)
+    }
+
+    #[getter]
+    fn get_fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    #[pyo3(name = "write")]
+    fn write_py<'py>(&mut self, obj: &Bound<'py, PyAny>) -> PyResult<()> {
+        self.write(obj)
+    }
+
+    #[pyo3(name = "close")]
+    fn close_py(&mut self) -> PyResult<()> {
+        self.close()
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__<'py>(
+        &mut self,
+        _exc_type: Bound<'py, PyAny>,
+        _exc_value: Bound<'py, PyAny>,
+        _traceback: Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        self.close()
+    }
+}
+
+// Reads the records of a record file, either in order or by index. Files without an index (or
+// whose writer wasn't closed) are scanned as needed to find the records.
+#[pyclass(unsendable)]
+pub struct RecordReader {
+    reader: Box<dyn ReadSeek>,
+    serde_type: PyAnySerdeType,
+    serde: Box<dyn PyAnySerde>,
+    checksums: bool,
+    fingerprint: u64,
+    // The offset of the index, where the records end, if the file has one
+    records_end_option: Option<u64>,
+    // The length of the file when it was last checked, since it can grow while it is being read
+    file_len: u64,
+    // The offsets of the records found so far, which are all of them if all_records_found
+    record_offsets: Vec<u64>,
+    all_records_found: bool,
+    // The offset just after the last record in record_offsets
+    scan_offset: u64,
+    // The offset of the reader, and the index of the record at that offset
+    offset: u64,
+    record_idx: usize,
+}
+
+impl RecordReader {
+    // If serde_type is None, the serde type is reconstructed from the schema in the file, which
+    // unpickles any pickled classes and values it contains. Only do this with trusted files.
+    pub fn new(
+        py: Python<'_>,
+        mut reader: Box<dyn ReadSeek>,
+        serde_type: Option<&PyAnySerdeType>,
+    ) -> PyResult<Self> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic).map_err(eof_err)?;
+        if &magic != MAGIC {
+            Err(PyValueError::new_err("not a record file"))?
+        }
+        let mut flags = [0];
+        reader.read_exact(&mut flags).map_err(eof_err)?;
+        let flags = flags[0];
+        let fingerprint = read_u64(&mut reader)?;
+        let schema_len = read_u64(&mut reader)?;
+        let schema_bytes = AlignedBuffer::read_from(&mut reader, schema_len as usize)
+            .map_err(eof_err)?
+            .as_slice()
+            .to_vec();
+        let schema = String::from_utf8(schema_bytes).map_err(|err| {
+            PyValueError::new_err(format!("record file schema is invalid: {err}"))
+        })?;
+        if get_fingerprint(&schema) != fingerprint {
+            Err(PyValueError::new_err(
+                "record file schema does not match its fingerprint",
+            ))?
+        }
+        let serde_type = match serde_type {
+            Some(serde_type) => {
+                if get_schema_fingerprint(py, serde_type)? != fingerprint {
+                    Err(PyValueError::new_err(format!(
+                        "serde type does not match the schema of the record file: {schema}"
+                    )))?
+                }
+                serde_type.clone()
+            }
+            None => get_serde_type_from_schema(py, &schema)?,
+        };
+        let records_start = (MAGIC.len() + 1 + 2 * size_of::<u64>()) as u64 + schema_len;
+
+        let mut records_end_option = None;
+        let mut record_offsets = Vec::new();
+        let mut all_records_found = false;
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        if flags & FLAG_INDEX != 0 && file_len >= records_start + INDEX_TRAILER_SIZE {
+            reader.seek(SeekFrom::End(-(INDEX_TRAILER_SIZE as i64)))?;
+            let n_records = read_u64(&mut reader)?;
+            let index_offset = read_u64(&mut reader)?;
+            let mut index_magic = [0; INDEX_MAGIC.len()];
+            reader.read_exact(&mut index_magic).map_err(eof_err)?;
+            if &index_magic == INDEX_MAGIC
+                && index_offset >= records_start
+                && index_offset + n_records * size_of::<u64>() as u64 + INDEX_TRAILER_SIZE
+                    == file_len
+            {
+                reader.seek(SeekFrom::Start(index_offset))?;
+                record_offsets = (0..n_records)
+                    .map(|_| read_u64(&mut reader))
+                    .collect::<PyResult<_>>()?;
+                records_end_option = Some(index_offset);
+                all_records_found = true;
+            }
+        }
+        reader.seek(SeekFrom::Start(records_start))?;
+        Ok(RecordReader {
+            reader,
+            serde: (&serde_type).try_into()?,
+            serde_type,
+            checksums: flags & FLAG_CHECKSUMS != 0,
+            fingerprint,
+            records_end_option,
+            file_len,
+            record_offsets,
+            all_records_found,
+            scan_offset: records_start,
+            offset: records_start,
+            record_idx: 0,
+        })
+    }
+
+    pub fn open(
+        py: Python<'_>,
+        path: &Path,
+        serde_type: Option<&PyAnySerdeType>,
+    ) -> PyResult<Self> {
+        RecordReader::new(py, Box::new(BufReader::new(File::open(path)?)), serde_type)
+    }
+
+    pub fn serde_type(&self) -> &PyAnySerdeType {
+        &self.serde_type
+    }
+
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    fn record_header_size(&self) -> u64 {
+        if self.checksums {
+            (size_of::<u64>() + size_of::<u32>()) as u64
+        } else {
+            size_of::<u64>() as u64
+        }
+    }
+
+    // Reads the length at the current offset, or returns None if there are no more records
+    fn read_record_len(&mut self) -> PyResult<Option<u64>> {
+        if self
+            .records_end_option
+            .is_some_and(|records_end| self.offset >= records_end)
+        {
+            return Ok(None);
+        }
+        read_u64_or_eof(&mut self.reader)
+    }
+
+    // Returns the offset of the record after the one at the current offset, whose length len has
+    // just been read. Fails if the record would end past the end of the records.
+    fn next_record_offset(&mut self, len: u64) -> PyResult<u64> {
+        let next_offset_option = self
+            .offset
+            .checked_add(self.record_header_size())
+            .and_then(|offset| offset.checked_add(len));
+        if let Some(next_offset) = next_offset_option
+            && next_offset > self.file_len
+            && self.records_end_option.is_none()
+        {
+            let position = self.reader.stream_position()?;
+            self.file_len = self.reader.seek(SeekFrom::End(0))?;
+            self.reader.seek(SeekFrom::Start(position))?;
+        }
+        let records_end = self.records_end_option.unwrap_or(self.file_len);
+        next_offset_option
+            .filter(|&next_offset| next_offset <= records_end)
+            .ok_or_else(|| PyEOFError::new_err("record file ended in the middle of a record"))
+    }
+
+    // Finds records until n_records have been found (or all of them, if n_records is None)
+    fn scan(&mut self, n_records: Option<usize>) -> PyResult<()> {
+        if self.all_records_found {
+            return Ok(());
+        }
+        let (offset, record_idx) = (self.offset, self.record_idx);
+        self.offset = self.scan_offset;
+        self.reader.seek(SeekFrom::Start(self.offset))?;
+        let result = self.scan_from_offset(n_records);
+        self.scan_offset = self.offset;
+        (self.offset, self.record_idx) = (offset, record_idx);
+        self.reader.seek(SeekFrom::Start(self.offset))?;
+        result
+    }
+
+    fn scan_from_offset(&mut self, n_records: Option<usize>) -> PyResult<()> {
+        while n_records.is_none_or(|n_records| self.record_offsets.len() < n_records) {
+            let Some(len) = self.read_record_len()? else {
+                self.all_records_found = true;
+                break;
+            };
+            let next_offset = self.next_record_offset(len)?;
+            self.record_offsets.push(self.offset);
+            self.offset = next_offset;
+            self.reader.seek(SeekFrom::Start(self.offset))?;
+        }
+        Ok(())
+    }
+
+    pub fn len(&mut self) -> PyResult<usize> {
+        self.scan(None)?;
+        Ok(self.record_offsets.len())
+    }
+
+    pub fn is_empty(&mut self) -> PyResult<bool> {
+        self.scan(Some(1))?;
+        Ok(self.record_offsets.is_empty())
+    }
+
+    // Moves the reader to the record with index record_idx (or to the end, if record_idx is the
+    // number of records)
+    pub fn seek(&mut self, record_idx: usize) -> PyResult<()> {
+        self.scan(Some(record_idx + 1))?;
+        let offset = if let Some(&offset) = self.record_offsets.get(record_idx) {
+            offset
+        } else if record_idx == self.record_offsets.len() {
+            self.scan_offset
+        } else {
+            Err(PyIndexError::new_err(format!(
+                "record index {record_idx} is out of range for a record file with {} records",
+                self.record_offsets.len()
+            )))?
+        };
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.offset = offset;
+        self.record_idx = record_idx;
+        Ok(())
+    }
+
+    // Reads the checksum (if there is one) and the data of a record of len bytes, whose length has
+    // already been read
+    fn read_record_data(&mut self, len: usize) -> PyResult<(Option<u32>, AlignedBuffer)> {
+        let checksum_option = if self.checksums {
+            let mut bytes = [0; size_of::<u32>()];
+            self.reader.read_exact(&mut bytes).map_err(eof_err)?;
+            Some(u32::from_le_bytes(bytes))
+        } else {
+            None
+        };
+        let aligned = AlignedBuffer::read_from(&mut self.reader, len).map_err(eof_err)?;
+        Ok((checksum_option, aligned))
+    }
+
+    // Reads the record at the current offset, returning None at the end of the records. If the
+    // record is incomplete, the reader stays at the start of the record. Otherwise, it moves on to
+    // the next record even if this one fails its checksum or can't be retrieved.
+    pub fn read_next<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let record = self.read_record_len().and_then(|len_option| {
+            len_option
+                .map(|len| {
+                    let next_offset = self.next_record_offset(len)?;
+                    let (checksum_option, aligned) = self.read_record_data(len as usize)?;
+                    Ok((next_offset, checksum_option, aligned))
+                })
+                .transpose()
+        });
+        let (next_offset, checksum_option, aligned) = match record {
+            Ok(Some(record)) => record,
+            Ok(None) => {
+                if self.record_idx == self.record_offsets.len() {
+                    self.all_records_found = true;
+                }
+                return Ok(None);
+            }
+            Err(err) => {
+                self.reader.seek(SeekFrom::Start(self.offset))?;
+                return Err(err);
+            }
+        };
+        let buf = aligned.as_slice();
+        let len = buf.len();
+        let record_idx = self.record_idx;
+        if self.record_idx == self.record_offsets.len() {
+            self.record_offsets.push(self.offset);
+        }
+        self.offset = next_offset;
+        self.record_idx += 1;
+        if self.record_idx == self.record_offsets.len() {
+            self.scan_offset = self.scan_offset.max(self.offset);
+        }
+        if let Some(checksum) = checksum_option
+            && crc32c(buf) != checksum
+        {
+            Err(ChecksumError::new_err(format!(
+                "record {record_idx} of the record file failed its checksum"
+            )))?
+        }
+        let (obj, offset) = self.serde.retrieve(py, buf, 0)?;
+        if offset != len {
+            Err(PyValueError::new_err(format!(
+                "record {record_idx} of {len} bytes contained {offset} bytes of serialized data"
+            )))?
+        }
+        Ok(Some(obj))
+    }
+
+    pub fn read_record<'py>(
+        &mut self,
+        py: Python<'py>,
+        record_idx: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.seek(record_idx)?;
+        match self.read_next(py)? {
+            Some(obj) => Ok(obj),
+            None => Err(PyIndexError::new_err(format!(
+                "record index {record_idx} is out of range for a record file with {} records",
+                self.len()?
+            ))),
+        }
+    }
+}
+
+#[pymethods]
+impl RecordReader {
+    #[new]
+    #[pyo3(signature = (path, serde_type = None))]
+    fn new_py(py: Python<'_>, path: PathBuf, serde_type: Option<PyAnySerdeType>) -> PyResult<Self> {
+        RecordReader::open(py, &path, serde_type.as_ref())
+    }
+
+    #[getter]
+    fn get_serde_type(&self) -> PyAnySerdeType {
+        self.serde_type.clone()
+    }
+
+    #[getter]
+    fn get_fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    #[pyo3(name = "seek")]
+    fn seek_py(&mut self, record_idx: usize) -> PyResult<()> {
+        self.seek(record_idx)
+    }
+
+    fn __len__(&mut self) -> PyResult<usize> {
+        self.len()
+    }
+
+    fn __getitem__<'py>(
+        &mut self,
+        py: Python<'py>,
+        record_idx: isize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let record_idx = if record_idx < 0 {
+            let len = self.len()?;
+            usize::try_from(len as isize + record_idx).map_err(|_| {
+                PyIndexError::new_err(format!(
+                    "record index {record_idx} is out of range for a record file with {len} records"
+                ))
+            })?
+        } else {
+            record_idx as usize
+        };
+        self.read_record(py, record_idx)
+    }
+
+    fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.read_next(py)
+    }
+}
@@ -1,14 +1,22 @@
-use pyo3::{prelude::*, types::PyDict};
-use std::{
-    collections::{BTreeMap, HashSet},
-    fs,
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict},
 };
+use std::fs;
+use std::io::Cursor;
 use strum::IntoEnumIterator;
 
 use crate::{
-    PyAnySerdeType,
-    pyany_serde_impl::{InitStrategy, InitStrategyKind, NumpySerdeConfig, NumpySerdeConfigKind},
-    pyany_serde_type::PyAnySerdeTypeKind,
+    PyAnySerde, PyAnySerdeType,
+    arrow_export::ArrowIpcExporter,
+    common::ChecksumError,
+    communication::RingBuffer,
+    pyany_serde_impl::{
+        ExtraPolicy, ExtraPolicyKind, InitStrategy, InitStrategyKind, NumpySerdeConfig,
+        NumpySerdeConfigKind, SchemaPolicy, SchemaPolicyKind,
+    },
+    pyany_serde_type::{FieldSerdeTypeMap, PyAnySerdeTypeKind},
+    record_file::{RecordReader, RecordWriter},
 };
 
 fn validate_fn_eq<'py>(
@@ -68,6 +76,100 @@ pub fn validate_init_strategy_eq(
                 panic!("Expected field {field} to be InitStrategy::NONE {{}} but was {actual}");
             };
         }
+        InitStrategy::AUTO {} => {
+            let InitStrategy::AUTO {} = expected.clone() else {
+                panic!("Expected field {field} to be InitStrategy::AUTO {{}} but was {actual}");
+            };
+        }
+        InitStrategy::BYPASS {} => {
+            let InitStrategy::BYPASS {} = expected.clone() else {
+                panic!("Expected field {field} to be InitStrategy::BYPASS {{}} but was {actual}");
+            };
+        }
+        InitStrategy::MODELCONSTRUCT {} => {
+            let InitStrategy::MODELCONSTRUCT {} = expected.clone() else {
+                panic!(
+                    "Expected field {field} to be InitStrategy::MODELCONSTRUCT {{}} but was {actual}"
+                );
+            };
+        }
+    };
+    Ok(())
+}
+
+#[pyfunction(name = "validate_eq")]
+pub fn validate_extra_policy_eq<'py>(
+    py: Python<'py>,
+    expected: &ExtraPolicy,
+    actual: &ExtraPolicy,
+    field: String,
+) -> PyResult<()> {
+    match actual.clone() {
+        ExtraPolicy::IGNORE {} => {
+            let ExtraPolicy::IGNORE {} = expected.clone() else {
+                panic!("Expected field {field} to be ExtraPolicy::IGNORE {{}} but was {actual}");
+            };
+        }
+        ExtraPolicy::FORBID {} => {
+            let ExtraPolicy::FORBID {} = expected.clone() else {
+                panic!("Expected field {field} to be ExtraPolicy::FORBID {{}} but was {actual}");
+            };
+        }
+        ExtraPolicy::PASSTHROUGH {
+            fallback_serde_type: actual_fallback_serde_type,
+        } => {
+            let ExtraPolicy::PASSTHROUGH {
+                fallback_serde_type: expected_fallback_serde_type,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be ExtraPolicy::PASSTHROUGH {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_fallback_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_fallback_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.fallback_serde_type"),
+            )?;
+        }
+    };
+    Ok(())
+}
+
+#[pyfunction(name = "validate_eq")]
+pub fn validate_schema_policy_eq<'py>(
+    py: Python<'py>,
+    expected: &SchemaPolicy,
+    actual: &SchemaPolicy,
+    field: String,
+) -> PyResult<()> {
+    match actual.clone() {
+        SchemaPolicy::FIXED {} => {
+            let SchemaPolicy::FIXED {} = expected.clone() else {
+                panic!("Expected field {field} to be SchemaPolicy::FIXED {{}} but was {actual}");
+            };
+        }
+        SchemaPolicy::VERSIONED {
+            field_defaults: actual_field_defaults,
+        } => {
+            let SchemaPolicy::VERSIONED {
+                field_defaults: expected_field_defaults,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be SchemaPolicy::VERSIONED {{..}} but was {actual}"
+                );
+            };
+            let actual_field_defaults = actual_field_defaults.into_pyobject(py)?;
+            let expected_field_defaults = expected_field_defaults.into_pyobject(py)?;
+            assert!(
+                actual_field_defaults.eq(&expected_field_defaults)?,
+                "Expected field {field}.field_defaults to be {} but was {}",
+                expected_field_defaults.repr()?,
+                actual_field_defaults.repr()?,
+            );
+        }
     };
     Ok(())
 }
@@ -161,15 +263,15 @@ pub fn validate_numpy_serde_config_eq<'py>(
 
 fn validate_key_serde_type_dict_eq<'py>(
     py: Python<'py>,
-    expected: &BTreeMap<String, PyAnySerdeType>,
-    actual: &BTreeMap<String, PyAnySerdeType>,
+    expected: &FieldSerdeTypeMap,
+    actual: &FieldSerdeTypeMap,
     field: String,
 ) -> PyResult<()> {
-    let expected_keys = expected.keys().collect::<HashSet<_>>();
-    let actual_keys = actual.keys().collect::<HashSet<_>>();
+    let expected_keys = expected.keys().collect::<Vec<_>>();
+    let actual_keys = actual.keys().collect::<Vec<_>>();
     assert_eq!(
         expected_keys, actual_keys,
-        "Expected {field} to have keys {:?} but had keys {:?} instead",
+        "Expected {field} to have keys {:?} (in this order) but had keys {:?} instead",
         expected_keys, actual_keys
     );
     for (key, expected_serde_type) in expected.iter() {
@@ -201,6 +303,48 @@ pub fn validate_pyany_serde_type_eq<'py>(
                 panic!("Expected field {field} to be PyAnySerdeType::BYTES {{}} but was {actual}");
             };
         }
+        PyAnySerdeType::CHECKSUMMED {
+            value_serde_type: actual_value_serde_type,
+        } => {
+            let PyAnySerdeType::CHECKSUMMED {
+                value_serde_type: expected_value_serde_type,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::CHECKSUMMED {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_value_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_value_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.value_serde_type"),
+            )?;
+        }
+        PyAnySerdeType::COLUMNARLIST {
+            items_serde_type: actual_items_serde_type,
+            as_columns: actual_as_columns,
+        } => {
+            let PyAnySerdeType::COLUMNARLIST {
+                items_serde_type: expected_items_serde_type,
+                as_columns: expected_as_columns,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::COLUMNARLIST {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.items_serde_type"),
+            )?;
+            assert_eq!(
+                expected_as_columns, actual_as_columns,
+                "Expected field {field}.as_columns to be {expected_as_columns} but was {actual_as_columns}",
+            );
+        }
         PyAnySerdeType::COMPLEX {} => {
             let PyAnySerdeType::COMPLEX {} = expected.clone() else {
                 panic!(
@@ -208,15 +352,58 @@ pub fn validate_pyany_serde_type_eq<'py>(
                 );
             };
         }
+        PyAnySerdeType::COMPRESSED {
+            value_serde_type: actual_value_serde_type,
+            codec: actual_codec,
+            level: actual_level,
+            min_size: actual_min_size,
+        } => {
+            let PyAnySerdeType::COMPRESSED {
+                value_serde_type: expected_value_serde_type,
+                codec: expected_codec,
+                level: expected_level,
+                min_size: expected_min_size,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::COMPRESSED {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_value_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_value_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.value_serde_type"),
+            )?;
+            assert_eq!(
+                expected_codec, actual_codec,
+                "Expected field {field}.codec to be {expected_codec} but was {actual_codec}",
+            );
+            assert_eq!(
+                expected_level, actual_level,
+                "Expected field {field}.level to be {:?} but was {:?}",
+                expected_level, actual_level
+            );
+            assert_eq!(
+                expected_min_size, actual_min_size,
+                "Expected field {field}.min_size to be {expected_min_size} but was {actual_min_size}",
+            );
+        }
         PyAnySerdeType::DATACLASS {
             clazz: actual_clazz,
             init_strategy: actual_init_strategy,
             field_serde_type_dict: actual_field_serde_type_dict,
+            extra: actual_extra,
+            alphabetical_field_order: actual_alphabetical_field_order,
+            schema: actual_schema,
         } => {
             let PyAnySerdeType::DATACLASS {
                 clazz: expected_clazz,
                 init_strategy: expected_init_strategy,
                 field_serde_type_dict: expected_field_serde_type_dict,
+                extra: expected_extra,
+                alphabetical_field_order: expected_alphabetical_field_order,
+                schema: expected_schema,
             } = expected.clone()
             else {
                 panic!(
@@ -242,6 +429,94 @@ pub fn validate_pyany_serde_type_eq<'py>(
                 &actual_field_serde_type_dict,
                 format!("{field}.field_serde_type_dict"),
             )?;
+            validate_extra_policy_eq(py, &expected_extra, &actual_extra, format!("{field}.extra"))?;
+            assert_eq!(
+                expected_alphabetical_field_order, actual_alphabetical_field_order,
+                "Expected field {field}.alphabetical_field_order to be {} but was {}",
+                expected_alphabetical_field_order, actual_alphabetical_field_order
+            );
+            validate_schema_policy_eq(
+                py,
+                &expected_schema,
+                &actual_schema,
+                format!("{field}.schema"),
+            )?;
+        }
+        PyAnySerdeType::DEFAULTDICT {
+            keys_serde_type: actual_keys_serde_type,
+            values_serde_type: actual_values_serde_type,
+        } => {
+            let PyAnySerdeType::DEFAULTDICT {
+                keys_serde_type: expected_keys_serde_type,
+                values_serde_type: expected_values_serde_type,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::DEFAULTDICT {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_keys_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_keys_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.keys_serde_type"),
+            )?;
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_values_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_values_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.values_serde_type"),
+            )?;
+        }
+        PyAnySerdeType::DEFINITIONS {
+            definitions: actual_definitions,
+            root_serde_type: actual_root_serde_type,
+            max_depth: actual_max_depth,
+        } => {
+            let PyAnySerdeType::DEFINITIONS {
+                definitions: expected_definitions,
+                root_serde_type: expected_root_serde_type,
+                max_depth: expected_max_depth,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::DEFINITIONS {{..}} but was {actual}"
+                );
+            };
+            validate_key_serde_type_dict_eq(
+                py,
+                &expected_definitions,
+                &actual_definitions,
+                format!("{field}.definitions"),
+            )?;
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_root_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_root_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.root_serde_type"),
+            )?;
+            assert_eq!(
+                expected_max_depth, actual_max_depth,
+                "Expected field {field}.max_depth to be {expected_max_depth} but was {actual_max_depth}",
+            );
+        }
+        PyAnySerdeType::DEQUE {
+            items_serde_type: actual_items_serde_type,
+        } => {
+            let PyAnySerdeType::DEQUE {
+                items_serde_type: expected_items_serde_type,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::DEQUE {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.items_serde_type"),
+            )?;
         }
         PyAnySerdeType::DICT {
             keys_serde_type: actual_keys_serde_type,
@@ -274,11 +549,76 @@ pub fn validate_pyany_serde_type_eq<'py>(
                 );
             };
         }
+        PyAnySerdeType::ENUM {
+            values: actual_values,
+        } => {
+            let PyAnySerdeType::ENUM {
+                values: expected_values,
+            } = expected.clone()
+            else {
+                panic!("Expected field {field} to be PyAnySerdeType::ENUM {{..}} but was {actual}");
+            };
+            assert_eq!(
+                expected_values.len(),
+                actual_values.len(),
+                "Expected field {field}.values to have length {} but was {}",
+                expected_values.len(),
+                actual_values.len()
+            );
+            for (idx, (expected_value, actual_value)) in
+                expected_values.iter().zip(actual_values.iter()).enumerate()
+            {
+                let expected_value = expected_value.bind(py);
+                let actual_value = actual_value.bind(py);
+                assert!(
+                    actual_value.eq(expected_value)?,
+                    "Expected field {field}.values[{idx}] to be {} but was {}",
+                    expected_value.repr()?,
+                    actual_value.repr()?,
+                );
+            }
+        }
         PyAnySerdeType::FLOAT {} => {
             let PyAnySerdeType::FLOAT {} = expected.clone() else {
                 panic!("Expected field {field} to be PyAnySerdeType::FLOAT {{}} but was {actual}");
             };
         }
+        PyAnySerdeType::FROZENSET {
+            items_serde_type: actual_items_serde_type,
+        } => {
+            let PyAnySerdeType::FROZENSET {
+                items_serde_type: expected_items_serde_type,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::FROZENSET {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.items_serde_type"),
+            )?;
+        }
+        PyAnySerdeType::HOMOGENEOUSTUPLE {
+            items_serde_type: actual_items_serde_type,
+        } => {
+            let PyAnySerdeType::HOMOGENEOUSTUPLE {
+                items_serde_type: expected_items_serde_type,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::HOMOGENEOUSTUPLE {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.items_serde_type"),
+            )?;
+        }
         PyAnySerdeType::INT {} => {
             let PyAnySerdeType::INT {} = expected.clone() else {
                 panic!("Expected field {field} to be PyAnySerdeType::INT {{}} but was {actual}");
@@ -300,6 +640,59 @@ pub fn validate_pyany_serde_type_eq<'py>(
                 format!("{field}.items_serde_type"),
             )?;
         }
+        PyAnySerdeType::MEMO {
+            value_serde_type: actual_value_serde_type,
+        } => {
+            let PyAnySerdeType::MEMO {
+                value_serde_type: expected_value_serde_type,
+            } = expected.clone()
+            else {
+                panic!("Expected field {field} to be PyAnySerdeType::MEMO {{..}} but was {actual}");
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_value_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_value_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.value_serde_type"),
+            )?;
+        }
+        PyAnySerdeType::NAMEDTUPLE {
+            clazz: actual_clazz,
+            item_serde_types: actual_item_serde_types,
+        } => {
+            let PyAnySerdeType::NAMEDTUPLE {
+                clazz: expected_clazz,
+                item_serde_types: expected_item_serde_types,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::NAMEDTUPLE {{..}} but was {actual}"
+                );
+            };
+            let actual_clazz = actual_clazz.bind(py);
+            let expected_clazz = expected_clazz.bind(py);
+            assert!(
+                actual_clazz.eq(expected_clazz)?,
+                "Expected field {field}.clazz to be {} but was {}",
+                expected_clazz.repr()?,
+                actual_clazz.repr()?,
+            );
+            assert_eq!(
+                expected_item_serde_types.len(),
+                actual_item_serde_types.len(),
+                "Expected field {field}.item_serde_types to have length {} but was {}",
+                expected_item_serde_types.len(),
+                actual_item_serde_types.len()
+            );
+            for (idx, actual_serde_type) in actual_item_serde_types.iter().enumerate() {
+                validate_pyany_serde_type_eq(
+                    py,
+                    expected_item_serde_types.get(idx).unwrap(),
+                    actual_serde_type,
+                    format!("{field}.item_serde_types[{idx}]"),
+                )?;
+            }
+        }
         PyAnySerdeType::NUMPY {
             dtype: actual_dtype,
             config: actual_config,
@@ -343,11 +736,68 @@ pub fn validate_pyany_serde_type_eq<'py>(
                 format!("{field}.value_serde_type"),
             )?;
         }
+        PyAnySerdeType::ORDEREDDICT {
+            keys_serde_type: actual_keys_serde_type,
+            values_serde_type: actual_values_serde_type,
+        } => {
+            let PyAnySerdeType::ORDEREDDICT {
+                keys_serde_type: expected_keys_serde_type,
+                values_serde_type: expected_values_serde_type,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::ORDEREDDICT {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_keys_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_keys_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.keys_serde_type"),
+            )?;
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_values_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_values_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.values_serde_type"),
+            )?;
+        }
         PyAnySerdeType::PICKLE {} => {
             let PyAnySerdeType::PICKLE {} = expected.clone() else {
                 panic!("Expected field {field} to be PyAnySerdeType::PICKLE {{}} but was {actual}");
             };
         }
+        PyAnySerdeType::PRIMITIVELIST {
+            items_serde_type: actual_items_serde_type,
+            length: actual_length,
+            as_tuple: actual_as_tuple,
+        } => {
+            let PyAnySerdeType::PRIMITIVELIST {
+                items_serde_type: expected_items_serde_type,
+                length: expected_length,
+                as_tuple: expected_as_tuple,
+            } = expected.clone()
+            else {
+                panic!(
+                    "Expected field {field} to be PyAnySerdeType::PRIMITIVELIST {{..}} but was {actual}"
+                );
+            };
+            validate_pyany_serde_type_eq(
+                py,
+                &expected_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                &actual_items_serde_type.extract::<PyAnySerdeType>(py)?,
+                format!("{field}.items_serde_type"),
+            )?;
+            assert_eq!(
+                expected_length, actual_length,
+                "Expected field {field}.length to be {:?} but was {:?}",
+                expected_length, actual_length
+            );
+            assert_eq!(
+                expected_as_tuple, actual_as_tuple,
+                "Expected field {field}.as_tuple to be {expected_as_tuple} but was {actual_as_tuple}",
+            );
+        }
         PyAnySerdeType::PYTHONSERDE {
             python_serde: actual_python_serde,
         } => {
@@ -368,6 +818,18 @@ pub fn validate_pyany_serde_type_eq<'py>(
                 expected_python_serde.repr()?
             );
         }
+        PyAnySerdeType::REF { name: actual_name } => {
+            let PyAnySerdeType::REF {
+                name: expected_name,
+            } = expected.clone()
+            else {
+                panic!("Expected field {field} to be PyAnySerdeType::REF {{..}} but was {actual}");
+            };
+            assert_eq!(
+                expected_name, actual_name,
+                "Expected field {field}.name to be {expected_name} but was {actual_name}",
+            );
+        }
         PyAnySerdeType::SET {
             items_serde_type: actual_items_serde_type,
         } => {
@@ -418,9 +880,17 @@ pub fn validate_pyany_serde_type_eq<'py>(
         }
         PyAnySerdeType::TYPEDDICT {
             key_serde_type_dict: actual_key_serde_type_dict,
+            optional_keys: actual_optional_keys,
+            extra: actual_extra,
+            alphabetical_field_order: actual_alphabetical_field_order,
+            schema: actual_schema,
         } => {
             let PyAnySerdeType::TYPEDDICT {
                 key_serde_type_dict: expected_key_serde_type_dict,
+                optional_keys: expected_optional_keys,
+                extra: expected_extra,
+                alphabetical_field_order: expected_alphabetical_field_order,
+                schema: expected_schema,
             } = expected.clone()
             else {
                 panic!(
@@ -433,6 +903,23 @@ pub fn validate_pyany_serde_type_eq<'py>(
                 &actual_key_serde_type_dict,
                 format!("{field}.key_serde_type_dict"),
             )?;
+            assert_eq!(
+                expected_optional_keys, actual_optional_keys,
+                "Expected field {field}.optional_keys to be {:?} but was {:?}",
+                expected_optional_keys, actual_optional_keys
+            );
+            validate_extra_policy_eq(py, &expected_extra, &actual_extra, format!("{field}.extra"))?;
+            assert_eq!(
+                expected_alphabetical_field_order, actual_alphabetical_field_order,
+                "Expected field {field}.alphabetical_field_order to be {} but was {}",
+                expected_alphabetical_field_order, actual_alphabetical_field_order
+            );
+            validate_schema_policy_eq(
+                py,
+                &expected_schema,
+                &actual_schema,
+                format!("{field}.schema"),
+            )?;
         }
         PyAnySerdeType::UNION {
             option_serde_types: actual_option_serde_types,
@@ -472,15 +959,91 @@ pub fn validate_pyany_serde_type_eq<'py>(
     Ok(())
 }
 
-pub fn run_python_test_file<'py>(
+// Serializes obj with a serde of serde_type, checking that append, append_vec, and append_to_writer
+// all write the same bytes
+#[pyfunction]
+pub fn serialize<'py>(
+    py: Python<'py>,
+    serde_type: &PyAnySerdeType,
+    obj: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let mut serde: Box<dyn PyAnySerde> = serde_type.try_into()?;
+    let mut v = Vec::new();
+    serde.append_vec(&mut v, Some(0), obj)?;
+    let mut buf = vec![0; v.len()];
+    let end = serde.append(&mut buf, 0, obj)?;
+    assert_eq!(end, v.len(), "Expected append to write {} bytes", v.len());
+    assert_eq!(
+        buf, v,
+        "Expected append to write the same bytes as append_vec"
+    );
+    let mut stream = Vec::new();
+    serde.append_to_writer(&mut stream, obj)?;
+    assert_eq!(
+        stream, v,
+        "Expected append_to_writer to write the same bytes as append_vec"
+    );
+    Ok(PyBytes::new(py, &v))
+}
+
+// Retrieves an object from data with a serde of serde_type, checking that retrieve and
+// retrieve_from_reader agree and that all of data is used
+#[pyfunction]
+pub fn deserialize<'py>(
+    py: Python<'py>,
+    serde_type: &PyAnySerdeType,
+    data: &[u8],
+) -> PyResult<Bound<'py, PyAny>> {
+    let mut serde: Box<dyn PyAnySerde> = serde_type.try_into()?;
+    let (obj, offset) = serde.retrieve(py, data, 0)?;
+    assert_eq!(
+        offset,
+        data.len(),
+        "Expected retrieve to read all of the data"
+    );
+    let mut reader = Cursor::new(data);
+    let streamed_obj = serde.retrieve_from_reader(py, &mut reader)?.unwrap();
+    assert_eq!(
+        reader.position() as usize,
+        data.len(),
+        "Expected retrieve_from_reader to read all of the data"
+    );
+    assert!(
+        streamed_obj.eq(&obj)?,
+        "Expected retrieve_from_reader to retrieve {} but retrieved {}",
+        obj.repr()?,
+        streamed_obj.repr()?,
+    );
+    Ok(obj)
+}
+
+// Runs the python file at path as the python_test module, with tests_submod as a submodule of
+// pyany_serde, and returns the globals of the module
+pub fn load_python_test_file<'py>(
     py: Python<'py>,
     path: &str,
     tests_submod: Bound<'py, PyModule>,
-) -> PyResult<()> {
+) -> PyResult<Bound<'py, PyDict>> {
     let module = PyModule::new(py, "pyany_serde")?;
+    module.add_class::<ArrowIpcExporter>()?;
+    module.add("ChecksumError", py.get_type::<ChecksumError>())?;
+    module.add_class::<ExtraPolicy>()?;
     module.add_class::<InitStrategy>()?;
     module.add_class::<NumpySerdeConfig>()?;
     module.add_class::<PyAnySerdeType>()?;
+    module.add_class::<RecordReader>()?;
+    module.add_class::<RecordWriter>()?;
+    module.add_class::<RingBuffer>()?;
+    module.add_class::<SchemaPolicy>()?;
+    module
+        .getattr("ArrowIpcExporter")?
+        .setattr("__module__", module.name()?)?;
+    module
+        .getattr("ExtraPolicy")?
+        .setattr("__module__", module.name()?)?;
+    for kind in ExtraPolicyKind::iter() {
+        kind.type_object(py).setattr("__module__", module.name()?)?;
+    }
     module
         .getattr("InitStrategy")?
         .setattr("__module__", module.name()?)?;
@@ -499,6 +1062,21 @@ pub fn run_python_test_file<'py>(
     for kind in PyAnySerdeTypeKind::iter() {
         kind.type_object(py).setattr("__module__", module.name()?)?;
     }
+    module
+        .getattr("RecordReader")?
+        .setattr("__module__", module.name()?)?;
+    module
+        .getattr("RecordWriter")?
+        .setattr("__module__", module.name()?)?;
+    module
+        .getattr("RingBuffer")?
+        .setattr("__module__", module.name()?)?;
+    module
+        .getattr("SchemaPolicy")?
+        .setattr("__module__", module.name()?)?;
+    for kind in SchemaPolicyKind::iter() {
+        kind.type_object(py).setattr("__module__", module.name()?)?;
+    }
     module.add_submodule(&tests_submod)?;
     let modules = py
         .import("sys")?
@@ -529,8 +1107,15 @@ pub fn run_python_test_file<'py>(
         Some(&globals),
     )?;
     modules.set_item("python_test", &test_module)?;
+    Ok(globals)
+}
 
-    for (name, value) in globals.iter() {
+pub fn run_python_test_file<'py>(
+    py: Python<'py>,
+    path: &str,
+    tests_submod: Bound<'py, PyModule>,
+) -> PyResult<()> {
+    for (name, value) in load_python_test_file(py, path, tests_submod)?.iter() {
         let Some(name) = name.extract::<&str>().ok() else {
             continue;
         };
@@ -546,3 +1131,37 @@ pub fn run_python_test_file<'py>(
 
     Ok(())
 }
+
+// Defines tests_submod, which makes the given pyfunctions available to python/tests/{name}.py as
+// the pyany_serde.{name}_tests module, and the test run_{name}_tests, which runs that file
+#[macro_export]
+macro_rules! python_tests {
+    ($name:ident $(, $function:path)* $(,)?) => {
+        paste::paste! {
+            fn tests_submod<'py>(
+                py: pyo3::Python<'py>,
+            ) -> pyo3::PyResult<pyo3::Bound<'py, pyo3::types::PyModule>> {
+                let sub = pyo3::types::PyModule::new(py, concat!(stringify!($name), "_tests"))?;
+                $(
+                    pyo3::types::PyModuleMethods::add_function(
+                        &sub,
+                        pyo3::wrap_pyfunction!($function, py)?,
+                    )?;
+                )*
+                Ok(sub)
+            }
+
+            #[test]
+            fn [<run_ $name _tests>]() -> pyo3::PyResult<()> {
+                pyo3::Python::initialize();
+                pyo3::Python::attach(|py| {
+                    $crate::tests::run_python_test_file(
+                        py,
+                        concat!("python/tests/", stringify!($name), ".py"),
+                        tests_submod(py)?,
+                    )
+                })
+            }
+        }
+    };
+}
@@ -0,0 +1,4 @@
+use crate::python_tests;
+use crate::tests::{deserialize, serialize};
+
+python_tests!(definitions, serialize, deserialize);
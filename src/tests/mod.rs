@@ -1,8 +1,18 @@
 mod common;
+mod containers;
+mod definitions;
+mod enum_serde;
+mod memo;
 mod pickling;
 mod pydantic;
+mod record_file;
+mod ring_buffer;
+mod type_hint;
+mod typed_dict;
+mod versioned;
 
 pub use common::{
-    run_python_test_file, validate_init_strategy_eq, validate_numpy_serde_config_eq,
-    validate_pyany_serde_type_eq,
+    deserialize, load_python_test_file, run_python_test_file, serialize, validate_extra_policy_eq,
+    validate_init_strategy_eq, validate_numpy_serde_config_eq, validate_pyany_serde_type_eq,
+    validate_schema_policy_eq,
 };
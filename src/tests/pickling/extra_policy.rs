@@ -0,0 +1,21 @@
+use pyo3::prelude::*;
+
+use crate::tests::{run_python_test_file, validate_extra_policy_eq};
+
+fn tests_submod<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyModule>> {
+    let sub = PyModule::new(py, "pickling_extra_policy_tests")?;
+    sub.add_function(wrap_pyfunction!(validate_extra_policy_eq, py)?)?;
+    Ok(sub)
+}
+
+#[test]
+fn run_pickling_tests() -> PyResult<()> {
+    Python::initialize();
+    Python::attach(|py| {
+        run_python_test_file(
+            py,
+            "python/tests/pickling/extra_policy.py",
+            tests_submod(py)?,
+        )
+    })
+}
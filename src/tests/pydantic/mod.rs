@@ -1,3 +1,5 @@
+mod extra_policy;
 mod init_strategy;
 mod numpy_serde_config;
 mod pyany_serde_type;
+mod schema_policy;
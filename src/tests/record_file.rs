@@ -0,0 +1,3 @@
+use crate::python_tests;
+
+python_tests!(record_file);
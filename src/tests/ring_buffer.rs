@@ -0,0 +1,37 @@
+use std::env;
+
+use pyo3::prelude::*;
+
+use crate::python_tests;
+use crate::tests::load_python_test_file;
+
+// The command which runs run_ring_buffer_producer in a new process
+#[pyfunction]
+fn get_producer_command() -> PyResult<Vec<String>> {
+    Ok(vec![
+        env::current_exe()?.to_string_lossy().into_owned(),
+        "--exact".to_owned(),
+        "tests::ring_buffer::run_ring_buffer_producer".to_owned(),
+        "--quiet".to_owned(),
+    ])
+}
+
+python_tests!(ring_buffer, get_producer_command);
+
+// Sends objects to the RingBuffer named by RING_BUFFER_PRODUCER_NAME when run by test_processes,
+// and does nothing otherwise
+#[test]
+fn run_ring_buffer_producer() -> PyResult<()> {
+    let Ok(name) = env::var("RING_BUFFER_PRODUCER_NAME") else {
+        return Ok(());
+    };
+    Python::initialize();
+    Python::attach(|py| {
+        let globals = load_python_test_file(py, "python/tests/ring_buffer.py", tests_submod(py)?)?;
+        globals
+            .get_item("produce")?
+            .expect("Expected python/tests/ring_buffer.py to define produce")
+            .call1((name,))?;
+        Ok(())
+    })
+}
@@ -0,0 +1,4 @@
+use crate::python_tests;
+use crate::tests::validate_pyany_serde_type_eq;
+
+python_tests!(type_hint, validate_pyany_serde_type_eq);
@@ -0,0 +1,558 @@
+use pyo3::PyTypeInfo;
+use pyo3::exceptions::PyValueError;
+use pyo3::ffi::c_str;
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::{
+    PyBool, PyBytes, PyComplex, PyDict, PyFloat, PyFrozenSet, PyFunction, PyInt, PyList, PySet,
+    PyString, PyTuple, PyType,
+};
+
+use crate::PyAnySerdeType;
+use crate::common::NumpyDtype;
+use crate::pyany_serde_impl::{
+    DEFAULT_MAX_DEPTH, ExtraPolicy, InitStrategy, NumpySerdeConfig, SchemaPolicy,
+};
+use crate::pyany_serde_type::FieldSerdeTypeMap;
+
+// UNION needs a Python function to choose the option for a value, so one is generated from the
+// runtime classes of the options. Exact type matches take priority so that e.g. a bool is not
+// matched to an int option that comes before a bool option.
+static MAKE_OPTION_CHOICE_FN: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+const MAKE_OPTION_CHOICE_FN_CODE: &std::ffi::CStr = c_str!(
+    r#"
+def make_option_choice_fn(option_classes):
+    def option_choice_fn(value):
+        for idx, option_class in enumerate(option_classes):
+            if type(value) is option_class:
+                return idx
+        for idx, option_class in enumerate(option_classes):
+            if isinstance(value, option_class):
+                return idx
+        raise ValueError(f"{value!r} does not match any of the options of this union")
+
+    return option_choice_fn
+"#
+);
+
+#[pymethods]
+impl PyAnySerdeType {
+    #[staticmethod]
+    #[pyo3(signature = (tp, strict = false))]
+    pub fn from_type_hint<'py>(tp: &Bound<'py, PyAny>, strict: bool) -> PyResult<PyAnySerdeType> {
+        let mut inference = TypeHintInference::new(tp.py(), strict)?;
+        let root_serde_type = inference.infer(tp)?;
+        inference.finish(root_serde_type)
+    }
+}
+
+type InferClassFn<'py> =
+    fn(&mut TypeHintInference<'py>, &Bound<'py, PyType>) -> PyResult<PyAnySerdeType>;
+
+struct TypeHintInference<'py> {
+    typing: Bound<'py, PyModule>,
+    collections: Bound<'py, PyModule>,
+    dataclasses: Bound<'py, PyModule>,
+    strict: bool,
+    // The classes currently being inferred, used to detect recursive types
+    in_progress: Vec<Bound<'py, PyType>>,
+    // Recursive classes are inferred once as a definition of a DEFINITIONS wrapping the inferred
+    // serde type, and are referred to everywhere they are used by the names given to them here
+    definition_names: Vec<(Bound<'py, PyType>, String)>,
+    definitions: FieldSerdeTypeMap,
+}
+
+impl<'py> TypeHintInference<'py> {
+    fn new(py: Python<'py>, strict: bool) -> PyResult<Self> {
+        Ok(TypeHintInference {
+            typing: py.import("typing")?,
+            collections: py.import("collections")?,
+            dataclasses: py.import("dataclasses")?,
+            strict,
+            in_progress: Vec::new(),
+            definition_names: Vec::new(),
+            definitions: FieldSerdeTypeMap::default(),
+        })
+    }
+
+    fn finish(self, root_serde_type: PyAnySerdeType) -> PyResult<PyAnySerdeType> {
+        if self.definitions.is_empty() {
+            return Ok(root_serde_type);
+        }
+        Ok(PyAnySerdeType::DEFINITIONS {
+            definitions: self.definitions,
+            root_serde_type: Py::new(self.typing.py(), root_serde_type)?,
+            max_depth: DEFAULT_MAX_DEPTH,
+        })
+    }
+
+    fn get_definition_name(&self, cls: &Bound<'py, PyType>) -> Option<String> {
+        self.definition_names
+            .iter()
+            .find(|(definition_cls, _)| definition_cls.is(cls))
+            .map(|(_, name)| name.clone())
+    }
+
+    // Names the definition of cls after its qualname, unless another class already has that name
+    fn add_definition_name(&mut self, cls: &Bound<'py, PyType>) -> PyResult<String> {
+        let mut name = cls.qualname()?.to_string();
+        if self
+            .definition_names
+            .iter()
+            .any(|(_, definition_name)| *definition_name == name)
+        {
+            name = format!("{name}_{}", self.definition_names.len());
+        }
+        self.definition_names.push((cls.clone(), name.clone()));
+        Ok(name)
+    }
+
+    fn infer_class(
+        &mut self,
+        cls: &Bound<'py, PyType>,
+        infer_class_fn: InferClassFn<'py>,
+    ) -> PyResult<PyAnySerdeType> {
+        self.in_progress.push(cls.clone());
+        let pyany_serde_type = infer_class_fn(self, cls);
+        self.in_progress.pop();
+        let pyany_serde_type = pyany_serde_type?;
+        // The class was referred to from within itself while it was being inferred
+        match self.get_definition_name(cls) {
+            Some(name) => {
+                self.definitions.0.push((name.clone(), pyany_serde_type));
+                Ok(PyAnySerdeType::REF { name })
+            }
+            None => Ok(pyany_serde_type),
+        }
+    }
+
+    fn infer(&mut self, tp: &Bound<'py, PyAny>) -> PyResult<PyAnySerdeType> {
+        match self.try_infer(tp)? {
+            Some(pyany_serde_type) => Ok(pyany_serde_type),
+            None if self.strict => Err(PyValueError::new_err(format!(
+                "Unable to infer a PyAnySerdeType from type hint {}",
+                tp.repr()?
+            ))),
+            None => Ok(PyAnySerdeType::PICKLE {}),
+        }
+    }
+
+    fn infer_py(&mut self, tp: &Bound<'py, PyAny>) -> PyResult<Py<PyAnySerdeType>> {
+        Py::new(tp.py(), self.infer(tp)?)
+    }
+
+    fn get_origin_and_args(
+        &self,
+        tp: &Bound<'py, PyAny>,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyTuple>)> {
+        Ok((
+            self.typing.call_method1("get_origin", (tp,))?,
+            self.typing
+                .call_method1("get_args", (tp,))?
+                .cast_into::<PyTuple>()?,
+        ))
+    }
+
+    fn try_infer(&mut self, tp: &Bound<'py, PyAny>) -> PyResult<Option<PyAnySerdeType>> {
+        let py = tp.py();
+        let (origin, args) = self.get_origin_and_args(tp)?;
+        if !origin.is_none() {
+            return self.try_infer_generic(&origin, &args);
+        }
+        if tp.is(self.typing.getattr("Any")?) {
+            return Ok(Some(PyAnySerdeType::DYNAMIC {}));
+        }
+        let Ok(cls) = tp.cast::<PyType>() else {
+            return Ok(None);
+        };
+        if cls.is(PyBool::type_object(py)) {
+            return Ok(Some(PyAnySerdeType::BOOL {}));
+        }
+        if cls.is(PyInt::type_object(py)) {
+            return Ok(Some(PyAnySerdeType::INT {}));
+        }
+        if cls.is(PyFloat::type_object(py)) {
+            return Ok(Some(PyAnySerdeType::FLOAT {}));
+        }
+        if cls.is(PyComplex::type_object(py)) {
+            return Ok(Some(PyAnySerdeType::COMPLEX {}));
+        }
+        if cls.is(PyString::type_object(py)) {
+            return Ok(Some(PyAnySerdeType::STRING {}));
+        }
+        if cls.is(PyBytes::type_object(py)) {
+            return Ok(Some(PyAnySerdeType::BYTES {}));
+        }
+        // Unparameterized containers are treated as containers of Any
+        let any = self.typing.getattr("Any")?;
+        if cls.is(PyTuple::type_object(py)) {
+            let args = PyTuple::new(py, [any, py.Ellipsis().into_bound(py)])?;
+            return self.try_infer_generic(cls.as_any(), &args);
+        }
+        if self.is_single_item_container(cls.as_any())? {
+            return self.try_infer_generic(cls.as_any(), &PyTuple::new(py, [any])?);
+        }
+        if self.is_key_value_container(cls.as_any())? {
+            return self.try_infer_generic(cls.as_any(), &PyTuple::new(py, [&any, &any])?);
+        }
+        if let Some(name) = self.get_definition_name(cls) {
+            return Ok(Some(PyAnySerdeType::REF { name }));
+        }
+        if self
+            .in_progress
+            .iter()
+            .any(|in_progress| in_progress.is(cls))
+        {
+            return Ok(Some(PyAnySerdeType::REF {
+                name: self.add_definition_name(cls)?,
+            }));
+        }
+        if cls.is_subclass(&py.import("enum")?.getattr("Enum")?)? {
+            return Ok(Some(PyAnySerdeType::ENUM {
+                values: cls
+                    .try_iter()?
+                    .map(|value| value.map(|value| value.unbind()))
+                    .collect::<PyResult<_>>()?,
+            }));
+        }
+        let infer_class_fn: InferClassFn<'py> = if is_typed_dict(cls)? {
+            Self::infer_typed_dict
+        } else if cls.is_subclass_of::<PyTuple>()? && cls.hasattr("_fields")? {
+            Self::infer_named_tuple
+        } else if cls.hasattr("__attrs_attrs__")? {
+            Self::infer_attrs
+        } else if cls.hasattr("model_fields")? && cls.hasattr("model_construct")? {
+            Self::infer_pydantic_model
+        } else if self
+            .dataclasses
+            .call_method1("is_dataclass", (cls,))?
+            .extract::<bool>()?
+        {
+            Self::infer_dataclass
+        } else {
+            return Ok(None);
+        };
+        self.infer_class(cls, infer_class_fn).map(Some)
+    }
+
+    fn is_single_item_container(&self, origin: &Bound<'py, PyAny>) -> PyResult<bool> {
+        let py = origin.py();
+        Ok(origin.is(PyList::type_object(py))
+            || origin.is(PySet::type_object(py))
+            || origin.is(PyFrozenSet::type_object(py))
+            || origin.is(self.collections.getattr("deque")?))
+    }
+
+    fn is_key_value_container(&self, origin: &Bound<'py, PyAny>) -> PyResult<bool> {
+        Ok(origin.is(PyDict::type_object(origin.py()))
+            || origin.is(self.collections.getattr("OrderedDict")?)
+            || origin.is(self.collections.getattr("defaultdict")?))
+    }
+
+    fn try_infer_generic(
+        &mut self,
+        origin: &Bound<'py, PyAny>,
+        args: &Bound<'py, PyTuple>,
+    ) -> PyResult<Option<PyAnySerdeType>> {
+        let py = origin.py();
+        if origin.is(self.typing.getattr("Annotated")?) {
+            return self.try_infer(&args.get_item(0)?);
+        }
+        if origin.is(self.typing.getattr("Literal")?) {
+            return Ok(Some(PyAnySerdeType::ENUM {
+                values: args.iter().map(|arg| arg.unbind()).collect(),
+            }));
+        }
+        if origin.is(self.typing.getattr("Union")?)
+            || origin.is(py.import("types")?.getattr("UnionType")?)
+        {
+            return self.try_infer_union(args);
+        }
+        if origin.is(PyTuple::type_object(py)) {
+            if args.len() == 2 && args.get_item(1)?.is(py.Ellipsis()) {
+                return Ok(Some(PyAnySerdeType::HOMOGENEOUSTUPLE {
+                    items_serde_type: self.infer_py(&args.get_item(0)?)?,
+                }));
+            }
+            return Ok(Some(PyAnySerdeType::TUPLE {
+                item_serde_types: args
+                    .iter()
+                    .map(|arg| self.infer(&arg))
+                    .collect::<PyResult<_>>()?,
+            }));
+        }
+        if self.is_single_item_container(origin)? {
+            let items_serde_type = self.infer_py(&args.get_item(0)?)?;
+            return Ok(Some(if origin.is(PyList::type_object(py)) {
+                PyAnySerdeType::LIST { items_serde_type }
+            } else if origin.is(PySet::type_object(py)) {
+                PyAnySerdeType::SET { items_serde_type }
+            } else if origin.is(PyFrozenSet::type_object(py)) {
+                PyAnySerdeType::FROZENSET { items_serde_type }
+            } else {
+                PyAnySerdeType::DEQUE { items_serde_type }
+            }));
+        }
+        if self.is_key_value_container(origin)? {
+            let keys_serde_type = self.infer_py(&args.get_item(0)?)?;
+            let values_serde_type = self.infer_py(&args.get_item(1)?)?;
+            return Ok(Some(if origin.is(PyDict::type_object(py)) {
+                PyAnySerdeType::DICT {
+                    keys_serde_type,
+                    values_serde_type,
+                }
+            } else if origin.is(self.collections.getattr("OrderedDict")?) {
+                PyAnySerdeType::ORDEREDDICT {
+                    keys_serde_type,
+                    values_serde_type,
+                }
+            } else {
+                PyAnySerdeType::DEFAULTDICT {
+                    keys_serde_type,
+                    values_serde_type,
+                }
+            }));
+        }
+        if is_numpy_ndarray(origin)? {
+            // NDArray[T] is ndarray[shape, dtype[T]]
+            let Some(dtype_hint) = args.iter().last() else {
+                return Ok(None);
+            };
+            let (_, dtype_args) = self.get_origin_and_args(&dtype_hint)?;
+            let Some(Ok(dtype)) = dtype_args
+                .iter()
+                .next()
+                .map(|scalar_type| scalar_type.extract::<NumpyDtype>())
+            else {
+                return Ok(None);
+            };
+            return Ok(Some(PyAnySerdeType::NUMPY {
+                dtype,
+                config: NumpySerdeConfig::DYNAMIC {
+                    preprocessor_fn: None,
+                    postprocessor_fn: None,
+                },
+            }));
+        }
+        Ok(None)
+    }
+
+    fn try_infer_union(&mut self, args: &Bound<'py, PyTuple>) -> PyResult<Option<PyAnySerdeType>> {
+        let py = args.py();
+        let none_type = py.None().into_bound(py).get_type();
+        let option_args = args
+            .iter()
+            .filter(|arg| !arg.is(&none_type))
+            .collect::<Vec<_>>();
+        let value_serde_type = if option_args.len() == 1 {
+            self.infer(&option_args[0])?
+        } else {
+            let Some(option_classes) = option_args
+                .iter()
+                .map(|arg| self.get_option_class(arg))
+                .collect::<PyResult<Option<Vec<_>>>>()?
+            else {
+                return Ok(None);
+            };
+            let option_choice_fn = MAKE_OPTION_CHOICE_FN
+                .get_or_try_init(py, || {
+                    Ok::<_, PyErr>(
+                        PyModule::from_code(
+                            py,
+                            MAKE_OPTION_CHOICE_FN_CODE,
+                            c_str!("make_option_choice_fn.py"),
+                            c_str!("make_option_choice_fn"),
+                        )?
+                        .getattr("make_option_choice_fn")?
+                        .unbind(),
+                    )
+                })?
+                .bind(py)
+                .call1((PyTuple::new(py, option_classes)?,))?
+                .cast_into::<PyFunction>()?
+                .unbind();
+            PyAnySerdeType::UNION {
+                option_serde_types: option_args
+                    .iter()
+                    .map(|arg| self.infer(arg))
+                    .collect::<PyResult<_>>()?,
+                option_choice_fn,
+            }
+        };
+        Ok(Some(if option_args.len() < args.len() {
+            PyAnySerdeType::OPTION {
+                value_serde_type: Py::new(py, value_serde_type)?,
+            }
+        } else {
+            value_serde_type
+        }))
+    }
+
+    // The runtime class (or tuple of classes) that values of an option of a union are instances of
+    fn get_option_class(&self, tp: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let py = tp.py();
+        let (origin, args) = self.get_origin_and_args(tp)?;
+        if origin.is_none() {
+            return Ok(match tp.cast::<PyType>() {
+                Ok(cls) if is_typed_dict(cls)? => Some(PyDict::type_object(py).into_any()),
+                Ok(cls) => Some(cls.clone().into_any()),
+                Err(_) => None,
+            });
+        }
+        if origin.is(self.typing.getattr("Annotated")?) {
+            return self.get_option_class(&args.get_item(0)?);
+        }
+        if origin.is(self.typing.getattr("Literal")?) {
+            return Ok(Some(
+                PyTuple::new(py, args.iter().map(|arg| arg.get_type()))?.into_any(),
+            ));
+        }
+        Ok(origin.cast_into::<PyType>().ok().map(|cls| cls.into_any()))
+    }
+
+    fn get_type_hints(&self, cls: &Bound<'py, PyType>) -> PyResult<Bound<'py, PyDict>> {
+        Ok(self
+            .typing
+            .call_method1("get_type_hints", (cls,))?
+            .cast_into::<PyDict>()?)
+    }
+
+    fn infer_typed_dict(&mut self, cls: &Bound<'py, PyType>) -> PyResult<PyAnySerdeType> {
+        let optional_keys = cls.getattr("__optional_keys__")?;
+        let mut key_serde_type_dict = FieldSerdeTypeMap::default();
+        let mut typed_dict_optional_keys = Vec::new();
+        for (key, hint) in self.get_type_hints(cls)?.iter() {
+            let key = key.extract::<String>()?;
+            if optional_keys.contains(&key)? {
+                typed_dict_optional_keys.push(key.clone());
+            }
+            let serde_type = self.infer(&hint)?;
+            key_serde_type_dict.0.push((key, serde_type));
+        }
+        Ok(PyAnySerdeType::TYPEDDICT {
+            key_serde_type_dict,
+            optional_keys: typed_dict_optional_keys,
+            extra: ExtraPolicy::IGNORE {},
+            alphabetical_field_order: false,
+            schema: SchemaPolicy::FIXED {},
+        })
+    }
+
+    // Type hints of the fields of a class which may not be annotated, in which case Any is used
+    fn get_field_hint(
+        &self,
+        hints: &Bound<'py, PyDict>,
+        field: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        match hints.get_item(field)? {
+            Some(hint) => Ok(hint),
+            None => self.typing.getattr("Any"),
+        }
+    }
+
+    fn infer_named_tuple(&mut self, cls: &Bound<'py, PyType>) -> PyResult<PyAnySerdeType> {
+        let hints = self.get_type_hints(cls)?;
+        let item_serde_types = cls
+            .getattr("_fields")?
+            .try_iter()?
+            .map(|field| {
+                let hint = self.get_field_hint(&hints, &field?.extract::<String>()?)?;
+                self.infer(&hint)
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(PyAnySerdeType::NAMEDTUPLE {
+            clazz: cls.clone().unbind().into_any(),
+            item_serde_types,
+        })
+    }
+
+    fn infer_attrs(&mut self, cls: &Bound<'py, PyType>) -> PyResult<PyAnySerdeType> {
+        let hints = self.get_type_hints(cls)?;
+        let mut field_serde_type_dict = FieldSerdeTypeMap::default();
+        for attribute in cls.getattr("__attrs_attrs__")?.try_iter()? {
+            let name = attribute?.getattr("name")?.extract::<String>()?;
+            let serde_type = self.infer(&self.get_field_hint(&hints, &name)?)?;
+            field_serde_type_dict.0.push((name, serde_type));
+        }
+        Ok(PyAnySerdeType::DATACLASS {
+            clazz: cls.clone().unbind().into_any(),
+            init_strategy: InitStrategy::AUTO {},
+            field_serde_type_dict,
+            extra: ExtraPolicy::IGNORE {},
+            alphabetical_field_order: false,
+            schema: SchemaPolicy::FIXED {},
+        })
+    }
+
+    fn infer_pydantic_model(&mut self, cls: &Bound<'py, PyType>) -> PyResult<PyAnySerdeType> {
+        let mut field_serde_type_dict = FieldSerdeTypeMap::default();
+        for (name, field_info) in cls.getattr("model_fields")?.cast_into::<PyDict>()?.iter() {
+            let serde_type = self.infer(&field_info.getattr("annotation")?)?;
+            field_serde_type_dict
+                .0
+                .push((name.extract::<String>()?, serde_type));
+        }
+        Ok(PyAnySerdeType::DATACLASS {
+            clazz: cls.clone().unbind().into_any(),
+            init_strategy: InitStrategy::MODELCONSTRUCT {},
+            field_serde_type_dict,
+            extra: ExtraPolicy::IGNORE {},
+            alphabetical_field_order: false,
+            schema: SchemaPolicy::FIXED {},
+        })
+    }
+
+    fn infer_dataclass(&mut self, cls: &Bound<'py, PyType>) -> PyResult<PyAnySerdeType> {
+        let hints = self.get_type_hints(cls)?;
+        let mut field_serde_type_dict = FieldSerdeTypeMap::default();
+        let mut init_fields = Vec::new();
+        for field in self
+            .dataclasses
+            .call_method1("fields", (cls,))?
+            .try_iter()?
+        {
+            let field = field?;
+            let name = field.getattr("name")?.extract::<String>()?;
+            if field.getattr("init")?.extract::<bool>()? {
+                init_fields.push(name.clone());
+            }
+            let hint = match hints.get_item(&name)? {
+                Some(hint) => hint,
+                None => field.getattr("type")?,
+            };
+            let serde_type = self.infer(&hint)?;
+            field_serde_type_dict.0.push((name, serde_type));
+        }
+        let init_strategy = if init_fields.len() == field_serde_type_dict.len() {
+            InitStrategy::ALL {}
+        } else if init_fields.is_empty() {
+            InitStrategy::NONE {}
+        } else {
+            InitStrategy::SOME {
+                kwargs: init_fields,
+            }
+        };
+        Ok(PyAnySerdeType::DATACLASS {
+            clazz: cls.clone().unbind().into_any(),
+            init_strategy,
+            field_serde_type_dict,
+            extra: ExtraPolicy::IGNORE {},
+            alphabetical_field_order: false,
+            schema: SchemaPolicy::FIXED {},
+        })
+    }
+}
+
+fn is_typed_dict(cls: &Bound<'_, PyType>) -> PyResult<bool> {
+    Ok(cls.is_subclass_of::<PyDict>()?
+        && cls.hasattr("__required_keys__")?
+        && cls.hasattr("__optional_keys__")?)
+}
+
+// Checked by name so that numpy is not imported unless it is already in use
+fn is_numpy_ndarray(origin: &Bound<'_, PyAny>) -> PyResult<bool> {
+    let Ok(cls) = origin.cast::<PyType>() else {
+        return Ok(false);
+    };
+    Ok(cls.module()?.to_str()? == "numpy" && cls.name()?.to_str()? == "ndarray")
+}